@@ -0,0 +1,71 @@
+//! Audio-reactive rings without a microphone: a synthetic four-on-the-floor
+//! spectrum stands in for an analyzer, each ring swells with its band, and
+//! the beats are counted into bars.
+//!
+//! ```bash
+//! $ cargo run --example audio_reactive -- 128
+//! ```
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    audio::{AudioSpectrum, Bars, BarsElapsed, BeatDetector, RingBandMapping},
+    CellTable, CellsParam, MovingCellsPlugin,
+};
+
+const BANDS: usize = 8;
+
+/// Tempo of the synthetic track.
+#[derive(Resource)]
+struct Bpm(f32);
+
+fn main() {
+    let bpm = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(120.0);
+
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(CellsParam::new(CellTable::new("\
+            ┌→→→→→→→┐
+            ↑┏←←←←←┓↓
+            ↑↓┌→→→┐↑↓
+            ↑↓↑┏←┓↓↑↓
+            ↑↓↑↓0↑↓↑↓
+            ↑↓↑┗→┛↓↑↓
+            ↑↓└←←←┘↑↓
+            ↑┗→→→→→┛↓
+            └←←←←←←←┘")))
+        .add_plugins(MovingCellsPlugin)
+        .insert_resource(Bpm(bpm))
+        .insert_resource(AudioSpectrum { bands: vec![0.0; BANDS] })
+        .init_resource::<RingBandMapping>()
+        .init_resource::<BeatDetector>()
+        .insert_resource(Bars::new(4, 1))
+        .add_systems(PreUpdate, synthesize_spectrum)
+        .add_systems(Update, print_bars)
+        .run();
+}
+
+/// Kick on every beat in the lowest bands, an offbeat hat in the highest,
+/// and a slow sweep through the middle.
+fn synthesize_spectrum(time: Res<Time>, bpm: Res<Bpm>, mut spectrum: ResMut<AudioSpectrum>) {
+    let beats = time.elapsed_secs() * bpm.0 / 60.0;
+    let in_beat = beats.fract();
+    let kick = (-in_beat * 8.0).exp();
+    let hat = (-(in_beat - 0.5).rem_euclid(1.0) * 16.0).exp();
+    let sweep = (beats / 16.0).fract();
+
+    for (band, level) in spectrum.bands.iter_mut().enumerate() {
+        let at = band as f32 / (BANDS - 1) as f32;
+        let mid = 0.5 + 0.5 * ((at - sweep) * TAU).cos();
+        *level = (kick * (1.0 - at * 3.0).max(0.0) + hat * (at * 3.0 - 2.0).max(0.0) + 0.3 * mid).min(1.0);
+    }
+}
+
+fn print_bars(mut elapsed: EventReader<BarsElapsed>, mut bars: Local<u32>) {
+    for _ in elapsed.read() {
+        *bars += 1;
+        println!("bar {}", *bars);
+    }
+}
@@ -0,0 +1,104 @@
+//! Replaying a flow-field image (see `moving_cells_01::flow_field`).
+//!
+//! ```bash
+//! $ cargo run --example flow_field                      # a vortex drawn in code
+//! $ cargo run --example flow_field -- field.png 16 12   # assets/field.png as 16x12 cells
+//! ```
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageLoaderSettings,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use moving_cells_01::{
+    flow_field::table_from_flow_field, CellTable, CellsParam, MovingCellsPlugin, RespawnCells,
+};
+
+/// Image still loading, and the table size to quantize it to.
+#[derive(Resource)]
+struct PendingField {
+    image: Handle<Image>,
+    columns: u32,
+    rows: u32,
+}
+
+/// Clockwise square rings around the center, `cells` x `cells`, 8 px per cell.
+fn vortex(cells: u32) -> Image {
+    let size = cells * 8;
+    let mut data = Vec::new();
+    for py in 0..size {
+        for px in 0..size {
+            // y up, relative to the center
+            let d = Vec2::new(px as f32, (size - 1 - py) as f32) - Vec2::splat((size - 1) as f32 / 2.0);
+            let dir = if d.x.abs() > d.y.abs() {
+                Vec2::new(0.0, -d.x.signum())
+            } else {
+                Vec2::new(d.y.signum(), 0.0)
+            };
+            let [r, g] = ((dir + Vec2::ONE) / 2.0 * 255.0).to_array();
+            data.extend([r as u8, g as u8, 128, 255]);
+        }
+    }
+    Image::new(
+        Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let path = args.get(1).cloned();
+    let columns = args.get(2).and_then(|a| a.parse().ok()).unwrap_or(11);
+    let rows = args.get(3).and_then(|a| a.parse().ok()).unwrap_or(columns);
+
+    let table = match path {
+        // shown until the image has loaded
+        Some(_) => CellTable::new("0"),
+        None => table_from_flow_field(&vortex(columns), columns, columns).unwrap_or_else(|e| panic!("{}", e)),
+    };
+
+    let mut app = App::new();
+    app
+        .add_plugins(DefaultPlugins)
+        .insert_resource(CellsParam::new(table))
+        .add_plugins(MovingCellsPlugin);
+
+    if let Some(path) = path {
+        let image = app.world().resource::<AssetServer>().load_with_settings(
+            path,
+            // vectors, not colors
+            |settings: &mut ImageLoaderSettings| settings.is_srgb = false,
+        );
+        app
+            .insert_resource(PendingField { image, columns, rows })
+            .add_systems(Update, apply_flow_field.run_if(resource_exists::<PendingField>));
+    }
+
+    app.run();
+}
+
+fn apply_flow_field(
+    mut commands: Commands,
+    pending: Res<PendingField>,
+    images: Res<Assets<Image>>,
+    mut cells_param: ResMut<CellsParam>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    let Some(image) = images.get(&pending.image) else {
+        return;
+    };
+    match table_from_flow_field(image, pending.columns, pending.rows) {
+        Ok(table) => {
+            println!("flow field:\n{}", table.to_text());
+            cells_param.cell_table = table;
+            respawn.write(RespawnCells);
+        }
+        Err(e) => println!("{}", e),
+    }
+    commands.remove_resource::<PendingField>();
+}
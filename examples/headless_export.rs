@@ -0,0 +1,65 @@
+//! Running the simulation without a window and exporting agent positions as CSV.
+//!
+//! ```bash
+//! $ cargo run --example headless_export > positions.csv
+//! ```
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    agent::{self, Agent, CellBoundaryCrossed, LoopCompleted},
+    clock::SimClockPlugin,
+    CellTable, CellsParam,
+};
+
+const FPS: u64 = 30;
+const FRAMES: u32 = 90;
+
+fn main() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_nanos(1_000_000_000 / FPS)))
+        // parse() stays quiet, keeping stdout clean for the CSV
+        .insert_resource(CellsParam::new(CellTable::parse("\
+            ┌→┐
+            ↑0↓
+            └←┘").unwrap()))
+        .add_event::<CellBoundaryCrossed>()
+        .add_event::<LoopCompleted>()
+        .add_systems(Startup, spawn_agents)
+        .add_systems(Update, agent::move_agents);
+
+    println!("frame,agent,x,y");
+    for frame in 0..FRAMES {
+        app.update();
+        let world = app.world_mut();
+        let mut agents = world.query::<(Entity, &Agent, &Transform)>();
+        for (entity, _, transform) in agents.iter(world) {
+            println!(
+                "{},{},{:.3},{:.3}",
+                frame,
+                entity.index(),
+                transform.translation.x,
+                transform.translation.y,
+            );
+        }
+    }
+}
+
+/// One agent per track cell, without any rendering components.
+fn spawn_agents(mut commands: Commands, cells_param: Res<CellsParam>) {
+    for iy in 0..cells_param.cell_table.height {
+        for ix in 0..cells_param.cell_table.width {
+            let grid = UVec2::new(ix as u32, iy as u32);
+            if cells_param.move_type_at(grid).is_track() {
+                commands.spawn((
+                    Agent::new(grid, Color::WHITE, cells_param.circle_size),
+                    Transform::default(),
+                ));
+            }
+        }
+    }
+}
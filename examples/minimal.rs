@@ -0,0 +1,22 @@
+//! Embedding the artwork in your own app: `DefaultPlugins`, a table, and the plugin.
+//!
+//! ```bash
+//! $ cargo run --example minimal
+//! ```
+
+use bevy::prelude::*;
+
+use moving_cells_01::{CellTable, CellsParam, MovingCellsPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(CellsParam::new(CellTable::new("\
+            ┌→→→┐
+            ↑┏←┓↓
+            ↑↓0↑↓
+            ↑┗→┛↓
+            └←←←┘")))
+        .add_plugins(MovingCellsPlugin)
+        .run();
+}
@@ -0,0 +1,62 @@
+//! Headless simulation streamed to a browser canvas over WebSocket.
+//!
+//! ```bash
+//! $ cargo run --example preview_server --features server -- 0.0.0.0:9001
+//! ```
+//!
+//! then open `examples/preview_server.html` (`?ws://host:9001` for a remote machine);
+//! the digit keys there flip a ring.
+
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    create_cell,
+    direction::{flip_rings, FlowDirection},
+    move_cells,
+    ring::{update_rings, Rings},
+    server::{PreviewServer, PreviewServerPlugin},
+    CellTable, CellsParam,
+};
+
+fn main() {
+    let addr = std::env::args().nth(1).unwrap_or_else(|| "127.0.0.1:9001".into());
+    let server = PreviewServer::bind(&addr).unwrap_or_else(|e| panic!("can't listen on {}: {}", addr, e));
+    println!("streaming on ws://{}", addr);
+
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin, PreviewServerPlugin))
+        .insert_resource(server)
+        .insert_resource(CellsParam::new(CellTable::new("\
+            ┌→→→→→┐
+            ↑ ┏←┓ ↓
+            ↑ ↓0↑ ↓
+            ↑ ┗→┛ ↓
+            └←←←←←┘")))
+        .init_resource::<FlowDirection>()
+        .init_resource::<Rings>()
+        .add_systems(Startup, spawn_cells)
+        .add_systems(PreUpdate, update_rings.run_if(resource_changed::<CellsParam>))
+        .add_systems(Update, (flip_rings, move_cells).chain());
+
+    // drive the app ourselves so the tick rate can follow the audience
+    loop {
+        app.update();
+        std::thread::sleep(app.world().resource::<PreviewServer>().tick_interval());
+    }
+}
+
+/// One circle per cell, without any rendering components.
+fn spawn_cells(mut commands: Commands, cells_param: Res<CellsParam>) {
+    let table = &cells_param.cell_table;
+    for iy in 0..table.height {
+        for ix in 0..table.width {
+            let grid = UVec2::new(ix as u32, iy as u32);
+            let pos = cells_param.cell_center(ix, iy);
+            let cell = create_cell(table.get(ix, iy), grid, pos, cells_param.cell_extent(ix, iy));
+            let scale = table.scale(ix, iy).min_element();
+            commands.spawn((cell, Transform::from_translation(pos.extend(0.0)).with_scale(Vec3::splat(scale))));
+        }
+    }
+}
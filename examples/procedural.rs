@@ -0,0 +1,55 @@
+//! Generating a table in code: nested rings, alternating direction.
+//!
+//! ```bash
+//! $ cargo run --example procedural -- 6
+//! ```
+
+use bevy::prelude::*;
+
+use moving_cells_01::{agent::TravelMode, CellTable, CellsParam, MovingCellsPlugin};
+
+/// `rings` concentric rings, the outer one clockwise (thin keisen) and every
+/// other one counter clockwise (thick keisen), around a single center dot.
+fn nested_rings(rings: usize) -> String {
+    let size = rings * 2 + 1;
+    let mut grid = vec![vec![' '; size]; size];
+    grid[rings][rings] = '0';
+
+    for ring in 0..rings {
+        let (lo, hi) = (ring, size - 1 - ring);
+        // (top-left, top-right, bottom-right, bottom-left, top, right, bottom, left)
+        let glyphs = if ring % 2 == 0 {
+            ['┌', '┐', '┘', '└', '→', '↓', '←', '↑']
+        } else {
+            ['┏', '┓', '┛', '┗', '←', '↑', '→', '↓']
+        };
+        let mut set = |x: usize, y: usize, c: char| grid[y][x] = c;
+        for i in lo + 1..hi {
+            set(i, lo, glyphs[4]);
+            set(hi, i, glyphs[5]);
+            set(i, hi, glyphs[6]);
+            set(lo, i, glyphs[7]);
+        }
+        set(lo, lo, glyphs[0]);
+        set(hi, lo, glyphs[1]);
+        set(hi, hi, glyphs[2]);
+        set(lo, hi, glyphs[3]);
+    }
+
+    grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+}
+
+fn main() {
+    let rings = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(4);
+
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(CellsParam {
+            cell_size: Vec2::splat(30.0),
+            circle_size: 6.0,
+            travel_mode: TravelMode::Agents,
+            ..CellsParam::new(CellTable::new(&nested_rings(rings)))
+        })
+        .add_plugins(MovingCellsPlugin)
+        .run();
+}
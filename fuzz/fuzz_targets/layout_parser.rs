@@ -0,0 +1,46 @@
+#![no_main]
+
+use bevy::math::UVec2;
+use libfuzzer_sys::fuzz_target;
+use moving_cells_01::{dialect::Dialect, CellTable, CellsParam};
+
+// Layouts may come from the clipboard, the network or watch folders, so
+// arbitrary text must never panic -- neither while parsing (in any dialect)
+// nor while the coordinate service and the track graph walk the result.
+fuzz_target!(|text: &str| {
+    for dialect in [Dialect::Keisen, Dialect::Ascii, Dialect::Binary] {
+        let Ok(table) = dialect.parse(text) else {
+            continue;
+        };
+        walk(&table);
+
+        // what a dialect writes, it reads back as it was; the others may
+        // refuse it, but never panic
+        for to in [Dialect::Keisen, Dialect::Ascii, Dialect::Binary] {
+            let written = to.write(&table);
+            let reparsed = to.parse(&written);
+            if to == dialect && to != Dialect::Keisen {
+                let reparsed = reparsed.unwrap_or_else(|e| panic!("{:?} can't read back {:?}: {}", to, written, e));
+                assert_eq!((reparsed.width, reparsed.height), (table.width, table.height), "{:?}", written);
+                for y in 0..table.height {
+                    for x in 0..table.width {
+                        assert_eq!(reparsed.get(x, y), table.get(x, y), "{:?} at {},{}", written, x, y);
+                    }
+                }
+            }
+        }
+    }
+});
+
+fn walk(table: &CellTable) {
+    let param = CellsParam::new(table.clone());
+    for iy in 0..param.cell_table.height {
+        for ix in 0..param.cell_table.width {
+            let grid = UVec2::new(ix as u32, iy as u32);
+            let _ = param.cell_center(ix, iy);
+            let _ = param.cell_extent(ix, iy);
+            let _ = param.move_type_at(grid);
+            let _ = param.next_cell(grid);
+        }
+    }
+}
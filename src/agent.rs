@@ -0,0 +1,271 @@
+//! Traveling agents.
+//!
+//! In [`TravelMode::Agents`] the track cells don't oscillate their own circle.
+//! Instead every agent walks the track graph cell-to-cell, keeping its own
+//! color and size all the way around a loop.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng as _, SeedableRng};
+
+use crate::{
+    clock::SimClock,
+    gravity::Gravity,
+    layers::{Layer, OnLayer},
+    move_offset, ring,
+    tempo::Tempo,
+    tween::SizeTween,
+    CellsParam,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum TravelMode {
+    /// every track cell oscillates its own circle (the original look)
+    #[default]
+    Oscillate,
+    /// agents travel around whole loops
+    Agents,
+    /// `per_loop` agents on every ring, evenly spaced along its path and
+    /// traveling as a train; tails and dead ends stay empty
+    Train { per_loop: usize },
+}
+
+#[derive(Component, Debug)]
+pub struct Agent {
+    /// current cell in table coordinates (y down)
+    pub grid: UVec2,
+    /// 0..1 through the current cell
+    pub progress: f32,
+    /// the easing plays backwards, since an odd number of flips (see
+    /// [`CellsParam::ease`])
+    pub mirrored: bool,
+    /// the cell the agent started from, used for lap counting
+    pub origin: UVec2,
+    pub laps: u32,
+    pub color: Color,
+    pub size: f32,
+}
+
+impl Agent {
+    pub fn new(grid: UVec2, color: Color, size: f32) -> Self {
+        Agent {
+            grid,
+            progress: 0.0,
+            mirrored: false,
+            origin: grid,
+            laps: 0,
+            color,
+            size,
+        }
+    }
+}
+
+/// Sent every time an agent leaves one cell for the next.
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct CellBoundaryCrossed {
+    pub agent: Entity,
+    pub from: UVec2,
+    pub to: UVec2,
+}
+
+/// Sent when an agent is back at the cell it started from.
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct LoopCompleted {
+    pub agent: Entity,
+    pub laps: u32,
+}
+
+/// Spawn one agent on every track cell, each with its own color and size.
+pub fn spawn_agents(
+    commands: &mut Commands,
+    cells_param: &CellsParam,
+    mesh: &Handle<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for iy in 0..cells_param.cell_table.height {
+        for ix in 0..cells_param.cell_table.width {
+            let grid = UVec2::new(ix as u32, iy as u32);
+            if !cells_param.move_type_at(grid).is_track() {
+                continue;
+            }
+            let count = cells_param.circles_per_cell.max(1) * cells_param.cell_table.density(ix, iy);
+            for k in 0..count {
+                let mut agent = Agent::new(
+                    grid,
+                    Color::hsl(rng.random_range(0.0..360.0), 0.8, 0.7),
+                    cells_param.circle_size * rng.random_range(0.6..1.2),
+                );
+                agent.progress = k as f32 / count as f32;
+                let pos = cells_param.cell_center(ix, iy);
+                commands.spawn((
+                    Mesh2d(mesh.clone()),
+                    Transform::from_translation(pos.extend(Layer::Circles.z()))
+                        .with_scale(Vec3::splat(agent.size / cells_param.circle_size)),
+                    MeshMaterial2d(materials.add(agent.color)),
+                    agent,
+                    OnLayer(Layer::Circles),
+                ));
+            }
+        }
+    }
+}
+
+/// Points every cell's path is sampled at to measure it.
+const PATH_SAMPLES: usize = 32;
+
+/// Length of the path through `grid` from its entry up to every sample,
+/// with the linear progress at that sample.
+fn path_lengths(cells_param: &CellsParam, grid: UVec2) -> [(f32, f32); PATH_SAMPLES + 1] {
+    let half = cells_param.cell_extent(grid.x as usize, grid.y as usize) / 2.0;
+    let move_type = cells_param.move_type_at(grid);
+    let at = |t: f32| move_offset(move_type, t, half, cells_param.corner_style);
+    let mut lengths = [(0.0, 0.0); PATH_SAMPLES + 1];
+    for i in 1..=PATH_SAMPLES {
+        let (t0, t1) = ((i - 1) as f32 / PATH_SAMPLES as f32, i as f32 / PATH_SAMPLES as f32);
+        lengths[i] = (lengths[i - 1].0 + at(t0).distance(at(t1)), t1);
+    }
+    lengths
+}
+
+/// Cell and progress of `count` points spaced evenly by path length around
+/// `ring`, the first at the start of its first cell.
+pub fn train_placements(cells_param: &CellsParam, ring: &ring::Ring, count: usize) -> Vec<(UVec2, f32)> {
+    let cells = ring
+        .cells
+        .iter()
+        .map(|grid| (*grid, path_lengths(cells_param, *grid)))
+        .collect::<Vec<_>>();
+    let total = cells.iter().map(|(_, lengths)| lengths[PATH_SAMPLES].0).sum::<f32>();
+
+    let mut placements = Vec::with_capacity(count);
+    let (mut cell, mut start) = (0, 0.0);
+    for k in 0..count {
+        let along = total * k as f32 / count as f32;
+        while cell + 1 < cells.len() && start + cells[cell].1[PATH_SAMPLES].0 <= along {
+            start += cells[cell].1[PATH_SAMPLES].0;
+            cell += 1;
+        }
+        let (grid, lengths) = &cells[cell];
+        let along = along - start;
+        let i = lengths.iter().rposition(|(length, _)| *length <= along).unwrap_or(0).min(PATH_SAMPLES - 1);
+        let ((l0, t0), (l1, t1)) = (lengths[i], lengths[i + 1]);
+        let t = if l1 > l0 { t0 + (t1 - t0) * (along - l0) / (l1 - l0) } else { t0 };
+        placements.push((*grid, t));
+    }
+    placements
+}
+
+/// Spawn a train of `per_loop` agents on every ring, one color per train.
+pub fn spawn_trains(
+    commands: &mut Commands,
+    cells_param: &CellsParam,
+    per_loop: usize,
+    mesh: &Handle<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for ring in ring::find_rings(cells_param) {
+        let color = Color::hsl(rng.random_range(0.0..360.0), 0.8, 0.7);
+        for (grid, progress) in train_placements(cells_param, &ring, per_loop) {
+            let mut agent = Agent::new(grid, color, cells_param.circle_size);
+            agent.progress = progress;
+            let pos = cells_param.cell_center(grid.x as usize, grid.y as usize);
+            commands.spawn((
+                Mesh2d(mesh.clone()),
+                Transform::from_translation(pos.extend(Layer::Circles.z())),
+                MeshMaterial2d(materials.add(agent.color)),
+                agent,
+                OnLayer(Layer::Circles),
+            ));
+        }
+    }
+}
+
+/// Cells an agent may cross in one frame; the rest of a longer step is dropped.
+pub const MAX_CROSSINGS: usize = 64;
+
+pub fn move_agents(
+    clock: Res<SimClock>,
+    tempo: Res<Tempo>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    mut agents: Query<(Entity, &mut Agent, &mut Transform)>,
+    mut crossed: EventWriter<CellBoundaryCrossed>,
+    mut completed: EventWriter<LoopCompleted>,
+) {
+    // a zero or broken tempo would cross cells forever
+    let span_sec = tempo.span_sec();
+    let step = if span_sec.is_finite() && span_sec > 0.0 { clock.delta_secs() / span_sec } else { 0.0 };
+    let (cell_size, circle_size) = match &tween {
+        Some(tween) => (tween.cell_size(&cells_param), tween.circle_size(&cells_param)),
+        None => (cells_param.cell_size, cells_param.circle_size),
+    };
+    let wells = match cells_param.gravity {
+        Some(_) => Gravity::wells(&cells_param, cell_size),
+        None => Vec::new(),
+    };
+
+    for (entity, mut agent, mut transform) in agents.iter_mut() {
+        agent.progress += step * cells_param.cell_table.speed(agent.grid.x as usize, agent.grid.y as usize);
+
+        let mut crossings = 0;
+        while agent.progress >= 1.0 && crossings < MAX_CROSSINGS {
+            crossings += 1;
+            let Some(next) = cells_param.next_cell(agent.grid) else {
+                // dead end: wait at the edge of the cell
+                agent.progress = 1.0;
+                break;
+            };
+            agent.progress -= 1.0;
+            crossed.write(CellBoundaryCrossed {
+                agent: entity,
+                from: agent.grid,
+                to: next,
+            });
+            agent.grid = next;
+            if next == agent.origin {
+                agent.laps += 1;
+                completed.write(LoopCompleted {
+                    agent: entity,
+                    laps: agent.laps,
+                });
+            }
+        }
+
+        // stepping back through the clock
+        while agent.progress < 0.0 && crossings < MAX_CROSSINGS {
+            crossings += 1;
+            let Some(prev) = cells_param.prev_cell(agent.grid) else {
+                agent.progress = 0.0;
+                break;
+            };
+            agent.progress += 1.0;
+            crossed.write(CellBoundaryCrossed {
+                agent: entity,
+                from: agent.grid,
+                to: prev,
+            });
+            if agent.grid == agent.origin {
+                agent.laps = agent.laps.saturating_sub(1);
+            }
+            agent.grid = prev;
+        }
+        agent.progress = agent.progress.clamp(0.0, 1.0);
+
+        let (ix, iy) = (agent.grid.x as usize, agent.grid.y as usize);
+        let center = cells_param.cell_center_at(ix, iy, cell_size);
+        let half = cells_param.cell_extent_at(ix, iy, cell_size) / 2.0;
+        let eased = cells_param.ease(cells_param.easing_at(agent.grid), agent.progress, agent.mirrored);
+        let mut offset = move_offset(cells_param.move_type_at(agent.grid), eased, half, cells_param.corner_style)
+            + cells_param.jitter.offset(agent.origin, clock.elapsed_secs_f64() as f32);
+        if let Some(gravity) = cells_param.gravity {
+            offset += gravity.offset(center + offset, &wells, cell_size);
+        }
+        transform.translation.x = center.x + offset.x;
+        transform.translation.y = center.y + offset.y;
+        let region_scale = cells_param.cell_table.scale(ix, iy).min_element();
+        transform.scale = Vec3::splat(agent.size / circle_size * region_scale);
+    }
+}
@@ -0,0 +1,53 @@
+//! Debug overlay for authoring tables: an arrow across every track cell the
+//! way its circles go, and a ring on every center.
+
+use bevy::prelude::*;
+
+use crate::{
+    capability::{self, Capability},
+    direction::FlowDirection,
+    tween::SizeTween,
+    CellsParam, MoveType,
+};
+
+/// Share of the cell an arrow spans.
+const LENGTH: f32 = 0.6;
+const COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+
+pub struct DirectionArrowsPlugin;
+
+impl Plugin for DirectionArrowsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, draw_arrows.run_if(capability::enabled(Capability::Arrows)));
+    }
+}
+
+/// Start and tip of the arrow of a `move_type` cell of half extent `half`,
+/// around its center; corners are crossed diagonally, from the side circles
+/// enter on to the side they leave by.
+pub fn arrow(move_type: MoveType, half: Vec2, reversed: bool) -> Option<(Vec2, Vec2)> {
+    let (from, to) = move_type.endpoints()?;
+    let (from, to) = (from * half * LENGTH, to * half * LENGTH);
+    Some(if reversed { (to, from) } else { (from, to) })
+}
+
+fn draw_arrows(
+    mut gizmos: Gizmos,
+    cells_param: Res<CellsParam>,
+    flow: Res<FlowDirection>,
+    tween: Option<Res<SizeTween>>,
+) {
+    let cell_size = tween.as_ref().map_or(cells_param.cell_size, |tween| tween.cell_size(&cells_param));
+    for iy in 0..cells_param.cell_table.height {
+        for ix in 0..cells_param.cell_table.width {
+            let move_type = cells_param.move_type_at(UVec2::new(ix as u32, iy as u32));
+            let center = cells_param.cell_center_at(ix, iy, cell_size);
+            let half = cells_param.cell_extent_at(ix, iy, cell_size) / 2.0;
+            if move_type == MoveType::Center {
+                gizmos.circle_2d(center, half.min_element() * LENGTH / 2.0, COLOR);
+            } else if let Some((from, to)) = arrow(move_type, half, flow.reversed) {
+                gizmos.arrow_2d(center + from, center + to, COLOR).with_tip_length(half.min_element() / 3.0);
+            }
+        }
+    }
+}
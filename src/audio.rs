@@ -0,0 +1,303 @@
+//! Audio-reactive rings: every ring listens to its own frequency band, the
+//! outermost ring to the lowest and the innermost to the highest.
+//!
+//! The crate doesn't capture audio itself; whatever analyzes the input writes
+//! band levels into [`AudioSpectrum`] every frame. From those levels a
+//! [`BeatDetector`] finds onsets and [`Bars`] counts them into bars, so
+//! unattended shows can follow a DJ without a MIDI clock.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::{
+    agent::Agent,
+    materials::{paint, quantize, SharedMaterials},
+    ring::Rings,
+    Cell, CellsParam, Circles,
+};
+
+/// Levels (0..=1) of the frequency bands, lowest first.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct AudioSpectrum {
+    pub bands: Vec<f32>,
+}
+
+impl AudioSpectrum {
+    /// Level of `band`, silent if the analyzer doesn't have that many.
+    pub fn level(&self, band: usize) -> f32 {
+        self.bands.get(band).copied().unwrap_or(0.0).clamp(0.0, 1.0)
+    }
+}
+
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SharedMaterials>()
+            .add_event::<Beat>()
+            .add_event::<BarsElapsed>()
+            .add_systems(
+                Update,
+                (
+                    react_to_spectrum
+                        .after(crate::agent::move_agents)
+                        .run_if(resource_exists::<RingBandMapping>),
+                    (detect_beats, count_bars.run_if(resource_exists::<Bars>))
+                        .chain()
+                        .run_if(resource_exists::<BeatDetector>),
+                )
+                    .run_if(resource_exists::<AudioSpectrum>),
+            );
+    }
+}
+
+/// How rings react to their band; insert it to turn the mapping on.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct RingBandMapping {
+    /// extra scale of a ring's circles at full level
+    pub size_depth: f32,
+    /// how much darker (in Oklch lightness) a ring's circles are at silence
+    pub brightness_depth: f32,
+    /// band of every ring, outermost first; empty spreads the rings evenly
+    /// from the lowest band to the highest
+    pub bands: Vec<usize>,
+}
+
+impl Default for RingBandMapping {
+    fn default() -> Self {
+        RingBandMapping {
+            size_depth: 0.5,
+            brightness_depth: 0.4,
+            bands: Vec::new(),
+        }
+    }
+}
+
+impl RingBandMapping {
+    /// Band of the `ring`-th ring from the outside, out of `rings`.
+    pub fn band(&self, ring: usize, rings: usize, bands: usize) -> usize {
+        if let Some(band) = self.bands.get(ring) {
+            return *band;
+        }
+        if rings <= 1 || bands == 0 {
+            return 0;
+        }
+        // round to the nearest band
+        (ring * (bands - 1) + (rings - 1) / 2) / (rings - 1)
+    }
+}
+
+/// Indices into `rings`, from the outermost ring (furthest from the middle of
+/// the table on average) to the innermost.
+pub fn rings_outside_in(rings: &Rings, cells_param: &CellsParam) -> Vec<usize> {
+    let radius = |i: usize| {
+        let cells = &rings.0[i].cells;
+        let sum = cells
+            .iter()
+            .map(|cell| cells_param.cell_center(cell.x as usize, cell.y as usize).length())
+            .sum::<f32>();
+        sum / cells.len().max(1) as f32
+    };
+    let mut order = (0..rings.0.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| radius(*b).total_cmp(&radius(*a)));
+    order
+}
+
+/// What the mapping applied last frame, so it can be taken back before the
+/// new levels go on and other systems' changes are kept.
+#[derive(Default)]
+pub struct AppliedLevels {
+    /// (scale without the mapping, scale as written)
+    scales: HashMap<Entity, (Vec3, Vec3)>,
+    colors: HashMap<Entity, Painted>,
+}
+
+/// The color a circle was painted with, and what it was painted from.
+struct Painted {
+    material: AssetId<ColorMaterial>,
+    color: Color,
+    base: Color,
+    offset: f32,
+}
+
+type Circle<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut MeshMaterial2d<ColorMaterial>,
+    Option<&'a Cell>,
+    Option<&'a Agent>,
+);
+
+#[allow(clippy::too_many_arguments)]
+pub fn react_to_spectrum(
+    spectrum: Res<AudioSpectrum>,
+    mapping: Res<RingBandMapping>,
+    rings: Res<Rings>,
+    cells_param: Res<CellsParam>,
+    mut applied: Local<AppliedLevels>,
+    mut circles: Query<Circle, Circles>,
+    mut shared: ResMut<SharedMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let order = rings_outside_in(&rings, &cells_param);
+    let mut levels = HashMap::new();
+    for (nth, ring) in order.iter().enumerate() {
+        let band = mapping.band(nth, order.len(), spectrum.bands.len());
+        // in steps, so the circles of a ring in a color share a material
+        let level = quantize(spectrum.level(band));
+        levels.extend(rings.0[*ring].cells.iter().map(|cell| (*cell, level)));
+    }
+
+    let AppliedLevels { scales, colors } = &mut *applied;
+    let mut seen = HashSet::new();
+    for (entity, mut transform, mut material, cell, agent) in circles.iter_mut() {
+        let grid = cell.map(|cell| cell.grid).or(agent.map(|agent| agent.grid));
+        // tails, dead ends and centers aren't on a ring
+        let Some(level) = grid.and_then(|grid| levels.get(&grid)).copied() else {
+            continue;
+        };
+        let Some(current) = materials.get(&material.0).map(|m| m.color) else {
+            continue;
+        };
+        seen.insert(entity);
+
+        let (base, written) = scales.entry(entity).or_insert((transform.scale, transform.scale));
+        if transform.scale != *written {
+            // rescaled by someone else (agents every frame)
+            *base = transform.scale;
+        }
+        transform.scale = *base * (1.0 + mapping.size_depth * level);
+        *written = transform.scale;
+
+        let base = match colors.get(&entity) {
+            Some(painted) if painted.material == material.0.id() && painted.color == current => painted.base,
+            // shifted in place since (LFOs, the timeline): keep the shift
+            Some(painted) if painted.material == material.0.id() => lighten(current, -painted.offset),
+            // repainted by someone else
+            _ => current,
+        };
+        let offset = -mapping.brightness_depth * (1.0 - level);
+        // unclamped, so it comes off again exactly
+        let color = lighten(base, offset);
+        paint(&mut material, color, &mut shared, &mut materials);
+        colors.insert(entity, Painted { material: material.0.id(), color, base, offset });
+    }
+    scales.retain(|entity, _| seen.contains(entity));
+    colors.retain(|entity, _| seen.contains(entity));
+}
+
+fn lighten(color: Color, by: f32) -> Color {
+    let mut color = Oklcha::from(color);
+    color.lightness += by;
+    color.into()
+}
+
+/// Frames of spectral flux the onset threshold is averaged over.
+const FLUX_HISTORY: usize = 43;
+
+/// Onset detection by spectral flux: a beat is a jump in the band levels well
+/// above the recent average jump.
+#[derive(Resource, Clone, Debug)]
+pub struct BeatDetector {
+    /// how many times the average flux counts as an onset
+    pub sensitivity: f32,
+    /// flux below this is never an onset, so silence doesn't trigger
+    pub floor: f32,
+    /// onsets closer than this are the same beat
+    pub min_interval_sec: f32,
+    previous: Vec<f32>,
+    history: VecDeque<f32>,
+    since_beat: f32,
+}
+
+impl Default for BeatDetector {
+    fn default() -> Self {
+        BeatDetector {
+            sensitivity: 1.5,
+            floor: 0.1,
+            min_interval_sec: 0.25,
+            previous: Vec::new(),
+            history: VecDeque::with_capacity(FLUX_HISTORY),
+            since_beat: f32::INFINITY,
+        }
+    }
+}
+
+impl BeatDetector {
+    /// Take this frame's levels; true on an onset.
+    pub fn feed(&mut self, bands: &[f32], dt: f32) -> bool {
+        let flux = bands
+            .iter()
+            .enumerate()
+            .map(|(i, level)| (level - self.previous.get(i).copied().unwrap_or(0.0)).max(0.0))
+            .sum::<f32>();
+        self.previous.clear();
+        self.previous.extend_from_slice(bands);
+
+        let average = self.history.iter().sum::<f32>() / self.history.len().max(1) as f32;
+        if self.history.len() == FLUX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(flux);
+
+        self.since_beat += dt;
+        let onset = flux > self.floor && flux > average * self.sensitivity && self.since_beat >= self.min_interval_sec;
+        if onset {
+            self.since_beat = 0.0;
+        }
+        onset
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct Beat;
+
+/// Counts beats into bars; insert it to get [`BarsElapsed`].
+#[derive(Resource, Clone, Debug)]
+pub struct Bars {
+    pub beats_per_bar: u32,
+    /// bars between two [`BarsElapsed`]
+    pub every: u32,
+    beats: u32,
+}
+
+impl Bars {
+    pub fn new(beats_per_bar: u32, every: u32) -> Self {
+        Bars { beats_per_bar, every, beats: 0 }
+    }
+
+    /// Count a beat; true when `every` bars are complete.
+    pub fn beat(&mut self) -> bool {
+        self.beats += 1;
+        if self.beats >= self.beats_per_bar * self.every {
+            self.beats = 0;
+            return true;
+        }
+        false
+    }
+}
+
+/// Sent every [`Bars::every`] bars of detected beats.
+#[derive(Event, Debug)]
+pub struct BarsElapsed;
+
+pub fn detect_beats(
+    time: Res<Time>,
+    spectrum: Res<AudioSpectrum>,
+    mut detector: ResMut<BeatDetector>,
+    mut beats: EventWriter<Beat>,
+) {
+    if detector.feed(&spectrum.bands, time.delta_secs()) {
+        beats.write(Beat);
+    }
+}
+
+pub fn count_bars(mut beats: EventReader<Beat>, mut bars: ResMut<Bars>, mut elapsed: EventWriter<BarsElapsed>) {
+    for _ in beats.read() {
+        if bars.beat() {
+            elapsed.write(BarsElapsed);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! What's behind the circles: a solid color, a vertical gradient or a color
+//! slowly drifting round the hue wheel, set by `CellsParam::background`.
+//!
+//! Solid and drifting backgrounds are the [`ClearColor`]; a gradient is a
+//! quad filling the `main` camera's view, behind everything, with the clear
+//! color set to its bottom color (the veils of
+//! [`feedback`](crate::feedback) fade to that).
+
+use bevy::{
+    prelude::*,
+    render::mesh::{Mesh, VertexAttributeValues},
+    window::PrimaryWindow,
+};
+
+use crate::{
+    calibration::OutputName,
+    input::{Action, Hotkeys},
+    layers::{Layer, OnLayer},
+    CellsParam,
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Background {
+    Solid(Color),
+    /// from `top` at the top of the view to `bottom` at the bottom
+    Gradient { top: Color, bottom: Color },
+    /// `color` with its hue turning `degrees_per_sec`
+    HueDrift { color: Color, degrees_per_sec: f32 },
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(ClearColor::default().0)
+    }
+}
+
+impl Background {
+    pub fn next(self) -> Self {
+        match self {
+            Background::Solid(_) => Background::Gradient {
+                top: Color::srgb(0.05, 0.07, 0.2),
+                bottom: Color::BLACK,
+            },
+            Background::Gradient { .. } => Background::HueDrift {
+                color: Color::srgb(0.2, 0.05, 0.1),
+                degrees_per_sec: 6.0,
+            },
+            Background::HueDrift { .. } => Background::Solid(Color::BLACK),
+        }
+    }
+
+    /// The clear color `seconds` in.
+    pub fn clear_color(&self, seconds: f32) -> Color {
+        match *self {
+            Background::Solid(color) => color,
+            Background::Gradient { bottom, .. } => bottom,
+            Background::HueDrift { color, degrees_per_sec } => color.rotate_hue(degrees_per_sec * seconds),
+        }
+    }
+}
+
+/// The quad of a gradient background.
+#[derive(Component)]
+pub struct GradientQuad;
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (cycle_background, apply_background, sync_gradient).chain());
+    }
+}
+
+fn cycle_background(hotkeys: Hotkeys, mut cells_param: ResMut<CellsParam>) {
+    if hotkeys.just_pressed(Action::CycleBackground) {
+        cells_param.background = cells_param.background.next();
+        println!("background: {:?}", cells_param.background);
+    }
+}
+
+pub fn apply_background(
+    mut commands: Commands,
+    time: Res<Time>,
+    cells_param: Res<CellsParam>,
+    clear_color: Option<ResMut<ClearColor>>,
+) {
+    let color = cells_param.background.clear_color(time.elapsed_secs());
+    match clear_color {
+        Some(mut clear_color) => {
+            if clear_color.0 != color {
+                clear_color.0 = color;
+            }
+        }
+        None => commands.insert_resource(ClearColor(color)),
+    }
+}
+
+/// A unit quad, `top` along its upper edge and `bottom` along the lower.
+pub fn gradient_mesh(top: Color, bottom: Color) -> Mesh {
+    let mut mesh = Mesh::from(Rectangle::new(1.0, 1.0));
+    let colors = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions
+            .iter()
+            .map(|p| if p[1] > 0.0 { top } else { bottom }.to_linear().to_f32_array())
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh
+}
+
+type MainCamera<'a> = (&'a OutputName, &'a Transform, &'a Projection);
+
+/// Spawn, recolor, fit or drop the gradient quad.
+#[allow(clippy::too_many_arguments)]
+pub fn sync_gradient(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<MainCamera, (With<Camera2d>, Without<GradientQuad>)>,
+    mut quads: Query<(Entity, &Mesh2d, &mut Transform), With<GradientQuad>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut colors: Local<Option<(Color, Color)>>,
+) {
+    let Background::Gradient { top, bottom } = cells_param.background else {
+        for (entity, ..) in quads.iter() {
+            commands.entity(entity).despawn();
+        }
+        *colors = None;
+        return;
+    };
+    let Ok((_, mesh, mut transform)) = quads.single_mut() else {
+        commands.spawn((
+            Mesh2d(meshes.add(gradient_mesh(top, bottom))),
+            // vertex colors times white
+            MeshMaterial2d(materials.add(Color::WHITE)),
+            Transform::from_xyz(0.0, 0.0, Layer::Background.z()),
+            GradientQuad,
+            OnLayer(Layer::Background),
+        ));
+        *colors = Some((top, bottom));
+        return;
+    };
+    if *colors != Some((top, bottom)) {
+        meshes.insert(mesh.0.id(), gradient_mesh(top, bottom));
+        *colors = Some((top, bottom));
+    }
+
+    let Some((_, camera, projection)) = cameras.iter().find(|(name, ..)| name.0 == "main") else {
+        return;
+    };
+    let scale = match projection {
+        Projection::Orthographic(ortho) => ortho.scale,
+        _ => 1.0,
+    };
+    transform.translation = camera.translation.truncate().extend(Layer::Background.z());
+    if let Ok(window) = window.single() {
+        // a little larger, so no edge shows while the camera moves
+        transform.scale = (window.size() * scale * 1.1).extend(1.0);
+    }
+}
@@ -0,0 +1,72 @@
+//! Run the standard performance scenes headless and print one JSON report
+//! per scene, optionally compared against an earlier run.
+//!
+//! ```bash
+//! $ cargo run --release --bin perf_report > before.jsonl
+//! $ # ...change things...
+//! $ cargo run --release --bin perf_report -- --baseline before.jsonl > after.jsonl
+//! ```
+//!
+//! With `--baseline`, the change of every scene's mean frame time is printed
+//! to stderr, and the exit code is 1 if any scene got slower than
+//! `--threshold` percent (10 by default). `--frames N` measures N frames per
+//! scene (120 by default), `--scene NAME` runs only the scenes whose name
+//! contains NAME.
+
+use std::process::ExitCode;
+
+use moving_cells_01::perf::{run, standard_scenes, Report};
+
+fn arg(args: &[String], name: &str) -> Option<String> {
+    let i = args.iter().position(|a| a == name)?;
+    Some(args.get(i + 1).cloned().unwrap_or_else(|| panic!("{} expects a value", name)))
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().collect::<Vec<_>>();
+    let frames = arg(&args, "--frames").map_or(120, |n| n.parse().expect("--frames expects a number"));
+    let threshold = arg(&args, "--threshold").map_or(10.0, |p| p.parse::<f64>().expect("--threshold expects a percentage"));
+    let baseline = arg(&args, "--baseline").map(|path| {
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("can't read {}: {}", path, e))
+    });
+    let filter = arg(&args, "--scene");
+
+    let mut regressed = false;
+    for scene in standard_scenes() {
+        if filter.as_ref().is_some_and(|f| !scene.name.contains(f.as_str())) {
+            continue;
+        }
+        let report = run(&scene, frames);
+        println!("{}", report.to_json());
+
+        let Some(baseline) = &baseline else {
+            continue;
+        };
+        let before = baseline
+            .lines()
+            .find(|line| Report::field(line, "scene").as_deref() == Some(scene.name.as_str()))
+            .and_then(|line| Report::field(line, "mean_ms")?.parse::<f64>().ok());
+        match before {
+            Some(before) => {
+                let change = (report.mean_ms / before - 1.0) * 100.0;
+                let slower = change > threshold;
+                regressed |= slower;
+                eprintln!(
+                    "{:<12} {:>9.3} ms -> {:>9.3} ms  {:+6.1}%{}",
+                    scene.name,
+                    before,
+                    report.mean_ms,
+                    change,
+                    if slower { "  REGRESSION" } else { "" },
+                );
+            }
+            None => eprintln!("{:<12} not in the baseline", scene.name),
+        }
+    }
+
+    if regressed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
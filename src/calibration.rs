@@ -0,0 +1,262 @@
+//! Per-output color calibration.
+//!
+//! Each output camera can carry an [`OutputCalibration`], applied as a
+//! full-screen pass after tonemapping (of 2D and 3D cameras alike), so
+//! projectors in a multi-output rig can be matched visually.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::embedded_asset,
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    image::BevyDefault as _,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+const SHADER_PATH: &str = "embedded://moving_cells_01/shaders/calibration.wgsl";
+
+pub use uniform::OutputCalibration;
+
+// encase's ShaderType derive emits per-field checks that are never called
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{prelude::*, render::{extract_component::ExtractComponent, render_resource::ShaderType}};
+
+    /// Color adjustment for one output, applied as
+    /// `matrix * (color * white_point) * brightness`.
+    #[derive(Component, Clone, Copy, Debug, ExtractComponent, ShaderType)]
+    pub struct OutputCalibration {
+        /// per-channel gain, e.g. to pull a bluish projector back to D65
+        pub white_point: Vec3,
+        pub brightness: f32,
+        pub matrix: Mat3,
+    }
+
+    impl Default for OutputCalibration {
+        fn default() -> Self {
+            OutputCalibration {
+                white_point: Vec3::ONE,
+                brightness: 1.0,
+                matrix: Mat3::IDENTITY,
+            }
+        }
+    }
+}
+
+/// Names a camera's output so a profile can be assigned to it.
+#[derive(Component, Clone, Debug)]
+pub struct OutputName(pub String);
+
+/// Calibration profiles, keyed by [`OutputName`].
+#[derive(Resource, Default)]
+pub struct OutputProfiles(pub HashMap<String, OutputCalibration>);
+
+pub struct CalibrationPlugin;
+
+impl Plugin for CalibrationPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/calibration.wgsl");
+
+        app
+            .init_resource::<OutputProfiles>()
+            .add_plugins((
+                ExtractComponentPlugin::<OutputCalibration>::default(),
+                UniformComponentPlugin::<OutputCalibration>::default(),
+            ))
+            .add_systems(PostUpdate, apply_output_profiles);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<CalibrationNode>>(Core2d, CalibrationLabel)
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::Tonemapping,
+                    CalibrationLabel,
+                    Node2d::EndMainPassPostProcessing,
+                ),
+            )
+            // the 3D view's camera stands in for the main one
+            .add_render_graph_node::<ViewNodeRunner<CalibrationNode>>(Core3d, CalibrationLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    CalibrationLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<CalibrationPipeline>();
+    }
+}
+
+fn apply_output_profiles(
+    mut commands: Commands,
+    profiles: Res<OutputProfiles>,
+    outputs: Query<(Entity, &OutputName, Option<&OutputCalibration>)>,
+) {
+    for (entity, name, current) in outputs.iter() {
+        if current.is_some() && !profiles.is_changed() {
+            continue;
+        }
+        let calibration = profiles.0.get(&name.0).copied().unwrap_or_default();
+        commands.entity(entity).insert(calibration);
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub(crate) struct CalibrationLabel;
+
+#[derive(Default)]
+struct CalibrationNode;
+
+impl ViewNode for CalibrationNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<OutputCalibration>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let calibration_pipeline = world.resource::<CalibrationPipeline>();
+        let pipeline_id = if view_target.is_hdr() {
+            calibration_pipeline.hdr_pipeline_id
+        } else {
+            calibration_pipeline.pipeline_id
+        };
+        let Some(pipeline) = world.resource::<PipelineCache>().get_render_pipeline(pipeline_id) else {
+            return Ok(());
+        };
+        let Some(uniforms) = world
+            .resource::<ComponentUniforms<OutputCalibration>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "calibration_bind_group",
+            &calibration_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &calibration_pipeline.sampler,
+                uniforms,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("calibration_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct CalibrationPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+    hdr_pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for CalibrationPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "calibration_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<OutputCalibration>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.load_asset(SHADER_PATH);
+
+        let mut queue = |format: TextureFormat| {
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("calibration_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: shader.clone(),
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                    zero_initialize_workgroup_memory: false,
+                })
+        };
+        let pipeline_id = queue(TextureFormat::bevy_default());
+        let hdr_pipeline_id = queue(ViewTarget::TEXTURE_FORMAT_HDR);
+
+        CalibrationPipeline {
+            layout,
+            sampler,
+            pipeline_id,
+            hdr_pipeline_id,
+        }
+    }
+}
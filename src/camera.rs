@@ -0,0 +1,478 @@
+//! The `main` camera: spawned by `setup` looking at the whole table, and
+//! refitted when the window is resized or the table changes.
+//!
+//! On by default; remove [`CameraFit`] to keep the camera where it is.
+//! Dragging with the left button pans and the wheel zooms ([`PanZoom`]),
+//! which stops the fitting until the view is reset.
+//!
+//! Clicking a circle (or cycling with Tab) makes the camera follow it
+//! ([`Followed`]) until the view is dragged or reset.
+//!
+//! With `CellsParam::swing`, the camera drifts back and forth on top of all
+//! that, in 2D and 3D alike, and with [`Shake`] it is kicked on the beat.
+
+use std::f32::consts::TAU;
+
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::{
+    calibration::OutputName,
+    clock::MotionPhase,
+    input::{Action, Hotkeys, UiFocus},
+    three_d::ThreeD,
+    CellsParam, Circles, StaticCell,
+};
+
+/// Keep the whole table in view.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct CameraFit {
+    /// left free on each side, as a fraction of the window
+    pub margin: f32,
+}
+
+impl Default for CameraFit {
+    fn default() -> Self {
+        CameraFit { margin: 0.05 }
+    }
+}
+
+/// Mouse pan and zoom of the `main` camera.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PanZoom {
+    /// closest zoom, in world units per pixel
+    pub min_scale: f32,
+    /// farthest zoom, in world units per pixel
+    pub max_scale: f32,
+    /// zoom factor of one wheel notch
+    pub step: f32,
+}
+
+impl Default for PanZoom {
+    fn default() -> Self {
+        PanZoom { min_scale: 0.05, max_scale: 20.0, step: 1.1 }
+    }
+}
+
+/// A slow sway of the `main` camera.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Swing {
+    /// how far the camera goes each way, in world units
+    pub amplitude: f32,
+    /// back and forth per second
+    pub frequency: f32,
+    /// which way it goes; z (toward the viewer) only counts in 3D
+    pub axis: Vec3,
+}
+
+impl Default for Swing {
+    fn default() -> Self {
+        Swing { amplitude: 10.0, frequency: 0.1, axis: Vec3::X }
+    }
+}
+
+impl Swing {
+    /// Where the camera is off its place `secs` in.
+    pub fn offset(&self, secs: f32) -> Vec3 {
+        self.axis.normalize_or_zero() * self.amplitude * (TAU * self.frequency * secs).sin()
+    }
+}
+
+/// The circle the `main` camera follows.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Followed;
+
+/// How quickly the camera catches up with a [`Followed`] circle: the part
+/// of the way left it goes per second, roughly.
+pub const FOLLOW_RATE: f32 = 4.0;
+
+/// A jolt of the `main` camera every time the circles cross into the next
+/// cell, on the beat of the [`Tempo`](crate::tempo::Tempo).
+///
+/// Opt-in.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Shake {
+    /// how far the camera is knocked, in world units
+    pub intensity: f32,
+    /// how much closer it jumps, as a fraction of the zoom (2D only)
+    pub punch: f32,
+    /// how quickly it settles, per second
+    pub decay: f32,
+}
+
+impl Default for Shake {
+    fn default() -> Self {
+        Shake { intensity: 6.0, punch: 0.03, decay: 6.0 }
+    }
+}
+
+/// How fast a shaken camera rattles, per second.
+const SHAKE_HZ: f32 = 9.0;
+
+impl Shake {
+    fn envelope(&self, since: f32) -> f32 {
+        (-self.decay * since).exp()
+    }
+
+    /// Where the camera is knocked `since` seconds into `cell`; a different
+    /// way every cell.
+    pub fn offset(&self, cell: i64, since: f32) -> Vec3 {
+        // the golden angle, so consecutive kicks never line up
+        let envelope = self.envelope(since);
+        if envelope == 0.0 {
+            return Vec3::ZERO;
+        }
+        let way = Vec2::from_angle(cell as f32 * 2.399_963);
+        (way * self.intensity * envelope * (TAU * SHAKE_HZ * since).cos()).extend(0.0)
+    }
+
+    /// What the zoom is multiplied by `since` seconds into a cell.
+    pub fn zoom(&self, since: f32) -> f32 {
+        1.0 - self.punch * self.envelope(since)
+    }
+}
+
+/// How far a camera is swung (and shaken) off the place everything else
+/// puts it, and how much it is zoomed in on top.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Swung {
+    pub offset: Vec3,
+    pub zoom: f32,
+}
+
+impl Default for Swung {
+    fn default() -> Self {
+        Swung { offset: Vec3::ZERO, zoom: 1.0 }
+    }
+}
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<CameraFit>()
+            .init_resource::<PanZoom>()
+            .add_systems(
+                Update,
+                (
+                    unswing_camera,
+                    // the mouse orbits the 3D camera instead
+                    (select_followed, pan_zoom).run_if(not(resource_exists::<ThreeD>)),
+                    fit_camera,
+                    follow_camera
+                        .after(crate::move_cells)
+                        .after(crate::agent::move_agents)
+                        .after(crate::spring::apply_springs),
+                    swing_camera,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// World units per pixel so that `bounds` fits a `window` of that size,
+/// `margin` of it left free on each side.
+pub fn fit_scale(bounds: Rect, window: Vec2, margin: f32) -> f32 {
+    let room = window * (1.0 - 2.0 * margin).max(0.01);
+    (bounds.size() / room).max_element()
+}
+
+/// The logical size of what `camera` draws to in `window`: its viewport, or
+/// the whole window.
+pub fn view_size(camera: &Camera, window: &Window) -> Vec2 {
+    camera
+        .viewport
+        .as_ref()
+        .map_or(window.size(), |viewport| viewport.physical_size.as_vec2() / window.scale_factor())
+}
+
+/// Center `transform` on `bounds` and zoom `projection` so they fit the
+/// window.
+pub fn fit(bounds: Rect, window: Vec2, camera_fit: &CameraFit, transform: &mut Transform, projection: &mut Projection) {
+    let Projection::Orthographic(ortho) = projection else {
+        return;
+    };
+    if window.min_element() <= 0.0 || bounds.is_empty() {
+        return;
+    }
+    ortho.scale = fit_scale(bounds, window, camera_fit.margin);
+    transform.translation = bounds.center().extend(transform.translation.z);
+}
+
+type MainCamera<'a> = (&'a OutputName, &'a mut Transform, &'a mut Projection);
+
+/// Refit the `main` camera when the window is resized, the table changes, or
+/// fitting is switched on.
+pub fn fit_camera(
+    mut resized: EventReader<WindowResized>,
+    camera_fit: Option<Res<CameraFit>>,
+    cells_param: Res<CellsParam>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&Camera, MainCamera), With<Camera2d>>,
+) {
+    let resized = resized.read().count() > 0;
+    let Some(camera_fit) = camera_fit else {
+        return;
+    };
+    if !(resized || camera_fit.is_changed() || cells_param.is_changed()) {
+        return;
+    }
+    let Ok(window) = window.single() else {
+        return;
+    };
+    for (camera, (name, mut transform, mut projection)) in cameras.iter_mut() {
+        if name.0 == "main" {
+            fit(cells_param.bounds(), view_size(camera, window), &camera_fit, &mut transform, &mut projection);
+        }
+    }
+}
+
+/// Where the cursor is, in pixels off the center of the window, y up.
+pub fn cursor_offset(window: &Window) -> Option<Vec2> {
+    Some((window.cursor_position()? - window.size() / 2.0) * Vec2::new(1.0, -1.0))
+}
+
+/// Zoom from `scale` to `to` world units per pixel, keeping what is
+/// `cursor` pixels off the center of the window (y up) where it is.
+pub fn zoom_about(translation: &mut Vec3, scale: &mut f32, to: f32, cursor: Vec2) {
+    let shift = cursor * (*scale - to);
+    translation.x += shift.x;
+    translation.y += shift.y;
+    *scale = to;
+}
+
+/// Pan while the left button is held, zoom on the wheel, and go back to the
+/// fitted view on [`Action::ResetView`].
+#[allow(clippy::too_many_arguments)]
+pub fn pan_zoom(
+    mut commands: Commands,
+    pan_zoom: Option<Res<PanZoom>>,
+    camera_fit: Option<Res<CameraFit>>,
+    // the fitting taken over by the mouse, back on reset
+    mut fitted: Local<Option<CameraFit>>,
+    hotkeys: Hotkeys,
+    buttons: Res<ButtonInput<MouseButton>>,
+    (motion, scroll): (Res<AccumulatedMouseMotion>, Res<AccumulatedMouseScroll>),
+    focus: Option<Res<UiFocus>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<MainCamera, With<Camera2d>>,
+) {
+    if hotkeys.just_pressed(Action::ResetView) {
+        commands.insert_resource(fitted.take().or(camera_fit.as_deref().cloned()).unwrap_or_default());
+        return;
+    }
+    // dragging a slider, scrolling a combo box
+    if UiFocus::pointer(focus.as_deref()) {
+        return;
+    }
+    let Some(pan_zoom) = pan_zoom else {
+        return;
+    };
+    let drag = if buttons.pressed(MouseButton::Left) { motion.delta } else { Vec2::ZERO };
+    let notches = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        // touchpads
+        MouseScrollUnit::Pixel => scroll.delta.y / 100.0,
+    };
+    if drag == Vec2::ZERO && notches == 0.0 {
+        return;
+    }
+    let Some((_, mut transform, mut projection)) = cameras.iter_mut().find(|(name, ..)| name.0 == "main") else {
+        return;
+    };
+    let Projection::Orthographic(ortho) = projection.as_mut() else {
+        return;
+    };
+    // window y is down, the world's up
+    transform.translation.x -= drag.x * ortho.scale;
+    transform.translation.y += drag.y * ortho.scale;
+    if notches != 0.0 {
+        let to = (ortho.scale / pan_zoom.step.powf(notches)).clamp(pan_zoom.min_scale, pan_zoom.max_scale);
+        let cursor = window.single().ok().and_then(cursor_offset).unwrap_or(Vec2::ZERO);
+        zoom_about(&mut transform.translation, &mut ortho.scale, to, cursor);
+    }
+    if let Some(camera_fit) = camera_fit {
+        *fitted = Some(camera_fit.clone());
+        commands.remove_resource::<CameraFit>();
+    }
+}
+
+/// Put swung (and shaken) cameras back in place, so panning and fitting
+/// move the place rather than the swing.
+pub fn unswing_camera(mut cameras: Query<(&mut Transform, &mut Projection, &mut Swung)>) {
+    for (mut transform, mut projection, mut swung) in cameras.iter_mut() {
+        if swung.offset != Vec3::ZERO {
+            transform.translation -= swung.offset;
+            swung.offset = Vec3::ZERO;
+        }
+        if swung.zoom != 1.0 {
+            if let Projection::Orthographic(ortho) = projection.as_mut() {
+                ortho.scale /= swung.zoom;
+            }
+            swung.zoom = 1.0;
+        }
+    }
+}
+
+type SwingCamera<'a> =
+    (Entity, &'a OutputName, &'a mut Transform, &'a mut Projection, Option<&'a mut Swung>, Has<Camera3d>);
+
+/// The 2D cameras, and the 3D one standing in for `main`.
+type SwingCameras<'w, 's> = Query<'w, 's, SwingCamera<'static>, Or<(With<Camera2d>, With<Camera3d>)>>;
+
+/// Swing the `main` camera by `CellsParam::swing` and kick it with
+/// [`Shake`] on every cell the circles cross; the 3D one keeps looking at
+/// the middle of the table.
+#[allow(clippy::too_many_arguments)]
+pub fn swing_camera(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    shake: Option<Res<Shake>>,
+    phase: Res<MotionPhase>,
+    time: Res<Time>,
+    // the cell the circles are in, and seconds since they entered it
+    mut beat: Local<Option<(i64, f32)>>,
+    mut cameras: SwingCameras,
+) {
+    let cell = phase.cells().floor() as i64;
+    let (cell, since) = match *beat {
+        Some((last, since)) if last == cell => (cell, since + time.delta_secs()),
+        Some(_) => (cell, 0.0),
+        // no kick for where it starts
+        None => (cell, f32::INFINITY),
+    };
+    *beat = Some((cell, since));
+
+    for (entity, name, mut transform, mut projection, swung, three_d) in cameras.iter_mut() {
+        if name.0 != "main" {
+            continue;
+        }
+        if cells_param.swing.is_none() && shake.is_none() {
+            if swung.is_some() {
+                commands.entity(entity).remove::<Swung>();
+                if three_d {
+                    transform.look_at(Vec3::ZERO, Vec3::Y);
+                }
+            }
+            continue;
+        }
+        let mut offset = cells_param.swing.map_or(Vec3::ZERO, |swing| swing.offset(time.elapsed_secs()));
+        let mut zoom = 1.0;
+        if let Some(shake) = &shake {
+            offset += shake.offset(cell, since);
+            zoom = shake.zoom(since);
+        }
+        if !three_d {
+            offset.z = 0.0;
+        }
+        transform.translation += offset;
+        if three_d {
+            transform.look_at(Vec3::ZERO, Vec3::Y);
+        } else if let Projection::Orthographic(ortho) = projection.as_mut() {
+            ortho.scale *= zoom;
+        } else {
+            zoom = 1.0;
+        }
+        match swung {
+            Some(mut swung) => *swung = Swung { offset, zoom },
+            None => {
+                commands.entity(entity).insert(Swung { offset, zoom });
+            }
+        }
+    }
+}
+
+type Followable<'a> = (Entity, &'a Transform, Has<crate::agent::Agent>, Has<StaticCell>, Has<Followed>);
+
+/// Follow the circle clicked on, or the next one on [`Action::FollowNext`]
+/// (the agents walking the loops if there are any, every travelling circle
+/// otherwise); stop on a drag, a click off the circles, or
+/// [`Action::ResetView`].
+#[allow(clippy::too_many_arguments)]
+pub fn select_followed(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    hotkeys: Hotkeys,
+    (buttons, motion): (Res<ButtonInput<MouseButton>>, Res<AccumulatedMouseMotion>),
+    focus: Option<Res<UiFocus>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&OutputName, &Transform, &Projection), With<Camera2d>>,
+    circles: Query<Followable, Circles>,
+) {
+    let mouse = !UiFocus::pointer(focus.as_deref());
+    let followed = circles.iter().find(|(.., followed)| *followed).map(|(entity, ..)| entity);
+    let next = if hotkeys.just_pressed(Action::FollowNext) {
+        let agents = circles.iter().any(|(_, _, agent, ..)| agent);
+        let mut candidates = circles
+            .iter()
+            .filter(|(_, _, agent, still, _)| if agents { *agent } else { !still })
+            .map(|(entity, ..)| entity)
+            .collect::<Vec<_>>();
+        candidates.sort();
+        // past the last one, back to no one
+        match followed.and_then(|entity| candidates.iter().position(|c| *c == entity)) {
+            Some(i) => candidates.get(i + 1).copied(),
+            None => candidates.first().copied(),
+        }
+    } else if mouse && buttons.just_pressed(MouseButton::Left) {
+        let main = cameras.iter().find(|(name, ..)| name.0 == "main");
+        let clicked = window.single().ok().and_then(cursor_offset).zip(main).and_then(|(cursor, (_, camera, projection))| {
+            let Projection::Orthographic(ortho) = projection else {
+                return None;
+            };
+            let at = camera.translation.truncate() + cursor * ortho.scale;
+            circles
+                .iter()
+                .map(|(entity, transform, ..)| (entity, transform.translation.truncate().distance(at)))
+                .filter(|(_, distance)| *distance <= cells_param.circle_size.max(4.0 * ortho.scale))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(entity, _)| entity)
+        });
+        match clicked {
+            Some(entity) => Some(entity),
+            None if followed.is_some() => None,
+            None => return,
+        }
+    } else if hotkeys.just_pressed(Action::ResetView)
+        || (mouse && buttons.pressed(MouseButton::Left) && motion.delta != Vec2::ZERO)
+    {
+        None
+    } else {
+        return;
+    };
+    if next == followed {
+        return;
+    }
+    if let Some(entity) = followed {
+        commands.entity(entity).remove::<Followed>();
+    }
+    match next {
+        Some(entity) => {
+            commands.entity(entity).insert(Followed);
+            commands.remove_resource::<CameraFit>();
+            println!("following {}", entity);
+        }
+        None => println!("following: off"),
+    }
+}
+
+/// Glide the `main` camera toward the [`Followed`] circle.
+pub fn follow_camera(
+    time: Res<Time>,
+    followed: Query<&Transform, (With<Followed>, Without<Camera2d>)>,
+    mut cameras: Query<(&OutputName, &mut Transform), With<Camera2d>>,
+) {
+    let Ok(target) = followed.single() else {
+        return;
+    };
+    let Some((_, mut camera)) = cameras.iter_mut().find(|(name, _)| name.0 == "main") else {
+        return;
+    };
+    let t = 1.0 - (-FOLLOW_RATE * time.delta_secs()).exp();
+    let at = camera.translation.truncate().lerp(target.translation.truncate(), t);
+    camera.translation = at.extend(camera.translation.z);
+}
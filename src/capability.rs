@@ -0,0 +1,85 @@
+//! Switching heavyweight subsystems on and off at runtime.
+//!
+//! Each subsystem gates its systems with [`enabled`] and builds or tears down
+//! whatever it owns (windows, offsets, files) when its [`Capability`] flips,
+//! so nothing needs a restart.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::input::{Action, Hotkeys};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Capability {
+    /// the latency-compensated preview window (if `PreviewLatency` is set)
+    Preview,
+    /// the loop clock overlay
+    ClockWidget,
+    Lfo,
+    Timeline,
+    /// crash recovery of unsaved layouts
+    Autosave,
+    /// fading ghosts behind the circles
+    Trails,
+    /// lines along the paths under the circles
+    Track,
+    /// an arrow across every cell, for authoring tables
+    Arrows,
+    /// the character and coordinates of every cell
+    Labels,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Capabilities(HashSet<Capability>);
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities(HashSet::from([
+            Capability::Preview,
+            Capability::Lfo,
+            Capability::Timeline,
+            Capability::Autosave,
+        ]))
+    }
+}
+
+impl Capabilities {
+    pub fn is_enabled(&self, capability: Capability) -> bool {
+        self.0.contains(&capability)
+    }
+
+    pub fn set(&mut self, capability: Capability, enabled: bool) {
+        if enabled {
+            self.0.insert(capability);
+        } else {
+            self.0.remove(&capability);
+        }
+    }
+
+    pub fn toggle(&mut self, capability: Capability) {
+        let enabled = !self.is_enabled(capability);
+        self.set(capability, enabled);
+        println!("{:?}: {}", capability, if enabled { "on" } else { "off" });
+    }
+}
+
+/// Run condition; everything is enabled in apps without [`Capabilities`].
+pub fn enabled(capability: Capability) -> impl Fn(Option<Res<Capabilities>>) -> bool + Clone {
+    move |capabilities| capabilities.is_none_or(|c| c.is_enabled(capability))
+}
+
+pub fn toggle_capabilities(hotkeys: Hotkeys, mut capabilities: ResMut<Capabilities>) {
+    for (action, capability) in [
+        (Action::ToggleClock, Capability::ClockWidget),
+        (Action::TogglePreview, Capability::Preview),
+        (Action::ToggleTrails, Capability::Trails),
+        (Action::ToggleTrack, Capability::Track),
+        (Action::ToggleArrows, Capability::Arrows),
+        (Action::ToggleLabels, Capability::Labels),
+    ] {
+        if hotkeys.just_pressed(action) {
+            capabilities.toggle(capability);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Nothing but the animation, for screenshots and performances: F12 hides
+//! the egui windows (with the `egui` feature), every overlay (help, messages,
+//! the diagnostics HUD), the debug layer and gizmos (arrows, the clock) at
+//! once, and the second press brings back exactly what was shown before.
+//!
+//! Overlays toggled while the view is clean stay hidden, and come back as
+//! they were before.
+
+use std::{any::TypeId, collections::HashMap};
+
+use bevy::{gizmos::config::GizmoConfigStore, prelude::*};
+
+use crate::{
+    input::{Action, Hotkeys},
+    layers::{HiddenLayers, Layer},
+};
+
+/// The view is clean; holds what was shown before.
+#[derive(Resource, Default, Debug)]
+pub struct CleanView {
+    /// visibility of every overlay, when first hidden
+    overlays: HashMap<Entity, Visibility>,
+    /// whether the debug layer was hidden already
+    debug_hidden: bool,
+    /// whether each gizmo group was enabled
+    gizmos: HashMap<TypeId, bool>,
+}
+
+pub struct CleanViewPlugin;
+
+impl Plugin for CleanViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, clean_view.before(crate::layers::show_layers));
+    }
+}
+
+type Overlay<'a> = (Entity, &'a mut Visibility);
+
+/// Toggle [`CleanView`], and keep overlays hidden while it is there.
+pub fn clean_view(
+    mut commands: Commands,
+    hotkeys: Hotkeys,
+    clean: Option<ResMut<CleanView>>,
+    mut overlays: Query<Overlay, (With<Node>, Without<ChildOf>)>,
+    mut hidden: ResMut<HiddenLayers>,
+    gizmos: Option<ResMut<GizmoConfigStore>>,
+) {
+    let toggled = hotkeys.just_pressed(Action::ToggleCleanView);
+    match clean {
+        Some(clean) if toggled => {
+            for (entity, mut visibility) in overlays.iter_mut() {
+                if let Some(shown) = clean.overlays.get(&entity) {
+                    *visibility = *shown;
+                }
+            }
+            if !clean.debug_hidden {
+                hidden.0.remove(&Layer::Debug);
+            }
+            if let Some(mut gizmos) = gizmos {
+                for (group, config, _) in gizmos.iter_mut() {
+                    config.enabled = clean.gizmos.get(group).copied().unwrap_or(config.enabled);
+                }
+            }
+            commands.remove_resource::<CleanView>();
+            println!("clean view: off");
+        }
+        Some(mut clean) => hide_overlays(&mut clean, &mut overlays),
+        None if toggled => {
+            let mut clean = CleanView { debug_hidden: hidden.0.contains(&Layer::Debug), ..default() };
+            hidden.0.insert(Layer::Debug);
+            if let Some(mut gizmos) = gizmos {
+                for (group, config, _) in gizmos.iter_mut() {
+                    clean.gizmos.insert(*group, config.enabled);
+                    config.enabled = false;
+                }
+            }
+            hide_overlays(&mut clean, &mut overlays);
+            commands.insert_resource(clean);
+            println!("clean view: on");
+        }
+        None => {}
+    }
+}
+
+fn hide_overlays(clean: &mut CleanView, overlays: &mut Query<Overlay, (With<Node>, Without<ChildOf>)>) {
+    for (entity, mut visibility) in overlays.iter_mut() {
+        clean.overlays.entry(entity).or_insert(*visibility);
+        visibility.set_if_neq(Visibility::Hidden);
+    }
+}
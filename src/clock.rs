@@ -0,0 +1,184 @@
+//! Simulation clock, decoupled from [`Time`] so the animation can be paused,
+//! single-stepped and sped up or slowed down.
+
+use bevy::prelude::*;
+
+use crate::{
+    input::{Action, Hotkeys},
+    tempo::Tempo,
+};
+
+/// Step size for a single-step while paused.
+const STEP_SEC: f32 = 1.0 / 60.0;
+
+const SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+#[derive(Resource, Debug)]
+pub struct SimClock {
+    elapsed: f64,
+    delta: f32,
+    pub paused: bool,
+    speed: f32,
+    // seconds to advance on the next tick while paused (negative steps back)
+    pending_step: f32,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        SimClock {
+            elapsed: 0.0,
+            delta: 0.0,
+            paused: false,
+            speed: 1.0,
+            pending_step: 0.0,
+        }
+    }
+}
+
+impl SimClock {
+    pub fn elapsed_secs_f64(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Simulated seconds since the last tick; negative when stepping back.
+    pub fn delta_secs(&self) -> f32 {
+        self.delta
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(SPEEDS[0], SPEEDS[SPEEDS.len() - 1]);
+    }
+
+    /// Queue a single step (backwards if `frames` is negative); only used while paused.
+    pub fn step(&mut self, frames: i32) {
+        self.pending_step += frames as f32 * STEP_SEC;
+    }
+
+    /// Jump to `elapsed` seconds on the next tick, for scrubbing; only used
+    /// while paused, like [`step`](Self::step).
+    pub fn seek(&mut self, elapsed: f64) {
+        self.pending_step = (elapsed.max(0.0) - self.elapsed) as f32;
+    }
+
+    fn advance(&mut self, real_delta: f32) {
+        self.delta = if self.paused {
+            std::mem::take(&mut self.pending_step)
+        } else {
+            self.pending_step = 0.0;
+            real_delta * self.speed
+        };
+        self.elapsed = (self.elapsed + self.delta as f64).max(0.0);
+    }
+}
+
+/// How far the circles have travelled, in cells.
+///
+/// Accumulated from the clock at the current [`Tempo`] rather than derived
+/// from `elapsed % span`, so changing the tempo never makes circles jump.
+#[derive(Resource, Default, Debug)]
+pub struct MotionPhase {
+    cells: f64,
+}
+
+impl MotionPhase {
+    pub fn cells(&self) -> f64 {
+        self.cells
+    }
+
+    /// Global 0..1 position within the current cell.
+    pub fn rate(&self) -> f32 {
+        self.cells.rem_euclid(1.0) as f32
+    }
+
+    /// Seconds on `clock` at which the circles are `cells` along, getting
+    /// there from here at `tempo`.
+    pub fn elapsed_at(&self, clock: &SimClock, tempo: &Tempo, cells: f64) -> f64 {
+        clock.elapsed + (cells - self.cells) * tempo.span_sec() as f64
+    }
+}
+
+/// How the simulation advances.
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub enum Timestep {
+    /// once per frame, by the frame's delta
+    #[default]
+    Variable,
+    /// `hz` ticks per (virtual) second on `FixedUpdate`, so the motion comes
+    /// out bit-identical however the frames are paced; for offline rendering
+    /// and golden-image tests
+    Fixed { hz: f64 },
+}
+
+/// Run condition: the simulation ticks on `FixedUpdate`.
+pub fn fixed_timestep(timestep: Res<Timestep>) -> bool {
+    matches!(*timestep, Timestep::Fixed { .. })
+}
+
+/// Ticks [`SimClock`] from [`Time`] and advances [`MotionPhase`] every frame
+/// before `Update`, or every fixed tick in [`Timestep::Fixed`].
+pub struct SimClockPlugin;
+
+impl Plugin for SimClockPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SimClock>()
+            .init_resource::<Tempo>()
+            .init_resource::<MotionPhase>()
+            .init_resource::<Timestep>()
+            .add_systems(First, apply_timestep.run_if(resource_changed::<Timestep>))
+            .add_systems(
+                PreUpdate,
+                (tick_sim_clock, advance_motion_phase).chain().run_if(not(fixed_timestep)),
+            )
+            .add_systems(
+                FixedPreUpdate,
+                (tick_sim_clock, advance_motion_phase).chain().run_if(fixed_timestep),
+            );
+    }
+}
+
+fn apply_timestep(timestep: Res<Timestep>, mut fixed: ResMut<Time<Fixed>>) {
+    if let Timestep::Fixed { hz } = *timestep {
+        fixed.set_timestep_hz(hz);
+    }
+}
+
+// `Time` is `Time<Fixed>` on the fixed schedules
+fn tick_sim_clock(time: Res<Time>, mut clock: ResMut<SimClock>) {
+    clock.advance(time.delta_secs());
+}
+
+fn advance_motion_phase(clock: Res<SimClock>, tempo: Res<Tempo>, mut phase: ResMut<MotionPhase>) {
+    phase.cells += clock.delta_secs() as f64 / tempo.span_sec() as f64;
+}
+
+/// Pause, single-step while paused, and speed (see [`InputMap`](crate::input::InputMap)).
+pub fn control_sim_clock(hotkeys: Hotkeys, mut clock: ResMut<SimClock>) {
+    if hotkeys.just_pressed(Action::Pause) {
+        clock.paused = !clock.paused;
+        println!("paused: {}", clock.paused);
+    }
+    if clock.paused {
+        if hotkeys.just_pressed(Action::StepForward) {
+            clock.step(1);
+        }
+        if hotkeys.just_pressed(Action::StepBack) {
+            clock.step(-1);
+        }
+    }
+
+    let i = SPEEDS.iter().position(|s| *s == clock.speed).unwrap_or(2);
+    let i = if hotkeys.just_pressed(Action::SpeedUp) {
+        (i + 1).min(SPEEDS.len() - 1)
+    } else if hotkeys.just_pressed(Action::SpeedDown) {
+        i.saturating_sub(1)
+    } else {
+        return;
+    };
+    clock.set_speed(SPEEDS[i]);
+    println!("speed: x{}", clock.speed);
+}
@@ -0,0 +1,67 @@
+//! On-screen clock: the global loop phase and each ring's phase as
+//! concentric arcs, so performers can see loop boundaries coming.
+
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    calibration::OutputName,
+    capability::{self, Capability},
+    clock::MotionPhase,
+    ring::Rings,
+};
+
+const RADIUS: f32 = 48.0;
+const SPACING: f32 = 6.0;
+const MARGIN: f32 = 16.0;
+
+pub struct ClockWidgetPlugin;
+
+impl Plugin for ClockWidgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, draw_clock_widget.run_if(capability::enabled(Capability::ClockWidget)));
+    }
+}
+
+fn draw_clock_widget(
+    mut gizmos: Gizmos,
+    phase: Res<MotionPhase>,
+    rings: Res<Rings>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&GlobalTransform, &Projection, &OutputName), With<Camera2d>>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+    let Some((camera, projection, _)) = cameras.iter().find(|(.., name)| name.0 == "main") else {
+        return;
+    };
+    // world units per pixel: the widget keeps its size on screen while zooming
+    let scale = match projection {
+        Projection::Orthographic(ortho) => ortho.scale,
+        _ => 1.0,
+    };
+
+    // top right corner of the main view
+    let corner = (window.size() / 2.0 - Vec2::splat(MARGIN + RADIUS)) * scale;
+    let center = camera.translation().truncate() + corner;
+
+    let mut arc = |radius: f32, phase: f32, color: Color| {
+        let radius = radius * scale;
+        gizmos.circle_2d(center, radius, color.with_alpha(0.15));
+        // clockwise from 12 o'clock
+        let angle = phase * TAU;
+        gizmos.arc_2d(Isometry2d::new(center, Rot2::radians(-angle)), angle, radius, color);
+    };
+
+    // the global loop outermost, then every ring from the outside in
+    arc(RADIUS, rings.global_phase(phase.cells()), Color::WHITE);
+    for (i, ring) in rings.0.iter().enumerate() {
+        let radius = RADIUS - SPACING * (i + 1) as f32;
+        if radius <= 0.0 {
+            break;
+        }
+        arc(radius, ring.phase(phase.cells()), Color::hsl(i as f32 * 50.0, 0.8, 0.6));
+    }
+}
@@ -0,0 +1,140 @@
+//! Coloring circles by the direction they travel in, so the structure of the
+//! table reads at a glance.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{
+    agent::Agent,
+    materials::{paint, SharedMaterials},
+    palette::Palette,
+    ring::{Ring, Rings},
+    Cell, CellsParam, Circles, MoveType,
+};
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum ColorMode {
+    /// cells colored by the [`Palette`], agents in their own colors
+    #[default]
+    Palette,
+    /// hue from the angle of travel through the cell: 0 is right, 90 up
+    Direction { saturation: f32, lightness: f32 },
+}
+
+impl ColorMode {
+    /// Hue in degrees of a circle on a `move_type` cell, taken from the line
+    /// between where it enters and leaves; `None` for cells that don't travel.
+    pub fn hue(move_type: MoveType) -> Option<f32> {
+        let (from, to) = move_type.endpoints()?;
+        Some((to - from).to_angle().to_degrees().rem_euclid(360.0))
+    }
+
+    /// Color of a circle on a `move_type` cell, if this mode sets one.
+    pub fn color(&self, move_type: MoveType) -> Option<Color> {
+        match *self {
+            ColorMode::Palette => None,
+            ColorMode::Direction { saturation, lightness } => {
+                Self::hue(move_type).map(|hue| Color::hsl(hue, saturation, lightness))
+            }
+        }
+    }
+}
+
+type Circle<'a> = (Entity, &'a mut MeshMaterial2d<ColorMaterial>, Option<&'a Cell>, Option<&'a Agent>);
+
+/// The mode colors were last set for, the hue every circle got in
+/// [`ColorMode::Direction`] and the cells painted in [`ColorMode::Palette`].
+#[derive(Default)]
+pub struct AppliedColors {
+    mode: Option<ColorMode>,
+    hues: HashMap<Entity, f32>,
+    painted: HashSet<Entity>,
+    /// the rings the palette was handed out over
+    rings: Vec<Ring>,
+}
+
+/// Set every color when the mode (or the palette) changes, and the colors of
+/// circles new since last frame. Afterwards, when a circle's direction
+/// changes (agents turning, flips), only its hue is rotated, so modulation
+/// on top (LFOs, audio) carries on.
+pub fn apply_color_mode(
+    cells_param: Res<CellsParam>,
+    palette: Option<Res<Palette>>,
+    rings: Option<Res<Rings>>,
+    mut applied: Local<AppliedColors>,
+    mut circles: Query<Circle, Circles>,
+    mut shared: ResMut<SharedMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mode = cells_param.color_mode;
+    let switched = applied.mode != Some(mode);
+    applied.mode = Some(mode);
+    let no_rings = Rings::default();
+    let rings = rings.as_deref().unwrap_or(&no_rings);
+    // rebuilt with every new table; only a new layout of rings counts
+    let relaid = applied.rings != rings.0;
+    if relaid {
+        applied.rings = rings.0.clone();
+    }
+    if switched || relaid || palette.as_ref().is_some_and(|p| p.is_changed()) {
+        applied.hues.clear();
+        applied.painted.clear();
+    }
+    let default_palette;
+    let palette = match &palette {
+        Some(palette) => &**palette,
+        None => {
+            default_palette = Palette::default();
+            &default_palette
+        }
+    };
+
+    for (entity, mut material, cell, agent) in circles.iter_mut() {
+        let move_type = match (cell, agent) {
+            (Some(cell), _) => cell.move_type,
+            (_, Some(agent)) => cells_param.move_type_at(agent.grid),
+            _ => continue,
+        };
+        let Some(current) = materials.get(&material.0).map(|m| m.color) else {
+            continue;
+        };
+        let color = match mode {
+            ColorMode::Palette => {
+                if !applied.painted.insert(entity) {
+                    continue;
+                }
+                match (cell, agent) {
+                    (Some(cell), _) => palette.color(cell.grid, rings),
+                    // agents keep their own colors, back from another mode
+                    (_, Some(agent)) if switched => agent.color,
+                    _ => continue,
+                }
+            }
+            ColorMode::Direction { .. } => {
+                let hue = ColorMode::hue(move_type);
+                let color = match (mode.color(move_type), applied.hues.get(&entity)) {
+                    // turned since last frame
+                    (Some(_), Some(last)) => {
+                        let hue = hue.unwrap_or(*last);
+                        if hue == *last {
+                            continue;
+                        }
+                        current.rotate_hue(hue - last)
+                    }
+                    // new, or the mode changed
+                    (Some(color), None) => color,
+                    (None, _) if switched => Color::WHITE,
+                    (None, _) => continue,
+                };
+                if let Some(hue) = hue {
+                    applied.hues.insert(entity, hue);
+                }
+                color
+            }
+        };
+        paint(&mut material, color, &mut shared, &mut materials);
+    }
+    applied.hues.retain(|entity, _| circles.contains(*entity));
+    applied.painted.retain(|entity| circles.contains(*entity));
+}
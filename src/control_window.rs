@@ -0,0 +1,88 @@
+//! A second window for the operator, so the primary one can be projected
+//! with nothing on it but the animation.
+//!
+//! The control window's camera draws no part of the scene; it is the default
+//! UI camera, so the help, editor messages and the egui panel (with the
+//! `egui` feature) show there instead of over the output. Closing it puts
+//! them back on the output.
+//!
+//! Opt-in with [`ControlWindow`].
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{ClearColorConfig, RenderTarget},
+        view::RenderLayers,
+    },
+    window::WindowRef,
+};
+
+/// Nothing is drawn on this layer; it keeps the scene off the control window.
+const CONTROL_LAYER: usize = 4;
+
+/// Opt-in: the operator's window.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct ControlWindow {
+    pub title: String,
+}
+
+impl Default for ControlWindow {
+    fn default() -> Self {
+        ControlWindow { title: "moving cells (controls)".into() }
+    }
+}
+
+/// The control window and its camera.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct ControlOutput;
+
+pub struct ControlWindowPlugin;
+
+impl Plugin for ControlWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, sync_control_window);
+    }
+}
+
+/// Open the window when [`ControlWindow`] is inserted, close it when it is
+/// removed, and remove it when the window is closed.
+pub fn sync_control_window(
+    mut commands: Commands,
+    control: Option<Res<ControlWindow>>,
+    windows: Query<Entity, (With<ControlOutput>, With<Window>)>,
+    cameras: Query<Entity, (With<ControlOutput>, With<Camera>)>,
+) {
+    let Some(control) = control else {
+        for entity in windows.iter().chain(cameras.iter()) {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    match (windows.is_empty(), cameras.is_empty()) {
+        (true, true) => spawn_control_window(&mut commands, &control),
+        // closed by the user
+        (true, false) => {
+            for entity in cameras.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.remove_resource::<ControlWindow>();
+        }
+        _ => {}
+    }
+}
+
+fn spawn_control_window(commands: &mut Commands, control: &ControlWindow) {
+    let window = commands.spawn((Window { title: control.title.clone(), ..default() }, ControlOutput)).id();
+
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(window)),
+            clear_color: ClearColorConfig::Custom(Color::srgb(0.1, 0.1, 0.1)),
+            ..default()
+        },
+        RenderLayers::layer(CONTROL_LAYER),
+        IsDefaultUiCamera,
+        ControlOutput,
+    ));
+}
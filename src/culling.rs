@@ -0,0 +1,84 @@
+//! Culling for tables larger than the window: circles of cells outside the
+//! `main` camera's view, give or take a margin, are marked [`Offscreen`] and
+//! left where they are by [`move_cells`](crate::move_cells) until the view
+//! comes back to them. Drawing them is already skipped by Bevy's frustum
+//! culling.
+//!
+//! Opt-in with [`Culling`].
+
+use bevy::prelude::*;
+
+use crate::{calibration::OutputName, Cell};
+
+/// Opt-in: don't animate cells out of view.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Culling {
+    /// kept animating round the view, in world units, so circles are in
+    /// place before they scroll in
+    pub margin: f32,
+}
+
+impl Default for Culling {
+    fn default() -> Self {
+        Culling { margin: 64.0 }
+    }
+}
+
+/// A circle whose cell is out of view.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Offscreen;
+
+pub struct CullingPlugin;
+
+impl Plugin for CullingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, cull_offscreen.before(crate::move_cells));
+    }
+}
+
+/// The world rect a camera at `transform` sees through `projection`, grown
+/// by `margin`; `None` for perspective cameras.
+pub fn view_rect(transform: &Transform, projection: &Projection, margin: f32) -> Option<Rect> {
+    let Projection::Orthographic(ortho) = projection else {
+        return None;
+    };
+    let center = transform.translation.truncate();
+    let area = ortho.area;
+    Some(Rect::from_corners(center + area.min, center + area.max).inflate(margin))
+}
+
+/// Whether a cell at `pos`, `size` across, overlaps `view`.
+pub fn in_view(view: Rect, pos: Vec2, size: Vec2) -> bool {
+    !view.intersect(Rect::from_center_size(pos, size)).is_empty()
+}
+
+type CellCircle<'a> = (Entity, &'a Cell, Has<Offscreen>);
+
+/// Mark the circles that went out of view, unmark those that came back (and
+/// every one without [`Culling`]).
+pub fn cull_offscreen(
+    mut commands: Commands,
+    culling: Option<Res<Culling>>,
+    cameras: Query<(&OutputName, &Transform, &Projection), With<Camera2d>>,
+    circles: Query<CellCircle>,
+    marked: Query<Entity, With<Offscreen>>,
+) {
+    let Some(culling) = culling else {
+        for entity in marked.iter() {
+            commands.entity(entity).remove::<Offscreen>();
+        }
+        return;
+    };
+    let view = cameras
+        .iter()
+        .find(|(name, ..)| name.0 == "main")
+        .and_then(|(_, transform, projection)| view_rect(transform, projection, culling.margin));
+    for (entity, cell, offscreen) in circles.iter() {
+        let visible = view.is_none_or(|view| in_view(view, cell.pos, cell.size));
+        if visible && offscreen {
+            commands.entity(entity).remove::<Offscreen>();
+        } else if !visible && !offscreen {
+            commands.entity(entity).insert(Offscreen);
+        }
+    }
+}
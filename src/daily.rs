@@ -0,0 +1,149 @@
+//! The daily layout: a table generated from the (UTC) date, so every
+//! instance shows the same pattern on the same day.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::CellTable;
+
+/// A calendar date (proleptic Gregorian).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today_utc() -> Date {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Date::from_days((secs / 86_400) as i64)
+    }
+
+    /// `YYYY-MM-DD`
+    pub fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        let date = Date { year, month, day };
+        // reject 2025-02-30 and friends
+        (Date::from_days(date.days()) == date).then_some(date)
+    }
+
+    /// Days since 1970-01-01.
+    pub fn days(self) -> i64 {
+        // Howard Hinnant's days_from_civil
+        let y = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// splitmix64; spelled out here because the output of `rand`'s generators
+/// may change between versions, and the layout of a date must not.
+struct DayRng(u64);
+
+impl DayRng {
+    fn new(date: Date) -> Self {
+        DayRng(date.days() as u64 ^ 0x6d6f_7669_6e67_5f63)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `lo..=hi`.
+    fn range(&mut self, lo: usize, hi: usize) -> usize {
+        lo + (self.next() % (hi - lo + 1) as u64) as usize
+    }
+
+    fn coin(&mut self) -> bool {
+        self.next() & 1 == 1
+    }
+}
+
+/// Draw a loop with corners (x0, y0) and (x1, y1), in thin keisen if
+/// clockwise and thick keisen otherwise.
+fn draw_loop(grid: &mut [Vec<char>], (x0, y0): (usize, usize), (x1, y1): (usize, usize), clockwise: bool) {
+    // (top-left, top-right, bottom-right, bottom-left, top, right, bottom, left)
+    let glyphs = if clockwise {
+        ['┌', '┐', '┘', '└', '→', '↓', '←', '↑']
+    } else {
+        ['┏', '┓', '┛', '┗', '←', '↑', '→', '↓']
+    };
+    let mut set = |x: usize, y: usize, c: char| grid[y][x] = c;
+    for x in x0 + 1..x1 {
+        set(x, y0, glyphs[4]);
+        set(x, y1, glyphs[6]);
+    }
+    for y in y0 + 1..y1 {
+        set(x1, y, glyphs[5]);
+        set(x0, y, glyphs[7]);
+    }
+    set(x0, y0, glyphs[0]);
+    set(x1, y0, glyphs[1]);
+    set(x1, y1, glyphs[2]);
+    set(x0, y1, glyphs[3]);
+}
+
+/// The layout of `date`: either concentric rings or a tiling of small
+/// loops, each turning whichever way the day's dice say.
+pub fn daily_layout(date: Date) -> CellTable {
+    let mut rng = DayRng::new(date);
+
+    let grid = if rng.coin() {
+        let rings = rng.range(2, 5);
+        let size = rings * 2 + 1;
+        let mut grid = vec![vec![' '; size]; size];
+        grid[rings][rings] = '0';
+        for ring in 0..rings {
+            let (lo, hi) = (ring, size - 1 - ring);
+            draw_loop(&mut grid, (lo, lo), (hi, hi), rng.coin());
+        }
+        grid
+    } else {
+        let (columns, rows) = (rng.range(2, 4), rng.range(2, 4));
+        // each tile is a loop of 2x2 or 3x3, padded to 3x3
+        let mut grid = vec![vec![' '; columns * 3]; rows * 3];
+        for ty in 0..rows {
+            for tx in 0..columns {
+                let size = rng.range(2, 3);
+                let (x0, y0) = (tx * 3, ty * 3);
+                draw_loop(&mut grid, (x0, y0), (x0 + size - 1, y0 + size - 1), rng.coin());
+                if size == 3 {
+                    grid[y0 + 1][x0 + 1] = '0';
+                }
+            }
+        }
+        grid
+    };
+
+    CellTable::from_rows(grid).expect("generated layouts only use known glyphs")
+}
@@ -0,0 +1,154 @@
+//! A heads-up display of how fast big tables are drawn: frame rate and time
+//! from Bevy's [`FrameTimeDiagnosticsPlugin`], the entity count, and counters
+//! of our own, registered as diagnostics too: circles animated this frame,
+//! color materials, and an estimate of the draw calls.
+//!
+//! Opt-in with [`DiagnosticsHud`], toggled with F9.
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+    },
+    prelude::*,
+};
+
+use crate::{
+    culling::Offscreen,
+    input::{Action, Hotkeys},
+    instanced::CircleInstances,
+    split::Compared,
+    Cell,
+};
+
+/// Circles [`move_cells`](crate::move_cells) moves this frame, those culled
+/// or compared left out.
+pub const CELLS_ANIMATED: DiagnosticPath = DiagnosticPath::const_new("cells/animated");
+/// All the circles, animated or not.
+pub const CELLS: DiagnosticPath = DiagnosticPath::const_new("cells/total");
+/// [`ColorMaterial`] assets.
+pub const MATERIALS: DiagnosticPath = DiagnosticPath::const_new("render/materials");
+/// One per visible mesh and one per instanced batch; Bevy batches meshes
+/// sharing a material, so there are at most this many.
+pub const DRAW_CALLS: DiagnosticPath = DiagnosticPath::const_new("render/draw_calls");
+
+/// Opt-in: the HUD is shown.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct DiagnosticsHud;
+
+#[derive(Component)]
+struct HudText;
+
+pub struct DiagnosticsHudPlugin;
+
+impl Plugin for DiagnosticsHudPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin);
+        }
+        app.register_diagnostic(Diagnostic::new(CELLS_ANIMATED))
+            .register_diagnostic(Diagnostic::new(CELLS))
+            .register_diagnostic(Diagnostic::new(MATERIALS))
+            .register_diagnostic(Diagnostic::new(DRAW_CALLS))
+            .add_systems(Startup, spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    toggle_diagnostics_hud,
+                    (measure_counters, update_hud_text).run_if(resource_exists::<DiagnosticsHud>),
+                    show_hud,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            right: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.7)),
+        Visibility::Hidden,
+        HudText,
+    ));
+}
+
+fn toggle_diagnostics_hud(mut commands: Commands, hotkeys: Hotkeys, hud: Option<Res<DiagnosticsHud>>) {
+    if !hotkeys.just_pressed(Action::ToggleDiagnostics) {
+        return;
+    }
+    match hud {
+        Some(_) => commands.remove_resource::<DiagnosticsHud>(),
+        None => commands.insert_resource(DiagnosticsHud),
+    }
+}
+
+type Draws<'a> = (&'a ViewVisibility, Has<Mesh2d>, Has<Mesh3d>);
+
+pub fn measure_counters(
+    mut diagnostics: Diagnostics,
+    cells: Query<(Has<Offscreen>, Has<Compared>), With<Cell>>,
+    materials: Option<Res<Assets<ColorMaterial>>>,
+    meshes: Query<Draws>,
+    batches: Query<&CircleInstances>,
+) {
+    diagnostics.add_measurement(&CELLS, || cells.iter().count() as f64);
+    diagnostics.add_measurement(&CELLS_ANIMATED, || {
+        cells.iter().filter(|(offscreen, compared)| !offscreen && !compared).count() as f64
+    });
+    diagnostics.add_measurement(&MATERIALS, || materials.map_or(0, |materials| materials.len()) as f64);
+    diagnostics.add_measurement(&DRAW_CALLS, || {
+        let meshes = meshes.iter().filter(|(visibility, d2, d3)| visibility.get() && (*d2 || *d3)).count();
+        let batches = batches.iter().filter(|batch| !batch.instances.is_empty()).count();
+        (meshes + batches) as f64
+    });
+}
+
+/// The lines of the HUD, from what is in `store` so far.
+pub fn hud_lines(store: &DiagnosticsStore) -> String {
+    let fixed = |path: &DiagnosticPath, decimals: usize| {
+        store
+            .get(path)
+            .and_then(|d| d.smoothed().or_else(|| d.value()))
+            .map_or("-".to_string(), |v| format!("{:.*}", decimals, v))
+    };
+    let animated = format!("{} / {}", fixed(&CELLS_ANIMATED, 0), fixed(&CELLS, 0));
+    [
+        (fixed(&FrameTimeDiagnosticsPlugin::FPS, 1), "fps"),
+        (fixed(&FrameTimeDiagnosticsPlugin::FRAME_TIME, 2), "ms a frame"),
+        (fixed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT, 0), "entities"),
+        (animated, "circles animated"),
+        (fixed(&MATERIALS, 0), "materials"),
+        (fixed(&DRAW_CALLS, 0), "draw calls at most"),
+    ]
+    .iter()
+    .map(|(value, what)| format!("{:>12}  {}", value, what))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn update_hud_text(store: Res<DiagnosticsStore>, mut hud: Query<&mut Text, With<HudText>>) {
+    for mut text in hud.iter_mut() {
+        text.0 = hud_lines(&store);
+    }
+}
+
+fn show_hud(shown: Option<Res<DiagnosticsHud>>, mut hud: Query<&mut Visibility, With<HudText>>) {
+    let visibility = if shown.is_some() { Visibility::Inherited } else { Visibility::Hidden };
+    for mut current in hud.iter_mut() {
+        current.set_if_neq(visibility);
+    }
+}
@@ -0,0 +1,417 @@
+//! Layout dialects, and switching between them at runtime.
+//!
+//! The same layout can be written as keisen (the native box-drawing glyphs),
+//! as plain ASCII or as a binary occupancy grid. Switching the dialect
+//! re-parses the current source with the new one and shows both side by
+//! side before anything is applied; a source the new dialect can't read is
+//! migrated (the current table, written in the new dialect) instead.
+//! Images go through [`flow_field`](crate::flow_field) and aren't text, so
+//! they are no dialect here.
+
+use bevy::prelude::*;
+
+use crate::{
+    char_from_move_type,
+    input::{key_name, Action, Hotkeys, InputMap},
+    recovery::EditorSession,
+    ring::find_rings,
+    AssetLoadingState, CellTable, CellsParam, LayoutError, MoveType, RespawnCells,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Dialect {
+    /// box-drawing corners and arrows, see [`CellTable::parse`]
+    #[default]
+    Keisen,
+    /// `<` `>` `^` `v` for the way out of a cell, `0` for centers, `.` for
+    /// blanks; turns are wherever the way in is not straight behind
+    Ascii,
+    /// `1` for track, `0` for blank; every loop runs clockwise
+    Binary,
+}
+
+impl Dialect {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dialect::Keisen => "keisen",
+            Dialect::Ascii => "ascii",
+            Dialect::Binary => "binary",
+        }
+    }
+
+    pub fn next(self) -> Dialect {
+        match self {
+            Dialect::Keisen => Dialect::Ascii,
+            Dialect::Ascii => Dialect::Binary,
+            Dialect::Binary => Dialect::Keisen,
+        }
+    }
+
+    pub fn parse(self, text: &str) -> Result<CellTable, LayoutError> {
+        match self {
+            Dialect::Keisen => CellTable::parse(text),
+            Dialect::Ascii => CellTable::from_rows(parse_ascii(&grid(text))?),
+            Dialect::Binary => CellTable::from_rows(parse_binary(&grid(text))?),
+        }
+    }
+
+    /// `table` as this dialect writes it; what it can't express is lost.
+    pub fn write(self, table: &CellTable) -> String {
+        if self == Dialect::Keisen {
+            return table.to_text();
+        }
+        (0..table.height)
+            .map(|y| {
+                (0..table.width)
+                    .map(|x| {
+                        let move_type = crate::try_move_type_from_char(table.get(x, y)).unwrap_or(MoveType::Blank);
+                        match self {
+                            Dialect::Ascii => ascii_char(move_type),
+                            _ if move_type.is_track() => '1',
+                            _ => '0',
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Trimmed rows of characters.
+fn grid(text: &str) -> Vec<Vec<char>> {
+    text.lines().map(|line| line.trim().chars().collect()).collect()
+}
+
+/// Table directions (y down).
+const SIDES: [IVec2; 4] = [IVec2::X, IVec2::NEG_Y, IVec2::NEG_X, IVec2::Y];
+
+fn ascii_exit(c: char) -> Option<IVec2> {
+    match c {
+        '>' => Some(IVec2::X),
+        '<' => Some(IVec2::NEG_X),
+        '^' => Some(IVec2::NEG_Y),
+        'v' => Some(IVec2::Y),
+        _ => None,
+    }
+}
+
+fn ascii_char(move_type: MoveType) -> char {
+    match move_type.exit() {
+        Some(IVec2 { x: 1, .. }) => '>',
+        Some(IVec2 { x: -1, .. }) => '<',
+        Some(IVec2 { y: -1, .. }) => '^',
+        Some(_) => 'v',
+        None if move_type == MoveType::Center => '0',
+        None => '.',
+    }
+}
+
+fn at(rows: &[Vec<char>], cell: IVec2) -> char {
+    if cell.x < 0 || cell.y < 0 {
+        return ' ';
+    }
+    rows.get(cell.y as usize).and_then(|row| row.get(cell.x as usize)).copied().unwrap_or(' ')
+}
+
+/// The track glyph between neighbours on the `entry` and `exit` sides (table directions).
+fn track_glyph(entry: IVec2, exit: IVec2) -> char {
+    // endpoints are y up
+    let up = |d: IVec2| Vec2::new(d.x as f32, -d.y as f32);
+    let move_type = MoveType::from_endpoints(up(entry), up(exit)).expect("entry and exit are on different sides");
+    char_from_move_type(move_type)
+}
+
+fn parse_ascii(rows: &[Vec<char>]) -> Result<Vec<Vec<char>>, LayoutError> {
+    let mut glyphs = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        let mut glyph_row = Vec::new();
+        for (x, c) in row.iter().enumerate() {
+            let cell = IVec2::new(x as i32, y as i32);
+            let glyph = match (*c, ascii_exit(*c)) {
+                (_, Some(exit)) => {
+                    // fed from behind if possible, else from whichever side is
+                    let feeds = |side: IVec2| ascii_exit(at(rows, cell + side)) == Some(-side);
+                    let entry = std::iter::once(-exit)
+                        .chain(SIDES)
+                        .filter(|side| *side != exit)
+                        .find(|side| feeds(*side))
+                        .unwrap_or(-exit);
+                    track_glyph(entry, exit)
+                }
+                ('0', _) => '0',
+                ('.' | ' ', _) => ' ',
+                (c, _) => return Err(LayoutError::InvalidChar { c, x, y }),
+            };
+            glyph_row.push(glyph);
+        }
+        glyphs.push(glyph_row);
+    }
+    Ok(glyphs)
+}
+
+fn parse_binary(rows: &[Vec<char>]) -> Result<Vec<Vec<char>>, LayoutError> {
+    for (y, row) in rows.iter().enumerate() {
+        if let Some((x, c)) = row.iter().enumerate().find(|(_, c)| !matches!(c, '0' | '1' | '.' | ' ')) {
+            return Err(LayoutError::InvalidChar { c: *c, x, y });
+        }
+    }
+    let track = |cell: IVec2| at(rows, cell) == '1';
+    let mut glyphs = rows.iter().map(|row| vec![' '; row.len()]).collect::<Vec<_>>();
+
+    // row by row, so every loop is first met at its top-left cell, whose
+    // neighbours are to the right and below: start going right, clockwise
+    for (y, row) in rows.iter().enumerate() {
+        for x in 0..row.len() {
+            let start = IVec2::new(x as i32, y as i32);
+            if !track(start) || glyphs[y][x] != ' ' {
+                continue;
+            }
+            let (mut prev, mut cell) = (start, start);
+            loop {
+                let joined = SIDES.iter().copied().filter(|side| track(cell + *side)).collect::<Vec<_>>();
+                if joined.len() != 2 {
+                    return Err(LayoutError::OpenTrack { x: cell.x as usize, y: cell.y as usize });
+                }
+                let (entry, exit) = if cell == start {
+                    if !joined.contains(&IVec2::X) {
+                        return Err(LayoutError::OpenTrack { x, y });
+                    }
+                    (joined.into_iter().find(|side| *side != IVec2::X).expect("two neighbours"), IVec2::X)
+                } else {
+                    (prev - cell, joined.into_iter().find(|side| cell + *side != prev).expect("two neighbours"))
+                };
+                glyphs[cell.y as usize][cell.x as usize] = track_glyph(entry, exit);
+                (prev, cell) = (cell, cell + exit);
+                if cell == start {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(glyphs)
+}
+
+/// The text the current layout was written in.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct LayoutSource {
+    pub text: String,
+    pub dialect: Dialect,
+}
+
+/// What switching the dialect of a layout would do.
+pub struct DialectReport {
+    pub from: Dialect,
+    pub to: Dialect,
+    pub source: String,
+    /// the source, read in the new dialect
+    pub reparsed: Result<CellTable, LayoutError>,
+    /// the current table, written in the new dialect
+    pub migrated: String,
+    /// the migrated text, read back; the new dialect can't express every
+    /// table (binary loops can't touch)
+    pub remigrated: Result<CellTable, LayoutError>,
+}
+
+/// Cells, track cells and rings of a table.
+fn summary(table: &CellTable) -> String {
+    let cells_param = CellsParam::new(table.clone());
+    let (mut cells, mut tracks) = (0, 0);
+    for y in 0..table.height {
+        for x in 0..table.width {
+            let move_type = crate::try_move_type_from_char(table.get(x, y)).unwrap_or(MoveType::Blank);
+            cells += (move_type != MoveType::Blank) as usize;
+            tracks += move_type.is_track() as usize;
+        }
+    }
+    format!("{} cells, {} track, {} rings", cells, tracks, find_rings(&cells_param).len())
+}
+
+impl DialectReport {
+    pub fn new(source: &LayoutSource, table: &CellTable, to: Dialect) -> Self {
+        let migrated = to.write(table);
+        DialectReport {
+            from: source.dialect,
+            to,
+            source: source.text.clone(),
+            reparsed: to.parse(&source.text),
+            remigrated: to.parse(&migrated),
+            migrated,
+        }
+    }
+
+    /// The current source and what the new dialect makes of it, side by side.
+    pub fn to_text(&self, table: &CellTable) -> String {
+        let mut left = vec![format!("as {} (current)", self.from.name()), summary(table)];
+        left.extend(self.source.lines().map(str::to_string));
+        let mut right = vec![format!("as {}", self.to.name())];
+        match &self.reparsed {
+            Ok(reparsed) => {
+                right.push(summary(reparsed));
+                right.extend(self.to.write(reparsed).lines().map(str::to_string));
+            }
+            Err(e) => match &self.remigrated {
+                Ok(_) => {
+                    right.push(format!("{}; migrated instead:", e));
+                    right.extend(self.migrated.lines().map(str::to_string));
+                }
+                Err(why) => right.push(format!("{}; can't migrate: {}", e, why)),
+            },
+        }
+        let width = left.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        (0..left.len().max(right.len()))
+            .map(|i| {
+                let l = left.get(i).map_or("", String::as_str);
+                let r = right.get(i).map_or("", String::as_str);
+                format!("{}{}   | {}", l, " ".repeat(width - l.chars().count()), r).trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The source and table after switching: the re-parse if it worked,
+    /// otherwise the migrated text, if the new dialect can read it back.
+    pub fn apply(self) -> Result<(LayoutSource, CellTable), LayoutError> {
+        match (self.reparsed, self.remigrated) {
+            (Ok(table), _) => Ok((LayoutSource { text: self.source, dialect: self.to }, table)),
+            (Err(_), Ok(table)) => Ok((LayoutSource { text: self.migrated, dialect: self.to }, table)),
+            (Err(_), Err(e)) => Err(e),
+        }
+    }
+}
+
+/// A dialect switch waiting to be applied or cancelled.
+#[derive(Resource)]
+struct PendingDialect(Option<DialectReport>);
+
+#[derive(Component)]
+struct DialectPrompt;
+
+pub struct DialectPlugin;
+
+impl Plugin for DialectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                sync_layout_source.run_if(resource_changed::<CellsParam>),
+                offer_dialect,
+                answer_dialect.run_if(resource_exists::<PendingDialect>).run_if(in_state(AssetLoadingState::Loaded)),
+                close_dialect_prompt.run_if(resource_removed::<PendingDialect>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Start over from keisen whenever the table is replaced by something else
+/// (a layout file, an editor, the recovery).
+fn sync_layout_source(
+    mut commands: Commands,
+    source: Option<Res<LayoutSource>>,
+    cells_param: Res<CellsParam>,
+    mut checked: Local<Option<Vec<Vec<char>>>>,
+) {
+    // CellsParam changes every frame under LFOs; the table rarely does
+    if checked.as_ref() == Some(&cells_param.cell_table.table) {
+        return;
+    }
+    *checked = Some(cells_param.cell_table.table.clone());
+    let text = cells_param.cell_table.to_text();
+    let current = source.as_ref().is_some_and(|source| {
+        source.dialect.parse(&source.text).is_ok_and(|table| table.to_text() == text)
+    });
+    if !current {
+        commands.insert_resource(LayoutSource { text, dialect: Dialect::Keisen });
+    }
+}
+
+fn offer_dialect(
+    mut commands: Commands,
+    hotkeys: Hotkeys,
+    input_map: Res<InputMap>,
+    source: Option<Res<LayoutSource>>,
+    pending: Option<Res<PendingDialect>>,
+    cells_param: Res<CellsParam>,
+    prompt: Query<Entity, With<DialectPrompt>>,
+) {
+    if !hotkeys.just_pressed(Action::CycleDialect) {
+        return;
+    }
+    let Some(source) = source else {
+        return;
+    };
+    // pressing again moves on to the dialect after the offered one
+    let offered = pending.as_ref().and_then(|pending| pending.0.as_ref()).map(|report| report.to);
+    let mut to = offered.unwrap_or(source.dialect).next();
+    if to == source.dialect {
+        to = to.next();
+    }
+    let report = DialectReport::new(&source, &cells_param.cell_table, to);
+    let text = report.to_text(&cells_param.cell_table);
+    println!("{}", text);
+
+    for entity in prompt.iter() {
+        commands.entity(entity).despawn();
+    }
+    let key = |action| input_map.key(action).map(key_name).unwrap_or_else(|| "-".into());
+    commands.spawn((
+        Text::new(format!(
+            "{}\n\n{}: apply   {}: cancel   {}: next dialect",
+            text,
+            key(Action::ApplyDialect),
+            key(Action::CancelDialect),
+            key(Action::CycleDialect),
+        )),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.7)),
+        DialectPrompt,
+    ));
+    commands.insert_resource(PendingDialect(Some(report)));
+}
+
+fn answer_dialect(
+    mut commands: Commands,
+    hotkeys: Hotkeys,
+    mut pending: ResMut<PendingDialect>,
+    mut session: ResMut<EditorSession>,
+    mut cells_param: ResMut<CellsParam>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    if hotkeys.just_pressed(Action::ApplyDialect) && !hotkeys.alt_enter() {
+        let Some(report) = pending.0.take() else {
+            return;
+        };
+        let to = report.to;
+        match report.apply() {
+            Ok((source, table)) => {
+                println!("layout now written in {}:\n{}", source.dialect.name(), source.text);
+                cells_param.cell_table = table;
+                commands.insert_resource(source);
+                session.mark_dirty();
+                respawn.write(RespawnCells);
+            }
+            Err(e) => println!("can't write the layout in {}: {}", to.name(), e),
+        }
+    } else if !hotkeys.just_pressed(Action::CancelDialect) {
+        return;
+    }
+
+    commands.remove_resource::<PendingDialect>();
+}
+
+fn close_dialect_prompt(mut commands: Commands, prompt: Query<Entity, With<DialectPrompt>>) {
+    for entity in prompt.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,120 @@
+//! Runtime reversal of the flow (clockwise <-> counter clockwise), of the
+//! whole layout or of single rings.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    agent::Agent,
+    char_from_move_type,
+    clock::MotionPhase,
+    input::{Action, Hotkeys},
+    ring::Rings,
+    split::{Compared, SplitScreen},
+    Cell, CellsParam,
+};
+
+/// Tracks whether the flow has been reversed, plus the phase shift that keeps
+/// circles where they are at the moment of the flip.
+#[derive(Resource, Default, Debug)]
+pub struct FlowDirection {
+    pub reversed: bool,
+    shift: f32,
+}
+
+impl FlowDirection {
+    /// Rate of a cell with phase offset `phase` when the global rate is `rate`.
+    ///
+    /// Reversal mirrors the rate (`r -> 1 - r`), which also mirrors the
+    /// phase, so the ripple runs the other way along with the flow.
+    pub fn cell_rate(&self, rate: f32, phase: f32) -> f32 {
+        let phase = if self.reversed { -phase } else { phase };
+        (rate + phase + self.shift).rem_euclid(1.0)
+    }
+
+    /// Added to every cell's rate, so a flip doesn't move anything.
+    pub fn shift(&self) -> f32 {
+        self.shift
+    }
+
+    /// Flip the flow at global rate `rate` without moving any circle.
+    fn flip(&mut self, rate: f32) {
+        // r' = 1 - r for every cell: rate + shift' - phase = -(rate + shift + phase)
+        self.shift = (-2.0 * rate - self.shift).rem_euclid(1.0);
+        self.reversed = !self.reversed;
+    }
+}
+
+/// Reverse one ring (an index into [`Rings`]) without moving its circles,
+/// e.g. from a UI button or a cue.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct FlipRing(pub usize);
+
+pub fn toggle_direction(
+    hotkeys: Hotkeys,
+    phase: Res<MotionPhase>,
+    mut flow: ResMut<FlowDirection>,
+    mut cells_param: ResMut<CellsParam>,
+    split: Option<ResMut<SplitScreen>>,
+    mut cells: Query<&mut Cell>,
+    mut agents: Query<&mut Agent>,
+) {
+    if !hotkeys.just_pressed(Action::Reverse) {
+        return;
+    }
+
+    flow.flip(phase.rate());
+    cells_param.cell_table.reverse();
+    if let Some(mut split) = split {
+        // its circles are reversed below, like the others; no need to respawn them
+        split.bypass_change_detection().cells_param.cell_table.reverse();
+    }
+    for mut cell in cells.iter_mut() {
+        cell.reverse();
+        // r' = 1 - r on top of the flipped flow
+        cell.shift = (-cell.shift).rem_euclid(1.0);
+    }
+    for mut agent in agents.iter_mut() {
+        agent.progress = 1.0 - agent.progress;
+        agent.mirrored = !agent.mirrored;
+    }
+    println!("reversed: {}", flow.reversed);
+}
+
+pub fn flip_rings(
+    mut events: EventReader<FlipRing>,
+    phase: Res<MotionPhase>,
+    flow: Res<FlowDirection>,
+    rings: Res<Rings>,
+    mut cells_param: ResMut<CellsParam>,
+    // the rings are the main table's
+    mut cells: Query<&mut Cell, Without<Compared>>,
+    mut agents: Query<&mut Agent>,
+) {
+    for FlipRing(index) in events.read() {
+        let Some(ring) = rings.0.get(*index) else {
+            println!("no ring {} to flip", index);
+            continue;
+        };
+        let members = ring.cells.iter().copied().collect::<HashSet<_>>();
+
+        for grid in &members {
+            let (x, y) = (grid.x as usize, grid.y as usize);
+            let move_type = cells_param.move_type_at(*grid).reversed();
+            cells_param.cell_table.table[y][x] = char_from_move_type(move_type);
+        }
+        for mut cell in cells.iter_mut().filter(|cell| members.contains(&cell.grid)) {
+            // r' = 1 - r: rate * speed + shift' = 1 - (rate * speed + shift)
+            let speed = cells_param.cell_table.speed(cell.grid.x as usize, cell.grid.y as usize);
+            let rate = flow.cell_rate(phase.rate(), cell.phase);
+            cell.shift = (1.0 - 2.0 * rate * speed - cell.shift).rem_euclid(1.0);
+            cell.reverse();
+        }
+        for mut agent in agents.iter_mut().filter(|agent| members.contains(&agent.grid)) {
+            agent.progress = 1.0 - agent.progress;
+            agent.mirrored = !agent.mirrored;
+        }
+        println!("flipped ring {}", index);
+    }
+}
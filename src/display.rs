@@ -0,0 +1,260 @@
+//! The primary window: its size, monitor, title and whether it starts
+//! fullscreen, from the command line ([`WindowConfig`]), and F11 / Alt+Enter
+//! to switch between windowed and borderless fullscreen while running.
+//!
+//! For gallery installations, [`Kiosk`] keeps the window borderless and on
+//! top, hides the cursor once the mouse has been left alone, and ignores the
+//! keyboard but for Ctrl+Shift+Q, which quits.
+//!
+//! [`WindowConfig::overlay`] opens a transparent, click-through window on top
+//! of everything else, for running over the desktop or a stream; only the
+//! circles and what they draw show, as long as the background is
+//! transparent too (`Background::Solid(Color::NONE)`).
+//!
+//! [`FramePacing`] picks the present mode (vsync, mailbox or immediate) and
+//! can cap the frame rate, trading smoothness for power on weak hardware or
+//! battery.
+
+use std::time::Duration;
+
+use bevy::{
+    input::{mouse::AccumulatedMouseMotion, InputSystem},
+    prelude::*,
+    window::{
+        CompositeAlphaMode, MonitorSelection, PresentMode, PrimaryWindow, WindowLevel, WindowMode,
+        WindowPosition, WindowResolution,
+    },
+};
+
+use crate::input::{Action, Hotkeys};
+
+/// How the primary window is opened; goes into `WindowPlugin::primary_window`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowConfig {
+    pub title: String,
+    /// logical size; Bevy's default when `None`
+    pub size: Option<UVec2>,
+    /// index of the monitor to open on; the primary one when `None`
+    pub monitor: Option<usize>,
+    /// borderless fullscreen from the start
+    pub fullscreen: bool,
+    /// transparent, undecorated, always on top and letting clicks through
+    pub overlay: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            title: "moving cells".into(),
+            size: None,
+            monitor: None,
+            fullscreen: false,
+            overlay: false,
+        }
+    }
+}
+
+impl WindowConfig {
+    fn monitor_selection(&self) -> MonitorSelection {
+        match self.monitor {
+            Some(index) => MonitorSelection::Index(index),
+            None => MonitorSelection::Primary,
+        }
+    }
+
+    pub fn window(&self) -> Window {
+        let mut window = Window {
+            title: self.title.clone(),
+            position: WindowPosition::Centered(self.monitor_selection()),
+            ..default()
+        };
+        if let Some(size) = self.size {
+            window.resolution = WindowResolution::new(size.x as f32, size.y as f32);
+        }
+        if self.fullscreen {
+            window.mode = WindowMode::BorderlessFullscreen(self.monitor_selection());
+        }
+        if self.overlay {
+            window.transparent = true;
+            window.decorations = false;
+            window.window_level = WindowLevel::AlwaysOnTop;
+            window.cursor_options.hit_test = false;
+            window.composite_alpha_mode = overlay_alpha_mode();
+        }
+        window
+    }
+}
+
+/// What the compositor needs to blend a transparent window; `Auto` picks an
+/// opaque mode on some platforms.
+fn overlay_alpha_mode() -> CompositeAlphaMode {
+    if cfg!(target_os = "macos") {
+        CompositeAlphaMode::PostMultiplied
+    } else if cfg!(target_os = "linux") {
+        CompositeAlphaMode::PreMultiplied
+    } else {
+        CompositeAlphaMode::Auto
+    }
+}
+
+/// Opt-in: gallery mode.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Kiosk {
+    /// seconds without the mouse moving before the cursor is hidden
+    pub hide_cursor_after: f32,
+}
+
+impl Default for Kiosk {
+    fn default() -> Self {
+        Kiosk { hide_cursor_after: 3.0 }
+    }
+}
+
+/// How frames are presented, and how many at most.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct FramePacing {
+    pub present_mode: PresentMode,
+    /// frames per second at most; as many as `present_mode` lets through
+    /// when `None`
+    pub fps_cap: Option<f32>,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        FramePacing { present_mode: PresentMode::AutoVsync, fps_cap: None }
+    }
+}
+
+impl FramePacing {
+    /// `vsync`, `mailbox` or `immediate`.
+    pub fn parse_present_mode(name: &str) -> Option<PresentMode> {
+        match name {
+            "vsync" => Some(PresentMode::AutoVsync),
+            "mailbox" => Some(PresentMode::Mailbox),
+            "immediate" => Some(PresentMode::Immediate),
+            _ => None,
+        }
+    }
+
+    /// How long to wait after a frame that took `frame_time` to stay under
+    /// the cap.
+    pub fn wait(&self, frame_time: Duration) -> Duration {
+        match self.fps_cap {
+            Some(fps) if fps > 0.0 => Duration::from_secs_f32(1.0 / fps).saturating_sub(frame_time),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FramePacing>()
+            .add_systems(PreUpdate, kiosk_keys.after(InputSystem))
+            .add_systems(Update, (toggle_fullscreen, apply_kiosk, hide_idle_cursor))
+            .add_systems(Update, apply_present_mode.run_if(resource_changed::<FramePacing>));
+        // the browser paces frames itself, and can't be made to sleep
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Last, limit_frame_rate);
+    }
+}
+
+pub fn apply_present_mode(pacing: Res<FramePacing>, mut window: Query<&mut Window, With<PrimaryWindow>>) {
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+    if window.present_mode != pacing.present_mode {
+        window.present_mode = pacing.present_mode;
+    }
+}
+
+/// Sleep out what is left of the frame under [`FramePacing::fps_cap`].
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn limit_frame_rate(pacing: Res<FramePacing>, mut frame_start: Local<Option<std::time::Instant>>) {
+    if let Some(start) = *frame_start {
+        let wait = pacing.wait(start.elapsed());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+    *frame_start = Some(std::time::Instant::now());
+}
+
+/// In kiosk mode, quit on Ctrl+Shift+Q and swallow every other key press
+/// before the hotkeys see it.
+pub fn kiosk_keys(kiosk: Option<Res<Kiosk>>, mut keys: ResMut<ButtonInput<KeyCode>>, mut exit: EventWriter<AppExit>) {
+    if kiosk.is_none() {
+        return;
+    }
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        && keys.just_pressed(KeyCode::KeyQ)
+    {
+        exit.write(AppExit::Success);
+    }
+    // hotkeys go by just_pressed; keeping what is held lets the combo build up
+    keys.clear();
+}
+
+/// Borderless and always on top while [`Kiosk`] is there, back to normal
+/// when it goes.
+pub fn apply_kiosk(
+    kiosk: Option<Res<Kiosk>>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut applied: Local<bool>,
+) {
+    if kiosk.is_some() == *applied {
+        return;
+    }
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+    *applied = kiosk.is_some();
+    window.decorations = !*applied;
+    window.window_level = if *applied { WindowLevel::AlwaysOnTop } else { WindowLevel::Normal };
+    if !*applied {
+        window.cursor_options.visible = true;
+    }
+}
+
+/// Hide the cursor once the mouse has been still for
+/// [`Kiosk::hide_cursor_after`], show it again as soon as it moves.
+pub fn hide_idle_cursor(
+    time: Res<Time<Real>>,
+    kiosk: Option<Res<Kiosk>>,
+    motion: Res<AccumulatedMouseMotion>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut still_for: Local<f32>,
+) {
+    let Some(kiosk) = kiosk else {
+        *still_for = 0.0;
+        return;
+    };
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+    if motion.delta != Vec2::ZERO {
+        *still_for = 0.0;
+    } else {
+        *still_for += time.delta_secs();
+    }
+    let visible = *still_for < kiosk.hide_cursor_after;
+    if window.cursor_options.visible != visible {
+        window.cursor_options.visible = visible;
+    }
+}
+
+/// Windowed <-> borderless fullscreen on the monitor the window is on.
+pub fn toggle_fullscreen(hotkeys: Hotkeys, mut window: Query<&mut Window, With<PrimaryWindow>>) {
+    if !(hotkeys.alt_enter() || hotkeys.just_pressed(Action::ToggleFullscreen)) {
+        return;
+    }
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        _ => WindowMode::Windowed,
+    };
+}
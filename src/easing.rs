@@ -0,0 +1,52 @@
+//! Easing applied to `rate` before a circle is placed along its path.
+
+use bevy::math::curve::{Curve, EaseFunction};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    SineIn,
+    SineOut,
+    SineInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    Elastic,
+    Bounce,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 9] = [
+        Easing::Linear,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::Elastic,
+        Easing::Bounce,
+    ];
+
+    pub fn next(self) -> Easing {
+        let i = Easing::ALL.iter().position(|e| *e == self).unwrap_or(0);
+        Easing::ALL[(i + 1) % Easing::ALL.len()]
+    }
+
+    pub fn apply(self, t: f32) -> f32 {
+        let f = match self {
+            Easing::Linear => return t,
+            Easing::SineIn => EaseFunction::SineIn,
+            Easing::SineOut => EaseFunction::SineOut,
+            Easing::SineInOut => EaseFunction::SineInOut,
+            Easing::CubicIn => EaseFunction::CubicIn,
+            Easing::CubicOut => EaseFunction::CubicOut,
+            Easing::CubicInOut => EaseFunction::CubicInOut,
+            Easing::Elastic => EaseFunction::ElasticOut,
+            Easing::Bounce => EaseFunction::BounceOut,
+        };
+        f.sample_clamped(t)
+    }
+}
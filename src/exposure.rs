@@ -0,0 +1,166 @@
+//! Long exposures: one whole loop integrated into a single image, like a
+//! light painting of the track geometry.
+//!
+//! Rendering is offline and on the CPU: the simulation runs headless at a
+//! fixed step and every sub-frame's circles are accumulated into the image.
+
+use std::time::Duration;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    time::TimeUpdateStrategy,
+};
+
+use crate::{
+    agent::{self, CellBoundaryCrossed, LoopCompleted},
+    clock::SimClockPlugin,
+    direction::FlowDirection,
+    move_cells, ring, spawn_cells, spring,
+    tempo::Tempo,
+    CellsParam, Circles,
+};
+
+/// Accumulated coverage of every pixel.
+pub struct LongExposure {
+    pub width: u32,
+    pub height: u32,
+    /// pixels per world unit
+    pub scale: f32,
+    /// world position in the middle of the image
+    pub center: Vec2,
+    accum: Vec<Vec3>,
+    samples: u32,
+}
+
+impl LongExposure {
+    pub fn new(width: u32, height: u32, scale: f32) -> Self {
+        LongExposure {
+            width,
+            height,
+            scale,
+            center: Vec2::ZERO,
+            accum: vec![Vec3::ZERO; (width * height) as usize],
+            samples: 0,
+        }
+    }
+
+    /// Big enough for the whole layout with a cell of margin around it.
+    pub fn fit(cells_param: &CellsParam, scale: f32) -> Self {
+        let table = &cells_param.cell_table;
+        let (mut min, mut max) = (Vec2::MAX, Vec2::MIN);
+        for iy in 0..table.height {
+            for ix in 0..table.width {
+                let (center, half) = (cells_param.cell_center(ix, iy), cells_param.cell_extent(ix, iy) / 2.0);
+                min = min.min(center - half);
+                max = max.max(center + half);
+            }
+        }
+        let size = (max - min + cells_param.cell_size * 2.0) * scale;
+        let mut exposure = LongExposure::new(size.x.ceil() as u32, size.y.ceil() as u32, scale);
+        exposure.center = (min + max) / 2.0;
+        exposure
+    }
+
+    /// Sub-frames accumulated so far.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Add a disc at world position `center`, anti-aliased over a pixel.
+    pub fn add_circle(&mut self, center: Vec2, radius: f32, color: LinearRgba) {
+        let color = Vec3::new(color.red, color.green, color.blue) * color.alpha;
+        // world (y up, origin in the middle) to pixels (y down)
+        let center = (center - self.center) * self.scale;
+        let center = Vec2::new(self.width as f32 / 2.0 + center.x, self.height as f32 / 2.0 - center.y);
+        let radius = radius * self.scale;
+        let min = (center - radius - 1.0).max(Vec2::ZERO);
+        let max = (center + radius + 1.0).min(Vec2::new(self.width as f32, self.height as f32));
+        for y in min.y as u32..max.y.ceil() as u32 {
+            for x in min.x as u32..max.x.ceil() as u32 {
+                let d = Vec2::new(x as f32 + 0.5, y as f32 + 0.5).distance(center);
+                let coverage = (radius + 0.5 - d).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.accum[(y * self.width + x) as usize] += color * coverage;
+                }
+            }
+        }
+    }
+
+    /// Close a sub-frame.
+    pub fn end_sample(&mut self) {
+        self.samples += 1;
+    }
+
+    /// Mean coverage over the sub-frames, brightened by `gain`: pixels a circle
+    /// only passes briefly still show, pixels covered all the time saturate.
+    pub fn to_image(&self, gain: f32) -> Image {
+        let samples = self.samples.max(1) as f32;
+        let mut data = Vec::with_capacity(self.accum.len() * 4);
+        for value in &self.accum {
+            let exposed = Vec3::ONE - (-*value / samples * gain).exp();
+            let color = Color::linear_rgb(exposed.x, exposed.y, exposed.z).to_srgba().to_u8_array();
+            data.extend_from_slice(&color);
+        }
+        Image::new(
+            Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+/// Seconds until every ring of the layout is back at its start.
+pub fn loop_sec(cells_param: &CellsParam, tempo: &Tempo) -> f32 {
+    let rings = ring::Rings::new(ring::find_rings(cells_param));
+    rings.global_period() as f32 * tempo.span_sec()
+}
+
+/// Run one whole loop of `cells_param` headless in `sub_frames` steps and
+/// accumulate every circle of every step into `exposure`.
+pub fn expose_loop(cells_param: CellsParam, tempo: Tempo, sub_frames: u32, exposure: &mut LongExposure) {
+    let step = loop_sec(&cells_param, &tempo) / sub_frames.max(1) as f32;
+
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(step)))
+        .insert_resource(cells_param)
+        .insert_resource(tempo)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<FlowDirection>()
+        .add_event::<CellBoundaryCrossed>()
+        .add_event::<LoopCompleted>()
+        .add_systems(Startup, spawn)
+        .add_systems(Update, ((move_cells, agent::move_agents), spring::apply_springs).chain());
+    // steps longer than the default cap would be cut short
+    app.world_mut().resource_mut::<Time<Virtual>>().set_max_delta(Duration::from_secs_f32(step.max(0.25)));
+
+    // the first frame has no delta
+    app.update();
+    for _ in 0..sub_frames {
+        app.update();
+        let world = app.world_mut();
+        let circle_size = world.resource::<CellsParam>().circle_size;
+        let mut circles = world.query_filtered::<(&Transform, &MeshMaterial2d<ColorMaterial>), Circles>();
+        let materials = world.resource::<Assets<ColorMaterial>>();
+        for (transform, material) in circles.iter(world) {
+            let color = materials.get(&material.0).map_or(LinearRgba::WHITE, |m| m.color.to_linear());
+            exposure.add_circle(transform.translation.truncate(), circle_size * transform.scale.x, color);
+        }
+        exposure.end_sample();
+    }
+}
+
+fn spawn(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+}
@@ -0,0 +1,106 @@
+//! Video feedback: the cameras stop clearing and a translucent veil of the
+//! clear color is drawn over the last frame instead, so everything smears
+//! and fades out slowly. Unlike [`trails`](crate::trails) no entities follow
+//! the circles; the picture itself remembers.
+//!
+//! Opt-in by inserting [`Feedback`]; removing it clears normally again.
+
+use bevy::{prelude::*, render::camera::ClearColorConfig};
+
+use crate::layers::{Layer, OnLayer};
+
+#[derive(Resource, Clone, Debug)]
+pub struct Feedback {
+    /// share of the picture that fades away per second (0..1); 0 never fades
+    pub decay_per_sec: f32,
+}
+
+impl Default for Feedback {
+    fn default() -> Self {
+        Feedback { decay_per_sec: 0.9 }
+    }
+}
+
+impl Feedback {
+    /// Alpha of the veil over a frame of `dt` seconds.
+    pub fn veil_alpha(&self, dt: f32) -> f32 {
+        1.0 - (1.0 - self.decay_per_sec.clamp(0.0, 1.0)).powf(dt)
+    }
+}
+
+/// Fullscreen quad fading the last frame out, behind everything else.
+#[derive(Component)]
+pub struct FeedbackVeil;
+
+/// Large enough to cover any view, at the back of the default 2d camera range.
+const VEIL_SIZE: f32 = 100_000.0;
+
+pub struct FeedbackPlugin;
+
+impl Plugin for FeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (sync_feedback_cameras, sync_feedback_veil));
+    }
+}
+
+fn sync_feedback_cameras(
+    feedback: Option<Res<Feedback>>,
+    mut cameras: Query<(&mut Camera, &mut Msaa), With<Camera2d>>,
+    mut was_on: Local<bool>,
+) {
+    if feedback.is_none() {
+        if std::mem::take(&mut *was_on) {
+            for (mut camera, mut msaa) in cameras.iter_mut() {
+                camera.clear_color = ClearColorConfig::Default;
+                *msaa = Msaa::default();
+            }
+        }
+        return;
+    }
+    *was_on = true;
+    for (mut camera, mut msaa) in cameras.iter_mut() {
+        if !matches!(camera.clear_color, ClearColorConfig::None) {
+            camera.clear_color = ClearColorConfig::None;
+        }
+        // the multisampled texture isn't kept between frames
+        if *msaa != Msaa::Off {
+            *msaa = Msaa::Off;
+        }
+    }
+}
+
+fn sync_feedback_veil(
+    mut commands: Commands,
+    time: Res<Time>,
+    feedback: Option<Res<Feedback>>,
+    clear_color: Option<Res<ClearColor>>,
+    veils: Query<(Entity, &MeshMaterial2d<ColorMaterial>), With<FeedbackVeil>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(feedback) = feedback else {
+        for (entity, _) in veils.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let color = clear_color.map_or(ClearColor::default().0, |c| c.0);
+    let color = color.with_alpha(feedback.veil_alpha(time.delta_secs()));
+    match veils.single() {
+        Ok((_, material)) => {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.color = color;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                Mesh2d(meshes.add(Rectangle::new(VEIL_SIZE, VEIL_SIZE))),
+                MeshMaterial2d(materials.add(color)),
+                Transform::from_xyz(0.0, 0.0, Layer::Veil.z()),
+                FeedbackVeil,
+                OnLayer(Layer::Veil),
+            ));
+        }
+    }
+}
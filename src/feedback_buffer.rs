@@ -0,0 +1,211 @@
+//! Feedback into a texture: a second camera draws the circles into an
+//! accumulation image it never clears, fading it a little every frame with a
+//! veil, and the main camera shows that image under the live circles. Long
+//! luminous trails, with no ghost entities (see [`trails`](crate::trails))
+//! and without smearing the live picture (see [`feedback`](crate::feedback)).
+//!
+//! Opt-in by inserting [`FeedbackBuffer`].
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::BevyDefault as _,
+    prelude::*,
+    render::{
+        camera::{ClearColorConfig, RenderTarget},
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    window::PrimaryWindow,
+};
+
+use crate::{
+    calibration::OutputName,
+    feedback::Feedback,
+    layers::{Layer, OnLayer},
+};
+
+#[derive(Resource, Clone, Debug)]
+pub struct FeedbackBuffer {
+    /// share of the accumulated picture that fades away per second (0..1)
+    pub decay_per_sec: f32,
+}
+
+impl Default for FeedbackBuffer {
+    fn default() -> Self {
+        FeedbackBuffer { decay_per_sec: 0.5 }
+    }
+}
+
+/// Seen by the accumulation camera only: the veil.
+const VEIL_LAYER: usize = 2;
+/// Seen by the main camera only: the accumulated picture.
+const BUFFER_LAYER: usize = 3;
+const VEIL_SIZE: f32 = 100_000.0;
+
+/// Everything the buffer is made of.
+#[derive(Component)]
+struct BufferPart;
+
+#[derive(Component)]
+pub struct BufferCamera;
+
+#[derive(Component)]
+struct BufferVeil;
+
+/// The accumulated picture, as seen by the main camera.
+#[derive(Component)]
+pub struct BufferSprite;
+
+pub struct FeedbackBufferPlugin;
+
+impl Plugin for FeedbackBufferPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                drop_buffer.run_if(resource_removed::<FeedbackBuffer>),
+                (spawn_buffer, update_buffer).chain().run_if(resource_exists::<FeedbackBuffer>),
+            )
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+fn buffer_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d { width: size.x.max(1), height: size.y.max(1), depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::bevy_default(),
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+type MainCamera<'a> = (Entity, &'a OutputName, &'a Transform, &'a Projection);
+
+fn spawn_buffer(
+    mut commands: Commands,
+    window: Query<&Window, With<PrimaryWindow>>,
+    parts: Query<(), With<BufferPart>>,
+    cameras: Query<MainCamera, (With<Camera2d>, Without<BufferPart>)>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !parts.is_empty() {
+        return;
+    }
+    let (Ok(window), Some((main, _, transform, projection))) =
+        (window.single(), cameras.iter().find(|(_, name, ..)| name.0 == "main"))
+    else {
+        return;
+    };
+    let image = images.add(buffer_image(window.physical_size()));
+
+    commands.entity(main).insert(RenderLayers::from_layers(&[0, BUFFER_LAYER]));
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Image(image.clone().into()),
+            order: -1,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        // the multisampled texture isn't kept between frames
+        Msaa::Off,
+        *transform,
+        projection.clone(),
+        RenderLayers::from_layers(&[0, VEIL_LAYER]),
+        BufferCamera,
+        BufferPart,
+    ));
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(VEIL_SIZE, VEIL_SIZE))),
+        MeshMaterial2d(materials.add(Color::NONE)),
+        Transform::from_xyz(0.0, 0.0, Layer::Veil.z()),
+        RenderLayers::layer(VEIL_LAYER),
+        BufferVeil,
+        BufferPart,
+        OnLayer(Layer::Veil),
+    ));
+    commands.spawn((
+        Sprite { image, custom_size: Some(window.size()), ..default() },
+        Transform::from_xyz(transform.translation.x, transform.translation.y, Layer::Veil.z()),
+        RenderLayers::layer(BUFFER_LAYER),
+        BufferSprite,
+        BufferPart,
+        OnLayer(Layer::Veil),
+    ));
+}
+
+type OfBufferCamera = (With<BufferCamera>, With<BufferPart>);
+type OfBufferSprite = (With<BufferSprite>, With<BufferPart>, Without<BufferCamera>);
+
+/// Fade the buffer, keep it on the main view and as large as the window.
+#[allow(clippy::too_many_arguments)]
+fn update_buffer(
+    time: Res<Time>,
+    buffer: Res<FeedbackBuffer>,
+    clear_color: Option<Res<ClearColor>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<MainCamera, (With<Camera2d>, Without<BufferPart>)>,
+    mut buffer_camera: Query<(&mut Transform, &mut Projection), OfBufferCamera>,
+    mut sprite: Query<(&mut Sprite, &mut Transform), OfBufferSprite>,
+    veil: Query<&MeshMaterial2d<ColorMaterial>, With<BufferVeil>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let (Ok(window), Some((_, _, main_transform, main_projection))) =
+        (window.single(), cameras.iter().find(|(_, name, ..)| name.0 == "main"))
+    else {
+        return;
+    };
+
+    if let Ok(material) = veil.single() {
+        let color = clear_color.map_or(ClearColor::default().0, |c| c.0);
+        let alpha = Feedback { decay_per_sec: buffer.decay_per_sec }.veil_alpha(time.delta_secs());
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = color.with_alpha(alpha);
+        }
+    }
+
+    if let Ok((mut transform, mut projection)) = buffer_camera.single_mut() {
+        *transform = *main_transform;
+        *projection = main_projection.clone();
+    }
+
+    let scale = match main_projection {
+        Projection::Orthographic(ortho) => ortho.scale,
+        _ => 1.0,
+    };
+    if let Ok((mut sprite, mut transform)) = sprite.single_mut() {
+        transform.translation = main_transform.translation.truncate().extend(Layer::Veil.z());
+        let size = Some(window.size() * scale);
+        if sprite.custom_size != size {
+            sprite.custom_size = size;
+        }
+        let physical = window.physical_size().max(UVec2::ONE);
+        if images.get(&sprite.image).is_some_and(|image| image.size() != physical) {
+            // the old picture is dropped; trails build up again
+            images.insert(sprite.image.id(), buffer_image(physical));
+        }
+    }
+}
+
+fn drop_buffer(
+    mut commands: Commands,
+    parts: Query<Entity, With<BufferPart>>,
+    cameras: Query<(Entity, &OutputName), With<RenderLayers>>,
+) {
+    for entity in parts.iter() {
+        commands.entity(entity).despawn();
+    }
+    for (entity, name) in cameras.iter() {
+        if name.0 == "main" {
+            commands.entity(entity).remove::<RenderLayers>();
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Building a table from a flow-field image.
+//!
+//! Each pixel holds a direction vector the way normal maps do: red is x
+//! (right), green is y (up), both mapped from -1..1 to 0..1. Mid grey, or
+//! anything more transparent than half, means "no flow" and becomes a blank
+//! cell. Directions are snapped to the four axes; where the flow turns, the
+//! matching corner glyph is picked.
+
+use bevy::{image::TextureAccessError, prelude::*};
+
+use crate::{char_from_move_type, CellTable, LayoutError, MoveType};
+
+/// Vectors shorter than this are treated as blank.
+const MIN_MAGNITUDE: f32 = 0.25;
+
+/// Neighbour directions in table coordinates (y down).
+const SIDES: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::NEG_Y, IVec2::Y];
+
+#[derive(Debug)]
+pub enum FlowFieldError {
+    /// the image is smaller than the requested table
+    TooSmall { image: UVec2, table: UVec2 },
+    /// the pixel format can't be read back
+    Pixel(TextureAccessError),
+    Layout(LayoutError),
+}
+
+impl std::fmt::Display for FlowFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlowFieldError::TooSmall { image, table } => write!(
+                f,
+                "a {}x{} image can't fill a {}x{} table",
+                image.x, image.y, table.x, table.y
+            ),
+            FlowFieldError::Pixel(e) => write!(f, "unreadable flow field: {}", e),
+            FlowFieldError::Layout(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FlowFieldError {}
+
+/// Direction encoded in one pixel, y up.
+pub fn vector_from_color(image: &Image, color: Color) -> Vec2 {
+    // channels hold raw values, so undo whatever the texture format decodes
+    let [r, g, _, a] = if image.texture_descriptor.format.is_srgb() {
+        color.to_srgba().to_f32_array()
+    } else {
+        color.to_linear().to_f32_array()
+    };
+    if a < 0.5 {
+        return Vec2::ZERO;
+    }
+    Vec2::new(r, g) * 2.0 - Vec2::ONE
+}
+
+/// Quantize `image` into a `columns` x `rows` table, averaging the pixels
+/// that fall into each cell.
+pub fn table_from_flow_field(image: &Image, columns: u32, rows: u32) -> Result<CellTable, FlowFieldError> {
+    let size = image.size();
+    if size.x < columns || size.y < rows {
+        return Err(FlowFieldError::TooSmall { image: size, table: UVec2::new(columns, rows) });
+    }
+
+    // snapped exit direction per cell, table coordinates
+    let mut dirs = vec![vec![None; columns as usize]; rows as usize];
+    for (y, row) in dirs.iter_mut().enumerate() {
+        for (x, dir) in row.iter_mut().enumerate() {
+            let (x, y) = (x as u32, y as u32);
+            let mut sum = Vec2::ZERO;
+            let mut count = 0;
+            for py in y * size.y / rows..(y + 1) * size.y / rows {
+                for px in x * size.x / columns..(x + 1) * size.x / columns {
+                    let color = image.get_color_at(px, py).map_err(FlowFieldError::Pixel)?;
+                    sum += vector_from_color(image, color);
+                    count += 1;
+                }
+            }
+            *dir = snap(sum / count as f32);
+        }
+    }
+
+    let dir_at = |p: IVec2| {
+        if p.x < 0 || p.y < 0 || p.x >= columns as i32 || p.y >= rows as i32 {
+            return None;
+        }
+        dirs[p.y as usize][p.x as usize]
+    };
+
+    let mut table = Vec::new();
+    for (y, row) in dirs.iter().enumerate() {
+        let mut glyphs = Vec::new();
+        for (x, dir) in row.iter().enumerate() {
+            let Some(exit) = *dir else {
+                glyphs.push(char_from_move_type(MoveType::Blank));
+                continue;
+            };
+            let p = IVec2::new(x as i32, y as i32);
+            let feeds = |e: IVec2| dir_at(p - e) == Some(e);
+            // go straight if the flow allows it, otherwise turn in from a side
+            let entry = if feeds(exit) {
+                exit
+            } else {
+                SIDES.into_iter().find(|&e| e != -exit && feeds(e)).unwrap_or(exit)
+            };
+            // endpoints are y up, half-cell units
+            let y_up = |v: IVec2| Vec2::new(v.x as f32, -v.y as f32);
+            let move_type = MoveType::from_endpoints(y_up(-entry), y_up(exit))
+                .expect("every entry / exit pair off the same axis is a track");
+            glyphs.push(char_from_move_type(move_type));
+        }
+        table.push(glyphs);
+    }
+
+    CellTable::from_rows(table).map_err(FlowFieldError::Layout)
+}
+
+/// Nearest axis in table coordinates, or `None` if there is no flow.
+fn snap(v: Vec2) -> Option<IVec2> {
+    if v.length() < MIN_MAGNITUDE {
+        None
+    } else if v.x.abs() >= v.y.abs() {
+        Some(IVec2::new(v.x.signum() as i32, 0))
+    } else {
+        // flip y
+        Some(IVec2::new(0, -v.y.signum() as i32))
+    }
+}
@@ -0,0 +1,61 @@
+//! Glowing circles: an HDR main camera with bloom.
+//!
+//! The circles' [`ColorMaterial`]s are unlit, so flat circles bloom in their
+//! own colors; the spheres of the 3D mode are given their color as emissive
+//! on top (see [`three_d`](crate::three_d)).
+
+use bevy::{
+    core_pipeline::bloom::{Bloom, BloomPrefilter},
+    prelude::*,
+};
+
+use crate::{calibration::OutputName, CellsParam};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Glow {
+    /// strength of the bloom, 0..1
+    pub intensity: f32,
+    /// brightness below which nothing blooms, 0 for everything
+    pub threshold: f32,
+}
+
+impl Default for Glow {
+    fn default() -> Self {
+        Glow { intensity: 0.3, threshold: 0.0 }
+    }
+}
+
+impl Glow {
+    pub fn bloom(&self) -> Bloom {
+        Bloom {
+            intensity: self.intensity,
+            prefilter: BloomPrefilter { threshold: self.threshold, threshold_softness: 0.5 },
+            ..Bloom::NATURAL
+        }
+    }
+}
+
+/// Turn bloom on the `main` cameras on, off or to the current settings when
+/// `CellsParam::glow` changes, or a main camera is added.
+pub fn apply_glow(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    mut cameras: Query<(Entity, &mut Camera, Ref<OutputName>)>,
+) {
+    for (entity, mut camera, name) in cameras.iter_mut() {
+        if name.0 != "main" || !(cells_param.is_changed() || name.is_added()) {
+            continue;
+        }
+        match cells_param.glow {
+            Some(glow) => {
+                camera.hdr = true;
+                commands.entity(entity).insert(glow.bloom());
+            }
+            None if camera.hdr => {
+                camera.hdr = false;
+                commands.entity(entity).remove::<Bloom>();
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! Moving the circles of an [`Instanced`](crate::instanced::Instanced)
+//! layout on the GPU: the cells are uploaded once per layout, and each frame
+//! a compute shader works out every circle's position from a handful of
+//! parameters, straight into the buffer the instances are drawn from.
+//!
+//! Opt-in with [`GpuMotion`], on top of `Instanced`. Follows the rate, the
+//! flow direction, phases, per-cell speeds, easings, stepping, corner styles
+//! and the rotating idle; jitter and gravity are CPU-only.
+
+use bevy::{
+    asset::embedded_asset,
+    prelude::*,
+    render::{
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_graph::{self, RenderGraph, RenderLabel},
+        render_resource::{
+            binding_types::{storage_buffer_read_only_sized, storage_buffer_sized, uniform_buffer_sized},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        sync_world::RenderEntity,
+        Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::{
+    clock::MotionPhase,
+    direction::FlowDirection,
+    easing::Easing,
+    idle::Idle,
+    instanced::{CircleInstance, CircleInstances, InstanceBuffer, InstancedCells},
+    Cell, CellsParam, CornerStyle, MoveType, Stepping,
+};
+
+const SHADER_PATH: &str = "embedded://moving_cells_01/shaders/gpu_motion.wgsl";
+
+/// Circles handled by one invocation group; matches the shader.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Opt-in: move instanced circles in a compute shader.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GpuMotion;
+
+/// One circle as the shader reads it.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct GpuCell {
+    pub pos: Vec2,
+    pub half: Vec2,
+    /// entry and exit point on the unit cell, zero for centers
+    pub path: (Vec2, Vec2),
+    pub phase: f32,
+    pub shift: f32,
+    pub speed: f32,
+    /// index into [`Easing::ALL`]
+    pub easing: u32,
+    /// see [`Cell::mirrored`]
+    pub mirrored: bool,
+    pub z: f32,
+    pub size: f32,
+    /// 0 center, 1 straight, 2 corner
+    pub kind: u32,
+    pub color: LinearRgba,
+}
+
+impl GpuCell {
+    pub fn new(cell: &Cell, instance: &CircleInstance, cells_param: &CellsParam) -> Self {
+        let easing = cell.easing.unwrap_or(cells_param.easing);
+        let kind = match cell.move_type {
+            MoveType::Blank | MoveType::Center => 0,
+            move_type if move_type.is_corner() => 2,
+            _ => 1,
+        };
+        GpuCell {
+            pos: cell.pos,
+            half: cell.size / 2.0,
+            path: cell.move_type.endpoints().unwrap_or_default(),
+            phase: cell.phase,
+            shift: cell.shift,
+            speed: cells_param.cell_table.speed(cell.grid.x as usize, cell.grid.y as usize),
+            easing: Easing::ALL.iter().position(|e| *e == easing).unwrap_or(0) as u32,
+            mirrored: cell.mirrored,
+            z: instance.position.z,
+            size: instance.size,
+            kind,
+            color: instance.color,
+        }
+    }
+
+    fn to_array(self) -> [f32; 20] {
+        let (entry, exit) = self.path;
+        let [r, g, b, a] = self.color.to_f32_array();
+        [
+            self.pos.x, self.pos.y, self.half.x, self.half.y,
+            entry.x, entry.y, exit.x, exit.y,
+            self.phase, self.shift, self.speed, self.easing as f32,
+            self.z, self.size, self.kind as f32, self.mirrored as u32 as f32,
+            r, g, b, a,
+        ]
+    }
+}
+
+/// The cells of a batch, uploaded when they change.
+#[derive(Component, Clone, Debug, Default)]
+pub struct GpuCells(pub Vec<GpuCell>);
+
+/// What the shader needs to know this frame.
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default, ExtractResource)]
+pub struct MotionUniform {
+    pub rate: f32,
+    /// -1 with the flow reversed
+    pub flow_sign: f32,
+    pub flow_shift: f32,
+    /// half the swing of a hop, 0 when gliding
+    pub hop: f32,
+    /// radius of [`Idle::Rotate`]
+    pub idle_radius: f32,
+    pub arc_corners: bool,
+}
+
+impl MotionUniform {
+    pub fn new(cells_param: &CellsParam, flow: &FlowDirection, rate: f32) -> Self {
+        MotionUniform {
+            rate,
+            flow_sign: if flow.reversed { -1.0 } else { 1.0 },
+            flow_shift: flow.shift(),
+            hop: match cells_param.stepping {
+                Stepping::Glide => 0.0,
+                Stepping::Hop { swing } => (swing / 2.0).clamp(f32::EPSILON, 0.5),
+            },
+            idle_radius: match cells_param.idle {
+                Idle::Rotate { radius } => radius,
+                _ => 0.0,
+            },
+            arc_corners: cells_param.corner_style == CornerStyle::Arc,
+        }
+    }
+
+    fn to_array(self) -> [f32; 8] {
+        let arc = if self.arc_corners { 1.0 } else { 0.0 };
+        [self.rate, self.flow_sign, self.flow_shift, self.hop, self.idle_radius, arc, 0.0, 0.0]
+    }
+}
+
+fn bytes(floats: impl IntoIterator<Item = f32>) -> Vec<u8> {
+    floats.into_iter().flat_map(f32::to_ne_bytes).collect()
+}
+
+pub struct GpuMotionPlugin;
+
+impl Plugin for GpuMotionPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/gpu_motion.wgsl");
+
+        app
+            .init_resource::<MotionUniform>()
+            .add_plugins(ExtractResourcePlugin::<MotionUniform>::default())
+            .add_systems(
+                Update,
+                (pack_cells.after(crate::instanced::move_instances), update_motion)
+                    .run_if(resource_exists::<GpuMotion>),
+            );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_systems(ExtractSchedule, extract_gpu_cells)
+            .add_systems(
+                Render,
+                (
+                    prepare_gpu_cells.in_set(RenderSet::PrepareResources),
+                    prepare_dispatches.in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
+        let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
+        render_graph.add_node(GpuMotionLabel, GpuMotionNode);
+        render_graph.add_node_edge(GpuMotionLabel, bevy::render::graph::CameraDriverLabel);
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<GpuMotionPipeline>();
+    }
+}
+
+/// Hand the cells of every re-laid batch to the GPU, and stop moving (and
+/// extracting) its instances on the CPU.
+pub fn pack_cells(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    mut batches: Query<(Entity, Ref<InstancedCells>, &mut CircleInstances)>,
+) {
+    for (entity, batch, mut drawn) in batches.iter_mut() {
+        if !batch.is_changed() {
+            continue;
+        }
+        let cells = batch
+            .cells
+            .iter()
+            .zip(drawn.instances.iter())
+            .map(|(cell, instance)| GpuCell::new(cell, instance, &cells_param))
+            .collect();
+        commands.entity(entity).insert(GpuCells(cells));
+        drawn.instances.clear();
+    }
+}
+
+fn update_motion(
+    phase: Res<MotionPhase>,
+    cells_param: Res<CellsParam>,
+    flow: Res<FlowDirection>,
+    mut motion: ResMut<MotionUniform>,
+) {
+    *motion = MotionUniform::new(&cells_param, &flow, phase.rate());
+}
+
+/// Cells to upload; marks the batch as moved on the GPU from then on.
+#[derive(Component)]
+struct ExtractedGpuCells(Vec<GpuCell>);
+
+/// A batch moved by the compute shader.
+#[derive(Component)]
+pub(crate) struct GpuMoved;
+
+#[derive(Component)]
+struct GpuCellBuffer {
+    buffer: Buffer,
+    length: u32,
+}
+
+fn extract_gpu_cells(mut commands: Commands, batches: Extract<Query<(RenderEntity, Ref<GpuCells>)>>) {
+    for (entity, cells) in &batches {
+        if cells.is_changed() {
+            commands.entity(entity).insert((ExtractedGpuCells(cells.0.clone()), GpuMoved));
+        }
+    }
+}
+
+fn prepare_gpu_cells(
+    mut commands: Commands,
+    batches: Query<(Entity, &ExtractedGpuCells)>,
+    render_device: Res<RenderDevice>,
+) {
+    for (entity, cells) in &batches {
+        let mut entity = commands.entity(entity);
+        entity.remove::<ExtractedGpuCells>();
+        // nothing to bind, nothing to draw
+        if cells.0.is_empty() {
+            entity.remove::<(GpuCellBuffer, InstanceBuffer)>();
+            continue;
+        }
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("gpu_cell_buffer"),
+            contents: &bytes(cells.0.iter().flat_map(|cell| cell.to_array())),
+            usage: BufferUsages::STORAGE,
+        });
+        let instances = render_device.create_buffer(&BufferDescriptor {
+            label: Some("gpu_moved_instance_buffer"),
+            size: (cells.0.len() * size_of::<[f32; 8]>()) as u64,
+            usage: BufferUsages::STORAGE | BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+        entity.insert((
+            GpuCellBuffer { buffer, length: cells.0.len() as u32 },
+            InstanceBuffer { buffer: instances, length: cells.0.len() },
+        ));
+    }
+}
+
+/// The bind group and circle count of every batch, for this frame.
+#[derive(Resource, Default)]
+struct GpuMotionDispatches(Vec<(BindGroup, u32)>);
+
+fn prepare_dispatches(
+    mut commands: Commands,
+    pipeline: Res<GpuMotionPipeline>,
+    motion: Res<MotionUniform>,
+    batches: Query<(&GpuCellBuffer, &InstanceBuffer)>,
+    render_device: Res<RenderDevice>,
+) {
+    if batches.is_empty() {
+        commands.insert_resource(GpuMotionDispatches::default());
+        return;
+    }
+    let uniform = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("gpu_motion_uniform_buffer"),
+        contents: &bytes(motion.to_array()),
+        usage: BufferUsages::UNIFORM,
+    });
+    let dispatches = batches
+        .iter()
+        .map(|(cells, instances)| {
+            let bind_group = render_device.create_bind_group(
+                "gpu_motion_bind_group",
+                &pipeline.layout,
+                &BindGroupEntries::sequential((
+                    uniform.as_entire_binding(),
+                    cells.buffer.as_entire_binding(),
+                    instances.buffer.as_entire_binding(),
+                )),
+            );
+            (bind_group, cells.length)
+        })
+        .collect();
+    commands.insert_resource(GpuMotionDispatches(dispatches));
+}
+
+#[derive(Resource)]
+struct GpuMotionPipeline {
+    layout: BindGroupLayout,
+    pipeline_id: CachedComputePipelineId,
+}
+
+impl FromWorld for GpuMotionPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "gpu_motion_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    uniform_buffer_sized(false, None),
+                    storage_buffer_read_only_sized(false, None),
+                    storage_buffer_sized(false, None),
+                ),
+            ),
+        );
+        let shader = world.load_asset(SHADER_PATH);
+        let pipeline_id = world.resource::<PipelineCache>().queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("gpu_motion_pipeline".into()),
+            layout: vec![layout.clone()],
+            push_constant_ranges: vec![],
+            shader,
+            shader_defs: vec![],
+            entry_point: "main".into(),
+            zero_initialize_workgroup_memory: false,
+        });
+        GpuMotionPipeline { layout, pipeline_id }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct GpuMotionLabel;
+
+/// Runs the shader over every batch, before any camera draws.
+struct GpuMotionNode;
+
+impl render_graph::Node for GpuMotionNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let Some(dispatches) = world.get_resource::<GpuMotionDispatches>() else {
+            return Ok(());
+        };
+        let pipeline_id = world.resource::<GpuMotionPipeline>().pipeline_id;
+        let Some(pipeline) = world.resource::<PipelineCache>().get_compute_pipeline(pipeline_id) else {
+            return Ok(());
+        };
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor { label: Some("gpu_motion_pass"), ..default() });
+        pass.set_pipeline(pipeline);
+        for (bind_group, length) in &dispatches.0 {
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(length.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        Ok(())
+    }
+}
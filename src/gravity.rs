@@ -0,0 +1,55 @@
+//! Gravity wells: Center ('0') cells bend the paths of the circles passing
+//! nearby toward themselves, coupling the static hubs to the rings around
+//! them.
+
+use bevy::prelude::*;
+
+use crate::{CellsParam, MoveType};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Gravity {
+    /// pull right next to a well, in world units
+    pub strength: f32,
+    /// distance at which the pull has faded out, in cells
+    pub reach_cells: f32,
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity { strength: 6.0, reach_cells: 1.5 }
+    }
+}
+
+impl Gravity {
+    /// World positions of the wells: the centers of every Center cell, with
+    /// the grid drawn at `cell_size` (see [`SizeTween`](crate::tween::SizeTween)).
+    pub fn wells(cells_param: &CellsParam, cell_size: Vec2) -> Vec<Vec2> {
+        let table = &cells_param.cell_table;
+        let mut wells = Vec::new();
+        for iy in 0..table.height {
+            for ix in 0..table.width {
+                if cells_param.move_type_at(UVec2::new(ix as u32, iy as u32)) == MoveType::Center {
+                    wells.push(cells_param.cell_center_at(ix, iy, cell_size));
+                }
+            }
+        }
+        wells
+    }
+
+    /// Displacement of a circle at `pos` toward the `wells` in reach; a
+    /// circle is never pulled past a well.
+    pub fn offset(&self, pos: Vec2, wells: &[Vec2], cell_size: Vec2) -> Vec2 {
+        let reach = self.reach_cells * cell_size.max_element();
+        let mut offset = Vec2::ZERO;
+        for well in wells {
+            let to_well = *well - pos;
+            let distance = to_well.length();
+            if distance == 0.0 || distance >= reach {
+                continue;
+            }
+            let falloff = (1.0 - distance / reach).powi(2);
+            offset += to_well / distance * (self.strength * falloff).min(distance);
+        }
+        offset
+    }
+}
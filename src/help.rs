@@ -0,0 +1,62 @@
+//! Toggleable overlay listing every hotkey, generated from the [`InputMap`].
+
+use bevy::prelude::*;
+
+use crate::input::{key_name, Action, Hotkeys, InputMap};
+
+#[derive(Component)]
+struct HelpOverlay;
+
+pub struct HelpPlugin;
+
+impl Plugin for HelpPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, spawn_help_overlay)
+            .add_systems(Update, (toggle_help_overlay, update_help_text.run_if(resource_changed::<InputMap>)));
+    }
+}
+
+fn spawn_help_overlay(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.7)),
+        Visibility::Hidden,
+        HelpOverlay,
+    ));
+}
+
+fn toggle_help_overlay(
+    hotkeys: Hotkeys,
+    mut overlay: Query<&mut Visibility, With<HelpOverlay>>,
+) {
+    if !hotkeys.just_pressed(Action::ToggleHelp) {
+        return;
+    }
+    for mut visibility in overlay.iter_mut() {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+fn update_help_text(input_map: Res<InputMap>, mut overlay: Query<&mut Text, With<HelpOverlay>>) {
+    let lines = input_map
+        .0
+        .iter()
+        .map(|(action, key)| format!("{:>8}  {}", key_name(*key), action.description()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    for mut text in overlay.iter_mut() {
+        text.0 = lines.clone();
+    }
+}
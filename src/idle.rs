@@ -0,0 +1,106 @@
+//! Idle animations for the Center ('0') cells, so the anchor dots keep time
+//! with the circles moving around them.
+
+use std::{collections::HashMap, f32::consts::TAU};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::MotionPhase,
+    direction::FlowDirection,
+    input::{Action, Hotkeys},
+    Cell, CellsParam, MoveType,
+};
+
+/// What a center dot does while the tracks around it move. Every animation
+/// runs once per cell span, so it rises and falls with the rhythm.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum Idle {
+    #[default]
+    Still,
+    /// grows by up to `depth` of its size and back
+    Pulse { depth: f32 },
+    /// goes round the cell center, `radius` world units out
+    Rotate { radius: f32 },
+    /// fades by up to `depth` of its opacity and back
+    Breathe { depth: f32 },
+}
+
+impl Idle {
+    pub fn next(self) -> Self {
+        match self {
+            Idle::Still => Idle::Pulse { depth: 0.3 },
+            Idle::Pulse { .. } => Idle::Rotate { radius: 5.0 },
+            Idle::Rotate { .. } => Idle::Breathe { depth: 0.6 },
+            Idle::Breathe { .. } => Idle::Still,
+        }
+    }
+
+    /// 0 at the start of a cell span, 1 halfway.
+    fn swell(rate: f32) -> f32 {
+        (1.0 - (rate * TAU).cos()) / 2.0
+    }
+
+    /// Displacement from the cell center at `rate` (0..1).
+    pub fn offset(&self, rate: f32) -> Vec2 {
+        match *self {
+            Idle::Rotate { radius } => Vec2::from_angle(rate * TAU) * radius,
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// Scale factor at `rate` (0..1).
+    pub fn scale(&self, rate: f32) -> f32 {
+        match *self {
+            Idle::Pulse { depth } => 1.0 + depth * Self::swell(rate),
+            _ => 1.0,
+        }
+    }
+
+    /// Opacity factor at `rate` (0..1).
+    pub fn alpha(&self, rate: f32) -> f32 {
+        match *self {
+            Idle::Breathe { depth } => 1.0 - depth * Self::swell(rate),
+            _ => 1.0,
+        }
+    }
+}
+
+/// Scale factors applied to every center last frame.
+#[derive(Default)]
+pub struct AppliedIdle(HashMap<Entity, f32>);
+
+/// Pulse the centers; [`move_cells`](crate::move_cells) places them and
+/// [`apply_opacity`](crate::opacity::apply_opacity) breathes them. The scale
+/// is applied relative to last frame's, so whatever else scales the dots is
+/// kept.
+pub fn animate_centers(
+    phase: Res<MotionPhase>,
+    cells_param: Res<CellsParam>,
+    flow: Res<FlowDirection>,
+    mut applied: Local<AppliedIdle>,
+    mut centers: Query<(Entity, &Cell, &mut Transform)>,
+) {
+    let rate = phase.rate();
+    let mut seen = Vec::new();
+    for (entity, cell, mut transform) in centers.iter_mut() {
+        if cell.move_type != MoveType::Center {
+            continue;
+        }
+        seen.push(entity);
+        let scale = cells_param.idle.scale(flow.cell_rate(rate, cell.phase));
+        let last = applied.0.insert(entity, scale).unwrap_or(1.0);
+        if scale != last {
+            transform.scale *= scale / last;
+        }
+    }
+    applied.0.retain(|entity, _| seen.contains(entity));
+}
+
+pub fn cycle_idle(hotkeys: Hotkeys, mut cells_param: ResMut<CellsParam>) {
+    if hotkeys.just_pressed(Action::CycleIdle) {
+        cells_param.idle = cells_param.idle.next();
+        println!("center idle: {:?}", cells_param.idle);
+    }
+}
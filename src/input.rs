@@ -0,0 +1,251 @@
+//! Keyboard bindings: every hotkey goes through [`InputMap`] so the bindings
+//! can be listed (and changed) in one place.
+//!
+//! Keys are rebound with a RON file of [`Bindings`], only the actions moved
+//! elsewhere, e.g. `{ Pause: KeyJ, CyclePalette: F10, ToggleLayer(Debug): Backquote }`.
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::layers::Layer;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    ToggleHelp,
+    Pause,
+    StepForward,
+    StepBack,
+    SpeedUp,
+    SpeedDown,
+    Reverse,
+    ToggleClock,
+    TogglePreview,
+    ToggleTrails,
+    ToggleTrack,
+    ToggleArrows,
+    ToggleLabels,
+    TapTempo,
+    CycleEasing,
+    CycleCornerStyle,
+    CycleStepping,
+    CyclePhaseFormula,
+    CycleIdle,
+    CyclePalette,
+    RestoreLayout,
+    DiscardLayout,
+    CycleDialect,
+    ApplyDialect,
+    CancelDialect,
+    Toggle3d,
+    ToggleOrbit,
+    CycleBackground,
+    ResetView,
+    FollowNext,
+    ToggleFullscreen,
+    TogglePaint,
+    ToggleDiagnostics,
+    ToggleCleanView,
+    NextLayoutFile,
+    ToggleLayer(Layer),
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::ToggleHelp => "show / hide this help",
+            Action::Pause => "pause / resume",
+            Action::StepForward => "step forward (while paused)",
+            Action::StepBack => "step back (while paused)",
+            Action::SpeedUp => "faster",
+            Action::SpeedDown => "slower",
+            Action::Reverse => "reverse the flow",
+            Action::ToggleClock => "show / hide the loop clock",
+            Action::TogglePreview => "open / close the preview window",
+            Action::ToggleTrails => "show / hide motion trails",
+            Action::ToggleTrack => "show / hide the track",
+            Action::ToggleArrows => "show / hide the direction of every cell",
+            Action::ToggleLabels => "show / hide the character of every cell",
+            Action::TapTempo => "tap tempo",
+            Action::CycleEasing => "next easing",
+            Action::CycleCornerStyle => "arc / straight corners",
+            Action::CycleStepping => "glide / hop",
+            Action::CyclePhaseFormula => "next phase formula",
+            Action::CycleIdle => "next center animation",
+            Action::CyclePalette => "next palette",
+            Action::RestoreLayout => "restore the recovered layout",
+            Action::DiscardLayout => "discard the recovered layout",
+            Action::CycleDialect => "offer the layout in another dialect",
+            Action::ApplyDialect => "switch to the offered dialect",
+            Action::CancelDialect => "keep the current dialect",
+            Action::Toggle3d => "3D / flat",
+            Action::ToggleOrbit => "orbit around the table on its own (3D)",
+            Action::CycleBackground => "next background",
+            Action::ResetView => "reset the view after panning / zooming",
+            Action::FollowNext => "follow the next circle with the camera",
+            Action::ToggleFullscreen => "fullscreen / windowed (also Alt+Enter)",
+            Action::TogglePaint => "paint cells with the mouse / stop painting",
+            Action::ToggleDiagnostics => "show / hide frame rate, draw calls and counters",
+            Action::ToggleCleanView => "hide every window, overlay and debug drawing / bring them back",
+            Action::NextLayoutFile => "switch to the next layout file in the folder of the current one",
+            Action::ToggleLayer(layer) => match layer {
+                Layer::Background => "show / hide the background layer",
+                Layer::Veil => "show / hide the feedback layer",
+                Layer::Track => "show / hide the track layer",
+                Layer::Trails => "show / hide the trails layer",
+                Layer::Circles => "show / hide the circles layer",
+                Layer::Text => "show / hide the text layer",
+                Layer::Debug => "show / hide the debug layer",
+            },
+        }
+    }
+}
+
+/// Action -> key, in the order they are listed in the help.
+#[derive(Resource, Debug, Clone)]
+pub struct InputMap(pub Vec<(Action, KeyCode)>);
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputMap(vec![
+            (Action::ToggleHelp, KeyCode::F1),
+            (Action::Pause, KeyCode::Space),
+            (Action::StepForward, KeyCode::Period),
+            (Action::StepBack, KeyCode::Comma),
+            (Action::SpeedUp, KeyCode::ArrowUp),
+            (Action::SpeedDown, KeyCode::ArrowDown),
+            (Action::Reverse, KeyCode::KeyR),
+            (Action::ToggleClock, KeyCode::KeyK),
+            (Action::TogglePreview, KeyCode::KeyV),
+            (Action::ToggleTrails, KeyCode::KeyL),
+            (Action::ToggleTrack, KeyCode::KeyG),
+            (Action::ToggleArrows, KeyCode::KeyA),
+            (Action::ToggleLabels, KeyCode::KeyX),
+            (Action::TapTempo, KeyCode::KeyT),
+            (Action::CycleEasing, KeyCode::KeyE),
+            (Action::CycleCornerStyle, KeyCode::KeyC),
+            (Action::CycleStepping, KeyCode::KeyS),
+            (Action::CyclePhaseFormula, KeyCode::KeyP),
+            (Action::CycleIdle, KeyCode::KeyI),
+            (Action::CyclePalette, KeyCode::KeyO),
+            (Action::RestoreLayout, KeyCode::KeyY),
+            (Action::DiscardLayout, KeyCode::KeyN),
+            (Action::CycleDialect, KeyCode::KeyD),
+            (Action::ApplyDialect, KeyCode::Enter),
+            (Action::CancelDialect, KeyCode::Escape),
+            (Action::Toggle3d, KeyCode::Digit3),
+            (Action::ToggleOrbit, KeyCode::Digit4),
+            (Action::CycleBackground, KeyCode::KeyB),
+            (Action::ResetView, KeyCode::KeyH),
+            (Action::FollowNext, KeyCode::Tab),
+            (Action::ToggleFullscreen, KeyCode::F11),
+            (Action::TogglePaint, KeyCode::KeyM),
+            (Action::ToggleDiagnostics, KeyCode::F9),
+            (Action::ToggleCleanView, KeyCode::F12),
+            (Action::NextLayoutFile, KeyCode::KeyF),
+            (Action::ToggleLayer(Layer::Background), KeyCode::F2),
+            (Action::ToggleLayer(Layer::Veil), KeyCode::F3),
+            (Action::ToggleLayer(Layer::Track), KeyCode::F4),
+            (Action::ToggleLayer(Layer::Trails), KeyCode::F5),
+            (Action::ToggleLayer(Layer::Circles), KeyCode::F6),
+            (Action::ToggleLayer(Layer::Text), KeyCode::F7),
+            (Action::ToggleLayer(Layer::Debug), KeyCode::F8),
+        ])
+    }
+}
+
+impl InputMap {
+    pub fn key(&self, action: Action) -> Option<KeyCode> {
+        self.0.iter().find(|(a, _)| *a == action).map(|(_, key)| *key)
+    }
+
+    /// Move the actions of `bindings` to their new keys; the others keep
+    /// theirs.
+    pub fn rebind(&mut self, bindings: &Bindings) {
+        for (action, key) in bindings {
+            match self.0.iter_mut().find(|(a, _)| a == action) {
+                Some((_, bound)) => *bound = *key,
+                None => self.0.push((*action, *key)),
+            }
+        }
+    }
+
+    /// Keys bound to more than one action, in the order of the help.
+    pub fn conflicts(&self) -> Vec<KeyCode> {
+        let mut seen = Vec::new();
+        let mut conflicts = Vec::new();
+        for (_, key) in &self.0 {
+            if seen.contains(key) && !conflicts.contains(key) {
+                conflicts.push(*key);
+            }
+            seen.push(*key);
+        }
+        conflicts
+    }
+}
+
+/// Action -> key, as read from a bindings file.
+pub type Bindings = HashMap<Action, KeyCode>;
+
+/// `Ok(None)` when there is no bindings file.
+pub fn read_bindings(path: &Path) -> io::Result<Option<Result<Bindings, ron::error::SpannedError>>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(ron::from_str(&text))),
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Human readable key name, e.g. `KeyR` -> `R`.
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    for prefix in ["Key", "Digit", "Arrow"] {
+        if let Some(rest) = name.strip_prefix(prefix) {
+            return rest.to_string();
+        }
+    }
+    name
+}
+
+/// What the egui panel (with the `egui` feature) takes from the mouse and
+/// the keyboard; painting, the camera and the hotkeys leave it alone.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct UiFocus {
+    /// the pointer is over a window or dragging one of its widgets
+    pub pointer: bool,
+    /// something is being typed into a text field
+    pub keyboard: bool,
+}
+
+impl UiFocus {
+    pub fn pointer(focus: Option<&UiFocus>) -> bool {
+        focus.is_some_and(|focus| focus.pointer)
+    }
+}
+
+/// Keyboard state looked up through the [`InputMap`]; nothing is pressed
+/// while egui has the keyboard.
+#[derive(SystemParam)]
+pub struct Hotkeys<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    map: Res<'w, InputMap>,
+    focus: Option<Res<'w, UiFocus>>,
+}
+
+impl Hotkeys<'_> {
+    fn typing(&self) -> bool {
+        self.focus.as_ref().is_some_and(|focus| focus.keyboard)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        !self.typing() && self.map.key(action).is_some_and(|key| self.keys.just_pressed(key))
+    }
+
+    /// Alt+Enter, which goes fullscreen whatever Enter is bound to.
+    pub fn alt_enter(&self) -> bool {
+        !self.typing()
+            && self.keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+            && self.keys.just_pressed(KeyCode::Enter)
+    }
+}
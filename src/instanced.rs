@@ -0,0 +1,390 @@
+//! Instanced rendering for very large tables: instead of an entity per
+//! circle, one entity holds every circle of the layout and they are drawn
+//! as instances of a single mesh, in one draw call, from a buffer of
+//! positions, sizes and colors rewritten each frame.
+//!
+//! Opt-in with [`Instanced`]. Cells oscillate along their tracks, in the
+//! colors of the [`Palette`] (or of a
+//! [`ColorMode`](crate::color_mode::ColorMode)) and the shape of
+//! `CellsParam::shape`; what works on circle entities (agents, opacity,
+//! glow, trails, springs, ...) isn't drawn.
+
+use bevy::{
+    asset::embedded_asset,
+    core_pipeline::core_2d::Transparent2d,
+    ecs::{
+        query::QueryItem,
+        system::{lifetimeless::*, SystemParamItem},
+    },
+    math::FloatOrd,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        mesh::{allocator::MeshAllocator, MeshVertexBufferLayoutRef, RenderMesh, RenderMeshBufferInfo},
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+            RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::RenderDevice,
+        sync_world::MainEntity,
+        view::ExtractedView,
+        Render, RenderApp, RenderSet,
+    },
+    sprite::{Mesh2dPipeline, Mesh2dPipelineKey, SetMesh2dViewBindGroup},
+};
+
+use crate::{
+    cell_circles, cell_offset,
+    clock::{MotionPhase, SimClock},
+    direction::FlowDirection,
+    gpu_motion::GpuMoved,
+    gravity::Gravity,
+    layers::Layer,
+    palette::Palette,
+    ring::Rings,
+    tween::SizeTween,
+    Cell, CellsParam,
+};
+
+const SHADER_PATH: &str = "embedded://moving_cells_01/shaders/instanced.wgsl";
+
+/// Opt-in: draw the layout instanced rather than spawning its circles.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Instanced;
+
+/// What the shader gets for one circle.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct CircleInstance {
+    pub position: Vec3,
+    /// scale of the mesh
+    pub size: f32,
+    pub color: LinearRgba,
+}
+
+impl CircleInstance {
+    fn to_array(self) -> [f32; 8] {
+        let [x, y, z] = self.position.to_array();
+        let [r, g, b, a] = self.color.to_f32_array();
+        [x, y, z, self.size, r, g, b, a]
+    }
+}
+
+/// The circles of the layout, drawn instanced.
+#[derive(Component, Clone, Default)]
+pub struct InstancedCells {
+    pub cells: Vec<Cell>,
+}
+
+/// The mesh every circle is drawn with, and where and how each one is
+/// drawn this frame; parallel to [`InstancedCells::cells`].
+#[derive(Component, Clone, Debug, Default)]
+pub struct CircleInstances {
+    pub mesh: Handle<Mesh>,
+    pub instances: Vec<CircleInstance>,
+}
+
+impl ExtractComponent for CircleInstances {
+    type QueryData = &'static CircleInstances;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(item: QueryItem<'_, Self::QueryData>) -> Option<Self> {
+        Some(item.clone())
+    }
+}
+
+pub struct InstancedPlugin;
+
+impl Plugin for InstancedPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/instanced.wgsl");
+
+        app
+            .add_plugins(ExtractComponentPlugin::<CircleInstances>::default())
+            .add_systems(
+                Update,
+                (build_instances, move_instances)
+                    .chain()
+                    .after(crate::direction::toggle_direction)
+                    .run_if(resource_exists::<Instanced>),
+            );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_command::<Transparent2d, DrawInstanced>()
+            .init_resource::<SpecializedMeshPipelines<InstancedPipeline>>()
+            .add_systems(
+                Render,
+                (
+                    queue_instanced.in_set(RenderSet::QueueMeshes),
+                    prepare_instance_buffers.in_set(RenderSet::PrepareResources),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<InstancedPipeline>();
+    }
+}
+
+/// The circles of the whole layout, as [`spawn_cells`](crate::spawn_cells)
+/// would spawn them for oscillating cells.
+pub fn layout_cells(cells_param: &CellsParam) -> Vec<Cell> {
+    let table = &cells_param.cell_table;
+    (0..table.height)
+        .flat_map(|iy| (0..table.width).map(move |ix| (ix, iy)))
+        .flat_map(|(ix, iy)| cell_circles(cells_param, ix, iy))
+        .collect()
+}
+
+/// The color of the circles of `cell`.
+pub fn cell_color(cell: &Cell, cells_param: &CellsParam, palette: Option<&Palette>, rings: &Rings) -> Color {
+    cells_param
+        .color_mode
+        .color(cell.move_type)
+        .or_else(|| palette.map(|palette| palette.color(cell.grid, rings)))
+        .unwrap_or(Color::WHITE)
+}
+
+/// Lay the circles out again (and recolor them) whenever the parameters,
+/// the palette or the rings change.
+pub fn build_instances(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    palette: Option<Res<Palette>>,
+    rings: Res<Rings>,
+    mut batches: Query<(&mut InstancedCells, &mut CircleInstances)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let stale = cells_param.is_changed() || rings.is_changed() || palette.as_ref().is_some_and(|p| p.is_changed());
+    if !stale && !batches.is_empty() {
+        return;
+    }
+    let cells = layout_cells(&cells_param);
+    let instances = cells
+        .iter()
+        .map(|cell| CircleInstance {
+            position: cell.pos.extend(Layer::Circles.z()),
+            // circles shrink with their region
+            size: cells_param.cell_table.scale(cell.grid.x as usize, cell.grid.y as usize).min_element(),
+            color: cell_color(cell, &cells_param, palette.as_deref(), &rings).to_linear(),
+        })
+        .collect();
+    let mesh = cells_param.shape.mesh(cells_param.circle_size);
+    match batches.single_mut() {
+        Ok((mut batch, mut drawn)) => {
+            meshes.insert(drawn.mesh.id(), mesh);
+            batch.cells = cells;
+            drawn.instances = instances;
+        }
+        Err(_) => {
+            commands.spawn((
+                InstancedCells { cells },
+                CircleInstances { mesh: meshes.add(mesh), instances },
+            ));
+        }
+    }
+}
+
+/// Move every circle along its path, as [`move_cells`](crate::move_cells)
+/// moves circle entities.
+pub fn move_instances(
+    clock: Res<SimClock>,
+    phase: Res<MotionPhase>,
+    cells_param: Res<CellsParam>,
+    flow: Res<FlowDirection>,
+    tween: Option<Res<SizeTween>>,
+    mut batches: Query<(&InstancedCells, &mut CircleInstances)>,
+) {
+    let rate = phase.rate();
+    let secs = clock.elapsed_secs_f64() as f32;
+    let cell_size = tween.map_or(cells_param.cell_size, |tween| tween.cell_size(&cells_param));
+    let wells = match cells_param.gravity {
+        Some(_) => Gravity::wells(&cells_param, cell_size),
+        None => Vec::new(),
+    };
+    for (batch, mut drawn) in batches.iter_mut() {
+        for (cell, instance) in batch.cells.iter().zip(drawn.instances.iter_mut()) {
+            let offset = cell_offset(cell, &cells_param, &flow, rate, secs, &wells, cell_size);
+            instance.position = (cell.pos + offset).extend(instance.position.z);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_instanced(
+    draw_functions: Res<DrawFunctions<Transparent2d>>,
+    pipeline: Res<InstancedPipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<InstancedPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    meshes: Res<RenderAssets<RenderMesh>>,
+    batches: Query<(Entity, &MainEntity, &CircleInstances)>,
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
+    views: Query<(&ExtractedView, &Msaa)>,
+) {
+    let draw_function = draw_functions.read().id::<DrawInstanced>();
+    for (view, msaa) in &views {
+        let Some(phase) = phases.get_mut(&view.retained_view_entity) else {
+            continue;
+        };
+        let view_key = Mesh2dPipelineKey::from_msaa_samples(msaa.samples())
+            | Mesh2dPipelineKey::from_hdr(view.hdr)
+            | Mesh2dPipelineKey::BLEND_ALPHA;
+        for (entity, main_entity, batch) in &batches {
+            let Some(mesh) = meshes.get(batch.mesh.id()) else {
+                continue;
+            };
+            let key = view_key | Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology());
+            let Ok(pipeline) = pipelines.specialize(&pipeline_cache, &pipeline, key, &mesh.layout) else {
+                continue;
+            };
+            phase.add(Transparent2d {
+                sort_key: FloatOrd(Layer::Circles.z()),
+                entity: (entity, *main_entity),
+                pipeline,
+                draw_function,
+                batch_range: 0..1,
+                extracted_index: usize::MAX,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: mesh.indexed(),
+            });
+        }
+    }
+}
+
+/// The instances of a batch on the GPU.
+#[derive(Component)]
+pub(crate) struct InstanceBuffer {
+    pub(crate) buffer: Buffer,
+    pub(crate) length: usize,
+}
+
+fn prepare_instance_buffers(
+    mut commands: Commands,
+    batches: Query<(Entity, &CircleInstances), Without<GpuMoved>>,
+    render_device: Res<RenderDevice>,
+) {
+    for (entity, batch) in &batches {
+        let contents = batch
+            .instances
+            .iter()
+            .flat_map(|instance| instance.to_array())
+            .flat_map(f32::to_ne_bytes)
+            .collect::<Vec<u8>>();
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("circle_instance_buffer"),
+            contents: &contents,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        });
+        commands.entity(entity).insert(InstanceBuffer { buffer, length: batch.instances.len() });
+    }
+}
+
+#[derive(Resource)]
+struct InstancedPipeline {
+    shader: Handle<Shader>,
+    mesh2d_pipeline: Mesh2dPipeline,
+}
+
+impl FromWorld for InstancedPipeline {
+    fn from_world(world: &mut World) -> Self {
+        InstancedPipeline {
+            shader: world.load_asset(SHADER_PATH),
+            mesh2d_pipeline: world.resource::<Mesh2dPipeline>().clone(),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for InstancedPipeline {
+    type Key = Mesh2dPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh2d_pipeline.specialize(key, layout)?;
+        descriptor.label = Some("instanced_circles_pipeline".into());
+        // only the view: positions come with the instances
+        descriptor.layout = vec![self.mesh2d_pipeline.view_layout.clone()];
+        descriptor.vertex.shader = self.shader.clone();
+        descriptor.vertex.buffers.push(VertexBufferLayout {
+            array_stride: VertexFormat::Float32x4.size() * 2,
+            step_mode: VertexStepMode::Instance,
+            attributes: vec![
+                // clear of the mesh attributes, whichever the shape has
+                VertexAttribute { format: VertexFormat::Float32x4, offset: 0, shader_location: 8 },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VertexFormat::Float32x4.size(),
+                    shader_location: 9,
+                },
+            ],
+        });
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader = self.shader.clone();
+        }
+        Ok(descriptor)
+    }
+}
+
+type DrawInstanced = (SetItemPipeline, SetMesh2dViewBindGroup<0>, DrawCircleInstances);
+
+struct DrawCircleInstances;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawCircleInstances {
+    type Param = (SRes<RenderAssets<RenderMesh>>, SRes<MeshAllocator>);
+    type ViewQuery = ();
+    type ItemQuery = (Read<CircleInstances>, Read<InstanceBuffer>);
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: Option<(&'w CircleInstances, &'w InstanceBuffer)>,
+        (meshes, mesh_allocator): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let mesh_allocator = mesh_allocator.into_inner();
+        let Some((batch, instance_buffer)) = batch else {
+            return RenderCommandResult::Skip;
+        };
+        let mesh_id = batch.mesh.id();
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_id) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(vertex_slice) = mesh_allocator.mesh_vertex_slice(&mesh_id) else {
+            return RenderCommandResult::Skip;
+        };
+        if instance_buffer.length == 0 {
+            return RenderCommandResult::Skip;
+        }
+
+        pass.set_vertex_buffer(0, vertex_slice.buffer.slice(..));
+        pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+        let instances = 0..instance_buffer.length as u32;
+        match &gpu_mesh.buffer_info {
+            RenderMeshBufferInfo::Indexed { index_format, count } => {
+                let Some(index_slice) = mesh_allocator.mesh_index_slice(&mesh_id) else {
+                    return RenderCommandResult::Skip;
+                };
+                pass.set_index_buffer(index_slice.buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(
+                    index_slice.range.start..(index_slice.range.start + count),
+                    vertex_slice.range.start as i32,
+                    instances,
+                );
+            }
+            RenderMeshBufferInfo::NonIndexed => {
+                pass.draw(vertex_slice.range, instances);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
@@ -0,0 +1,143 @@
+//! The entrance: once loaded, circles fade and scale in one after another
+//! instead of the whole grid popping in at once.
+
+use bevy::prelude::*;
+
+use crate::{
+    agent::Agent,
+    materials::{paint, SharedMaterials},
+    AssetLoadingState, Cell, CellsParam, Circles,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum IntroOrder {
+    /// top row first
+    Rows,
+    /// from the middle of the table outwards
+    #[default]
+    Radial,
+}
+
+/// Remove (or insert before the plugin) to change or skip the entrance.
+#[derive(Resource, Clone, Debug)]
+pub struct Intro {
+    pub order: IntroOrder,
+    /// between one row (or one cell of distance) and the next
+    pub stagger_sec: f32,
+    /// of every circle's own fade
+    pub duration_sec: f32,
+}
+
+impl Default for Intro {
+    fn default() -> Self {
+        Intro {
+            order: IntroOrder::Radial,
+            stagger_sec: 0.08,
+            duration_sec: 0.4,
+        }
+    }
+}
+
+impl Intro {
+    /// When the circle at `grid` starts to come in.
+    pub fn delay(&self, grid: UVec2, cells_param: &CellsParam) -> f32 {
+        let steps = match self.order {
+            IntroOrder::Rows => grid.y as f32,
+            IntroOrder::Radial => {
+                let table = &cells_param.cell_table;
+                let middle = Vec2::new(table.width as f32 - 1.0, table.height as f32 - 1.0) / 2.0;
+                grid.as_vec2().distance(middle)
+            }
+        };
+        steps * self.stagger_sec
+    }
+}
+
+/// A circle still coming in.
+///
+/// Its scale is only shrunk for rendering: the full scale is put back in
+/// `First`, so systems that set or modulate the scale never see the intro's.
+#[derive(Component, Debug)]
+pub struct Entering {
+    /// seconds left before it starts
+    pub delay: f32,
+    elapsed: f32,
+    /// alpha it ends up at, taken on the first frame
+    alpha: Option<f32>,
+    unscaled: Option<Vec3>,
+}
+
+impl Entering {
+    pub fn new(delay: f32) -> Self {
+        Entering { delay, elapsed: 0.0, alpha: None, unscaled: None }
+    }
+}
+
+pub struct IntroPlugin;
+
+impl Plugin for IntroPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Intro>()
+            .add_systems(
+                OnEnter(AssetLoadingState::Loaded),
+                begin_intro.after(crate::setup).run_if(resource_exists::<Intro>),
+            )
+            .add_systems(First, unscale_entering)
+            .add_systems(PostUpdate, play_intro.before(TransformSystem::TransformPropagate));
+    }
+}
+
+pub fn begin_intro(
+    mut commands: Commands,
+    intro: Res<Intro>,
+    cells_param: Res<CellsParam>,
+    circles: Query<(Entity, Option<&Cell>, Option<&Agent>), Circles>,
+) {
+    for (entity, cell, agent) in circles.iter() {
+        let grid = cell.map_or_else(|| agent.map_or(UVec2::ZERO, |agent| agent.grid), |cell| cell.grid);
+        commands.entity(entity).insert(Entering::new(intro.delay(grid, &cells_param)));
+    }
+}
+
+pub fn unscale_entering(mut circles: Query<(&mut Entering, &mut Transform)>) {
+    for (mut entering, mut transform) in circles.iter_mut() {
+        if let Some(scale) = entering.unscaled.take() {
+            transform.scale = scale;
+        }
+    }
+}
+
+pub fn play_intro(
+    mut commands: Commands,
+    time: Res<Time>,
+    intro: Option<Res<Intro>>,
+    mut circles: Query<(Entity, &mut Entering, &mut Transform, &mut MeshMaterial2d<ColorMaterial>)>,
+    mut shared: ResMut<SharedMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let duration = intro.map_or(0.0, |intro| intro.duration_sec);
+    for (entity, mut entering, mut transform, mut material) in circles.iter_mut() {
+        let dt = time.delta_secs();
+        if entering.delay > 0.0 {
+            entering.delay -= dt;
+        } else {
+            entering.elapsed += dt;
+        }
+        let s = if duration > 0.0 { (entering.elapsed / duration).min(1.0) } else { 1.0 };
+        // ease out, so circles pop up quickly and settle
+        let s = 1.0 - (1.0 - s) * (1.0 - s);
+
+        let color = materials.get(&material.0).map(|m| m.color);
+        let alpha = *entering.alpha.get_or_insert_with(|| color.map_or(1.0, |c| c.alpha()));
+        if let Some(color) = color {
+            paint(&mut material, color.with_alpha(alpha * s), &mut shared, &mut materials);
+        }
+        if s >= 1.0 {
+            commands.entity(entity).remove::<Entering>();
+        } else {
+            entering.unscaled = Some(transform.scale);
+            transform.scale *= s;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Debug overlay for authoring tables: the character each cell was read from
+//! and its grid coordinates, at the top of the cell, so a glyph behaving
+//! differently on screen than intended is easy to trace back to the text.
+
+use bevy::prelude::*;
+
+use crate::{
+    capability::{self, Capability},
+    layers::{Layer, OnLayer},
+    tween::SizeTween,
+    CellsParam,
+};
+
+const FONT_SIZE: f32 = 9.0;
+const COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.7);
+
+/// The label of the cell at `grid` (y down).
+#[derive(Component, Debug)]
+pub struct CellLabel {
+    pub grid: UVec2,
+}
+
+pub struct CellLabelsPlugin;
+
+impl Plugin for CellLabelsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, sync_labels.run_if(capability::enabled(Capability::Labels)))
+            .add_systems(Update, clear_labels.run_if(not(capability::enabled(Capability::Labels))));
+    }
+}
+
+/// `c` and the coordinates of `grid`, one above the other.
+pub fn label_text(c: char, grid: UVec2) -> String {
+    format!("{}\n{},{}", c, grid.x, grid.y)
+}
+
+/// (Re)spawn a label on every non-blank cell when the layout or its size
+/// changes.
+pub fn sync_labels(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    labels: Query<Entity, With<CellLabel>>,
+) {
+    let resized = tween.as_ref().is_some_and(|tween| tween.is_changed());
+    if !cells_param.is_changed() && !resized && !labels.is_empty() {
+        return;
+    }
+    for entity in labels.iter() {
+        commands.entity(entity).despawn();
+    }
+    let cell_size = tween.as_ref().map_or(cells_param.cell_size, |tween| tween.cell_size(&cells_param));
+    let table = &cells_param.cell_table;
+    for iy in 0..table.height {
+        for ix in 0..table.width {
+            let c = table.get(ix, iy);
+            if c == ' ' {
+                continue;
+            }
+            let grid = UVec2::new(ix as u32, iy as u32);
+            let half = cells_param.cell_extent_at(ix, iy, cell_size) / 2.0;
+            let pos = cells_param.cell_center_at(ix, iy, cell_size) + Vec2::new(0.0, half.y - FONT_SIZE);
+            commands.spawn((
+                Text2d::new(label_text(c, grid)),
+                TextFont { font_size: FONT_SIZE, ..default() },
+                TextColor(COLOR),
+                Transform::from_translation(pos.extend(Layer::Debug.z())),
+                CellLabel { grid },
+                OnLayer(Layer::Debug),
+            ));
+        }
+    }
+}
+
+fn clear_labels(mut commands: Commands, labels: Query<Entity, With<CellLabel>>) {
+    for entity in labels.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,105 @@
+//! Render layers: everything drawn in 2D belongs to a [`Layer`], which puts
+//! it at a fixed depth, back to front, and can be hidden on its own.
+//!
+//! Entities are tagged with [`OnLayer`] where they are spawned and placed at
+//! [`Layer::z`]; within a layer, depth is left to its owner (trail ghosts
+//! step back behind their circles, translucent rings are stacked by
+//! [`opacity`](crate::opacity)), always by less than the gap to the next
+//! layer.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::{Action, Hotkeys};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Layer {
+    /// the gradient quad of the background
+    Background,
+    /// what feedback leaves behind: the veil fading it, the picture
+    /// accumulated by the feedback buffer
+    Veil,
+    /// lines along the paths
+    Track,
+    /// ghosts behind the circles
+    Trails,
+    Circles,
+    /// marquee glyphs
+    Text,
+    /// labels for authoring tables
+    Debug,
+}
+
+impl Layer {
+    /// Back to front.
+    pub const ALL: [Layer; 7] =
+        [Layer::Background, Layer::Veil, Layer::Track, Layer::Trails, Layer::Circles, Layer::Text, Layer::Debug];
+
+    /// Depth of the layer; the camera sees -1000..1000.
+    pub fn z(self) -> f32 {
+        match self {
+            Layer::Background => -999.9,
+            Layer::Veil => -999.0,
+            Layer::Track => -0.5,
+            // just behind the circles (see trails)
+            Layer::Trails => -0.001,
+            Layer::Circles => 0.0,
+            Layer::Text => 1.0,
+            Layer::Debug => 10.0,
+        }
+    }
+}
+
+/// What layer an entity is drawn on.
+#[derive(Component, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct OnLayer(pub Layer);
+
+/// The layers switched off.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct HiddenLayers(pub HashSet<Layer>);
+
+impl HiddenLayers {
+    pub fn toggle(&mut self, layer: Layer) {
+        if !self.0.remove(&layer) {
+            self.0.insert(layer);
+        }
+        println!("{:?} layer: {}", layer, if self.0.contains(&layer) { "hidden" } else { "shown" });
+    }
+}
+
+pub struct LayersPlugin;
+
+impl Plugin for LayersPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<HiddenLayers>()
+            .add_systems(PostUpdate, (toggle_layers, show_layers).chain());
+    }
+}
+
+fn toggle_layers(hotkeys: Hotkeys, mut hidden: ResMut<HiddenLayers>) {
+    for layer in Layer::ALL {
+        if hotkeys.just_pressed(Action::ToggleLayer(layer)) {
+            hidden.toggle(layer);
+        }
+    }
+}
+
+/// Hide or show everything on a layer when the hidden layers change, and
+/// whatever was spawned onto a hidden one.
+pub fn show_layers(
+    hidden: Res<HiddenLayers>,
+    mut entities: Query<(Ref<OnLayer>, &mut Visibility)>,
+) {
+    for (layer, mut visibility) in entities.iter_mut() {
+        if !(hidden.is_changed() || layer.is_added()) {
+            continue;
+        }
+        let shown = if hidden.0.contains(&layer.0) { Visibility::Hidden } else { Visibility::Inherited };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Layouts loaded from text files. Every layout file remembers its own
+//! parameter tweaks in a sidecar RON file next to it (`loop.txt` ->
+//! `loop.tweaks.ron`), restored whenever that layout is loaded again.
+//!
+//! Send [`LoadLayoutFile`] to switch layouts, storing the tweaks of the
+//! outgoing one; the F key goes to the next layout file in the folder of the
+//! current one, and the egui panel (with the `egui` feature) lists them.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    easing::Easing,
+    idle::Idle,
+    input::{Action, Hotkeys},
+    palette::{presets, Palette},
+    recovery::EditorSession,
+    tempo::Tempo,
+    CellTable, CellsParam, CornerStyle, LayoutError, PhaseFormula, RespawnCells, Stepping,
+};
+
+/// The parameters that are tuned per layout.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LayoutTweaks {
+    pub easing: Easing,
+    pub corner_style: CornerStyle,
+    pub stepping: Stepping,
+    pub phase_formula: PhaseFormula,
+    pub idle: Idle,
+    pub circle_size: f32,
+    pub bpm: f32,
+    /// name of a preset palette
+    pub palette: String,
+}
+
+impl LayoutTweaks {
+    pub fn capture(cells_param: &CellsParam, tempo: &Tempo, palette: &Palette) -> Self {
+        LayoutTweaks {
+            easing: cells_param.easing,
+            corner_style: cells_param.corner_style,
+            stepping: cells_param.stepping,
+            phase_formula: cells_param.phase_formula,
+            idle: cells_param.idle,
+            circle_size: cells_param.circle_size,
+            bpm: tempo.bpm,
+            palette: palette.name.clone(),
+        }
+    }
+
+    /// Set the tweaks; a palette that is no preset (any more) is left as it is.
+    pub fn apply(&self, cells_param: &mut CellsParam, tempo: &mut Tempo, palette: &mut Palette) {
+        cells_param.easing = self.easing;
+        cells_param.corner_style = self.corner_style;
+        cells_param.stepping = self.stepping;
+        cells_param.phase_formula = self.phase_formula;
+        cells_param.idle = self.idle;
+        cells_param.circle_size = self.circle_size;
+        tempo.bpm = self.bpm;
+        if palette.name != self.palette {
+            if let Some(preset) = presets().into_iter().find(|p| p.name == self.palette) {
+                *palette = preset;
+            }
+        }
+    }
+}
+
+/// Where the tweaks of the layout in `layout` are kept.
+pub fn sidecar_path(layout: &Path) -> PathBuf {
+    layout.with_extension("tweaks.ron")
+}
+
+/// A layout as written by hand: rows of glyphs, trimmed like [`CellTable::parse`].
+pub fn read_layout(path: &Path) -> io::Result<Result<CellTable, LayoutError>> {
+    Ok(CellTable::parse(&fs::read_to_string(path)?))
+}
+
+/// The layout files (`.txt`) in `dir`, by name.
+pub fn list_layouts(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut layouts = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            layouts.push(path);
+        }
+    }
+    layouts.sort();
+    Ok(layouts)
+}
+
+/// Where the layout files next to `file` (the working directory without
+/// one) are.
+pub fn layout_dir(file: Option<&LayoutFile>) -> PathBuf {
+    match file.and_then(|file| file.path.parent()) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+pub fn write_tweaks(path: &Path, tweaks: &LayoutTweaks) -> io::Result<()> {
+    let text = ron::ser::to_string_pretty(tweaks, ron::ser::PrettyConfig::default()).map_err(io::Error::other)?;
+    fs::write(path, text)
+}
+
+/// `Ok(None)` when the layout hasn't been tweaked yet.
+pub fn read_tweaks(path: &Path) -> io::Result<Option<Result<LayoutTweaks, ron::error::SpannedError>>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(ron::from_str(&text))),
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The file the current layout was loaded from.
+#[derive(Resource, Clone, Debug)]
+pub struct LayoutFile {
+    pub path: PathBuf,
+}
+
+/// Switch to the layout in a file, saving the tweaks of the current one.
+#[derive(Event, Clone, Debug)]
+pub struct LoadLayoutFile(pub PathBuf);
+
+/// Tweaks as last read from or written to the sidecar of the [`LayoutFile`].
+#[derive(Resource, Default, Debug)]
+struct SavedTweaks {
+    tweaks: Option<LayoutTweaks>,
+    since_save: f32,
+}
+
+/// Seconds between two writes of the sidecar; LFOs may change the tweaked
+/// values every frame.
+const SAVE_INTERVAL_SEC: f32 = 2.0;
+
+pub struct LayoutFilePlugin;
+
+impl Plugin for LayoutFilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SavedTweaks>()
+            .add_event::<LoadLayoutFile>()
+            .add_systems(Startup, restore_tweaks.run_if(resource_exists::<LayoutFile>))
+            .add_systems(
+                Update,
+                (next_layout_file, load_layout_files, save_tweaks.run_if(resource_exists::<LayoutFile>)).chain(),
+            )
+            .add_systems(Last, save_tweaks_on_exit.run_if(resource_exists::<LayoutFile>));
+    }
+}
+
+/// Apply the tweaks saved next to `layout`, if there are any.
+fn restore(layout: &Path, saved: &mut SavedTweaks, cells_param: &mut CellsParam, tempo: &mut Tempo, palette: &mut Palette) {
+    saved.since_save = 0.0;
+    let path = sidecar_path(layout);
+    match read_tweaks(&path) {
+        Ok(Some(Ok(tweaks))) => {
+            tweaks.apply(cells_param, tempo, palette);
+            println!("restored the tweaks of {}", layout.display());
+        }
+        Ok(None) => {}
+        Ok(Some(Err(e))) => println!("ignoring broken tweaks in {}: {}", path.display(), e),
+        Err(e) => println!("could not read tweaks from {}: {}", path.display(), e),
+    }
+    // nothing to write until something changes
+    saved.tweaks = Some(LayoutTweaks::capture(cells_param, tempo, palette));
+}
+
+/// Write the tweaks next to `layout` if they changed since they were last
+/// read or written.
+fn store(layout: &Path, saved: &mut SavedTweaks, tweaks: LayoutTweaks) {
+    saved.since_save = 0.0;
+    if saved.tweaks.as_ref() == Some(&tweaks) {
+        return;
+    }
+    let path = sidecar_path(layout);
+    if let Err(e) = write_tweaks(&path, &tweaks) {
+        println!("could not write tweaks to {}: {}", path.display(), e);
+    }
+    saved.tweaks = Some(tweaks);
+}
+
+fn restore_tweaks(
+    file: Res<LayoutFile>,
+    mut saved: ResMut<SavedTweaks>,
+    mut cells_param: ResMut<CellsParam>,
+    mut tempo: ResMut<Tempo>,
+    mut palette: ResMut<Palette>,
+) {
+    restore(&file.path, &mut saved, &mut cells_param, &mut tempo, &mut palette);
+}
+
+/// The layout file after the current one, wrapping around.
+fn next_layout_file(hotkeys: Hotkeys, file: Option<Res<LayoutFile>>, mut load: EventWriter<LoadLayoutFile>) {
+    if !hotkeys.just_pressed(Action::NextLayoutFile) {
+        return;
+    }
+    let dir = layout_dir(file.as_deref());
+    let layouts = match list_layouts(&dir) {
+        Ok(layouts) => layouts,
+        Err(e) => {
+            println!("could not list {}: {}", dir.display(), e);
+            return;
+        }
+    };
+    // the folder is the current file's, so its name is enough
+    let current = file.and_then(|file| layouts.iter().position(|path| path.file_name() == file.path.file_name()));
+    let next = match current {
+        Some(i) => layouts.get((i + 1) % layouts.len()),
+        None => layouts.first(),
+    };
+    match next {
+        Some(path) => {
+            load.write(LoadLayoutFile(path.clone()));
+        }
+        None => println!("no layout files in {}", dir.display()),
+    }
+}
+
+fn load_layout_files(
+    mut commands: Commands,
+    mut events: EventReader<LoadLayoutFile>,
+    mut file: Option<ResMut<LayoutFile>>,
+    mut saved: ResMut<SavedTweaks>,
+    mut cells_param: ResMut<CellsParam>,
+    mut tempo: ResMut<Tempo>,
+    mut palette: ResMut<Palette>,
+    mut session: Option<ResMut<EditorSession>>,
+) {
+    for LoadLayoutFile(path) in events.read() {
+        let table = match read_layout(path) {
+            Ok(Ok(table)) => table,
+            Ok(Err(e)) => {
+                println!("{} is no layout: {}", path.display(), e);
+                continue;
+            }
+            Err(e) => {
+                println!("could not read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if let Some(file) = &file {
+            store(&file.path, &mut saved, LayoutTweaks::capture(&cells_param, &tempo, &palette));
+        }
+        cells_param.cell_table = table;
+        restore(path, &mut saved, &mut cells_param, &mut tempo, &mut palette);
+        // the layout is the file's again; nothing of it to recover
+        if let Some(session) = session.as_mut() {
+            session.mark_saved();
+        }
+        match &mut file {
+            Some(file) => file.path = path.clone(),
+            None => commands.insert_resource(LayoutFile { path: path.clone() }),
+        }
+        commands.send_event(RespawnCells);
+    }
+}
+
+fn save_tweaks(
+    time: Res<Time>,
+    file: Res<LayoutFile>,
+    mut saved: ResMut<SavedTweaks>,
+    cells_param: Res<CellsParam>,
+    tempo: Res<Tempo>,
+    palette: Res<Palette>,
+) {
+    saved.since_save += time.delta_secs();
+    if saved.since_save >= SAVE_INTERVAL_SEC {
+        store(&file.path, &mut saved, LayoutTweaks::capture(&cells_param, &tempo, &palette));
+    }
+}
+
+fn save_tweaks_on_exit(
+    mut exit: EventReader<AppExit>,
+    file: Res<LayoutFile>,
+    mut saved: ResMut<SavedTweaks>,
+    cells_param: Res<CellsParam>,
+    tempo: Res<Tempo>,
+    palette: Res<Palette>,
+) {
+    if exit.read().count() > 0 {
+        store(&file.path, &mut saved, LayoutTweaks::capture(&cells_param, &tempo, &palette));
+    }
+}
@@ -0,0 +1,108 @@
+//! Lens deformations of the whole composition: barrel, pincushion and
+//! ripples, applied to where circles are drawn.
+//!
+//! Like the intro's scale, the warp is render-only: it is applied in
+//! `PostUpdate` and taken back out in `First`, so the movers, springs and
+//! trails only ever see the undeformed layout. Opt-in by inserting [`Lens`];
+//! its `strength` can be modulated by an [`Lfo`](crate::lfo::Lfo).
+
+use std::{collections::HashMap, f32::consts::TAU};
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::{agent::Agent, clock::SimClock, trails::TrailGhost, Cell};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LensKind {
+    /// the middle bulges out, the edges are squeezed in
+    Barrel,
+    /// the middle is pinched in, the edges stretched out
+    Pincushion,
+    /// rings of displacement running out from the center
+    Ripple {
+        /// world units between two crests
+        wavelength: f32,
+        /// world units per second the crests travel outward
+        speed: f32,
+    },
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Lens {
+    pub kind: LensKind,
+    /// 0 is no deformation; for barrel and pincushion the relative change in
+    /// distance at `radius`, for ripples the displacement in world units
+    pub strength: f32,
+    pub center: Vec2,
+    /// world units; distances are measured relative to this
+    pub radius: f32,
+}
+
+impl Lens {
+    pub fn new(kind: LensKind, strength: f32) -> Self {
+        Lens { kind, strength, center: Vec2::ZERO, radius: 300.0 }
+    }
+
+    /// Where a point at `pos` is drawn, `secs` into the simulation.
+    pub fn warp(&self, pos: Vec2, secs: f32) -> Vec2 {
+        let offset = pos - self.center;
+        let r = offset.length();
+        if r == 0.0 || self.strength == 0.0 {
+            return pos;
+        }
+        let u = r / self.radius.max(f32::EPSILON);
+        let r = match self.kind {
+            LensKind::Barrel => r * (1.0 - self.strength * u * u),
+            LensKind::Pincushion => r * (1.0 + self.strength * u * u),
+            LensKind::Ripple { wavelength, speed } => {
+                let wave = ((r - speed * secs) / wavelength.max(f32::EPSILON) * TAU).sin();
+                r + self.strength * wave
+            }
+        };
+        // a strong barrel would fold the edges back through the center
+        self.center + offset.normalize() * r.max(0.0)
+    }
+}
+
+/// Undeformed translations of everything warped this frame.
+#[derive(Resource, Default)]
+struct Unwarped(HashMap<Entity, Vec3>);
+
+type Drawn = Or<(With<Cell>, With<Agent>, With<TrailGhost>)>;
+
+pub struct LensPlugin;
+
+impl Plugin for LensPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Unwarped>()
+            .add_systems(First, unwarp)
+            .add_systems(
+                PostUpdate,
+                warp.run_if(resource_exists::<Lens>).before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+fn unwarp(mut unwarped: ResMut<Unwarped>, mut drawn: Query<&mut Transform, Drawn>) {
+    for (entity, translation) in unwarped.0.drain() {
+        if let Ok(mut transform) = drawn.get_mut(entity) {
+            transform.translation = translation;
+        }
+    }
+}
+
+fn warp(
+    lens: Res<Lens>,
+    clock: Res<SimClock>,
+    mut unwarped: ResMut<Unwarped>,
+    mut drawn: Query<(Entity, &mut Transform), Drawn>,
+) {
+    let secs = clock.elapsed_secs_f64() as f32;
+    for (entity, mut transform) in drawn.iter_mut() {
+        let translation = transform.translation;
+        unwarped.0.insert(entity, translation);
+        let warped = lens.warp(translation.truncate(), secs);
+        transform.translation = warped.extend(translation.z);
+    }
+}
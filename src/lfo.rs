@@ -0,0 +1,172 @@
+//! Low frequency oscillators modulating parameters over time.
+//!
+//! Every entity with an [`Lfo`] adds its output to its target, so several
+//! LFOs can run at once (also on the same target). Offsets are applied
+//! relative to what was added last frame, so the underlying values can still
+//! be changed by hand while modulated.
+
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::TAU,
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    capability::{Capabilities, Capability},
+    clock::SimClock,
+    lens::Lens,
+    tempo::Tempo,
+    tween::SizeTween,
+    CellsParam, Circles,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Waveform {
+    Sine,
+    Triangle,
+    Square,
+}
+
+impl Waveform {
+    /// -1..1 at `t` cycles.
+    pub fn sample(self, t: f32) -> f32 {
+        let t = t.rem_euclid(1.0);
+        match self {
+            Waveform::Sine => (t * TAU).sin(),
+            // starts at 0 going up, like the sine
+            Waveform::Triangle => 1.0 - 4.0 * ((t + 0.25).rem_euclid(1.0) - 0.5).abs(),
+            Waveform::Square => if t < 0.5 { 1.0 } else { -1.0 },
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum LfoTarget {
+    /// `CellsParam::circle_size`, in pixels (agents keep their own size)
+    CircleSize,
+    /// `Tempo::bpm`; keep the depth below the base tempo
+    Bpm,
+    /// hue of every circle, in degrees
+    Hue,
+    /// `Lens::strength`, when there is a [`Lens`]
+    LensStrength,
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct Lfo {
+    pub waveform: Waveform,
+    pub rate_hz: f32,
+    /// peak offset, in the target's unit
+    pub depth: f32,
+    pub target: LfoTarget,
+    /// in cycles
+    pub phase: f32,
+}
+
+impl Lfo {
+    pub fn new(waveform: Waveform, rate_hz: f32, depth: f32, target: LfoTarget) -> Self {
+        Lfo { waveform, rate_hz, depth, target, phase: 0.0 }
+    }
+
+    pub fn value(&self, secs: f64) -> f32 {
+        let cycles = (secs * self.rate_hz as f64).rem_euclid(1.0) as f32;
+        self.depth * self.waveform.sample(cycles + self.phase)
+    }
+}
+
+/// Summed LFO output per target, and what has been applied so far.
+#[derive(Resource, Default, Debug)]
+pub struct LfoOffsets {
+    current: HashMap<LfoTarget, f32>,
+    applied: HashMap<LfoTarget, f32>,
+}
+
+impl LfoOffsets {
+    pub fn get(&self, target: LfoTarget) -> f32 {
+        self.current.get(&target).copied().unwrap_or(0.0)
+    }
+
+    /// What has been added to `target` so far.
+    pub fn applied(&self, target: LfoTarget) -> f32 {
+        self.applied.get(&target).copied().unwrap_or(0.0)
+    }
+
+    /// The change since the last call for `target`.
+    fn take_delta(&mut self, target: LfoTarget) -> f32 {
+        let current = self.get(target);
+        let applied = self.applied.insert(target, current).unwrap_or(0.0);
+        current - applied
+    }
+}
+
+pub struct LfoPlugin;
+
+impl Plugin for LfoPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<LfoOffsets>()
+            .add_systems(Update, (sum_lfos, modulate_params, modulate_hue).chain());
+    }
+}
+
+fn sum_lfos(
+    clock: Res<SimClock>,
+    capabilities: Option<Res<Capabilities>>,
+    lfos: Query<&Lfo>,
+    mut offsets: ResMut<LfoOffsets>,
+) {
+    let secs = clock.elapsed_secs_f64();
+    offsets.current.clear();
+    // switched off: the offsets are taken back out
+    if capabilities.is_some_and(|c| !c.is_enabled(Capability::Lfo)) {
+        return;
+    }
+    for lfo in lfos.iter() {
+        *offsets.current.entry(lfo.target).or_default() += lfo.value(secs);
+    }
+}
+
+fn modulate_params(
+    mut offsets: ResMut<LfoOffsets>,
+    mut cells_param: ResMut<CellsParam>,
+    mut tempo: ResMut<Tempo>,
+    tween: Option<ResMut<SizeTween>>,
+    lens: Option<ResMut<Lens>>,
+) {
+    let size = offsets.take_delta(LfoTarget::CircleSize);
+    if size != 0.0 {
+        cells_param.circle_size += size;
+        if let Some(mut tween) = tween {
+            tween.shift(Vec2::ZERO, size);
+        }
+    }
+    let bpm = offsets.take_delta(LfoTarget::Bpm);
+    if bpm != 0.0 {
+        tempo.bpm += bpm;
+    }
+    if let Some(mut lens) = lens {
+        let strength = offsets.take_delta(LfoTarget::LensStrength);
+        if strength != 0.0 {
+            lens.strength += strength;
+        }
+    }
+}
+
+fn modulate_hue(
+    mut offsets: ResMut<LfoOffsets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    circles: Query<&MeshMaterial2d<ColorMaterial>, Circles>,
+) {
+    let hue = offsets.take_delta(LfoTarget::Hue);
+    if hue == 0.0 {
+        return;
+    }
+    // circles may share materials; rotate each once
+    let ids = circles.iter().map(|material| material.0.id()).collect::<HashSet<_>>();
+    for id in ids {
+        if let Some(material) = materials.get_mut(id) {
+            material.color = material.color.rotate_hue(hue);
+        }
+    }
+}
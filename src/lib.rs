@@ -0,0 +1,1809 @@
+use bevy::{ecs::query::QueryItem, prelude::*};
+
+use bevy_asset_loader::asset_collection::AssetCollection;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "egui")]
+use bevy_egui::{egui, EguiContext, EguiContexts, EguiPlugin};
+
+pub mod agent;
+pub mod arrows;
+pub mod audio;
+pub mod background;
+pub mod calibration;
+pub mod camera;
+pub mod capability;
+pub mod clean_view;
+pub mod clock;
+pub mod clock_widget;
+pub mod color_mode;
+pub mod control_window;
+pub mod culling;
+pub mod daily;
+pub mod diagnostics;
+pub mod dialect;
+pub mod direction;
+pub mod display;
+pub mod easing;
+pub mod exposure;
+pub mod feedback;
+pub mod feedback_buffer;
+pub mod flow_field;
+pub mod glow;
+pub mod gpu_motion;
+pub mod gravity;
+pub mod help;
+pub mod idle;
+pub mod input;
+pub mod instanced;
+pub mod intro;
+pub mod labels;
+pub mod layers;
+pub mod layout_file;
+pub mod lens;
+pub mod lfo;
+pub mod marquee;
+pub mod materials;
+pub mod motion_blur;
+pub mod noise;
+pub mod opacity;
+pub mod paint;
+pub mod palette;
+pub mod palette_file;
+pub mod perf;
+pub mod playlist;
+pub mod post;
+pub mod preset;
+pub mod preview;
+pub mod recovery;
+pub mod ring;
+pub mod shading;
+pub mod shape;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod split;
+pub mod spring;
+pub mod sprites;
+pub mod tempo;
+pub mod text_editor;
+pub mod thickness;
+pub mod three_d;
+pub mod timeline;
+pub mod track;
+pub mod tokens;
+pub mod trails;
+pub mod tween;
+pub mod wall;
+
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::FRAC_PI_2,
+    sync::OnceLock,
+};
+
+use bimap::BiMap;
+use input::{Action, Hotkeys};
+
+use agent::TravelMode;
+use easing::Easing;
+
+/// Everything the artwork needs on top of `DefaultPlugins`.
+///
+/// Insert a [`CellsParam`] before adding the plugin.
+pub struct MovingCellsPlugin;
+
+impl Plugin for MovingCellsPlugin {
+    fn build(&self, app: &mut App) {
+        use bevy_asset_loader::loading_state::{config::ConfigureLoadingState, LoadingState, LoadingStateAppExt};
+
+        app
+            .init_state::<AssetLoadingState>()
+            .add_loading_state(
+                LoadingState::new(AssetLoadingState::Loading)
+                    .continue_to_state(AssetLoadingState::Loaded)
+                    .load_collection::<GltfAssets>()
+            )
+            .add_systems(Startup, spawn_loading_text)
+            .add_systems(OnEnter(AssetLoadingState::Loaded), cleanup_loading_text.before(setup))
+            .add_systems(OnEnter(AssetLoadingState::Loaded), setup)
+            .add_plugins(intro::IntroPlugin)
+            .add_plugins(display::DisplayPlugin)
+            .add_plugins(control_window::ControlWindowPlugin)
+            .init_resource::<capability::Capabilities>()
+            .add_systems(Update, capability::toggle_capabilities)
+            .init_resource::<preview::PreviewLatency>()
+            .add_plugins(preview::PreviewPlugin)
+            .add_plugins(calibration::CalibrationPlugin)
+            .add_plugins(post::PostEffectsPlugin)
+            .add_event::<agent::CellBoundaryCrossed>()
+            .add_event::<agent::LoopCompleted>()
+            .add_plugins(clock::SimClockPlugin)
+            .add_systems(Update, clock::control_sim_clock)
+            .init_resource::<input::InputMap>()
+            .init_resource::<input::UiFocus>()
+            .add_plugins(help::HelpPlugin)
+            .add_plugins(diagnostics::DiagnosticsHudPlugin)
+            .add_plugins(clean_view::CleanViewPlugin)
+            .init_resource::<tempo::TapTempo>()
+            .add_systems(Update, tempo::tap_tempo)
+            .init_resource::<direction::FlowDirection>()
+            .add_event::<direction::FlipRing>()
+            .add_systems(
+                Update,
+                (direction::toggle_direction, direction::flip_rings)
+                    .before(move_cells)
+                    .before(agent::move_agents),
+            )
+            .add_systems(Update, move_cells)
+            // agents integrate the clock's delta, so they tick along with it
+            .add_systems(Update, agent::move_agents.run_if(not(clock::fixed_timestep)))
+            .add_systems(FixedUpdate, agent::move_agents.run_if(clock::fixed_timestep))
+            .add_systems(Update, spring::apply_springs.after(move_cells).after(agent::move_agents))
+            .add_systems(Update, idle::animate_centers.after(move_cells))
+            .add_systems(Update, glow::apply_glow)
+            .add_systems(Update, opacity::apply_opacity.after(move_cells).after(agent::move_agents))
+            .add_systems(
+                Update,
+                color_mode::apply_color_mode
+                    .after(palette::cycle_palette)
+                    .after(respawn_cells)
+                    .after(direction::toggle_direction)
+                    .after(direction::flip_rings)
+                    .after(agent::move_agents),
+            )
+            .add_systems(Update, cycle_easing)
+            .add_systems(Update, cycle_corner_style)
+            .add_systems(Update, cycle_stepping)
+            .add_systems(Update, cycle_phase_formula)
+            .add_systems(Update, idle::cycle_idle)
+            .init_resource::<palette::Palette>()
+            .add_systems(Update, palette::cycle_palette)
+            .add_plugins(palette_file::PaletteFilesPlugin)
+            .add_plugins(sprites::GlyphSpritesPlugin)
+            .add_plugins(tokens::GltfTokensPlugin)
+            .add_plugins(three_d::ThreeDPlugin)
+            .add_plugins(shading::CircleShadingPlugin)
+            .add_plugins(instanced::InstancedPlugin)
+            .add_plugins(gpu_motion::GpuMotionPlugin)
+            .add_plugins(culling::CullingPlugin)
+            .add_plugins(camera::CameraPlugin)
+            .add_plugins(split::SplitScreenPlugin)
+            .add_plugins(wall::DisplayWallPlugin)
+            .add_plugins(background::BackgroundPlugin)
+            .add_event::<RespawnCells>()
+            .add_systems(Update, respawn_cells.run_if(in_state(AssetLoadingState::Loaded)))
+            .add_systems(
+                Update,
+                marquee::sync_marquee
+                    .after(respawn_cells)
+                    .run_if(resource_exists::<marquee::Marquee>)
+                    .run_if(in_state(AssetLoadingState::Loaded)),
+            )
+            .add_plugins(recovery::RecoveryPlugin)
+            .add_plugins(layout_file::LayoutFilePlugin)
+            .add_plugins(preset::PresetPlugin)
+            .add_plugins(dialect::DialectPlugin)
+            .add_plugins(text_editor::TextEditorPlugin)
+            .add_plugins(paint::PaintPlugin)
+            .init_resource::<ring::Rings>()
+            .add_systems(PreUpdate, ring::update_rings.run_if(resource_changed::<CellsParam>))
+            .add_plugins(clock_widget::ClockWidgetPlugin)
+            .add_plugins(lfo::LfoPlugin)
+            .add_plugins(timeline::TimelinePlugin)
+            .add_plugins(audio::AudioPlugin)
+            .add_plugins(playlist::PlaylistPlugin)
+            .add_plugins(trails::TrailsPlugin)
+            .add_plugins(track::TrackPlugin)
+            .add_plugins(arrows::DirectionArrowsPlugin)
+            .add_plugins(labels::CellLabelsPlugin)
+            .add_plugins(layers::LayersPlugin)
+            .add_plugins(materials::SharedMaterialsPlugin)
+            .add_plugins(lens::LensPlugin)
+            .add_plugins(motion_blur::MotionBlurPlugin)
+            .add_plugins(feedback::FeedbackPlugin)
+            .add_plugins(feedback_buffer::FeedbackBufferPlugin)
+            .init_resource::<tween::SizeTween>()
+            .add_systems(PostUpdate, (tween::tween_sizes, (sync_circle_mesh, relayout_cells)).chain())
+            ;
+
+        #[cfg(feature = "egui")]
+        app
+            .add_plugins(EguiPlugin{enable_multipass_for_primary_context: false})
+            .add_systems(
+                Update,
+                (
+                    (attach_control_context, ui_system, layout_text_ui, paint_ui, preset_ui, layouts_ui, scrubber_ui, rings_ui)
+                        .chain()
+                        .run_if(not(resource_exists::<clean_view::CleanView>)),
+                    track_ui_focus,
+                )
+                    .chain(),
+            );
+
+        // every entity, and the parameters, open to poking
+        #[cfg(feature = "inspector")]
+        app
+            .register_type::<CellsParam>()
+            .register_type::<Cell>()
+            .register_type::<MoveType>()
+            .add_plugins(
+                bevy_inspector_egui::quick::WorldInspectorPlugin::new()
+                    .run_if(not(resource_exists::<clean_view::CleanView>)),
+            )
+            .add_plugins(
+                bevy_inspector_egui::quick::ResourceInspectorPlugin::<CellsParam>::default()
+                    .run_if(not(resource_exists::<clean_view::CleanView>)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct LoadingText;
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Cell {
+    /// table coordinates (y down)
+    pub grid: UVec2,
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub move_type: MoveType,
+    /// overrides `CellsParam::easing` for this cell
+    #[reflect(ignore)]
+    pub easing: Option<Easing>,
+    /// added to `rate`, so motion can ripple across the grid
+    pub phase: f32,
+    /// added to the rate after the flow direction; keeps circles in place
+    /// when their ring is flipped on its own (see [`direction::FlipRing`])
+    pub shift: f32,
+    /// flipped an odd number of times: the easing plays backwards, so
+    /// asymmetric easings don't jump at a flip (see [`CellsParam::ease`])
+    pub mirrored: bool,
+    /// where the circle enters and leaves, from the center, in world units;
+    /// worked out when the cell is made, reversed or resized
+    path: Option<(Vec2, Vec2)>,
+}
+
+/// A cell that doesn't travel: its circle stays on the center unless the
+/// idle motion or jitter moves it (see [`CellsParam::centers_move`]), so
+/// [`move_cells`] leaves it alone otherwise.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct StaticCell;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Reflect)]
+pub enum MoveType {
+    Blank,
+    Center,
+    Left,
+    BottomToLeft,
+    TopToLeft,
+    Right,
+    BottomToRight,
+    TopToRight,
+    Up,
+    LeftToTop,
+    RightToTop,
+    Down,
+    LeftToBottom,
+    RightToBottom,
+}
+
+impl MoveType {
+    pub const ALL: [MoveType; 14] = [
+        MoveType::Blank,
+        MoveType::Center,
+        MoveType::Left,
+        MoveType::BottomToLeft,
+        MoveType::TopToLeft,
+        MoveType::Right,
+        MoveType::BottomToRight,
+        MoveType::TopToRight,
+        MoveType::Up,
+        MoveType::LeftToTop,
+        MoveType::RightToTop,
+        MoveType::Down,
+        MoveType::LeftToBottom,
+        MoveType::RightToBottom,
+    ];
+
+    /// Direction (in table coordinates, y down) of the neighbour this cell hands over to.
+    pub fn exit(self) -> Option<IVec2> {
+        match self {
+            MoveType::Blank | MoveType::Center => None,
+            MoveType::Left | MoveType::BottomToLeft | MoveType::TopToLeft => Some(IVec2::new(-1, 0)),
+            MoveType::Right | MoveType::BottomToRight | MoveType::TopToRight => Some(IVec2::new(1, 0)),
+            MoveType::Up | MoveType::LeftToTop | MoveType::RightToTop => Some(IVec2::new(0, -1)),
+            MoveType::Down | MoveType::LeftToBottom | MoveType::RightToBottom => Some(IVec2::new(0, 1)),
+        }
+    }
+
+    pub fn is_track(self) -> bool {
+        self.exit().is_some()
+    }
+
+    /// Entry and exit points on the cell edges, in half-cell units.
+    pub fn endpoints(self) -> Option<(Vec2, Vec2)> {
+        let (l, r, t, b) = (Vec2::NEG_X, Vec2::X, Vec2::Y, Vec2::NEG_Y);
+        match self {
+            MoveType::Blank | MoveType::Center => None,
+            MoveType::Left => Some((r, l)),
+            MoveType::BottomToLeft => Some((b, l)),
+            MoveType::TopToLeft => Some((t, l)),
+            MoveType::Right => Some((l, r)),
+            MoveType::BottomToRight => Some((b, r)),
+            MoveType::TopToRight => Some((t, r)),
+            MoveType::Up => Some((b, t)),
+            MoveType::LeftToTop => Some((l, t)),
+            MoveType::RightToTop => Some((r, t)),
+            MoveType::Down => Some((t, b)),
+            MoveType::LeftToBottom => Some((l, b)),
+            MoveType::RightToBottom => Some((r, b)),
+        }
+    }
+
+    /// The track with the given entry and exit points (see [`MoveType::endpoints`]).
+    pub fn from_endpoints(entry: Vec2, exit: Vec2) -> Option<MoveType> {
+        glyphs().right_values().copied().find(|m| m.endpoints() == Some((entry, exit)))
+    }
+
+    /// The same path walked the other way round.
+    pub fn reversed(self) -> MoveType {
+        match self {
+            MoveType::Blank => MoveType::Blank,
+            MoveType::Center => MoveType::Center,
+            MoveType::Left => MoveType::Right,
+            MoveType::Right => MoveType::Left,
+            MoveType::Up => MoveType::Down,
+            MoveType::Down => MoveType::Up,
+            MoveType::BottomToLeft => MoveType::LeftToBottom,
+            MoveType::LeftToBottom => MoveType::BottomToLeft,
+            MoveType::TopToLeft => MoveType::LeftToTop,
+            MoveType::LeftToTop => MoveType::TopToLeft,
+            MoveType::BottomToRight => MoveType::RightToBottom,
+            MoveType::RightToBottom => MoveType::BottomToRight,
+            MoveType::TopToRight => MoveType::RightToTop,
+            MoveType::RightToTop => MoveType::TopToRight,
+        }
+    }
+
+    pub fn is_corner(self) -> bool {
+        !matches!(
+            self,
+            MoveType::Blank | MoveType::Center | MoveType::Left | MoveType::Right | MoveType::Up | MoveType::Down
+        )
+    }
+}
+
+/// Formula for each cell's phase offset, in fractions of a cell.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum PhaseFormula {
+    /// every cell perfectly in sync
+    #[default]
+    None,
+    /// lag `step` per cell of distance from the table center
+    DistanceFromCenter { step: f32 },
+    /// lag `step` per row, from the top
+    PerRow { step: f32 },
+    /// lag `step` per column, from the left
+    PerColumn { step: f32 },
+}
+
+impl PhaseFormula {
+    pub fn phase(self, grid: UVec2, table: &CellTable) -> f32 {
+        let phase = match self {
+            PhaseFormula::None => 0.0,
+            PhaseFormula::DistanceFromCenter { step } => {
+                let center = table.unit_size() / 2.0;
+                let d = table.unit_center(grid.x as usize, grid.y as usize).distance(center);
+                -d * step
+            }
+            PhaseFormula::PerRow { step } => -(grid.y as f32) * step,
+            PhaseFormula::PerColumn { step } => -(grid.x as f32) * step,
+        };
+        phase.rem_euclid(1.0)
+    }
+
+    pub fn next(self) -> PhaseFormula {
+        match self {
+            PhaseFormula::None => PhaseFormula::DistanceFromCenter { step: 0.1 },
+            PhaseFormula::DistanceFromCenter { .. } => PhaseFormula::PerRow { step: 0.1 },
+            PhaseFormula::PerRow { .. } => PhaseFormula::PerColumn { step: 0.1 },
+            PhaseFormula::PerColumn { .. } => PhaseFormula::None,
+        }
+    }
+}
+
+/// How circles turn at corner cells.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum CornerStyle {
+    /// follow a quarter circle around the inner corner
+    #[default]
+    Arc,
+    /// cut straight across the cell
+    Straight,
+}
+
+/// Whether circles glide continuously or hop from cell to cell.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum Stepping {
+    #[default]
+    Glide,
+    /// rest in the middle of the cell and hop to the next one within the
+    /// last `swing` (0..1) of each cell's span, like a step sequencer
+    Hop { swing: f32 },
+}
+
+impl Stepping {
+    /// Map the 0..1 rate within a cell to the 0..1 position along it.
+    ///
+    /// The hop straddles the cell boundary: the first half of it ends in the
+    /// middle of the next cell.
+    pub fn apply(self, rate: f32) -> f32 {
+        let Stepping::Hop { swing } = self else {
+            return rate;
+        };
+        let half = (swing / 2.0).clamp(f32::EPSILON, 0.5);
+        if rate < half {
+            0.5 * rate / half
+        } else if rate > 1.0 - half {
+            0.5 + 0.5 * (rate - (1.0 - half)) / half
+        } else {
+            0.5
+        }
+    }
+}
+
+impl Cell {
+    pub fn new(grid: UVec2, pos: Vec2, size: Vec2, move_type: MoveType) -> Self {
+        let path = cell_path(move_type, size / 2.0);
+        Cell { grid, pos, size, move_type, easing: None, phase: 0.0, shift: 0.0, mirrored: false, path }
+    }
+
+    /// Entry and exit point of the circle's path, from the center; `None`
+    /// for cells that don't travel.
+    pub fn path(&self) -> Option<(Vec2, Vec2)> {
+        self.path
+    }
+
+    /// Walk the path the other way round, the easing played backwards.
+    pub fn reverse(&mut self) {
+        self.move_type = self.move_type.reversed();
+        self.mirrored = !self.mirrored;
+        self.path = cell_path(self.move_type, self.size / 2.0);
+    }
+
+    /// Move the cell to `pos`, `size` across.
+    pub fn resize(&mut self, pos: Vec2, size: Vec2) {
+        self.pos = pos;
+        self.size = size;
+        self.path = cell_path(self.move_type, size / 2.0);
+    }
+
+    /// Offset from the center `eased` (0..1) along the path.
+    pub fn offset(&self, eased: f32, corner_style: CornerStyle) -> Vec2 {
+        match self.path {
+            Some(path) => path_offset(path, corner_style == CornerStyle::Arc && self.move_type.is_corner(), eased),
+            None => Vec2::ZERO,
+        }
+    }
+
+    /// Eased 0..1 position along the path at the global `rate`.
+    pub fn progress(&self, cells_param: &CellsParam, flow: &direction::FlowDirection, rate: f32) -> f32 {
+        let speed = cells_param.cell_table.speed(self.grid.x as usize, self.grid.y as usize);
+        let rate = (flow.cell_rate(rate, self.phase) * speed + self.shift).rem_euclid(1.0);
+        cells_param.ease(self.easing.unwrap_or(cells_param.easing), rate, self.mirrored)
+    }
+}
+
+#[derive(Clone)]
+pub struct CellTable {
+    pub table: Vec<Vec<char>>,
+    pub width: usize,
+    pub height: usize,
+    /// per-column / per-row scale set by region markers (1.0 elsewhere)
+    pub col_scales: Vec<f32>,
+    pub row_scales: Vec<f32>,
+    // left / top edge of each column / row, in cell units
+    col_starts: Vec<f32>,
+    row_starts: Vec<f32>,
+    /// what thick corners mean
+    pub thickness: thickness::Thickness,
+    /// cells drawn with a thick corner, when that isn't their direction
+    pub heavy: HashSet<UVec2>,
+}
+
+/// Skip the `#` lines above a table, reading its thickness from them.
+fn read_metadata(lines: &mut std::iter::Peekable<std::str::Lines>) -> Result<thickness::Thickness, LayoutError> {
+    let mut thickness = thickness::Thickness::Direction;
+    while let Some(line) = lines.next_if(|line| line.trim_start().starts_with('#')) {
+        let Some((key, value)) = line.trim_start()[1..].split_once(':') else {
+            continue;
+        };
+        if key.trim() == "thickness" {
+            thickness = thickness::Thickness::from_name(value.trim())
+                .ok_or_else(|| LayoutError::InvalidMetadata { line: line.to_string() })?;
+        }
+    }
+    Ok(thickness)
+}
+
+impl CellTable {
+    pub fn new(_cell_info: &str) -> Self {
+        let table = CellTable::parse(_cell_info).unwrap_or_else(|e| panic!("{}", e));
+        println!("cell_info:\n{}", table.to_text());
+        table
+    }
+
+    /// The rows joined by newlines, with thick corners drawn thick again and
+    /// a `# thickness:` line on top unless it is the classic reading; leading
+    /// blanks are kept, so use [`CellTable::from_text`] (not
+    /// [`CellTable::parse`]) to read it back.
+    pub fn to_text(&self) -> String {
+        let rows = self.table.iter().enumerate().map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, c)| {
+                    if self.heavy.contains(&UVec2::new(x as u32, y as u32)) {
+                        thickness::heavy_glyph(*c)
+                    } else {
+                        *c
+                    }
+                })
+                .collect::<String>()
+        });
+        let metadata = match self.thickness {
+            thickness::Thickness::Direction => None,
+            thickness => Some(format!("# thickness: {}", thickness.name())),
+        };
+        metadata.into_iter().chain(rows).collect::<Vec<String>>().join("\n")
+    }
+
+    /// Like [`CellTable::new`], but reports bad input instead of panicking.
+    ///
+    /// Lines starting with `#` above the table are comments, or metadata like
+    /// `# thickness: speed` (see [`thickness::Thickness`]).
+    pub fn parse(_cell_info: &str) -> Result<Self, LayoutError> {
+        let mut lines = _cell_info.lines().peekable();
+        let thickness = read_metadata(&mut lines)?;
+
+        // first, trimming
+        let rows = lines.map(|line| line.trim().chars().collect()).collect();
+        Ok(CellTable::from_rows(rows)?.with_thickness(thickness))
+    }
+
+    /// Read back what [`CellTable::to_text`] wrote: the metadata, then the
+    /// rows as they are, without trimming.
+    pub fn from_text(text: &str) -> Result<Self, LayoutError> {
+        let mut lines = text.lines().peekable();
+        let thickness = read_metadata(&mut lines)?;
+        let rows = lines.map(|line| line.chars().collect()).collect();
+        Ok(CellTable::from_rows(rows)?.with_thickness(thickness))
+    }
+
+    /// Build a table from rows of glyphs as they are, without trimming.
+    pub fn from_rows(table: Vec<Vec<char>>) -> Result<Self, LayoutError> {
+        let width = table.iter().map(|row| row.len()).max().unwrap_or(0);
+        let height = table.len();
+        if width == 0 {
+            return Err(LayoutError::Empty);
+        }
+
+        for (y, row) in table.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if try_move_type_from_char(*c).is_none() {
+                    return Err(LayoutError::InvalidChar { c: *c, x, y });
+                }
+            }
+        }
+
+        // region markers scale their whole column and row
+        let mut col_scales = vec![1.0_f32; width];
+        let mut row_scales = vec![1.0_f32; height];
+        for (y, row) in table.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if let Some(scale) = scale_from_char(*c) {
+                    col_scales[x] = col_scales[x].min(scale);
+                    row_scales[y] = row_scales[y].min(scale);
+                }
+            }
+        }
+
+        let starts = |scales: &[f32]| {
+            scales.iter().scan(0.0, |start, scale| {
+                let s = *start;
+                *start += scale;
+                Some(s)
+            }).collect::<Vec<f32>>()
+        };
+        let col_starts = starts(&col_scales);
+        let row_starts = starts(&row_scales);
+
+        Ok(CellTable {
+            width,
+            height,
+            table,
+            col_scales,
+            row_scales,
+            col_starts,
+            row_starts,
+            thickness: thickness::Thickness::Direction,
+            heavy: HashSet::new(),
+        })
+    }
+
+    /// Read thick corners as `thickness` says.
+    pub fn with_thickness(mut self, thickness: thickness::Thickness) -> Self {
+        self.thickness = thickness;
+        if thickness != thickness::Thickness::Direction {
+            let heavy = thickness::resolve_corners(&mut self.table);
+            self.heavy.extend(heavy);
+        }
+        self
+    }
+
+    /// How many times faster than others cell (x, y) is crossed.
+    pub fn speed(&self, x: usize, y: usize) -> f32 {
+        match self.thickness {
+            thickness::Thickness::Speed if self.heavy.contains(&UVec2::new(x as u32, y as u32)) => {
+                thickness::HEAVY_FACTOR as f32
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// How many times more circles than others cell (x, y) carries.
+    pub fn density(&self, x: usize, y: usize) -> usize {
+        match self.thickness {
+            thickness::Thickness::Density if self.heavy.contains(&UVec2::new(x as u32, y as u32)) => {
+                thickness::HEAVY_FACTOR
+            }
+            _ => 1,
+        }
+    }
+
+    /// Center of cell (x, y) in cell units, with y growing downwards.
+    pub fn unit_center(&self, x: usize, y: usize) -> Vec2 {
+        Vec2::new(
+            self.col_starts[x] + self.col_scales[x] / 2.0,
+            self.row_starts[y] + self.row_scales[y] / 2.0,
+        )
+    }
+
+    /// Total extent of the table in cell units.
+    pub fn unit_size(&self) -> Vec2 {
+        Vec2::new(
+            self.col_scales.iter().sum(),
+            self.row_scales.iter().sum(),
+        )
+    }
+
+    pub fn scale(&self, x: usize, y: usize) -> Vec2 {
+        Vec2::new(self.col_scales[x], self.row_scales[y])
+    }
+
+    /// Flip every track glyph to the one flowing the other way.
+    pub fn reverse(&mut self) {
+        for c in self.table.iter_mut().flatten() {
+            if let Some(move_type) = try_move_type_from_char(*c).filter(|m| m.is_track()) {
+                *c = char_from_move_type(move_type.reversed());
+            }
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> char {
+        if y >= self.height {
+            return ' ';
+        }
+        let row = &self.table[y];
+        if x >= row.len() {
+            return ' ';
+        }
+        row[x]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// no cells at all
+    Empty,
+    /// `c` at column `x`, row `y` is not a known cell type
+    InvalidChar { c: char, x: usize, y: usize },
+    /// a metadata line with a value that isn't understood
+    InvalidMetadata { line: String },
+    /// the track at column `x`, row `y` doesn't join exactly two others
+    OpenTrack { x: usize, y: usize },
+}
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::Empty => write!(f, "layout is empty"),
+            LayoutError::InvalidChar { c, x, y } => {
+                write!(f, "Invalid cell type: {:?} at ({}, {})", c, x, y)
+            }
+            LayoutError::InvalidMetadata { line } => write!(f, "Invalid metadata: {:?}", line),
+            LayoutError::OpenTrack { x, y } => write!(f, "Open track at ({}, {})", x, y),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Only the sizes and the number of circles a cell are reflected (and so
+/// shown by the `inspector`); the rest is edited through the hotkeys and the
+/// egui panel.
+#[derive(Resource, Reflect)]
+#[reflect(from_reflect = false)]
+pub struct CellsParam {
+    #[reflect(ignore)]
+    pub cell_table: CellTable,
+    pub cell_size: Vec2,
+    pub circle_size: f32,
+    #[reflect(ignore)]
+    pub travel_mode: TravelMode,
+    #[reflect(ignore)]
+    pub easing: Easing,
+    /// per-cell easing overrides, keyed by table coordinates (y down)
+    #[reflect(ignore)]
+    pub cell_easings: HashMap<UVec2, Easing>,
+    #[reflect(ignore)]
+    pub corner_style: CornerStyle,
+    #[reflect(ignore)]
+    pub phase_formula: PhaseFormula,
+    #[reflect(ignore)]
+    pub stepping: Stepping,
+    /// noise on top of the motion, for a hand-drawn feel
+    #[reflect(ignore)]
+    pub jitter: noise::Jitter,
+    /// pull circles toward their position with a spring instead of placing
+    /// them on it
+    #[reflect(ignore)]
+    pub spring: Option<spring::Spring>,
+    /// what the Center ('0') cells do
+    #[reflect(ignore)]
+    pub idle: idle::Idle,
+    /// let the Center cells pull passing circles toward themselves
+    #[reflect(ignore)]
+    pub gravity: Option<gravity::Gravity>,
+    /// circles on every track cell, evenly spaced along it
+    pub circles_per_cell: usize,
+    /// fading along the paths or across the layout
+    #[reflect(ignore)]
+    pub opacity: opacity::Opacity,
+    /// how circles are colored
+    #[reflect(ignore)]
+    pub color_mode: color_mode::ColorMode,
+    /// what circles are drawn as
+    #[reflect(ignore)]
+    pub shape: shape::Shape,
+    /// overrides `shape` for the cells of a kind of glyph
+    #[reflect(ignore)]
+    pub class_shapes: HashMap<shape::GlyphClass, shape::Shape>,
+    /// bloom around the circles
+    #[reflect(ignore)]
+    pub glow: Option<glow::Glow>,
+    /// how overlapping circles mix
+    #[reflect(ignore)]
+    pub blend: shading::Blend,
+    /// what's behind the circles
+    #[reflect(ignore)]
+    pub background: background::Background,
+    /// the camera drifting back and forth
+    #[reflect(ignore)]
+    pub swing: Option<camera::Swing>,
+}
+
+impl CellsParam {
+    /// Parameters for `cell_table` with the defaults of the original piece.
+    pub fn new(cell_table: CellTable) -> Self {
+        CellsParam {
+            cell_table,
+            cell_size: Vec2::new(50.0, 50.0),
+            circle_size: 10.0,
+            travel_mode: TravelMode::Oscillate,
+            easing: Easing::Linear,
+            cell_easings: HashMap::new(),
+            corner_style: CornerStyle::Arc,
+            phase_formula: PhaseFormula::None,
+            stepping: Stepping::Glide,
+            jitter: noise::Jitter::default(),
+            spring: None,
+            idle: idle::Idle::Still,
+            gravity: None,
+            circles_per_cell: 1,
+            opacity: opacity::Opacity::Opaque,
+            color_mode: color_mode::ColorMode::Palette,
+            shape: shape::Shape::Circle,
+            class_shapes: HashMap::new(),
+            glow: None,
+            blend: shading::Blend::Normal,
+            background: background::Background::default(),
+            swing: None,
+        }
+    }
+
+    /// What the circles on a `move_type` cell are drawn as.
+    pub fn shape_for(&self, move_type: MoveType) -> shape::Shape {
+        shape::GlyphClass::of(move_type)
+            .and_then(|class| self.class_shapes.get(&class))
+            .copied()
+            .unwrap_or(self.shape)
+    }
+
+    /// World position of the center of cell (ix, iy), where iy counts rows from the top.
+    ///
+    /// Columns and rows may have different sizes (see `scale_from_char`).
+    pub fn cell_center(&self, ix: usize, iy: usize) -> Vec2 {
+        self.cell_center_at(ix, iy, self.cell_size)
+    }
+
+    /// [`cell_center`](Self::cell_center) with the grid drawn at `cell_size`,
+    /// e.g. the one shown by a running [`tween::SizeTween`].
+    pub fn cell_center_at(&self, ix: usize, iy: usize, cell_size: Vec2) -> Vec2 {
+        let total = self.cell_table.unit_size();
+        let center = self.cell_table.unit_center(ix, iy);
+
+        // flip y
+        let unit = Vec2::new(center.x, total.y - center.y);
+
+        // NOTE: the half-cell shift keeps uniform grids where they always were
+        (unit - (total + Vec2::ONE) / 2.0) * cell_size
+    }
+
+    /// World size of cell (ix, iy).
+    pub fn cell_extent(&self, ix: usize, iy: usize) -> Vec2 {
+        self.cell_extent_at(ix, iy, self.cell_size)
+    }
+
+    /// [`cell_extent`](Self::cell_extent) with the grid drawn at `cell_size`.
+    pub fn cell_extent_at(&self, ix: usize, iy: usize, cell_size: Vec2) -> Vec2 {
+        self.cell_table.scale(ix, iy) * cell_size
+    }
+
+    /// World rect covered by the whole table, blanks included.
+    pub fn bounds(&self) -> Rect {
+        let total = self.cell_table.unit_size();
+        // cell_center of the edges: unit 0 and unit total
+        let min = -(total + Vec2::ONE) / 2.0 * self.cell_size;
+        Rect::from_corners(min, min + total * self.cell_size)
+    }
+
+    pub fn easing_at(&self, grid: UVec2) -> Easing {
+        self.cell_easings.get(&grid).copied().unwrap_or(self.easing)
+    }
+
+    /// `rate` stepped and eased; `mirrored`, the same motion played
+    /// backwards, so that after `r -> 1 - r` the circle is where it was.
+    pub fn ease(&self, easing: Easing, rate: f32, mirrored: bool) -> f32 {
+        let eased = |rate| easing.apply(self.stepping.apply(rate));
+        if mirrored {
+            1.0 - eased(1.0 - rate)
+        } else {
+            eased(rate)
+        }
+    }
+
+    /// Whether anything moves the circles of [`StaticCell`]s.
+    pub fn centers_move(&self) -> bool {
+        matches!(self.idle, idle::Idle::Rotate { radius } if radius != 0.0) || self.jitter.amplitude != 0.0
+    }
+
+    pub fn move_type_at(&self, grid: UVec2) -> MoveType {
+        move_type_from_char(self.cell_table.get(grid.x as usize, grid.y as usize))
+    }
+
+    /// The cell a circle entering `grid` comes from, if the track continues there.
+    pub fn prev_cell(&self, grid: UVec2) -> Option<UVec2> {
+        let (entry, _) = self.move_type_at(grid).endpoints()?;
+        // endpoints are y up, the table is y down
+        let prev = grid.as_ivec2() + IVec2::new(entry.x as i32, -entry.y as i32);
+        if prev.x < 0 || prev.y < 0 {
+            return None;
+        }
+        let prev = prev.as_uvec2();
+        if self.next_cell(prev) == Some(grid) {
+            Some(prev)
+        } else {
+            None
+        }
+    }
+
+    /// The cell a circle leaving `grid` enters next, if the track continues there.
+    pub fn next_cell(&self, grid: UVec2) -> Option<UVec2> {
+        let next = grid.as_ivec2() + self.move_type_at(grid).exit()?;
+        if next.x < 0 || next.y < 0 {
+            return None;
+        }
+        let next = next.as_uvec2();
+        if self.move_type_at(next).is_track() {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+fn spawn_loading_text(mut commands: Commands) {
+    commands
+        .spawn( (
+            Text::new("loading..."),
+            Node {
+                position_type: PositionType::Relative,
+                top: Val::Percent(50.0),
+                left: Val::Percent(50.0),
+                ..default()
+            },
+            LoadingText,
+        ));
+}
+
+fn cleanup_loading_text(
+    mut commands: Commands,
+    loading_text: Query<Entity, With<LoadingText>>,
+) {
+    for entity in loading_text.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct GltfAssets {
+//   #[asset(path = "models/stairs.glb")]
+//   pub iroha: Handle<Gltf>,
+    /// drawn in place of the circles with [`tokens::GltfTokens`]
+    #[asset(path = "models/token.glb")]
+    pub token: Handle<Gltf>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
+pub enum AssetLoadingState {
+    #[default]
+    Loading,
+    Loaded,
+}
+
+struct MyTransform(Transform);
+
+impl From<Vec2> for MyTransform {
+    fn from(value: Vec2) -> Self {
+        MyTransform(Transform::from_xyz(value.x, value.y, 0.0))
+    }
+}
+
+/// Region markers: a marker cell shrinks its whole column and row by the given
+/// factor (the cell itself stays blank). Put markers on the diagonal of a
+/// region to scale that region.
+pub fn scale_from_char(c: char) -> Option<f32> {
+    match c {
+        '½' => Some(0.5),
+        '¼' => Some(0.25),
+        '¾' => Some(0.75),
+        _ => None,
+    }
+}
+
+pub fn move_type_from_char(c: char) -> MoveType {
+    try_move_type_from_char(c).unwrap_or_else(|| panic!("Invalid cell type: {}", c))
+}
+
+/// Canonical glyph of every move type.
+fn glyphs() -> &'static BiMap<char, MoveType> {
+    static GLYPHS: OnceLock<BiMap<char, MoveType>> = OnceLock::new();
+    // NOTE
+    // - thin keisen: clock wise
+    // - thick keisen: counter clock wise
+    GLYPHS.get_or_init(|| BiMap::from_iter([
+        (' ', MoveType::Blank),
+        ('0', MoveType::Center),
+        ('←', MoveType::Left),
+        ('┓', MoveType::BottomToLeft),
+        ('┘', MoveType::TopToLeft),
+        ('→', MoveType::Right),
+        ('┌', MoveType::BottomToRight),
+        ('┗', MoveType::TopToRight),
+        ('↑', MoveType::Up),
+        ('┛', MoveType::LeftToTop),
+        ('└', MoveType::RightToTop),
+        ('↓', MoveType::Down),
+        ('┐', MoveType::LeftToBottom),
+        ('┏', MoveType::RightToBottom),
+    ]))
+}
+
+pub fn try_move_type_from_char(c: char) -> Option<MoveType> {
+    if scale_from_char(c).is_some() {
+        return Some(MoveType::Blank);
+    }
+    glyphs().get_by_left(&c).copied()
+}
+
+pub fn char_from_move_type(move_type: MoveType) -> char {
+    *glyphs().get_by_right(&move_type).expect("every move type has a glyph")
+}
+
+pub fn create_cell(cell_type: char, grid: UVec2, pos: Vec2, size: Vec2) -> Cell {
+    let move_type = move_type_from_char(cell_type);
+    Cell::new(grid, pos, size, move_type)
+}
+
+fn setup(
+    mut commands: Commands,
+    // mut asset_server: ResMut<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    // gltf_res: Res<GltfAssets>,
+    // assets_gltf: Res<Assets<Gltf>>,
+    // assets_gltfmeshes: Res<Assets<GltfMesh>>,
+    // assets_gltfnodes: Res<Assets<GltfNode>>,
+    cells_param: Res<CellsParam>,
+    instanced: Option<Res<instanced::Instanced>>,
+    camera_fit: Option<Res<camera::CameraFit>>,
+    window: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    // Create a camera, looking at the whole table
+    let mut transform = Transform::default();
+    let mut projection = Projection::Orthographic(OrthographicProjection::default_2d());
+    if let (Some(camera_fit), Ok(window)) = (camera_fit, window.single()) {
+        camera::fit(cells_param.bounds(), window.size(), &camera_fit, &mut transform, &mut projection);
+    }
+    commands.spawn((
+        Camera2d,
+        transform,
+        projection,
+        calibration::OutputName("main".into()),
+    ));
+
+    // drawn by instanced::build_instances instead
+    if instanced.is_none() {
+        spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+    }
+}
+
+/// Spawn a circle for every cell of the layout (and the agents in
+/// [`TravelMode::Agents`] and [`TravelMode::Train`]).
+pub fn spawn_cells(
+    commands: &mut Commands,
+    cells_param: &CellsParam,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+
+        // commands.spawn((
+        //     Mesh3d(mesh_handle.clone()),
+        //     Transform::from_xyz(x, y, z).with_rotation(rotation),
+        //     MeshMaterial3d( materials.add(
+        //         StandardMaterial {
+        //             base_color: Color::srgb(0.8, 0.7, 0.6),
+        //             ..default()
+        //         }
+        //     ))
+        // ));
+        
+    // one mesh per shape in use
+    let mut shape_meshes: Vec<(shape::Shape, Handle<Mesh>)> = Vec::new();
+    let mut mesh_for = |shape: shape::Shape| match shape_meshes.iter().find(|(s, _)| *s == shape) {
+        Some((_, mesh)) => mesh.clone(),
+        None => {
+            let mesh = meshes.add(shape.mesh(cells_param.circle_size));
+            shape_meshes.push((shape, mesh.clone()));
+            mesh
+        }
+    };
+
+    // one material until they are colored (see materials)
+    let white = materials.add(Color::WHITE);
+
+    let w = cells_param.cell_table.width;
+    let h = cells_param.cell_table.height;
+
+    for _iy in 0..h {
+        // flip y
+        let iy = h - _iy - 1;
+        for ix in 0..w {
+            let c = cells_param.cell_table.get(ix, iy);
+            println!("{}, {} = {:?}", ix, _iy, move_type_from_char(c));
+            let pos = cells_param.cell_center(ix, iy);
+            if move_type_from_char(c) == MoveType::Blank {
+                // nothing to draw
+                continue;
+            }
+            if cells_param.travel_mode != TravelMode::Oscillate && move_type_from_char(c).is_track() {
+                // the track is walked by agents instead
+                continue;
+            }
+            let rot = Quat::from_rotation_z(0.0);
+            // circles shrink with their region
+            let scale = cells_param.cell_table.scale(ix, iy).min_element();
+            let mesh = mesh_for(cells_param.shape_for(move_type_from_char(c)));
+            for cell in cell_circles(cells_param, ix, iy) {
+                let still = !cell.move_type.is_track();
+                let mut circle = commands.spawn((
+                    Mesh2d(mesh.clone()),
+                    MyTransform::from(pos).0.with_rotation(rot).with_scale(Vec3::splat(scale)),
+                    // colored by color_mode::apply_color_mode
+                    MeshMaterial2d(white.clone()),
+                    cell,
+                    layers::OnLayer(layers::Layer::Circles),
+                ));
+                if still {
+                    circle.insert(StaticCell);
+                }
+            }
+        }
+    }
+
+    // agents cross every kind of cell
+    let mesh = mesh_for(cells_param.shape);
+    match cells_param.travel_mode {
+        TravelMode::Oscillate => {}
+        TravelMode::Agents => agent::spawn_agents(commands, cells_param, &mesh, materials),
+        TravelMode::Train { per_loop } => agent::spawn_trains(commands, cells_param, per_loop, &mesh, materials),
+    }
+}
+
+/// The circles of the cell at `ix`, `iy`: one on a center, evenly spread
+/// along a track, none on a blank.
+pub fn cell_circles(cells_param: &CellsParam, ix: usize, iy: usize) -> Vec<Cell> {
+    let c = cells_param.cell_table.get(ix, iy);
+    if move_type_from_char(c) == MoveType::Blank {
+        return Vec::new();
+    }
+    let grid = UVec2::new(ix as u32, iy as u32);
+    let mut cell = create_cell(c, grid, cells_param.cell_center(ix, iy), cells_param.cell_extent(ix, iy));
+    cell.easing = cells_param.cell_easings.get(&grid).copied();
+    cell.phase = cells_param.phase_formula.phase(grid, &cells_param.cell_table);
+    let count = if cell.move_type.is_track() {
+        cells_param.circles_per_cell.max(1) * cells_param.cell_table.density(ix, iy)
+    } else {
+        1
+    };
+    (0..count)
+        .map(|k| Cell { shift: k as f32 / count as f32, ..cell.clone() })
+        .collect()
+}
+
+/// Rebuild every circle from the current [`CellsParam`], e.g. after the layout
+/// was replaced.
+#[derive(Event, Default)]
+pub struct RespawnCells;
+
+/// Every circle on screen: oscillating cells and traveling agents.
+pub type Circles = Or<(With<Cell>, With<agent::Agent>)>;
+
+/// Resize the circle mesh as the (tweened) `circle_size` changes, so the size
+/// can be changed (or modulated) live.
+fn sync_circle_mesh(
+    cells_param: Res<CellsParam>,
+    tween: Res<tween::SizeTween>,
+    mut last_size: Local<Option<f32>>,
+    circles: Query<(&Mesh2d, Option<&Cell>), Circles>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let size = tween.circle_size(&cells_param);
+    let Some(last) = last_size.replace(size) else {
+        // spawned at this size
+        return;
+    };
+    if last == size {
+        return;
+    }
+    let shapes = circles
+        .iter()
+        .map(|(mesh, cell)| (mesh.0.id(), cell.map_or(cells_param.shape, |cell| cells_param.shape_for(cell.move_type))))
+        .collect::<HashMap<_, _>>();
+    for (id, shape) in shapes {
+        meshes.insert(id, shape.mesh(size));
+    }
+}
+
+/// Move cells to where the (tweened) `cell_size` puts them now.
+fn relayout_cells(
+    cells_param: Res<CellsParam>,
+    tween: Res<tween::SizeTween>,
+    mut last_size: Local<Option<Vec2>>,
+    // the compared table is laid out by its own parameters
+    mut cells: Query<(&mut Cell, &mut Transform), Without<split::Compared>>,
+) {
+    let size = tween.cell_size(&cells_param);
+    let Some(last) = last_size.replace(size) else {
+        return;
+    };
+    if last == size {
+        return;
+    }
+    for (mut cell, mut transform) in cells.iter_mut() {
+        let (ix, iy) = (cell.grid.x as usize, cell.grid.y as usize);
+        cell.resize(cells_param.cell_center_at(ix, iy, size), cells_param.cell_extent_at(ix, iy, size));
+        // tracks are placed by move_cells, centers stay here
+        transform.translation = cell.pos.extend(transform.translation.z);
+    }
+}
+
+fn respawn_cells(
+    mut commands: Commands,
+    mut events: EventReader<RespawnCells>,
+    cells_param: Res<CellsParam>,
+    // the compared table is respawned by split::sync_split
+    circles: Query<Entity, (Circles, Without<split::Compared>)>,
+    instanced: Option<Res<instanced::Instanced>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if events.read().count() == 0 || instanced.is_some() {
+        return;
+    }
+    for entity in circles.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+}
+
+pub fn map (
+    input: f32,
+    in_min: f32,
+    in_max: f32,
+    out_min: f32,
+    out_max: f32,
+) -> f32 {
+    (input - in_min) * (out_max - out_min) / (in_max - in_min) + out_min
+}
+
+/// Offset from the cell center at `rate` (0..1) along the path of `move_type`.
+pub fn move_offset(move_type: MoveType, rate: f32, half: Vec2, corner_style: CornerStyle) -> Vec2 {
+    let Some(path) = cell_path(move_type, half) else {
+        return Vec2::ZERO;
+    };
+    path_offset(path, corner_style == CornerStyle::Arc && move_type.is_corner(), rate)
+}
+
+/// Entry and exit point of a `move_type` cell of half extent `half`, from
+/// its center.
+pub fn cell_path(move_type: MoveType, half: Vec2) -> Option<(Vec2, Vec2)> {
+    let (from, to) = move_type.endpoints()?;
+    Some((from * half, to * half))
+}
+
+/// Offset at `rate` (0..1) along the path from `from` to `to`: a straight
+/// line, or with `arc` a quarter circle round the corner they share.
+pub fn path_offset((from, to): (Vec2, Vec2), arc: bool, rate: f32) -> Vec2 {
+    if arc {
+        // quarter arc around the cell corner shared by both edges
+        let pivot = from + to;
+        let (sin, cos) = (rate * FRAC_PI_2).sin_cos();
+        return pivot + (from - pivot) * cos + (to - pivot) * sin;
+    }
+    from.lerp(to, rate)
+}
+
+fn cycle_corner_style(
+    hotkeys: Hotkeys,
+    mut cells_param: ResMut<CellsParam>,
+) {
+    if hotkeys.just_pressed(Action::CycleCornerStyle) {
+        cells_param.corner_style = match cells_param.corner_style {
+            CornerStyle::Arc => CornerStyle::Straight,
+            CornerStyle::Straight => CornerStyle::Arc,
+        };
+        println!("corner style: {:?}", cells_param.corner_style);
+    }
+}
+
+type MovingCell = (&'static mut Transform, &'static Cell, Option<&'static mut motion_blur::Velocity>);
+
+/// Circles of the main table in view (the compared table is moved by
+/// [`split::move_compared`]).
+type InView = (Without<culling::Offscreen>, Without<split::Compared>);
+
+pub fn move_cells(
+    clock: Res<clock::SimClock>,
+    phase: Res<clock::MotionPhase>,
+    cells_param: Res<CellsParam>,
+    flow: Res<direction::FlowDirection>,
+    tween: Option<Res<tween::SizeTween>>,
+    mut cells: Query<MovingCell, (Without<StaticCell>, InView)>,
+    mut centers: Query<MovingCell, (With<StaticCell>, InView)>,
+) {
+    let rate = phase.rate();
+    let dt = clock.delta_secs();
+    let secs = clock.elapsed_secs_f64() as f32;
+    let cell_size = tween.map_or(cells_param.cell_size, |tween| tween.cell_size(&cells_param));
+    let wells = match cells_param.gravity {
+        Some(_) => gravity::Gravity::wells(&cells_param, cell_size),
+        None => Vec::new(),
+    };
+
+    // move circle from right to left
+    // every circle on its own, so spread over the compute task pool (sized
+    // with `--threads`)
+    let place = |(mut transform, cell, velocity): QueryItem<MovingCell>| {
+        let from = transform.translation.truncate();
+        let offset = cell_offset(cell, &cells_param, &flow, rate, secs, &wells, cell_size);
+        transform.translation.x = cell.pos.x + offset.x;
+        transform.translation.y = cell.pos.y + offset.y;
+        if let Some(mut velocity) = velocity {
+            velocity.track(from, transform.translation.truncate(), dt, cell.size.max_element() / 2.0);
+        }
+    };
+    cells.par_iter_mut().for_each(&place);
+    // once more after a change, to put them back when the idle stops
+    if cells_param.centers_move() || cells_param.is_changed() {
+        centers.par_iter_mut().for_each(&place);
+    }
+
+}
+
+/// Where the circle of `cell` is, from the cell center, at `rate` through the
+/// motion and `secs` in.
+pub fn cell_offset(
+    cell: &Cell,
+    cells_param: &CellsParam,
+    flow: &direction::FlowDirection,
+    rate: f32,
+    secs: f32,
+    wells: &[Vec2],
+    cell_size: Vec2,
+) -> Vec2 {
+    match cell.move_type {
+        // never spawned
+        MoveType::Blank => Vec2::ZERO,
+        MoveType::Center => {
+            let rate = flow.cell_rate(rate, cell.phase);
+            cells_param.idle.offset(rate) + cells_param.jitter.offset(cell.grid, secs)
+        }
+        _ => {
+            let eased = cell.progress(cells_param, flow, rate);
+            let mut offset = cell.offset(eased, cells_param.corner_style) + cells_param.jitter.offset(cell.grid, secs);
+            if let Some(gravity) = cells_param.gravity {
+                offset += gravity.offset(cell.pos + offset, wells, cell_size);
+            }
+            offset
+        }
+    }
+}
+
+fn cycle_stepping(
+    hotkeys: Hotkeys,
+    mut cells_param: ResMut<CellsParam>,
+) {
+    if hotkeys.just_pressed(Action::CycleStepping) {
+        cells_param.stepping = match cells_param.stepping {
+            Stepping::Glide => Stepping::Hop { swing: 0.25 },
+            Stepping::Hop { .. } => Stepping::Glide,
+        };
+        println!("stepping: {:?}", cells_param.stepping);
+    }
+}
+
+fn cycle_easing(
+    hotkeys: Hotkeys,
+    mut cells_param: ResMut<CellsParam>,
+) {
+    if hotkeys.just_pressed(Action::CycleEasing) {
+        cells_param.easing = cells_param.easing.next();
+        println!("easing: {:?}", cells_param.easing);
+    }
+}
+
+fn cycle_phase_formula(
+    hotkeys: Hotkeys,
+    mut cells_param: ResMut<CellsParam>,
+    // the compared table keeps the phases of its own parameters
+    mut cells: Query<&mut Cell, Without<split::Compared>>,
+) {
+    if hotkeys.just_pressed(Action::CyclePhaseFormula) {
+        cells_param.phase_formula = cells_param.phase_formula.next();
+        println!("phase formula: {:?}", cells_param.phase_formula);
+        for mut cell in cells.iter_mut() {
+            cell.phase = cells_param.phase_formula.phase(cell.grid, &cells_param.cell_table);
+        }
+    }
+}
+
+/// The panel moves to the control window while it is open.
+#[cfg(feature = "egui")]
+fn attach_control_context(
+    mut commands: Commands,
+    cameras: Query<Entity, (With<control_window::ControlOutput>, With<Camera>, Without<EguiContext>)>,
+) {
+    for camera in cameras.iter() {
+        commands.entity(camera).insert(EguiContext::default());
+    }
+}
+
+/// The control window's egui context, if it has one.
+#[cfg(feature = "egui")]
+type ControlContext<'w, 's> = Query<'w, 's, Entity, (With<control_window::ControlOutput>, With<EguiContext>)>;
+
+/// Where the panels go: the control window when there is one, the primary
+/// window otherwise.
+#[cfg(feature = "egui")]
+fn panel_ctx<'a>(contexts: &'a mut EguiContexts, control: &ControlContext) -> &'a mut egui::Context {
+    match control.single() {
+        Ok(camera) => contexts.ctx_for_entity_mut(camera),
+        Err(_) => contexts.ctx_mut(),
+    }
+}
+
+/// Keep painting, the camera and the hotkeys off what the egui windows
+/// take, on any window; nothing is taken while they are hidden.
+#[cfg(feature = "egui")]
+fn track_ui_focus(
+    clean: Option<Res<clean_view::CleanView>>,
+    mut contexts: Query<&mut EguiContext>,
+    mut focus: ResMut<input::UiFocus>,
+) {
+    let mut taken = input::UiFocus::default();
+    if clean.is_none() {
+        for mut context in contexts.iter_mut() {
+            let ctx = context.get_mut();
+            taken.pointer |= ctx.wants_pointer_input() || ctx.is_pointer_over_area();
+            taken.keyboard |= ctx.wants_keyboard_input();
+        }
+    }
+    focus.set_if_neq(taken);
+}
+
+#[cfg(feature = "egui")]
+#[allow(clippy::too_many_arguments)]
+fn ui_system(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    mut cells_param: ResMut<CellsParam>,
+    mut tempo: ResMut<tempo::Tempo>,
+    mut palette: ResMut<palette::Palette>,
+    files: Option<Res<palette_file::FilePalettes>>,
+    mut frame_pacing: ResMut<display::FramePacing>,
+    mut latency: ResMut<preview::PreviewLatency>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    use bevy::window::PresentMode;
+
+    // only touch the parameters (and wake everything watching them) on edits
+    let mut cell_size = cells_param.cell_size;
+    let mut circle_size = cells_param.circle_size;
+    let mut circles_per_cell = cells_param.circles_per_cell;
+    let mut easing = cells_param.easing;
+    let mut span_sec = tempo.span_sec();
+    let mut palette_name = palette.name.clone();
+    let mut colors = palette.colors.clone();
+    let mut respawn_now = false;
+    let mut glow = cells_param.glow;
+    let mut swing = cells_param.swing;
+    let mut pacing = *frame_pacing;
+    let mut delay_sec = latency.delay_sec;
+    let mut palettes = palette::presets();
+    palettes.extend(files.iter().flat_map(|files| files.0.iter().cloned()));
+    let ctx = panel_ctx(&mut contexts, &control);
+    egui::Window::new("Moving Cells").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("cell size");
+            ui.add(egui::DragValue::new(&mut cell_size.x).speed(0.5).range(1.0..=500.0).prefix("x "));
+            ui.add(egui::DragValue::new(&mut cell_size.y).speed(0.5).range(1.0..=500.0).prefix("y "));
+        });
+        ui.add(egui::Slider::new(&mut circle_size, 1.0..=100.0).text("circle size"));
+        ui.add(egui::Slider::new(&mut span_sec, 0.1..=10.0).logarithmic(true).text("seconds per cell"));
+        egui::ComboBox::from_label("easing").selected_text(format!("{:?}", easing)).show_ui(ui, |ui| {
+            for choice in Easing::ALL {
+                ui.selectable_value(&mut easing, choice, format!("{:?}", choice));
+            }
+        });
+        egui::ComboBox::from_label("palette").selected_text(palette_name.clone()).show_ui(ui, |ui| {
+            for choice in &palettes {
+                ui.selectable_value(&mut palette_name, choice.name.clone(), &choice.name);
+            }
+        });
+        // the slots of the palette, each with a color picker
+        ui.horizontal_wrapped(|ui| {
+            for color in colors.iter_mut() {
+                let mut rgba = Srgba::from(*color).to_f32_array();
+                if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+                    *color = Srgba::from_f32_array(rgba).into();
+                }
+            }
+            if ui.small_button("+").clicked() {
+                colors.push(colors.last().copied().unwrap_or(Color::WHITE));
+            }
+            if colors.len() > 1 && ui.small_button("-").clicked() {
+                colors.pop();
+            }
+        });
+        ui.add(egui::Slider::new(&mut circles_per_cell, 1..=8).text("circles per cell"));
+        respawn_now = ui.button("respawn").clicked();
+
+        ui.separator();
+        let mut on = glow.is_some();
+        ui.checkbox(&mut on, "glow");
+        glow = on.then(|| glow.unwrap_or_default());
+        if let Some(glow) = &mut glow {
+            ui.add(egui::Slider::new(&mut glow.intensity, 0.0..=1.0).text("intensity"));
+            ui.add(egui::Slider::new(&mut glow.threshold, 0.0..=2.0).text("threshold"));
+        }
+
+        let mut on = swing.is_some();
+        ui.checkbox(&mut on, "swing camera");
+        swing = on.then(|| swing.unwrap_or_default());
+        if let Some(swing) = &mut swing {
+            ui.add(egui::Slider::new(&mut swing.amplitude, 0.0..=200.0).text("amplitude"));
+            ui.add(egui::Slider::new(&mut swing.frequency, 0.0..=2.0).text("frequency"));
+            ui.horizontal(|ui| {
+                ui.label("axis");
+                ui.add(egui::DragValue::new(&mut swing.axis.x).speed(0.05).prefix("x "));
+                ui.add(egui::DragValue::new(&mut swing.axis.y).speed(0.05).prefix("y "));
+                ui.add(egui::DragValue::new(&mut swing.axis.z).speed(0.05).prefix("z "));
+            });
+        }
+
+        ui.separator();
+        egui::ComboBox::from_label("present mode")
+            .selected_text(format!("{:?}", pacing.present_mode))
+            .show_ui(ui, |ui| {
+                for mode in [PresentMode::AutoVsync, PresentMode::Mailbox, PresentMode::Immediate] {
+                    ui.selectable_value(&mut pacing.present_mode, mode, format!("{:?}", mode));
+                }
+            });
+        let mut on = pacing.fps_cap.is_some();
+        ui.checkbox(&mut on, "cap frame rate");
+        pacing.fps_cap = on.then(|| pacing.fps_cap.unwrap_or(30.0));
+        if let Some(fps) = &mut pacing.fps_cap {
+            ui.add(egui::Slider::new(fps, 1.0..=240.0).text("fps"));
+        }
+
+        ui.separator();
+        // 0 closes the preview window
+        ui.add(egui::Slider::new(&mut delay_sec, 0.0..=2.0).text("preview delay (s)"));
+    });
+    if cell_size != cells_param.cell_size {
+        cells_param.cell_size = cell_size;
+    }
+    if circle_size != cells_param.circle_size {
+        cells_param.circle_size = circle_size;
+    }
+    if easing != cells_param.easing {
+        cells_param.easing = easing;
+    }
+    if span_sec != tempo.span_sec() {
+        // the bpm stays in step with the music; the cells take more or fewer beats
+        tempo.beats_per_cell = span_sec * tempo.bpm / 60.0;
+    }
+    if palette_name != palette.name {
+        if let Some(choice) = palettes.into_iter().find(|p| p.name == palette_name) {
+            *palette = choice;
+        }
+    } else if colors != palette.colors {
+        // recolors the circles as they are
+        palette.colors = colors;
+    }
+    // more or fewer circles on a cell are new entities
+    if circles_per_cell != cells_param.circles_per_cell {
+        cells_param.circles_per_cell = circles_per_cell;
+        respawn_now = true;
+    }
+    if respawn_now {
+        respawn.write(RespawnCells);
+    }
+    if glow != cells_param.glow {
+        cells_param.glow = glow;
+    }
+    if swing != cells_param.swing {
+        cells_param.swing = swing;
+    }
+    if pacing != *frame_pacing {
+        *frame_pacing = pacing;
+    }
+    if delay_sec != latency.delay_sec {
+        latency.delay_sec = delay_sec;
+    }
+}
+
+/// The layout as text, with the culprit of the last failed Apply marked.
+#[cfg(feature = "egui")]
+fn layout_text_ui(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    source: Option<Res<dialect::LayoutSource>>,
+    mut layout_text: ResMut<text_editor::LayoutText>,
+    mut apply: EventWriter<text_editor::ApplyLayoutText>,
+) {
+    let dialect = source.map_or(dialect::Dialect::Keisen, |source| source.dialect);
+    let marked = layout_text.error_range(dialect);
+    let ctx = panel_ctx(&mut contexts, &control);
+    egui::Window::new("Layout").show(ctx, |ui| {
+        let mut layouter = |ui: &egui::Ui, text: &str, _wrap_width: f32| {
+            let format = egui::TextFormat::simple(egui::FontId::monospace(14.0), ui.visuals().text_color());
+            let mut job = egui::text::LayoutJob::default();
+            match marked.clone().filter(|range| text.get(range.clone()).is_some()) {
+                Some(range) => {
+                    job.append(&text[..range.start], 0.0, format.clone());
+                    let mark = egui::TextFormat { background: egui::Color32::DARK_RED, ..format.clone() };
+                    job.append(&text[range.clone()], 0.0, mark);
+                    job.append(&text[range.end..], 0.0, format);
+                }
+                None => job.append(text, 0.0, format),
+            }
+            ui.fonts(|fonts| fonts.layout_job(job))
+        };
+        let mut text = layout_text.text.clone();
+        let edited = ui
+            .add(egui::TextEdit::multiline(&mut text).code_editor().desired_rows(12).layouter(&mut layouter))
+            .changed();
+        if edited {
+            layout_text.text = text;
+            layout_text.error = None;
+        }
+        ui.horizontal(|ui| {
+            if ui.add_enabled(layout_text.is_edited(), egui::Button::new("apply")).clicked() {
+                apply.write(text_editor::ApplyLayoutText);
+            }
+            ui.label(format!("({})", dialect.name()));
+        });
+        if let Some(e) = &layout_text.error {
+            let at = layout_text.error_position(dialect).map_or(String::new(), |(line, column)| {
+                format!("line {}, column {}: ", line + 1, column + 1)
+            });
+            ui.colored_label(egui::Color32::LIGHT_RED, format!("{}{}", at, e));
+        }
+    });
+}
+
+/// The brushes, while painting.
+#[cfg(feature = "egui")]
+fn paint_ui(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    editor: Option<ResMut<paint::PaintEditor>>,
+) {
+    let Some(mut editor) = editor else {
+        return;
+    };
+    let mut brush = editor.brush;
+    let ctx = panel_ctx(&mut contexts, &control);
+    egui::Window::new("Paint").show(ctx, |ui| {
+        ui.selectable_value(&mut brush, None, "next kind of cell");
+        ui.horizontal_wrapped(|ui| {
+            for glyph in paint::brushes() {
+                let label = egui::RichText::new(if glyph == ' ' { "blank".to_string() } else { glyph.to_string() })
+                    .monospace()
+                    .size(18.0);
+                ui.selectable_value(&mut brush, Some(glyph), label);
+            }
+        });
+    });
+    if brush != editor.brush {
+        editor.brush = brush;
+    }
+}
+
+/// The presets to load, and a name to save the current state as.
+#[cfg(feature = "egui")]
+fn preset_ui(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    presets: Res<preset::Presets>,
+    mut save: EventWriter<preset::SavePreset>,
+    mut load: EventWriter<preset::LoadPreset>,
+    mut name: Local<String>,
+) {
+    let ctx = panel_ctx(&mut contexts, &control);
+    egui::Window::new("Presets").show(ctx, |ui| {
+        for preset in &presets.names {
+            ui.horizontal(|ui| {
+                if ui.button("load").clicked() {
+                    load.write(preset::LoadPreset(preset.clone()));
+                }
+                ui.label(preset);
+            });
+        }
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut *name);
+            if ui.add_enabled(preset::valid_name(&name), egui::Button::new("save current as")).clicked() {
+                save.write(preset::SavePreset(name.trim().to_string()));
+            }
+        });
+    });
+}
+
+/// The layout files next to the current one, to switch to.
+#[cfg(feature = "egui")]
+fn layouts_ui(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    file: Option<Res<layout_file::LayoutFile>>,
+    mut load: EventWriter<layout_file::LoadLayoutFile>,
+    mut listed: Local<Option<Vec<std::path::PathBuf>>>,
+) {
+    let dir = layout_file::layout_dir(file.as_deref());
+    // the folder is read again when the layout changes, or on request
+    if listed.is_none() || file.as_ref().is_some_and(|file| file.is_changed()) {
+        *listed = Some(layout_file::list_layouts(&dir).unwrap_or_else(|e| {
+            println!("could not list {}: {}", dir.display(), e);
+            Vec::new()
+        }));
+    }
+    let ctx = panel_ctx(&mut contexts, &control);
+    let mut rescan = false;
+    egui::Window::new("Layouts").show(ctx, |ui| {
+        for path in listed.iter().flatten() {
+            let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into());
+            let current = file.as_ref().is_some_and(|file| file.path.file_name() == path.file_name());
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!current, egui::Button::new("load")).clicked() {
+                    load.write(layout_file::LoadLayoutFile(path.clone()));
+                }
+                ui.label(name);
+            });
+        }
+        ui.separator();
+        rescan = ui.button(format!("rescan {}", dir.display())).clicked();
+    });
+    if rescan {
+        *listed = None;
+    }
+}
+
+/// Where the animation is, within the cell and within the whole loop (and
+/// the timeline, if there is one); draggable while paused.
+#[cfg(feature = "egui")]
+fn scrubber_ui(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    mut clock: ResMut<clock::SimClock>,
+    phase: Res<clock::MotionPhase>,
+    tempo: Res<tempo::Tempo>,
+    rings: Res<ring::Rings>,
+    timeline: Option<Res<timeline::Timeline>>,
+) {
+    let ctx = panel_ctx(&mut contexts, &control);
+    let mut paused = clock.paused;
+    let mut rate = phase.rate();
+    let period = rings.global_period() as f64;
+    let loop_start = phase.cells() - phase.cells().rem_euclid(period);
+    let mut in_loop = phase.cells() - loop_start;
+    let duration = timeline.map_or(0.0, |timeline| timeline.duration());
+    let mut elapsed = clock.elapsed_secs_f64();
+    let mut seek = None;
+    egui::Window::new("Timeline").show(ctx, |ui| {
+        ui.checkbox(&mut paused, "paused (drag to scrub)");
+        let cell = egui::Slider::new(&mut rate, 0.0..=1.0).text(format!("cell ({:.2} s)", tempo.span_sec()));
+        if ui.add_enabled(paused, cell).changed() {
+            seek = Some(phase.elapsed_at(&clock, &tempo, phase.cells().floor() + rate as f64));
+        }
+        let whole = egui::Slider::new(&mut in_loop, 0.0..=period).text(format!("loop ({} cells)", period));
+        if ui.add_enabled(paused, whole).changed() {
+            seek = Some(phase.elapsed_at(&clock, &tempo, loop_start + in_loop));
+        }
+        if duration > 0.0 {
+            let keyframes = egui::Slider::new(&mut elapsed, 0.0..=duration).text("timeline (s)");
+            if ui.add_enabled(paused, keyframes).changed() {
+                seek = Some(elapsed);
+            }
+        }
+    });
+    if paused != clock.paused {
+        clock.paused = paused;
+    }
+    if let Some(elapsed) = seek {
+        clock.seek(elapsed);
+    }
+}
+
+/// Every ring, with a button reversing it alone.
+#[cfg(feature = "egui")]
+fn rings_ui(
+    mut contexts: EguiContexts,
+    control: ControlContext,
+    rings: Res<ring::Rings>,
+    mut flips: EventWriter<direction::FlipRing>,
+) {
+    let ctx = panel_ctx(&mut contexts, &control);
+    egui::Window::new("Rings").show(ctx, |ui| {
+        for (index, ring) in rings.0.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.button("flip").clicked() {
+                    flips.write(direction::FlipRing(index));
+                }
+                ui.label(format!("ring {} ({} cells)", index, ring.len()));
+            });
+        }
+    });
+}
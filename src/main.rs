@@ -1,375 +1,375 @@
-use std::f32::consts::PI;
-
-use bevy::{color::palettes::css::WHITE, gltf::{GltfMesh, GltfNode}, math::ops::sin_cos, prelude::*};
-
-use bevy_asset_loader::asset_collection::AssetCollection;
-
-#[cfg(feature = "egui")]
-use bevy_egui::{egui, EguiContexts, EguiPlugin};
-use bimap::BiMap;
-use rand::{rngs::StdRng, Rng as _, SeedableRng};
-
-fn main() {
-    use bevy_asset_loader::loading_state::{config::ConfigureLoadingState, LoadingState, LoadingStateAppExt};
-
-    let mut app = App::new();
-
-    app
-        .add_plugins(DefaultPlugins)
-        // .insert_resource(ClearColor(Color::srgb(0.0, 0.0, 0.0)))
-        .insert_resource(AmbientLight {
-            // brightness: 750.0,
-            brightness: 200.0,
-            ..default()
-        })
-        .insert_resource(CellsParam {
-            cell_table: CellTable::new("\
-               ┌→→→→→→→→→┐
-               ↑ ┌→→→→→┐ ↓
-               ↑ ↑0   0↓ ↓
-               ↑ └←←←←←┘ ↓
-               ↑ ┏←┓ ┌→┐ ↓
-               ↑ ↓0↑ ↑0↓ ↓
-               ↑ ┗→┛ └←┘ ↓
-               ↑ ┏←←←←←┓ ↓
-               ↑ ↓0   0↑ ↓
-               ↑ ┗→→→→→┛ ↓
-               └←←←←←←←←←┘\
-                "),
-            cell_size: Vec2::new(50.0, 50.0),
-            circle_size: 10.0,
-            span_sec: 1.0,
-        })
-        .init_state::<AssetLoadingState>()
-        .add_loading_state(
-            LoadingState::new(AssetLoadingState::Loading)
-                .continue_to_state(AssetLoadingState::Loaded)
-                .load_collection::<GltfAssets>()
-        )
-        .add_systems(Startup, spawn_loading_text)
-        .add_systems(OnEnter(AssetLoadingState::Loaded), cleanup_loading_text.before(setup))
-        .add_systems(OnEnter(AssetLoadingState::Loaded), setup)
-        .add_systems(Update, move_cells)
-        // .add_systems(Update, swing_camera)
-        ;
-
-    #[cfg(feature = "egui")]
-    app
-        .add_plugins(EguiPlugin{enable_multipass_for_primary_context: false})
-        .add_systems(Update, ui_system);
-
-    app
-        .run();
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use bevy::{app::TaskPoolOptions, prelude::*};
+
+use moving_cells_01::{
+    agent::TravelMode,
+    background::Background,
+    camera::{Shake, Swing},
+    clock::Timestep,
+    color_mode::ColorMode,
+    control_window::ControlWindow,
+    culling::Culling,
+    daily::{daily_layout, Date},
+    diagnostics::DiagnosticsHud,
+    display::{FramePacing, Kiosk, WindowConfig},
+    easing::Easing,
+    exposure::{expose_loop, LongExposure},
+    feedback_buffer::FeedbackBuffer,
+    gpu_motion::GpuMotion,
+    idle::Idle,
+    input::{key_name, read_bindings, InputMap},
+    instanced::Instanced,
+    layout_file::{read_layout, LayoutFile},
+    motion_blur::MotionBlur,
+    noise::Jitter,
+    opacity::Opacity,
+    perf::{parse_size, run_table, tiled_table, Bench, BenchPlugin},
+    post::{Pixelate, PostEffects},
+    preview::PreviewLatency,
+    shading::{Blend, CircleShading},
+    shape::Shape,
+    split::SplitScreen,
+    sprites::{read_manifest, MANIFEST_PATH},
+    tempo::Tempo,
+    tokens::GltfTokens,
+    wall::DisplayWall,
+    CellTable, CellsParam, CornerStyle, MovingCellsPlugin, PhaseFormula, Stepping,
+};
+
+/// `--daily` shows today's layout, `--date YYYY-MM-DD` the one of any other day.
+fn daily_date() -> Option<Date> {
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(i) = args.iter().position(|a| a == "--date") {
+        let date = args.get(i + 1).and_then(|d| Date::parse(d));
+        return Some(date.unwrap_or_else(|| panic!("--date expects YYYY-MM-DD")));
+    }
+    args.iter().any(|a| a == "--daily").then(Date::today_utc)
 }
 
-#[derive(Component)]
-struct LoadingText;
-
-#[derive(Component)]
-struct Cell {
-    pub pos: Vec2,
-    pub move_type: MoveType,
+/// `--long-exposure out.png` renders one loop into a single image instead.
+fn long_exposure_path() -> Option<String> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let i = args.iter().position(|a| a == "--long-exposure")?;
+    Some(args.get(i + 1).cloned().unwrap_or_else(|| panic!("--long-exposure expects a file name")))
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum MoveType {
-    Blank,
-    Center,
-    Left,
-    BottomToLeft,
-    TopToLeft,
-    Right,
-    BottomToRight,
-    TopToRight,
-    Up,
-    LeftToTop,
-    RightToTop,
-    Down,
-    LeftToBottom,
-    RightToBottom,
+/// `--layout loop.txt` loads the layout from a file; its tweaks are kept in
+/// `loop.tweaks.ron` next to it.
+fn layout_path() -> Option<PathBuf> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let i = args.iter().position(|a| a == "--layout")?;
+    Some(args.get(i + 1).map(PathBuf::from).unwrap_or_else(|| panic!("--layout expects a file name")))
 }
 
-impl Cell {
-    fn new(pos: Vec2, move_type: MoveType) -> Self {
-        Cell { pos, move_type }
-    }
+/// `--compare other.txt` shows another layout next to the main one.
+fn compare_path() -> Option<PathBuf> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let i = args.iter().position(|a| a == "--compare")?;
+    Some(args.get(i + 1).map(PathBuf::from).unwrap_or_else(|| panic!("--compare expects a file name")))
 }
 
-struct CellTable {
-    pub table: Vec<Vec<char>>,
-    pub width: usize,
-    pub height: usize,
+/// `--bench 400x250` times a generated table of that many cells for
+/// `--frames` frames (300 by default), in a window or with `--headless`,
+/// prints the statistics and quits.
+fn bench() -> Option<(usize, usize, u32)> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let i = args.iter().position(|a| a == "--bench")?;
+    let (width, height) = args
+        .get(i + 1)
+        .and_then(|size| parse_size(size))
+        .unwrap_or_else(|| panic!("--bench expects a size like 400x250"));
+    let frames = match args.iter().position(|a| a == "--frames") {
+        Some(i) => args.get(i + 1).and_then(|n| n.parse().ok()).unwrap_or_else(|| panic!("--frames expects a number")),
+        None => 300,
+    };
+    Some((width, height, frames))
 }
 
-impl CellTable {
-    fn new(_cell_info: &str) -> Self {
-        // first, trimming
-        let cell_info = _cell_info.lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n");
-        println!("cell_info:\n{}", cell_info);
-
-        // construct a table
-        let mut table = Vec::new();
-
-        let width = cell_info.lines().map(|line| line.chars().count()).max().unwrap();
-        let height = cell_info.lines().count();
-
-        for line in cell_info.lines() {
-            let mut row = Vec::new();
-            for c in line.chars() {
-                row.push(c);
-            }
-            table.push(row);
-        }
+/// `--threads 4` caps the threads the simulation (e.g. `move_cells`) is
+/// spread over; all cores by default.
+fn task_pools() -> TaskPoolPlugin {
+    let args = std::env::args().collect::<Vec<_>>();
+    let Some(i) = args.iter().position(|a| a == "--threads") else {
+        return TaskPoolPlugin::default();
+    };
+    let threads = args.get(i + 1).and_then(|n| n.parse().ok()).unwrap_or_else(|| panic!("--threads expects a number"));
+    TaskPoolPlugin { task_pool_options: TaskPoolOptions::with_num_threads(threads) }
+}
 
-        CellTable {
-            width,
-            height,
-            table,
-        }
+/// `--size 1280x720`, `--monitor 1`, `--title "..."` and `--fullscreen`
+/// open the window that way; F11 or Alt+Enter toggles fullscreen later on.
+/// `--kiosk` is fullscreen too; `--overlay` is transparent and click-through.
+fn window_plugin() -> WindowPlugin {
+    let args = std::env::args().collect::<Vec<_>>();
+    let value = |flag: &str| args.iter().position(|a| a == flag).map(|i| args.get(i + 1));
+    let mut config = WindowConfig::default();
+    if let Some(size) = value("--size") {
+        let (width, height) = size
+            .and_then(|size| parse_size(size))
+            .unwrap_or_else(|| panic!("--size expects a size like 1280x720"));
+        config.size = Some(UVec2::new(width as u32, height as u32));
     }
-
-    fn get(&self, x: usize, y: usize) -> char {
-        if y >= self.height {
-            return ' ';
-        }
-        let row = &self.table[y];
-        if x >= row.len() {
-            return ' ';
-        }
-        row[x]
+    if let Some(monitor) = value("--monitor") {
+        config.monitor =
+            Some(monitor.and_then(|n| n.parse().ok()).unwrap_or_else(|| panic!("--monitor expects a number")));
     }
-}
-
-#[derive(Resource)]
-struct CellsParam {
-    pub cell_table: CellTable,
-    pub cell_size: Vec2,
-    pub circle_size: f32,
-    pub span_sec: f32,
-}
-
-fn spawn_loading_text(mut commands: Commands) {
-    commands
-        .spawn( (
-            Text::new("loading..."),
-            Node {
-                position_type: PositionType::Relative,
-                top: Val::Percent(50.0),
-                left: Val::Percent(50.0),
-                ..default()
-            },
-            LoadingText,
-        ));
-}
-
-fn cleanup_loading_text(
-    mut commands: Commands,
-    loading_text: Query<Entity, With<LoadingText>>,
-) {
-    for entity in loading_text.iter() {
-        commands.entity(entity).despawn();
+    if let Some(title) = value("--title") {
+        config.title = title.cloned().unwrap_or_else(|| panic!("--title expects a title"));
     }
+    config.fullscreen = args.iter().any(|a| a == "--fullscreen" || a == "--kiosk");
+    config.overlay = args.iter().any(|a| a == "--overlay");
+    WindowPlugin { primary_window: Some(config.window()), ..default() }
 }
 
-#[derive(AssetCollection, Resource)]
-pub struct GltfAssets {
-//   #[asset(path = "models/stairs.glb")]
-//   pub iroha: Handle<Gltf>,
+/// `--present-mode vsync|mailbox|immediate` and `--fps 30` pace the frames.
+fn frame_pacing() -> FramePacing {
+    let args = std::env::args().collect::<Vec<_>>();
+    let value = |flag: &str| args.iter().position(|a| a == flag).map(|i| args.get(i + 1));
+    let mut pacing = FramePacing::default();
+    if let Some(mode) = value("--present-mode") {
+        pacing.present_mode = mode
+            .and_then(|mode| FramePacing::parse_present_mode(mode))
+            .unwrap_or_else(|| panic!("--present-mode expects vsync, mailbox or immediate"));
+    }
+    if let Some(fps) = value("--fps") {
+        pacing.fps_cap = Some(fps.and_then(|n| n.parse().ok()).unwrap_or_else(|| panic!("--fps expects a number")));
+    }
+    pacing
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
-pub enum AssetLoadingState {
-    #[default]
-    Loading,
-    Loaded,
+/// `--preview-delay 0.2` opens the operator preview, 0.2 seconds behind the
+/// output (see [`PreviewLatency`]).
+fn preview_latency() -> Option<PreviewLatency> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let i = args.iter().position(|a| a == "--preview-delay")?;
+    let delay_sec = args
+        .get(i + 1)
+        .and_then(|n| n.parse().ok())
+        .filter(|sec: &f32| *sec >= 0.0)
+        .unwrap_or_else(|| panic!("--preview-delay expects seconds"));
+    Some(PreviewLatency { delay_sec })
 }
 
-struct MyTransform(Transform);
-
-impl From<Vec2> for MyTransform {
-    fn from(value: Vec2) -> Self {
-        MyTransform(Transform::from_xyz(value.x, value.y, 0.0))
+/// `--wall 3x2` spreads the table over 3 by 2 monitors, from the one given
+/// by `--wall-monitor` (0 by default), with `--bezel 40x30` pixels hidden
+/// behind the frames between them.
+fn display_wall() -> Option<DisplayWall> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let value = |flag: &str| args.iter().position(|a| a == flag).map(|i| args.get(i + 1));
+    let (columns, rows) = value("--wall")?
+        .and_then(|size| parse_size(size))
+        .unwrap_or_else(|| panic!("--wall expects a size like 3x2"));
+    let mut wall = DisplayWall { columns: columns as u32, rows: rows as u32, ..default() };
+    if let Some(monitor) = value("--wall-monitor") {
+        wall.first_monitor =
+            monitor.and_then(|n| n.parse().ok()).unwrap_or_else(|| panic!("--wall-monitor expects a number"));
     }
-}
-
-fn move_type_from_char(c: char) -> MoveType {
-    // NOTE
-    // - thin keisen: clock wise
-    // - thick keisen: counter clock wise
-    match c {
-        ' ' => MoveType::Blank,
-        '0' => MoveType::Center,
-        '←' => MoveType::Left,
-        '┓' => MoveType::BottomToLeft,
-        '┘' => MoveType::TopToLeft,
-        '→' => MoveType::Right,
-        '┌' => MoveType::BottomToRight,
-        '┗' => MoveType::TopToRight,
-        '↑' => MoveType::Up,
-        '┛' => MoveType::LeftToTop,
-        '└' => MoveType::RightToTop,
-        '↓' => MoveType::Down,
-        '┐' => MoveType::LeftToBottom,
-        '┏' => MoveType::RightToBottom,
-        _ => panic!("Invalid cell type: {}", c),
+    if let Some(bezel) = value("--bezel") {
+        // unlike the sizes above, either may be 0
+        let (x, y) = bezel
+            .and_then(|size| size.split_once(['x', 'X']))
+            .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .unwrap_or_else(|| panic!("--bezel expects a size like 40x30"));
+        wall.bezel = Vec2::new(x, y);
     }
+    Some(wall)
 }
 
-fn create_cell(cell_type: char, pos: Vec2) -> Cell {
-    let move_type = move_type_from_char(cell_type);
-    Cell::new(pos, move_type)
+/// `--keys bindings.ron` rebinds the hotkeys; `keys.ron` is read if it is
+/// there.
+fn input_map() -> InputMap {
+    let args = std::env::args().collect::<Vec<_>>();
+    let path = match args.iter().position(|a| a == "--keys") {
+        Some(i) => args.get(i + 1).map(PathBuf::from).unwrap_or_else(|| panic!("--keys expects a file name")),
+        None => PathBuf::from("keys.ron"),
+    };
+    let mut input_map = InputMap::default();
+    match read_bindings(&path) {
+        Ok(Some(Ok(bindings))) => {
+            input_map.rebind(&bindings);
+            println!("key bindings from {}", path.display());
+        }
+        Ok(None) => {}
+        Ok(Some(Err(e))) => println!("ignoring broken key bindings {}: {}", path.display(), e),
+        Err(e) => println!("could not read {}: {}", path.display(), e),
+    }
+    for key in input_map.conflicts() {
+        println!("{} is bound to more than one action", key_name(key));
+    }
+    input_map
 }
 
-fn setup(
-    mut commands: Commands,
-    // mut asset_server: ResMut<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    // gltf_res: Res<GltfAssets>,
-    // assets_gltf: Res<Assets<Gltf>>,
-    // assets_gltfmeshes: Res<Assets<GltfMesh>>,
-    // assets_gltfnodes: Res<Assets<GltfNode>>,
-    mut cells_param: ResMut<CellsParam>,
-    mut meshes: ResMut<Assets<Mesh>>,
-) {
-    // Create a camera
-    commands.spawn((
-        Camera2d::default(),
-    ));
-
-        // commands.spawn((
-        //     Mesh3d(mesh_handle.clone()),
-        //     Transform::from_xyz(x, y, z).with_rotation(rotation),
-        //     MeshMaterial3d( materials.add(
-        //         StandardMaterial {
-        //             base_color: Color::srgb(0.8, 0.7, 0.6),
-        //             ..default()
-        //         }
-        //     ))
-        // ));
-        
-    let mesh = meshes.add(Circle::new (
-        cells_param.circle_size
-    ));
-
-    let pos = Vec2::new(0.0, 0.0);
-    let rot = Quat::from_rotation_z(0.0);
-
-    let w = cells_param.cell_table.width;
-    let h = cells_param.cell_table.height;
-
-    let base_x = -((w as f32) * cells_param.cell_size.x) / 2.0;
-    let base_y = -((h as f32) * cells_param.cell_size.y) / 2.0;
-
-    for _iy in 0..h {
-        // flip y
-        let iy = h - _iy - 1;
-        for ix in 0..w {
-            let c = cells_param.cell_table.get(ix, iy);
-            println!("{}, {} = {:?}", ix, _iy, move_type_from_char(c));
-            let x = ix as f32 * cells_param.cell_size.x + base_x;
-            let y = _iy as f32 * cells_param.cell_size.y + base_y;
-            let pos = Vec2::new(x as f32, y as f32);
-            let rot = Quat::from_rotation_z(0.0);
-            commands.spawn((
-                Mesh2d(mesh.clone()),
-                MyTransform::from(pos).0.with_rotation(rot),
-                MeshMaterial2d(materials.add(Color::from(WHITE))),
-                create_cell(c, pos),
-            ));
+fn main() {
+    let bench = bench();
+    if let Some((width, height, frames)) = bench {
+        if std::env::args().any(|a| a == "--headless") {
+            // the pools are made once; the headless app finds these
+            task_pools().task_pool_options.create_default_pools();
+            let name = format!("{}x{}", width, height);
+            println!("{}", run_table(&name, tiled_table(width, height, false), false, frames).to_json());
+            return;
         }
     }
-}
 
-fn map (
-    input: f32,
-    in_min: f32,
-    in_max: f32,
-    out_min: f32,
-    out_max: f32,
-) -> f32 {
-    (input - in_min) * (out_max - out_min) / (in_max - in_min) + out_min
-}
+    let layout_file = layout_path();
+    let cell_table = match (bench, &layout_file, daily_date()) {
+        (Some((width, height, _)), ..) => tiled_table(width, height, false),
+        (None, Some(path), _) => match read_layout(path) {
+            Ok(Ok(table)) => table,
+            Ok(Err(e)) => panic!("{} is no layout: {}", path.display(), e),
+            Err(e) => panic!("could not read {}: {}", path.display(), e),
+        },
+        (None, None, Some(date)) => {
+            println!("daily layout of {}", date);
+            daily_layout(date)
+        }
+        (None, None, None) => CellTable::new("\
+               ┌→→→→→→→→→┐
+               ↑ ┌→→→→→┐ ↓
+               ↑ ↑0   0↓ ↓
+               ↑ └←←←←←┘ ↓
+               ↑ ┏←┓ ┌→┐ ↓
+               ↑ ↓0↑ ↑0↓ ↓
+               ↑ ┗→┛ └←┘ ↓
+               ↑ ┏←←←←←┓ ↓
+               ↑ ↓0   0↑ ↓
+               ↑ ┗→→→→→┛ ↓
+               └←←←←←←←←←┘\
+                "),
+    };
+
+    let cells_param = CellsParam {
+        cell_table,
+        cell_size: Vec2::new(50.0, 50.0),
+        circle_size: 10.0,
+        travel_mode: TravelMode::Oscillate,
+        easing: Easing::Linear,
+        cell_easings: HashMap::new(),
+        corner_style: CornerStyle::Arc,
+        phase_formula: PhaseFormula::None,
+        stepping: Stepping::Glide,
+        jitter: Jitter { amplitude: 0.0, frequency: 0.5 },
+        spring: None,
+        idle: Idle::Still,
+        gravity: None,
+        circles_per_cell: 1,
+        opacity: Opacity::Opaque,
+        color_mode: ColorMode::Palette,
+        shape: Shape::Circle,
+        class_shapes: HashMap::new(),
+        glow: None,
+        blend: if std::env::args().any(|a| a == "--additive") { Blend::Additive } else { Blend::Normal },
+        // nothing but the circles over the desktop
+        background: if std::env::args().any(|a| a == "--overlay") {
+            Background::Solid(Color::NONE)
+        } else {
+            Background::Solid(Color::BLACK)
+        },
+        swing: std::env::args().any(|a| a == "--swing").then(Swing::default),
+    };
+    let tempo = Tempo {
+        bpm: 60.0,
+        beats_per_cell: 1.0,
+    };
+
+    if let Some(path) = long_exposure_path() {
+        let mut exposure = LongExposure::fit(&cells_param, 2.0);
+        expose_loop(cells_param, tempo, 4000, &mut exposure);
+        let image = exposure.to_image(8.0).try_into_dynamic().expect("exposures are RGBA8");
+        if let Err(e) = image.save(&path) {
+            panic!("could not write {}: {}", path, e);
+        }
+        println!("wrote {} ({} sub-frames)", path, exposure.samples());
+        return;
+    }
 
-fn move_cells(
-    time: Res<Time>,
-    mut cells_param: ResMut<CellsParam>,
-    mut cells: Query<(&mut Transform, &mut Cell)>,
-) {
-    let ss = cells_param.span_sec;
-    let w = cells_param.cell_size.x;
-    let h = cells_param.cell_size.y;
-    let w_hf = w / 2.0;
-    let h_hf = h / 2.0;
-    let rate: f32 = ((time.elapsed_secs_f64() % (ss as f64)) / (ss as f64)) as f32;
+    let mut app = App::new();
 
-    // move circle from right to left
-    for (mut transform, mut cell) in cells.iter_mut() {
-        let x = cell.pos.x;
-        let y = cell.pos.y;
-        let move_type = cell.move_type;
-        match move_type {
-            MoveType::Blank => {
-                // WORKAROUND
-                transform.translation.x = -999999.0;
-                transform.translation.y = -999999.0;
-            }
-            MoveType::Center => {
-                // do nothing
-                // transform.translation.x = x;
-                // transform.translation.y = y;
-            }
-            MoveType::Left => {
-                transform.translation.x = map(rate, 0.0, 1.0, x + w_hf, x - w_hf);
-            }
-            MoveType::BottomToLeft => {
-                transform.translation.x = map(rate, 0.0, 1.0, x, x - w_hf);
-                transform.translation.y = map(rate, 0.0, 1.0, y - w_hf, y);
-            }
-            MoveType::TopToLeft => {
-                transform.translation.x = map(rate, 0.0, 1.0, x, x - w_hf);
-                transform.translation.y = map(rate, 0.0, 1.0, y + h_hf, y);
-            }
-            MoveType::Right => {
-                transform.translation.x = map(rate, 0.0, 1.0, x - w_hf, x + w_hf);
-            }
-            MoveType::BottomToRight => {
-                transform.translation.x = map(rate, 0.0, 1.0, x, x + w_hf);
-                transform.translation.y = map(rate, 0.0, 1.0, y - w_hf, y);
-            }
-            MoveType::TopToRight => {
-                transform.translation.x = map(rate, 0.0, 1.0, x, x + w_hf);
-                transform.translation.y = map(rate, 0.0, 1.0, y + h_hf, y);
-            }
-            MoveType::Up => {
-                transform.translation.y = map(rate, 0.0, 1.0, y - h_hf, y + h_hf);
-            }
-            MoveType::LeftToTop => {
-                transform.translation.x = map(rate, 0.0, 1.0, x - w_hf, x);
-                transform.translation.y = map(rate, 0.0, 1.0, y, y + h_hf);
-            }
-            MoveType::RightToTop => {
-                transform.translation.x = map(rate, 0.0, 1.0, x + w_hf, x);
-                transform.translation.y = map(rate, 0.0, 1.0, y, y + h_hf);
-            }
-            MoveType::Down => {
-                transform.translation.y = map(rate, 0.0, 1.0, y + h_hf, y - h_hf);
-            }
-            MoveType::LeftToBottom => {
-                transform.translation.x = map(rate, 0.0, 1.0, x - w_hf, x);
-                transform.translation.y = map(rate, 0.0, 1.0, y, y - h_hf);
-            }
-            MoveType::RightToBottom => {
-                transform.translation.x = map(rate, 0.0, 1.0, x + w_hf, x);
-                transform.translation.y = map(rate, 0.0, 1.0, y, y - h_hf);
-            }
+    app
+        .add_plugins(DefaultPlugins.set(task_pools()).set(window_plugin()))
+        .insert_resource(AmbientLight {
+            // brightness: 750.0,
+            brightness: 200.0,
+            ..default()
+        })
+        .insert_resource(cells_param)
+        .insert_resource(tempo)
+        .insert_resource(Timestep::Variable)
+        .insert_resource(frame_pacing())
+        .insert_resource(input_map())
+        .add_plugins(MovingCellsPlugin);
+    if let Some(latency) = preview_latency() {
+        app.insert_resource(latency);
+    }
+    if let Some(path) = layout_file {
+        app.insert_resource(LayoutFile { path });
+    }
+    if std::env::args().any(|a| a == "--tokens") {
+        app.insert_resource(GltfTokens::default());
+    }
+    if std::env::args().any(|a| a == "--shaded") {
+        app.insert_resource(CircleShading::default());
+    }
+    if std::env::args().any(|a| a == "--motion-blur") {
+        app.insert_resource(MotionBlur::default());
+    }
+    if std::env::args().any(|a| a == "--post") {
+        app.insert_resource(PostEffects::default());
+    }
+    if std::env::args().any(|a| a == "--pixelate") {
+        app.insert_resource(Pixelate::default());
+    }
+    if std::env::args().any(|a| a == "--feedback") {
+        app.insert_resource(FeedbackBuffer::default());
+    }
+    if std::env::args().any(|a| a == "--instanced") {
+        app.insert_resource(Instanced);
+    }
+    if let Some((width, height, frames)) = bench {
+        app.add_plugins(BenchPlugin).insert_resource(Bench { name: format!("{}x{}", width, height), frames });
+    }
+    if std::env::args().any(|a| a == "--cull") {
+        app.insert_resource(Culling::default());
+    }
+    if std::env::args().any(|a| a == "--diagnostics") {
+        app.insert_resource(DiagnosticsHud);
+    }
+    if let Some(path) = compare_path() {
+        let cell_table = match read_layout(&path) {
+            Ok(Ok(table)) => table,
+            Ok(Err(e)) => panic!("{} is no layout: {}", path.display(), e),
+            Err(e) => panic!("could not read {}: {}", path.display(), e),
+        };
+        app.insert_resource(SplitScreen { cells_param: CellsParam::new(cell_table) });
+    }
+    if let Some(wall) = display_wall() {
+        app.insert_resource(wall);
+    }
+    if std::env::args().any(|a| a == "--control-window") {
+        app.insert_resource(ControlWindow::default());
+    }
+    if std::env::args().any(|a| a == "--kiosk") {
+        app.insert_resource(Kiosk::default());
+    }
+    if std::env::args().any(|a| a == "--shake") {
+        app.insert_resource(Shake::default());
+    }
+    if std::env::args().any(|a| a == "--gpu-motion") {
+        app.insert_resource(Instanced).insert_resource(GpuMotion);
+    }
+    match read_manifest(Path::new(MANIFEST_PATH)) {
+        Ok(Some(Ok(manifest))) => {
+            app.insert_resource(manifest);
         }
+        Ok(None) => {}
+        Ok(Some(Err(e))) => println!("ignoring broken sprite manifest {}: {}", MANIFEST_PATH, e),
+        Err(e) => println!("could not read {}: {}", MANIFEST_PATH, e),
     }
 
+    app
+        .run();
 }
-
-#[cfg(feature = "egui")]
-fn ui_system(mut contexts: EguiContexts) {
-    egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
-        ui.label("world");
-    });
-}
\ No newline at end of file
@@ -0,0 +1,92 @@
+//! Path-following text: the glyphs of a string travel around a ring as
+//! agents, turning the ring into a marquee.
+
+use bevy::prelude::*;
+
+use crate::{
+    agent::Agent,
+    layers::{Layer, OnLayer},
+    ring::{Ring, Rings},
+    CellsParam,
+};
+
+#[derive(Resource, Clone, Debug)]
+pub struct Marquee {
+    pub text: String,
+    /// index into [`Rings`], 0 being the longest ring
+    pub ring: usize,
+    /// cells between two glyphs
+    pub spacing_cells: f32,
+    pub color: Color,
+    pub font_size: f32,
+}
+
+impl Marquee {
+    pub fn new(text: impl Into<String>) -> Self {
+        Marquee {
+            text: text.into(),
+            ring: 0,
+            spacing_cells: 0.5,
+            color: Color::WHITE,
+            font_size: 24.0,
+        }
+    }
+
+    /// Cell and progress of every glyph along `ring`; the first glyph leads,
+    /// so the text reads in the direction of travel. Whitespace keeps its
+    /// place but isn't spawned.
+    pub fn placements(&self, ring: &Ring) -> Vec<(char, UVec2, f32)> {
+        if ring.is_empty() {
+            return Vec::new();
+        }
+        let glyphs = self.text.chars().collect::<Vec<_>>();
+        glyphs
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(i, c)| {
+                let along = (glyphs.len() - 1 - i) as f32 * self.spacing_cells;
+                let cell = along.floor() as usize % ring.len();
+                (*c, ring.cells[cell], along.fract())
+            })
+            .collect()
+    }
+}
+
+#[derive(Component)]
+pub struct MarqueeGlyph;
+
+/// (Re)spawn the glyphs when the text or the rings change, or when they were
+/// despawned along with the other circles.
+pub fn sync_marquee(
+    mut commands: Commands,
+    marquee: Res<Marquee>,
+    rings: Res<Rings>,
+    cells_param: Res<CellsParam>,
+    glyphs: Query<Entity, With<MarqueeGlyph>>,
+) {
+    if !marquee.is_changed() && !rings.is_changed() && !glyphs.is_empty() {
+        return;
+    }
+    for entity in glyphs.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(ring) = rings.0.get(marquee.ring) else {
+        return;
+    };
+
+    for (c, grid, progress) in marquee.placements(ring) {
+        let mut agent = Agent::new(grid, marquee.color, cells_param.circle_size);
+        agent.progress = progress;
+        let pos = cells_param.cell_center(grid.x as usize, grid.y as usize);
+        commands.spawn((
+            Text2d::new(c),
+            TextFont { font_size: marquee.font_size, ..default() },
+            TextColor(marquee.color),
+            Transform::from_translation(pos.extend(Layer::Text.z())),
+            agent,
+            MarqueeGlyph,
+            OnLayer(Layer::Text),
+        ));
+    }
+}
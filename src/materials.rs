@@ -0,0 +1,89 @@
+//! Shared [`ColorMaterial`]s: circles of the same color are drawn with the
+//! same material, so big tables don't need an asset (and a bind group) per
+//! circle.
+//!
+//! Systems setting the color of a single circle (or of a ring, like audio)
+//! [`paint`] it, which points it at the material of the new color instead of
+//! changing the one it may share. Colors changing continuously are
+//! [`quantize`]d first, so the circles fading together keep sharing and the
+//! materials don't churn. Systems shifting every color at once (LFOs, the
+//! timeline) change each material in place, once. A material changed that
+//! way no longer has the color it was shared for, and is no longer handed
+//! out for it.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+/// Materials by color. Weak: a material nobody draws with any more is
+/// dropped as usual.
+#[derive(Resource, Default, Debug)]
+pub struct SharedMaterials(HashMap<[u32; 4], AssetId<ColorMaterial>>);
+
+fn key(color: Color) -> [u32; 4] {
+    color.to_srgba().to_f32_array().map(f32::to_bits)
+}
+
+impl SharedMaterials {
+    /// The material drawing in exactly `color`.
+    pub fn get(&mut self, color: Color, materials: &mut Assets<ColorMaterial>) -> Handle<ColorMaterial> {
+        let key = key(color);
+        if let Some(handle) = self.0.get(&key).and_then(|id| materials.get_strong_handle(*id)) {
+            if materials.get(&handle).is_some_and(|material| material.color == color) {
+                return handle;
+            }
+        }
+        let handle = materials.add(color);
+        self.0.insert(key, handle.id());
+        handle
+    }
+
+    /// Forget the materials that were dropped.
+    pub fn prune(&mut self, materials: &Assets<ColorMaterial>) {
+        self.0.retain(|_, id| materials.contains(*id));
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Steps of [`quantize`] between 0 and 1.
+pub const STEPS: f32 = 64.0;
+
+/// `value` (an alpha, a level) to the nearest of [`STEPS`] steps.
+pub fn quantize(value: f32) -> f32 {
+    (value * STEPS).round() / STEPS
+}
+
+/// Draw one circle in `color` from now on, without touching the material it
+/// had.
+pub fn paint(
+    material: &mut MeshMaterial2d<ColorMaterial>,
+    color: Color,
+    shared: &mut SharedMaterials,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    if materials.get(&material.0).is_some_and(|m| m.color == color) {
+        return;
+    }
+    material.0 = shared.get(color, materials);
+}
+
+pub struct SharedMaterialsPlugin;
+
+impl Plugin for SharedMaterialsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SharedMaterials>().add_systems(Last, prune_shared_materials);
+    }
+}
+
+fn prune_shared_materials(mut shared: ResMut<SharedMaterials>, materials: Res<Assets<ColorMaterial>>) {
+    if materials.is_changed() {
+        shared.prune(&materials);
+    }
+}
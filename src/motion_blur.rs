@@ -0,0 +1,106 @@
+//! Motion blur: circles are drawn stretched along the way they move, as long
+//! as a camera shutter open for `exposure_sec` would smear them, so fast
+//! tempos still read in recordings.
+//!
+//! [`move_cells`](crate::move_cells) keeps the [`Velocity`] of every cell that
+//! has one; with [`MotionBlur`] inserted every cell is given one. Like the
+//! [`lens`](crate::lens), the stretch is only there while drawing. Shapes
+//! other than circles are turned to face the way they move.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{tween::SizeTween, Cell, CellsParam};
+
+#[derive(Resource, Clone, Debug)]
+pub struct MotionBlur {
+    /// how long the shutter stays open
+    pub exposure_sec: f32,
+    /// longest smear, in circle diameters
+    pub max_stretch: f32,
+}
+
+impl Default for MotionBlur {
+    fn default() -> Self {
+        MotionBlur { exposure_sec: 1.0 / 30.0, max_stretch: 4.0 }
+    }
+}
+
+impl MotionBlur {
+    /// Length over width of a circle of `radius` moving at `velocity`.
+    pub fn stretch(&self, velocity: Vec2, radius: f32) -> f32 {
+        if radius <= 0.0 {
+            return 1.0;
+        }
+        (1.0 + velocity.length() * self.exposure_sec / (2.0 * radius)).clamp(1.0, self.max_stretch.max(1.0))
+    }
+}
+
+/// World units per second, from the last two positions.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Velocity(pub Vec2);
+
+impl Velocity {
+    /// Take the move from `from` to `to` over `dt` seconds; steps longer than
+    /// `max_step` (a circle starting over at the entry of its cell) and
+    /// paused frames keep the last velocity.
+    pub fn track(&mut self, from: Vec2, to: Vec2, dt: f32, max_step: f32) {
+        if dt > 0.0 && from.distance(to) <= max_step {
+            self.0 = (to - from) / dt;
+        }
+    }
+}
+
+/// Unstretched rotations and scales of everything blurred this frame.
+#[derive(Resource, Default)]
+struct Unblurred(HashMap<Entity, (Quat, Vec3)>);
+
+pub struct MotionBlurPlugin;
+
+impl Plugin for MotionBlurPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Unblurred>()
+            .add_systems(First, unblur)
+            .add_systems(Update, add_velocities.run_if(resource_exists::<MotionBlur>))
+            .add_systems(
+                PostUpdate,
+                blur.run_if(resource_exists::<MotionBlur>).before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+fn add_velocities(mut commands: Commands, cells: Query<Entity, (With<Cell>, Without<Velocity>)>) {
+    for entity in cells.iter() {
+        commands.entity(entity).insert(Velocity::default());
+    }
+}
+
+fn unblur(mut unblurred: ResMut<Unblurred>, mut cells: Query<&mut Transform, With<Cell>>) {
+    for (entity, (rotation, scale)) in unblurred.0.drain() {
+        if let Ok(mut transform) = cells.get_mut(entity) {
+            transform.rotation = rotation;
+            transform.scale = scale;
+        }
+    }
+}
+
+fn blur(
+    motion_blur: Res<MotionBlur>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    mut unblurred: ResMut<Unblurred>,
+    mut cells: Query<(Entity, &mut Transform, &Velocity), With<Cell>>,
+) {
+    let radius = tween.as_ref().map_or(cells_param.circle_size, |tween| tween.circle_size(&cells_param));
+    for (entity, mut transform, velocity) in cells.iter_mut() {
+        let stretch = motion_blur.stretch(velocity.0, radius * transform.scale.x);
+        if stretch == 1.0 {
+            continue;
+        }
+        unblurred.0.insert(entity, (transform.rotation, transform.scale));
+        transform.rotation = Quat::from_rotation_z(velocity.0.to_angle());
+        transform.scale.x *= stretch;
+    }
+}
@@ -0,0 +1,47 @@
+//! Gradient noise, for jitter layered over the deterministic motion.
+
+use bevy::prelude::*;
+
+/// Positional jitter of every circle; an `amplitude` of 0 turns it off.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Jitter {
+    /// largest displacement, in world units
+    pub amplitude: f32,
+    /// roughly how many wobbles per second
+    pub frequency: f32,
+}
+
+impl Jitter {
+    /// Displacement of the circle keyed by `grid` at `secs`; every cell
+    /// wobbles on its own, but the same way on every run.
+    pub fn offset(&self, grid: UVec2, secs: f32) -> Vec2 {
+        if self.amplitude == 0.0 {
+            return Vec2::ZERO;
+        }
+        let seed = grid.x.wrapping_mul(0x9e37_79b9) ^ grid.y.wrapping_mul(0x85eb_ca6b);
+        let t = secs * self.frequency;
+        Vec2::new(perlin(t, seed), perlin(t, seed ^ 0x68e3_1da4)) * self.amplitude
+    }
+}
+
+/// 1D Perlin noise in -1..=1, 0 at whole numbers.
+pub fn perlin(x: f32, seed: u32) -> f32 {
+    let i = x.floor();
+    let f = x - i;
+    let gradient = |i: f32| {
+        let h = hash(i as i32 as u32 ^ seed);
+        // -1..=1
+        h as f32 / u32::MAX as f32 * 2.0 - 1.0
+    };
+    let (g0, g1) = (gradient(i), gradient(i + 1.0));
+    // quintic fade
+    let s = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    // the extremes of two unit gradients meeting halfway are ±0.5
+    2.0 * (g0 * f + (g1 * (f - 1.0) - g0 * f) * s)
+}
+
+fn hash(mut x: u32) -> u32 {
+    x = (x ^ (x >> 16)).wrapping_mul(0x7feb_352d);
+    x = (x ^ (x >> 15)).wrapping_mul(0x846c_a68b);
+    x ^ (x >> 16)
+}
@@ -0,0 +1,205 @@
+//! Opacity curves: circles fade along their path or with their distance from
+//! the middle of the layout, per ring, per direction or in a pulse, and
+//! centers breathe (see [`Idle`]). The alpha is set per entity, by
+//! [`paint`]ing it in steps of [`quantize`] so circles at the same alpha
+//! share a material; `ColorMaterial`s are alpha blended.
+//!
+//! Translucent circles at the same depth would be drawn in no fixed order,
+//! flickering where they overlap, so while fading each ring is drawn a little
+//! further back than the one before it (the longest in front), and the
+//! circles of a ring in a fixed order.
+
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::TAU,
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    agent::Agent,
+    clock::MotionPhase,
+    direction::FlowDirection,
+    idle::Idle,
+    layers::Layer,
+    materials::{paint, quantize, SharedMaterials},
+    ring::Rings,
+    Cell, CellsParam, Circles, MoveType,
+};
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum Opacity {
+    #[default]
+    Opaque,
+    /// fades in and out over `width` (0..0.5) of the path at either end, so
+    /// circles dissolve as they cross a cell boundary
+    EdgeFade { width: f32 },
+    /// fades down to `floor` at `reach` world units from the middle of the
+    /// layout
+    DistanceFade { reach: f32, floor: f32 },
+    /// (progress, alpha) keys along the path, interpolated linearly
+    Curve(Vec<(f32, f32)>),
+    /// alpha of each ring, by index into [`Rings`]; circles off the rings and
+    /// rings past the end stay opaque
+    PerRing(Vec<f32>),
+    /// alpha of the circles crossing each kind of cell; others stay opaque
+    PerDirection(Vec<(MoveType, f32)>),
+    /// down to `floor` and back once every `cells` cell spans, in step with
+    /// each cell's phase
+    Pulse { cells: f32, floor: f32 },
+}
+
+impl Opacity {
+    /// Alpha factor of a circle `progress` (0..1) along its path, or `None`
+    /// for circles that don't travel, `distance` away from the middle of the
+    /// layout.
+    pub fn alpha(&self, progress: Option<f32>, distance: f32) -> f32 {
+        match self {
+            Opacity::Opaque => 1.0,
+            Opacity::EdgeFade { width } => progress.map_or(1.0, |p| {
+                let edge = p.min(1.0 - p);
+                (edge / width.max(f32::EPSILON)).clamp(0.0, 1.0)
+            }),
+            Opacity::DistanceFade { reach, floor } => {
+                let t = (distance / reach.max(f32::EPSILON)).clamp(0.0, 1.0);
+                1.0 + (floor - 1.0) * t
+            }
+            Opacity::Curve(keys) => progress.map_or(1.0, |p| sample(keys, p)),
+            _ => 1.0,
+        }
+    }
+
+    /// Alpha factor of a circle on ring `ring` (if any) crossing a
+    /// `move_type` cell, `cells` cell spans into the motion (phase included).
+    pub fn cell_alpha(&self, ring: Option<usize>, move_type: MoveType, cells: f64) -> f32 {
+        match self {
+            Opacity::PerRing(alphas) => ring.and_then(|ring| alphas.get(ring)).copied().unwrap_or(1.0),
+            Opacity::PerDirection(alphas) => {
+                alphas.iter().find(|(m, _)| *m == move_type).map_or(1.0, |(_, alpha)| *alpha)
+            }
+            Opacity::Pulse { cells: period, floor } => {
+                let t = (cells / period.max(f32::EPSILON) as f64).rem_euclid(1.0) as f32;
+                let swell = (1.0 - (t * TAU).cos()) / 2.0;
+                1.0 + (floor - 1.0) * swell
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// How far back each ring is drawn while fading.
+const RING_Z: f32 = 1e-4;
+/// How far apart the circles of a ring are drawn; a thousand fit in a ring's.
+const CIRCLE_Z: f32 = 1e-7;
+
+/// Depth of translucent circle `order` of ring `ring`: the longest ring in
+/// front, everything in front of the first trail ghosts.
+pub fn layer_z(ring: Option<usize>, order: u32) -> f32 {
+    let ring = ring.map_or(9, |ring| ring.min(8)) as f32;
+    Layer::Circles.z() - (ring * RING_Z + (order % 1000) as f32 * CIRCLE_Z)
+}
+
+/// `keys` at `x`, held flat before the first and after the last key.
+fn sample(keys: &[(f32, f32)], x: f32) -> f32 {
+    let Some(next) = keys.iter().position(|(k, _)| *k > x) else {
+        return keys.last().map_or(1.0, |(_, v)| *v);
+    };
+    if next == 0 {
+        return keys[0].1;
+    }
+    let ((x0, y0), (x1, y1)) = (keys[next - 1], keys[next]);
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
+
+/// Middle of the layout in world units.
+pub fn layout_center(cells_param: &CellsParam) -> Vec2 {
+    let table = &cells_param.cell_table;
+    if table.width == 0 || table.height == 0 {
+        return Vec2::ZERO;
+    }
+    (cells_param.cell_center(0, 0) + cells_param.cell_center(table.width - 1, table.height - 1)) / 2.0
+}
+
+type Circle<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut MeshMaterial2d<ColorMaterial>,
+    Option<&'a Cell>,
+    Option<&'a Agent>,
+);
+
+/// Alpha each circle had before fading and the alpha written last frame.
+#[derive(Default)]
+pub struct FadedAlphas(HashMap<Entity, (f32, f32)>);
+
+/// Runs after the movers. A circle whose alpha was changed by someone else
+/// since last frame is faded from that alpha on.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_opacity(
+    phase: Res<MotionPhase>,
+    cells_param: Res<CellsParam>,
+    flow: Res<FlowDirection>,
+    rings: Option<Res<Rings>>,
+    mut faded: Local<FadedAlphas>,
+    mut circles: Query<Circle, Circles>,
+    mut shared: ResMut<SharedMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let fading = cells_param.opacity != Opacity::Opaque || matches!(cells_param.idle, Idle::Breathe { .. });
+    if !fading && faded.0.is_empty() {
+        return;
+    }
+    let center = layout_center(&cells_param);
+    let no_rings = Rings::default();
+    let rings = rings.as_deref().unwrap_or(&no_rings);
+    let mut seen = HashSet::new();
+    for (entity, mut transform, mut material, cell, agent) in circles.iter_mut() {
+        let Some(color) = materials.get(&material.0).map(|m| m.color) else {
+            continue;
+        };
+        seen.insert(entity);
+        let progress = match (cell, agent) {
+            (Some(cell), _) if cell.move_type.is_track() => Some(cell.progress(&cells_param, &flow, phase.rate())),
+            (_, Some(agent)) => Some(
+                cells_param
+                    .easing_at(agent.grid)
+                    .apply(cells_param.stepping.apply(agent.progress)),
+            ),
+            _ => None,
+        };
+        let distance = transform.translation.truncate().distance(center);
+        let alpha = color.alpha();
+        let (base, written) = faded.0.entry(entity).or_insert((alpha, alpha));
+        if alpha != *written {
+            *base = alpha;
+        }
+        let breath = match cell {
+            Some(cell) if cell.move_type == MoveType::Center => {
+                cells_param.idle.alpha(flow.cell_rate(phase.rate(), cell.phase))
+            }
+            _ => 1.0,
+        };
+        let grid = match (cell, agent) {
+            (Some(cell), _) => cell.grid,
+            (_, Some(agent)) => agent.grid,
+            _ => continue,
+        };
+        let ring = rings.ring_of(grid);
+        let cells = phase.cells() + cell.map_or(0.0, |cell| cell.phase) as f64;
+        let at_cell = cells_param.opacity.cell_alpha(ring, cells_param.move_type_at(grid), cells);
+        *written = quantize(*base * cells_param.opacity.alpha(progress, distance) * at_cell * breath);
+        let z = if fading { layer_z(ring, entity.index()) } else { 0.0 };
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+        if *written != alpha {
+            // only touched when it changes, so unfaded materials aren't re-uploaded
+            paint(&mut material, color.with_alpha(*written), &mut shared, &mut materials);
+        }
+    }
+    faded.0.retain(|entity, _| seen.contains(entity));
+    if !fading {
+        // back to the alphas from before fading; nothing left to track
+        faded.0.clear();
+    }
+}
@@ -0,0 +1,128 @@
+//! Painting the layout with the mouse, a visual alternative to typing keisen.
+//!
+//! While [`PaintEditor`] is there (toggled with M), a click on a cell puts
+//! the brush glyph there, or, with no brush, turns the cell into the next
+//! kind of cell; the circles are respawned from the new table. Clicks are
+//! taken before the camera sees them, so it neither pans nor follows.
+//! With the `egui` feature, the brush is picked from a palette of glyphs.
+
+use bevy::{input::InputSystem, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    calibration::OutputName,
+    camera::cursor_offset,
+    char_from_move_type,
+    input::{Action, Hotkeys, UiFocus},
+    recovery::EditorSession,
+    try_move_type_from_char, CellTable, CellsParam, MoveType, RespawnCells,
+};
+
+/// Opt-in: edit mode.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct PaintEditor {
+    /// the glyph clicks put down; `None` cycles through the kinds of cell
+    pub brush: Option<char>,
+}
+
+impl PaintEditor {
+    /// What a click on a cell of glyph `current` leaves there.
+    pub fn glyph_for(&self, current: char) -> char {
+        if let Some(brush) = self.brush {
+            return brush;
+        }
+        let move_type = try_move_type_from_char(current).unwrap_or(MoveType::Blank);
+        let i = MoveType::ALL.iter().position(|m| *m == move_type).unwrap_or(0);
+        char_from_move_type(MoveType::ALL[(i + 1) % MoveType::ALL.len()])
+    }
+}
+
+/// The glyphs to paint with, one for every kind of cell.
+pub fn brushes() -> Vec<char> {
+    MoveType::ALL.iter().map(|m| char_from_move_type(*m)).collect()
+}
+
+/// The cell of `cells_param` covering the world position `at`.
+pub fn cell_at(cells_param: &CellsParam, at: Vec2) -> Option<UVec2> {
+    let table = &cells_param.cell_table;
+    (0..table.height)
+        .flat_map(|y| (0..table.width).map(move |x| (x, y)))
+        .find(|&(x, y)| {
+            let half = cells_param.cell_extent(x, y) / 2.0;
+            (at - cells_param.cell_center(x, y)).abs().cmple(half).all()
+        })
+        .map(|(x, y)| UVec2::new(x as u32, y as u32))
+}
+
+/// `table` with `glyph` at `grid`.
+pub fn paint(table: &CellTable, grid: UVec2, glyph: char) -> CellTable {
+    let mut rows = table.table.clone();
+    let row = &mut rows[grid.y as usize];
+    if row.len() <= grid.x as usize {
+        row.resize(grid.x as usize + 1, ' ');
+    }
+    row[grid.x as usize] = glyph;
+    let mut painted = CellTable::from_rows(rows).expect("brushes are valid glyphs");
+    // as read, not resolved again
+    painted.thickness = table.thickness;
+    painted.heavy = table.heavy.iter().copied().filter(|cell| *cell != grid).collect();
+    painted
+}
+
+pub struct PaintPlugin;
+
+impl Plugin for PaintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, paint_cells.after(InputSystem).run_if(resource_exists::<PaintEditor>))
+            .add_systems(Update, toggle_paint);
+    }
+}
+
+fn toggle_paint(mut commands: Commands, hotkeys: Hotkeys, paint: Option<Res<PaintEditor>>) {
+    if !hotkeys.just_pressed(Action::TogglePaint) {
+        return;
+    }
+    match paint {
+        Some(_) => commands.remove_resource::<PaintEditor>(),
+        None => commands.insert_resource(PaintEditor::default()),
+    }
+    println!("painting: {}", if paint.is_some() { "off" } else { "on" });
+}
+
+/// Paint the cell under a click with the `main` camera, unless the click
+/// is egui's.
+pub fn paint_cells(
+    editor: Res<PaintEditor>,
+    mut buttons: ResMut<ButtonInput<MouseButton>>,
+    focus: Option<Res<UiFocus>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&OutputName, &Transform, &Projection), With<Camera2d>>,
+    mut cells_param: ResMut<CellsParam>,
+    session: Option<ResMut<EditorSession>>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    // picking a brush is no click on the cell under it
+    if !buttons.just_pressed(MouseButton::Left) || UiFocus::pointer(focus.as_deref()) {
+        return;
+    }
+    // nor a pan, nor a follow
+    buttons.reset(MouseButton::Left);
+    let Some((_, camera, Projection::Orthographic(ortho))) = cameras.iter().find(|(name, ..)| name.0 == "main") else {
+        return;
+    };
+    let Some(cursor) = window.single().ok().and_then(cursor_offset) else {
+        return;
+    };
+    let Some(grid) = cell_at(&cells_param, camera.translation.truncate() + cursor * ortho.scale) else {
+        return;
+    };
+    let current = cells_param.cell_table.get(grid.x as usize, grid.y as usize);
+    let glyph = editor.glyph_for(current);
+    if glyph == current {
+        return;
+    }
+    cells_param.cell_table = paint(&cells_param.cell_table, grid, glyph);
+    if let Some(mut session) = session {
+        session.mark_dirty();
+    }
+    respawn.write(RespawnCells);
+}
@@ -0,0 +1,93 @@
+//! Color palettes for the cells: a list of colors and a way of handing them
+//! out, with built-in presets to cycle through.
+
+use bevy::prelude::*;
+
+use crate::{
+    input::{Action, Hotkeys},
+    palette_file::FilePalettes,
+    ring::Rings,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Assignment {
+    /// one color per ring; cells on no ring get the color after the last ring's
+    ByLoop,
+    /// one color per table row
+    ByRow,
+    /// every cell picks its own, the same on every run for the same seed
+    Random { seed: u64 },
+}
+
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<Color>,
+    pub assignment: Assignment,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        presets().swap_remove(0)
+    }
+}
+
+impl Palette {
+    pub fn new(name: impl Into<String>, colors: Vec<Color>, assignment: Assignment) -> Self {
+        Palette { name: name.into(), colors, assignment }
+    }
+
+    /// Color of the cell at `grid`, given the rings of the layout.
+    pub fn color(&self, grid: UVec2, rings: &Rings) -> Color {
+        if self.colors.is_empty() {
+            return Color::WHITE;
+        }
+        let index = match self.assignment {
+            Assignment::ByLoop => rings.ring_of(grid).unwrap_or(rings.0.len()),
+            Assignment::ByRow => grid.y as usize,
+            Assignment::Random { seed } => {
+                let mut x = seed ^ ((grid.x as u64) << 32 | grid.y as u64);
+                x = (x ^ (x >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+                x = (x ^ (x >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+                (x ^ (x >> 33)) as usize
+            }
+        };
+        self.colors[index % self.colors.len()]
+    }
+
+    /// The preset after this one (the first after a custom palette).
+    pub fn next(&self) -> Palette {
+        self.next_in(presets())
+    }
+
+    /// The palette after this one in `palettes`, by name (the first if this
+    /// one isn't among them).
+    pub fn next_in(&self, mut palettes: Vec<Palette>) -> Palette {
+        if palettes.is_empty() {
+            return self.clone();
+        }
+        let i = palettes.iter().position(|p| p.name == self.name).map_or(0, |i| (i + 1) % palettes.len());
+        palettes.swap_remove(i)
+    }
+}
+
+/// Built-in palettes; the first is the original all-white look.
+pub fn presets() -> Vec<Palette> {
+    let hex = |colors: &[&str]| colors.iter().map(|c| Srgba::hex(c).expect("valid preset color").into()).collect();
+    vec![
+        Palette::new("white", vec![Color::WHITE], Assignment::ByLoop),
+        Palette::new("sunset", hex(&["ff6b6b", "feca57", "ff9ff3", "f368e0"]), Assignment::ByLoop),
+        Palette::new("ocean", hex(&["48dbfb", "0abde3", "1dd1a1", "c8d6e5"]), Assignment::ByRow),
+        Palette::new("confetti", hex(&["ff6b6b", "feca57", "48dbfb", "1dd1a1", "ff9ff3"]), Assignment::Random { seed: 0 }),
+    ]
+}
+
+/// Cycle through the presets, then the palettes loaded from files.
+pub fn cycle_palette(hotkeys: Hotkeys, files: Option<Res<FilePalettes>>, mut palette: ResMut<Palette>) {
+    if hotkeys.just_pressed(Action::CyclePalette) {
+        let mut palettes = presets();
+        palettes.extend(files.iter().flat_map(|files| files.0.iter().cloned()));
+        *palette = palette.next_in(palettes);
+        println!("palette: {}", palette.name);
+    }
+}
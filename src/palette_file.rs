@@ -0,0 +1,194 @@
+//! Palettes loaded from files in `assets/palettes/`, in the formats
+//! palette sites like Lospec export: `.hex` (one `rrggbb` per line), GIMP
+//! `.gpl` and JASC `.pal`. Loaded palettes are cycled through after the
+//! presets (and, with bevy's `file_watcher` feature, reloaded when their
+//! file changes).
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadedFolder},
+    prelude::*,
+};
+
+use crate::palette::{Assignment, Palette};
+
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct PaletteFile(pub Palette);
+
+#[derive(Debug)]
+pub enum PaletteFileError {
+    Io(std::io::Error),
+    NotUtf8,
+    /// the file doesn't start the way its format does
+    MissingHeader,
+    /// 1-based `line` is no color
+    InvalidLine { line: usize, text: String },
+    /// no colors at all
+    Empty,
+}
+
+impl std::fmt::Display for PaletteFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaletteFileError::Io(e) => write!(f, "{}", e),
+            PaletteFileError::NotUtf8 => write!(f, "palette file is not UTF-8"),
+            PaletteFileError::MissingHeader => write!(f, "palette file has no header"),
+            PaletteFileError::InvalidLine { line, text } => write!(f, "Invalid color {:?} on line {}", text, line),
+            PaletteFileError::Empty => write!(f, "palette has no colors"),
+        }
+    }
+}
+
+impl std::error::Error for PaletteFileError {}
+
+impl From<std::io::Error> for PaletteFileError {
+    fn from(e: std::io::Error) -> Self {
+        PaletteFileError::Io(e)
+    }
+}
+
+/// Read a palette named `name` from the text of a file with `extension`.
+pub fn parse_palette(name: &str, extension: &str, text: &str) -> Result<Palette, PaletteFileError> {
+    let colors = match extension.to_ascii_lowercase().as_str() {
+        "gpl" => parse_gpl(text)?,
+        "pal" => parse_jasc(text)?,
+        _ => parse_hex(text)?,
+    };
+    if colors.is_empty() {
+        return Err(PaletteFileError::Empty);
+    }
+    Ok(Palette::new(name, colors, Assignment::ByLoop))
+}
+
+/// Non-empty lines, numbered from 1, trimmed.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines().enumerate().map(|(i, line)| (i + 1, line.trim())).filter(|(_, line)| !line.is_empty())
+}
+
+fn invalid(line: usize, text: &str) -> PaletteFileError {
+    PaletteFileError::InvalidLine { line, text: text.to_string() }
+}
+
+fn parse_hex(text: &str) -> Result<Vec<Color>, PaletteFileError> {
+    lines(text)
+        .map(|(i, line)| Srgba::hex(line).map(Color::from).map_err(|_| invalid(i, line)))
+        .collect()
+}
+
+/// Color from the first three fields of a line of `0`-`255` channels.
+fn rgb(i: usize, line: &str) -> Result<Color, PaletteFileError> {
+    let channels = line
+        .split_whitespace()
+        .take(3)
+        .map(|c| c.parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid(i, line))?;
+    match channels[..] {
+        [r, g, b] => Ok(Color::srgb_u8(r, g, b)),
+        _ => Err(invalid(i, line)),
+    }
+}
+
+fn parse_gpl(text: &str) -> Result<Vec<Color>, PaletteFileError> {
+    let mut lines = lines(text);
+    if lines.next().map(|(_, line)| line) != Some("GIMP Palette") {
+        return Err(PaletteFileError::MissingHeader);
+    }
+    lines
+        .filter(|(_, line)| !line.starts_with('#') && !line.starts_with("Name:") && !line.starts_with("Columns:"))
+        .map(|(i, line)| rgb(i, line))
+        .collect()
+}
+
+fn parse_jasc(text: &str) -> Result<Vec<Color>, PaletteFileError> {
+    let mut lines = lines(text);
+    if lines.next().map(|(_, line)| line) != Some("JASC-PAL") {
+        return Err(PaletteFileError::MissingHeader);
+    }
+    // version, then the number of colors
+    let _ = lines.next();
+    let count = match lines.next() {
+        Some((i, line)) => line.parse::<usize>().map_err(|_| invalid(i, line))?,
+        None => return Err(PaletteFileError::Empty),
+    };
+    lines.take(count).map(|(i, line)| rgb(i, line)).collect()
+}
+
+#[derive(Default)]
+pub struct PaletteFileLoader;
+
+impl AssetLoader for PaletteFileLoader {
+    type Asset = PaletteFile;
+    type Settings = ();
+    type Error = PaletteFileError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<PaletteFile, PaletteFileError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = String::from_utf8(bytes).map_err(|_| PaletteFileError::NotUtf8)?;
+        let path = load_context.path();
+        let name = path.file_stem().map_or("palette".into(), |s| s.to_string_lossy());
+        let extension = path.extension().map_or("".into(), |s| s.to_string_lossy());
+        parse_palette(&name, &extension, &text).map(PaletteFile)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["hex", "gpl", "pal"]
+    }
+}
+
+/// The palettes loaded from files so far, by name.
+#[derive(Resource, Default, Debug)]
+pub struct FilePalettes(pub Vec<Palette>);
+
+/// Keeps the folder, and with it every palette in it, loaded.
+#[derive(Resource)]
+struct PaletteFolder(#[allow(dead_code)] Handle<LoadedFolder>);
+
+/// Where palette files are looked for, under the asset folder.
+pub const PALETTE_FOLDER: &str = "palettes";
+
+pub struct PaletteFilesPlugin;
+
+impl Plugin for PaletteFilesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<PaletteFile>()
+            .init_asset_loader::<PaletteFileLoader>()
+            .init_resource::<FilePalettes>()
+            .add_systems(Update, collect_palette_files);
+        // folders can't be listed over HTTP
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Startup, load_palette_folder);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn load_palette_folder(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PaletteFolder(asset_server.load_folder(PALETTE_FOLDER)));
+}
+
+/// Rebuild [`FilePalettes`] whenever a file is loaded, changed or dropped,
+/// and refresh the current palette if it came from a file that changed.
+fn collect_palette_files(
+    mut events: EventReader<AssetEvent<PaletteFile>>,
+    files: Res<Assets<PaletteFile>>,
+    mut file_palettes: ResMut<FilePalettes>,
+    palette: Option<ResMut<Palette>>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    let mut palettes = files.iter().map(|(_, file)| file.0.clone()).collect::<Vec<_>>();
+    palettes.sort_by(|a, b| a.name.cmp(&b.name));
+    if let Some(mut palette) = palette {
+        if let Some(reloaded) = palettes.iter().find(|p| p.name == palette.name && **p != *palette) {
+            *palette = reloaded.clone();
+        }
+    }
+    file_palettes.0 = palettes;
+}
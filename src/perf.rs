@@ -0,0 +1,236 @@
+//! Standardized performance scenes, run headless and reported one JSON
+//! object per line, so the numbers before and after a change can be diffed
+//! (see `src/bin/perf_report.rs`).
+
+use std::time::{Duration, Instant};
+
+use bevy::{ecs::entity::Entities, prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    clock::SimClockPlugin,
+    create_cell,
+    direction::FlowDirection,
+    layers::{Layer, OnLayer},
+    move_cells,
+    trails::TrailsPlugin,
+    CellTable, CellsParam, StaticCell,
+};
+
+/// A square of 2x2 rings, tiled.
+const TILE: [[char; 2]; 2] = [['┏', '┓'], ['┗', '┛']];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scene {
+    pub name: String,
+    /// rings per side; the scene has `4 * tiles^2` cells
+    pub tiles: usize,
+    pub trails: bool,
+    /// every other ring replaced by four centers, which don't move
+    pub centers: bool,
+}
+
+impl Scene {
+    pub fn new(name: impl Into<String>, tiles: usize, trails: bool) -> Self {
+        Scene { name: name.into(), tiles, trails, centers: false }
+    }
+
+    pub fn with_centers(self) -> Self {
+        Scene { centers: true, ..self }
+    }
+
+    pub fn cells(&self) -> usize {
+        4 * self.tiles * self.tiles
+    }
+
+    pub fn cell_table(&self) -> CellTable {
+        tiled_table(self.tiles * 2, self.tiles * 2, self.centers)
+    }
+}
+
+/// A table of about `width` by `height` cells (rounded up to whole rings),
+/// tiled with rings, every other one replaced by four centers with
+/// `centers`.
+pub fn tiled_table(width: usize, height: usize, centers: bool) -> CellTable {
+    let (width, height) = (width.div_ceil(2) * 2, height.div_ceil(2) * 2);
+    let rows = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| if centers && (x / 2 + y / 2) % 2 == 1 { '0' } else { TILE[y % 2][x % 2] })
+                .collect()
+        })
+        .collect();
+    CellTable::from_rows(rows).expect("the tile is a valid layout")
+}
+
+/// `NxM`, as cells across and down.
+pub fn parse_size(size: &str) -> Option<(usize, usize)> {
+    let (width, height) = size.split_once(['x', 'X'])?;
+    let (width, height) = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// About 1k, 10k and 100k cells, each with trails off and on, and 100k
+/// half of which are centers.
+pub fn standard_scenes() -> Vec<Scene> {
+    let mut scenes = Vec::new();
+    for (name, tiles) in [("1k", 16), ("10k", 50), ("100k", 158)] {
+        scenes.push(Scene::new(name, tiles, false));
+        scenes.push(Scene::new(format!("{}+trails", name), tiles, true));
+    }
+    scenes.push(Scene::new("100k+centers", 158, false).with_centers());
+    scenes
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub scene: String,
+    pub cells: usize,
+    pub entities: usize,
+    pub frames: u32,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Report {
+    /// Statistics of the frame `times`, in milliseconds.
+    pub fn from_times(scene: &str, cells: usize, entities: usize, mut times: Vec<f64>) -> Self {
+        times.sort_by(f64::total_cmp);
+        let percentile = |p: f64| times.get(((times.len() as f64 * p) as usize).min(times.len().saturating_sub(1)));
+        Report {
+            scene: scene.to_string(),
+            cells,
+            entities,
+            frames: times.len() as u32,
+            mean_ms: times.iter().sum::<f64>() / times.len().max(1) as f64,
+            p50_ms: percentile(0.5).copied().unwrap_or(0.0),
+            p95_ms: percentile(0.95).copied().unwrap_or(0.0),
+            max_ms: times.last().copied().unwrap_or(0.0),
+        }
+    }
+
+    /// One line of JSON, keys always in the same order.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"scene\":\"{}\",\"cells\":{},\"entities\":{},\"frames\":{},\"mean_ms\":{:.4},\"p50_ms\":{:.4},\"p95_ms\":{:.4},\"max_ms\":{:.4}}}",
+            self.scene, self.cells, self.entities, self.frames, self.mean_ms, self.p50_ms, self.p95_ms, self.max_ms,
+        )
+    }
+
+    /// Read a field back out of a [`Report::to_json`] line.
+    pub fn field(line: &str, key: &str) -> Option<String> {
+        let start = line.find(&format!("\"{}\":", key))? + key.len() + 3;
+        let rest = &line[start..];
+        let end = rest.find([',', '}'])?;
+        Some(rest[..end].trim_matches('"').to_string())
+    }
+}
+
+/// Frames run before measuring, so first-frame spawning isn't counted.
+const WARMUP_FRAMES: u32 = 10;
+
+/// Run `scene` for `frames` frames at 60 fps simulated time and time every
+/// update.
+pub fn run(scene: &Scene, frames: u32) -> Report {
+    run_table(&scene.name, scene.cell_table(), scene.trails, frames)
+}
+
+/// Run `cell_table` headless for `frames` frames at 60 fps simulated time
+/// and time every update.
+pub fn run_table(name: &str, cell_table: CellTable, trails: bool, frames: u32) -> Report {
+    let cells = cell_table.width * cell_table.height;
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / 60.0)))
+        .insert_resource(CellsParam::new(cell_table))
+        .init_resource::<FlowDirection>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_systems(Startup, spawn_quietly)
+        .add_systems(Update, move_cells);
+    if trails {
+        app.add_plugins(TrailsPlugin);
+    }
+
+    for _ in 0..WARMUP_FRAMES {
+        app.update();
+    }
+    let times = (0..frames)
+        .map(|_| {
+            let start = Instant::now();
+            app.update();
+            start.elapsed().as_secs_f64() * 1000.0
+        })
+        .collect::<Vec<_>>();
+
+    Report::from_times(name, cells, app.world().entities().len() as usize, times)
+}
+
+/// Windowed benchmark: time `frames` frames of the running app, print the
+/// [`Report`] and quit.
+#[derive(Resource, Clone, Debug)]
+pub struct Bench {
+    pub name: String,
+    pub frames: u32,
+}
+
+pub struct BenchPlugin;
+
+impl Plugin for BenchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, record_bench.run_if(resource_exists::<Bench>));
+    }
+}
+
+fn record_bench(
+    bench: Res<Bench>,
+    time: Res<Time<Real>>,
+    cells_param: Res<CellsParam>,
+    entities: &Entities,
+    mut times: Local<Vec<f64>>,
+    mut warmup: Local<u32>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if *warmup < WARMUP_FRAMES {
+        *warmup += 1;
+        return;
+    }
+    times.push(time.delta_secs_f64() * 1000.0);
+    if times.len() as u32 == bench.frames {
+        let table = &cells_param.cell_table;
+        let report = Report::from_times(&bench.name, table.width * table.height, entities.len() as usize, times.clone());
+        println!("{}", report.to_json());
+        exit.write(AppExit::Success);
+    }
+}
+
+/// Like [`spawn_cells`](crate::spawn_cells), without logging every cell.
+fn spawn_quietly(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mesh = meshes.add(Circle::new(cells_param.circle_size));
+    let material = materials.add(Color::WHITE);
+    let table = &cells_param.cell_table;
+    for iy in 0..table.height {
+        for ix in 0..table.width {
+            let pos = cells_param.cell_center(ix, iy);
+            let cell = create_cell(table.get(ix, iy), UVec2::new(ix as u32, iy as u32), pos, cells_param.cell_extent(ix, iy));
+            let still = !cell.move_type.is_track();
+            let mut circle = commands.spawn((
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material.clone()),
+                Transform::from_translation(pos.extend(Layer::Circles.z())),
+                cell,
+                OnLayer(Layer::Circles),
+            ));
+            if still {
+                circle.insert(StaticCell);
+            }
+        }
+    }
+}
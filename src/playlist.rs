@@ -0,0 +1,84 @@
+//! A list of layouts shown one after another.
+
+use bevy::prelude::*;
+
+use crate::{audio::BarsElapsed, CellTable, CellsParam, RespawnCells};
+
+#[derive(Resource, Clone, Default)]
+pub struct Playlist {
+    tables: Vec<CellTable>,
+    current: usize,
+    /// move on every time [`BarsElapsed`] is sent
+    pub advance_on_bars: bool,
+}
+
+impl Playlist {
+    pub fn new(tables: Vec<CellTable>) -> Self {
+        Playlist { tables, current: 0, advance_on_bars: false }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Index of the layout on screen.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// The next layout, wrapping around at the end.
+    pub fn advance(&mut self) -> Option<&CellTable> {
+        if self.tables.is_empty() {
+            return None;
+        }
+        self.current = (self.current + 1) % self.tables.len();
+        self.tables.get(self.current)
+    }
+}
+
+/// Show the next layout of the [`Playlist`].
+#[derive(Event, Default)]
+pub struct AdvancePlaylist;
+
+pub struct PlaylistPlugin;
+
+impl Plugin for PlaylistPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<AdvancePlaylist>()
+            .add_event::<BarsElapsed>()
+            .add_systems(Update, advance_playlist.run_if(resource_exists::<Playlist>));
+    }
+}
+
+pub fn advance_playlist(
+    mut advance: EventReader<AdvancePlaylist>,
+    mut bars: EventReader<BarsElapsed>,
+    mut playlist: ResMut<Playlist>,
+    mut cells_param: ResMut<CellsParam>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    let mut steps = advance.read().count();
+    if playlist.advance_on_bars {
+        steps += bars.read().count();
+    } else {
+        bars.clear();
+    }
+    if steps == 0 {
+        return;
+    }
+    // only the last one would be seen anyway
+    for _ in 1..steps {
+        playlist.advance();
+    }
+    let Some(table) = playlist.advance() else {
+        return;
+    };
+    cells_param.cell_table = table.clone();
+    respawn.write(RespawnCells);
+    println!("playlist: {}/{}", playlist.current() + 1, playlist.len());
+}
@@ -0,0 +1,296 @@
+//! Post-processing: chromatic aberration, vignette and film grain for a
+//! cinematic look, or big pixels and scanlines for a retro LED board.
+//!
+//! With [`PostEffects`] or [`Pixelate`] inserted, the `main` cameras carry a
+//! [`PostFx`] and a full-screen pass redraws their picture with the effects,
+//! after tonemapping and before [`calibration`](crate::calibration). An effect
+//! at 0 is off.
+
+use bevy::{
+    asset::embedded_asset,
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    image::BevyDefault as _,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+use crate::calibration::{CalibrationLabel, OutputName};
+
+const SHADER_PATH: &str = "embedded://moving_cells_01/shaders/post.wgsl";
+
+pub use uniform::PostFx;
+
+// encase's ShaderType derive emits per-field checks that are never called
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{prelude::*, render::{extract_component::ExtractComponent, render_resource::ShaderType}};
+
+    /// What the post pass of one camera draws.
+    #[derive(Component, Clone, Copy, Debug, Default, PartialEq, ExtractComponent, ShaderType)]
+    pub struct PostFx {
+        pub chromatic_aberration: f32,
+        pub vignette: f32,
+        pub grain: f32,
+        /// seconds, to keep the grain moving
+        pub time: f32,
+        /// side of the blocks the picture is drawn in, in pixels
+        pub pixel_size: f32,
+        pub scanlines: f32,
+    }
+}
+
+/// Opt-in: how strong each effect is.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PostEffects {
+    /// how far red and blue drift apart at the edges, as a share of the view
+    pub chromatic_aberration: f32,
+    /// darkening of the corners, 0..1
+    pub vignette: f32,
+    /// amplitude of the noise
+    pub grain: f32,
+}
+
+impl Default for PostEffects {
+    fn default() -> Self {
+        PostEffects { chromatic_aberration: 0.01, vignette: 0.5, grain: 0.05 }
+    }
+}
+
+/// Opt-in: the picture at a lower resolution, each pixel a sharp block.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Pixelate {
+    /// side of a block, in screen pixels
+    pub pixel_size: u32,
+    /// darkening between the rows of blocks, 0..1
+    pub scanlines: f32,
+}
+
+impl Default for Pixelate {
+    fn default() -> Self {
+        Pixelate { pixel_size: 6, scanlines: 0.4 }
+    }
+}
+
+/// The settings of one camera, from whichever effects are on.
+pub fn post_fx(effects: Option<&PostEffects>, pixelate: Option<&Pixelate>, time: f32) -> PostFx {
+    let mut fx = PostFx { time, ..default() };
+    if let Some(effects) = effects {
+        fx.chromatic_aberration = effects.chromatic_aberration;
+        fx.vignette = effects.vignette;
+        fx.grain = effects.grain;
+    }
+    if let Some(pixelate) = pixelate {
+        fx.pixel_size = pixelate.pixel_size as f32;
+        fx.scanlines = pixelate.scanlines;
+    }
+    fx
+}
+
+pub struct PostEffectsPlugin;
+
+impl Plugin for PostEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/post.wgsl");
+
+        app
+            .add_plugins((
+                ExtractComponentPlugin::<PostFx>::default(),
+                UniformComponentPlugin::<PostFx>::default(),
+            ))
+            .add_systems(PostUpdate, sync_post_fx);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<PostFxNode>>(Core2d, PostFxLabel)
+            .add_render_graph_edges(Core2d, (Node2d::Tonemapping, PostFxLabel, CalibrationLabel));
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<PostFxPipeline>();
+    }
+}
+
+/// Give the `main` cameras the effects, or take them away.
+pub fn sync_post_fx(
+    mut commands: Commands,
+    time: Res<Time>,
+    effects: Option<Res<PostEffects>>,
+    pixelate: Option<Res<Pixelate>>,
+    mut outputs: Query<(Entity, &OutputName, Option<&mut PostFx>)>,
+) {
+    let on = effects.is_some() || pixelate.is_some();
+    for (entity, name, current) in outputs.iter_mut() {
+        match current {
+            current if on && name.0 == "main" => {
+                let fx = post_fx(effects.as_deref(), pixelate.as_deref(), time.elapsed_secs());
+                match current {
+                    Some(mut current) => *current = fx,
+                    None => {
+                        commands.entity(entity).insert(fx);
+                    }
+                }
+            }
+            Some(_) => {
+                commands.entity(entity).remove::<PostFx>();
+            }
+            None => {}
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct PostFxLabel;
+
+#[derive(Default)]
+struct PostFxNode;
+
+impl ViewNode for PostFxNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<PostFx>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let post_fx_pipeline = world.resource::<PostFxPipeline>();
+        let pipeline_id = if view_target.is_hdr() {
+            post_fx_pipeline.hdr_pipeline_id
+        } else {
+            post_fx_pipeline.pipeline_id
+        };
+        let Some(pipeline) = world.resource::<PipelineCache>().get_render_pipeline(pipeline_id) else {
+            return Ok(());
+        };
+        let Some(uniforms) = world
+            .resource::<ComponentUniforms<PostFx>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "post_fx_bind_group",
+            &post_fx_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &post_fx_pipeline.sampler,
+                uniforms,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("post_fx_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct PostFxPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+    hdr_pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for PostFxPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "post_fx_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<PostFx>(true),
+                ),
+            ),
+        );
+        // linear for the sub-pixel shifts of the aberration, clamped at the edges
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+        let shader = world.load_asset(SHADER_PATH);
+
+        let mut queue = |format: TextureFormat| {
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("post_fx_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: shader.clone(),
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                    zero_initialize_workgroup_memory: false,
+                })
+        };
+        let pipeline_id = queue(TextureFormat::bevy_default());
+        let hdr_pipeline_id = queue(ViewTarget::TEXTURE_FORMAT_HDR);
+
+        PostFxPipeline {
+            layout,
+            sampler,
+            pipeline_id,
+            hdr_pipeline_id,
+        }
+    }
+}
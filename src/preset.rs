@@ -0,0 +1,204 @@
+//! Named presets: a layout together with everything tuned on it, saved as
+//! `<name>.ron` in the preset folder ([`Presets::dir`], `presets/` by
+//! default) so that what was found live isn't lost.
+//!
+//! Send [`SavePreset`] and [`LoadPreset`]; the egui panel (with the `egui`
+//! feature) lists the presets and has a "save current as" field.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    layout_file::LayoutTweaks, palette::Palette, recovery::EditorSession, tempo::Tempo, CellTable, CellsParam, LayoutError,
+    RespawnCells,
+};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Preset {
+    /// rows of glyphs (and the thickness), as [`CellTable::to_text`] writes them
+    pub layout: String,
+    pub tweaks: LayoutTweaks,
+    pub cell_size: (f32, f32),
+    pub beats_per_cell: f32,
+}
+
+impl Preset {
+    pub fn capture(cells_param: &CellsParam, tempo: &Tempo, palette: &Palette) -> Self {
+        Preset {
+            layout: cells_param.cell_table.to_text(),
+            tweaks: LayoutTweaks::capture(cells_param, tempo, palette),
+            cell_size: cells_param.cell_size.into(),
+            beats_per_cell: tempo.beats_per_cell,
+        }
+    }
+
+    /// Set the layout and the parameters; nothing is changed if the layout
+    /// can't be read.
+    pub fn apply(&self, cells_param: &mut CellsParam, tempo: &mut Tempo, palette: &mut Palette) -> Result<(), LayoutError> {
+        // leading blanks are part of the layout here
+        cells_param.cell_table = CellTable::from_text(&self.layout)?;
+        self.tweaks.apply(cells_param, tempo, palette);
+        cells_param.cell_size = self.cell_size.into();
+        tempo.beats_per_cell = self.beats_per_cell;
+        Ok(())
+    }
+}
+
+/// Names that are a file name and nothing more.
+pub fn valid_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(['/', '\\', ':']) && !name.starts_with('.')
+}
+
+pub fn preset_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.ron", name))
+}
+
+pub fn write_preset(dir: &Path, name: &str, preset: &Preset) -> io::Result<()> {
+    let text = ron::ser::to_string_pretty(preset, ron::ser::PrettyConfig::default()).map_err(io::Error::other)?;
+    fs::create_dir_all(dir)?;
+    fs::write(preset_path(dir, name), text)
+}
+
+/// `Ok(None)` when there is no such preset.
+pub fn read_preset(dir: &Path, name: &str) -> io::Result<Option<Result<Preset, ron::error::SpannedError>>> {
+    match fs::read_to_string(preset_path(dir, name)) {
+        Ok(text) => Ok(Some(ron::from_str(&text))),
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Names of the presets in `dir`, sorted; none if there is no `dir` yet.
+pub fn list_presets(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "ron") {
+            names.extend(path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string));
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// The preset folder and what is in it.
+#[derive(Resource, Clone, Debug)]
+pub struct Presets {
+    pub dir: PathBuf,
+    pub names: Vec<String>,
+}
+
+impl Default for Presets {
+    fn default() -> Self {
+        Presets { dir: PathBuf::from("presets"), names: Vec::new() }
+    }
+}
+
+/// Save the current layout and parameters under a name, replacing any
+/// preset of that name.
+#[derive(Event, Clone, Debug)]
+pub struct SavePreset(pub String);
+
+#[derive(Event, Clone, Debug)]
+pub struct LoadPreset(pub String);
+
+pub struct PresetPlugin;
+
+impl Plugin for PresetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Presets>()
+            .add_event::<SavePreset>()
+            .add_event::<LoadPreset>()
+            .add_systems(Startup, refresh_presets)
+            .add_systems(Update, (save_presets, load_presets).chain());
+    }
+}
+
+fn refresh(presets: &mut Presets) {
+    match list_presets(&presets.dir) {
+        Ok(names) => presets.names = names,
+        Err(e) => println!("could not list the presets in {}: {}", presets.dir.display(), e),
+    }
+}
+
+fn refresh_presets(mut presets: ResMut<Presets>) {
+    refresh(&mut presets);
+}
+
+pub fn save_presets(
+    mut events: EventReader<SavePreset>,
+    mut presets: ResMut<Presets>,
+    cells_param: Res<CellsParam>,
+    tempo: Res<Tempo>,
+    palette: Res<Palette>,
+    mut session: Option<ResMut<EditorSession>>,
+) {
+    for SavePreset(name) in events.read() {
+        if !valid_name(name) {
+            println!("{:?} can't be the name of a preset", name);
+            continue;
+        }
+        let preset = Preset::capture(&cells_param, &tempo, &palette);
+        match write_preset(&presets.dir, name, &preset) {
+            Ok(()) => {
+                println!("saved preset {}", name);
+                // the layout is safe: nothing to recover any more
+                if let Some(session) = session.as_mut() {
+                    session.mark_saved();
+                }
+            }
+            Err(e) => println!("could not write {}: {}", preset_path(&presets.dir, name).display(), e),
+        }
+        refresh(&mut presets);
+    }
+}
+
+pub fn load_presets(
+    mut events: EventReader<LoadPreset>,
+    presets: Res<Presets>,
+    mut cells_param: ResMut<CellsParam>,
+    mut tempo: ResMut<Tempo>,
+    mut palette: ResMut<Palette>,
+    mut session: Option<ResMut<EditorSession>>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    for LoadPreset(name) in events.read() {
+        let path = preset_path(&presets.dir, name);
+        let preset = match read_preset(&presets.dir, name) {
+            Ok(Some(Ok(preset))) => preset,
+            Ok(None) => {
+                println!("there is no preset {}", name);
+                continue;
+            }
+            Ok(Some(Err(e))) => {
+                println!("ignoring broken preset {}: {}", path.display(), e);
+                continue;
+            }
+            Err(e) => {
+                println!("could not read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        match preset.apply(&mut cells_param, &mut tempo, &mut palette) {
+            Ok(()) => {
+                println!("loaded preset {}", name);
+                // a new layout, unsaved as far as the recovery file knows
+                if let Some(session) = session.as_mut() {
+                    session.mark_dirty();
+                }
+                respawn.write(RespawnCells);
+            }
+            Err(e) => println!("{} is no layout: {}", path.display(), e),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Latency-compensated operator preview.
+//!
+//! When the audience sees the output through a capture / NDI chain with a
+//! known delay, the operator's preview window replays the circles
+//! `delay_sec` late so cued hits line up with what the audience sees.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::{
+    prelude::*,
+    render::{camera::RenderTarget, view::RenderLayers},
+    window::WindowRef,
+};
+
+use crate::{
+    calibration::OutputName,
+    capability::{Capabilities, Capability},
+    Circles,
+};
+
+const PREVIEW_LAYER: usize = 1;
+
+#[derive(Resource, Default)]
+pub struct PreviewLatency {
+    /// 0.0 disables the preview window
+    pub delay_sec: f32,
+}
+
+#[derive(Component)]
+struct PreviewGhost(Entity);
+
+/// The preview window and its camera.
+#[derive(Component)]
+struct PreviewOutput;
+
+/// Translation and scale per circle at one point in time.
+type Snapshot = HashMap<Entity, (Vec3, Vec3)>;
+
+/// Snapshots, oldest first.
+#[derive(Resource, Default)]
+struct PreviewHistory(VecDeque<(f64, Snapshot)>);
+
+pub struct PreviewPlugin;
+
+impl Plugin for PreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PreviewHistory>()
+            .add_systems(Update, sync_preview_window)
+            .add_systems(
+                Update,
+                (spawn_ghosts, record_history, update_ghosts)
+                    .chain()
+                    .after(sync_preview_window)
+                    .run_if(preview_enabled),
+            );
+    }
+}
+
+fn preview_enabled(latency: Res<PreviewLatency>, capabilities: Option<Res<Capabilities>>) -> bool {
+    latency.delay_sec > 0.0 && capabilities.is_none_or(|c| c.is_enabled(Capability::Preview))
+}
+
+/// Open the preview window when it gets enabled and close it (dropping the
+/// ghosts and history) when it gets disabled.
+fn sync_preview_window(
+    mut commands: Commands,
+    latency: Res<PreviewLatency>,
+    capabilities: Option<Res<Capabilities>>,
+    mut history: ResMut<PreviewHistory>,
+    outputs: Query<Entity, With<PreviewOutput>>,
+    ghosts: Query<Entity, With<PreviewGhost>>,
+) {
+    let enabled = preview_enabled(latency, capabilities);
+    let open = !outputs.is_empty();
+    if enabled && !open {
+        spawn_preview_window(&mut commands);
+    } else if !enabled && open {
+        for entity in outputs.iter().chain(ghosts.iter()) {
+            commands.entity(entity).despawn();
+        }
+        history.0.clear();
+    }
+}
+
+fn spawn_preview_window(commands: &mut Commands) {
+    let window = commands
+        .spawn((
+            Window {
+                title: "moving_cells_01 (preview)".into(),
+                ..default()
+            },
+            PreviewOutput,
+        ))
+        .id();
+
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(window)),
+            ..default()
+        },
+        RenderLayers::layer(PREVIEW_LAYER),
+        OutputName("preview".into()),
+        PreviewOutput,
+    ));
+}
+
+/// A ghost for every circle that doesn't have one yet.
+fn spawn_ghosts(
+    mut commands: Commands,
+    circles: Query<(Entity, &Mesh2d, &MeshMaterial2d<ColorMaterial>, &Transform), Circles>,
+    ghosts: Query<&PreviewGhost>,
+) {
+    let haunted = ghosts.iter().map(|ghost| ghost.0).collect::<HashSet<_>>();
+    for (entity, mesh, material, transform) in circles.iter() {
+        if haunted.contains(&entity) {
+            continue;
+        }
+        commands.spawn((
+            mesh.clone(),
+            material.clone(),
+            *transform,
+            RenderLayers::layer(PREVIEW_LAYER),
+            PreviewGhost(entity),
+        ));
+    }
+}
+
+fn record_history(
+    time: Res<Time>,
+    latency: Res<PreviewLatency>,
+    mut history: ResMut<PreviewHistory>,
+    circles: Query<(Entity, &Transform), Circles>,
+) {
+    let now = time.elapsed_secs_f64();
+    let snapshot = circles
+        .iter()
+        .map(|(entity, transform)| (entity, (transform.translation, transform.scale)))
+        .collect();
+    history.0.push_back((now, snapshot));
+
+    // keep one snapshot older than the delay to replay from
+    let oldest_needed = now - latency.delay_sec as f64;
+    while history.0.len() > 1 && history.0[1].0 <= oldest_needed {
+        history.0.pop_front();
+    }
+}
+
+type Repainted = (Circles, Changed<MeshMaterial2d<ColorMaterial>>, Without<PreviewGhost>);
+
+fn update_ghosts(
+    time: Res<Time>,
+    latency: Res<PreviewLatency>,
+    history: Res<PreviewHistory>,
+    mut commands: Commands,
+    mut ghosts: Query<(Entity, &PreviewGhost, &mut Transform, &mut MeshMaterial2d<ColorMaterial>)>,
+    repainted: Query<&MeshMaterial2d<ColorMaterial>, Repainted>,
+) {
+    // colors are swapped materials, and show right away
+    for (_, ghost, _, mut material) in ghosts.iter_mut() {
+        if let Ok(painted) = repainted.get(ghost.0) {
+            if material.0 != painted.0 {
+                material.0 = painted.0.clone();
+            }
+        }
+    }
+
+    let target = time.elapsed_secs_f64() - latency.delay_sec as f64;
+    let Some((_, snapshot)) = history.0.iter().rev().find(|(t, _)| *t <= target) else {
+        // not enough history yet
+        return;
+    };
+
+    let latest = history.0.back().map(|(_, s)| s);
+
+    for (entity, ghost, mut transform, _) in ghosts.iter_mut() {
+        if let Some((translation, scale)) = snapshot.get(&ghost.0) {
+            transform.translation = *translation;
+            transform.scale = *scale;
+        } else if !latest.is_some_and(|s| s.contains_key(&ghost.0)) {
+            // the source is gone for good
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Crash / exit recovery of the working layout.
+//!
+//! While an editor has unsaved changes ([`EditorSession::mark_dirty`]) the
+//! layout is written to a recovery file every few seconds and once more on
+//! exit. If the file is still there on the next launch, a prompt offers to
+//! restore it. Saving a preset, or loading a layout file, makes the layout
+//! safe again ([`EditorSession::mark_saved`]).
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use bevy::prelude::*;
+
+use crate::{
+    capability::{self, Capability},
+    input::{key_name, Action, Hotkeys, InputMap},
+    AssetLoadingState, CellTable, CellsParam, LayoutError, RespawnCells,
+};
+
+#[derive(Resource, Debug, Clone)]
+pub struct Autosave {
+    pub path: PathBuf,
+    /// seconds between writes while there are unsaved changes
+    pub interval_sec: f32,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Autosave {
+            path: recovery_dir().join("moving_cells_01.recovery.txt"),
+            interval_sec: 5.0,
+        }
+    }
+}
+
+// there is no filesystem (nor temp dir) on the web; writes just fail there
+#[cfg(not(target_arch = "wasm32"))]
+fn recovery_dir() -> PathBuf {
+    std::env::temp_dir()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn recovery_dir() -> PathBuf {
+    PathBuf::new()
+}
+
+/// Whether the working layout differs from what the user last saved.
+#[derive(Resource, Default, Debug)]
+pub struct EditorSession {
+    dirty: bool,
+    // the recovery file holds the working layout
+    written: bool,
+    since_autosave: f32,
+}
+
+impl EditorSession {
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Call after every edit of `CellsParam::cell_table`.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Call once the layout has been saved for real (a preset) or replaced by
+    /// one that is (a layout file); the recovery file goes away.
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+}
+
+/// A layout found in the recovery file, waiting for the user to restore or discard it.
+#[derive(Resource)]
+struct PendingRecovery(Option<CellTable>);
+
+#[derive(Component)]
+struct RecoveryPrompt;
+
+pub fn write_recovery(path: &Path, table: &CellTable) -> io::Result<()> {
+    fs::write(path, table.to_text())
+}
+
+/// `Ok(None)` when there is nothing to recover.
+pub fn read_recovery(path: &Path) -> io::Result<Option<Result<CellTable, LayoutError>>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    // not trimmed: leading blanks are part of the layout
+    Ok(Some(CellTable::from_text(&text)))
+}
+
+pub struct RecoveryPlugin;
+
+impl Plugin for RecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Autosave>()
+            .init_resource::<EditorSession>()
+            .add_systems(Startup, offer_recovery)
+            .add_systems(
+                Update,
+                (
+                    answer_recovery
+                        .run_if(resource_exists::<PendingRecovery>)
+                        .run_if(in_state(AssetLoadingState::Loaded)),
+                    close_recovery_prompt.run_if(resource_removed::<PendingRecovery>),
+                    autosave_layout.run_if(capability::enabled(Capability::Autosave)),
+                ),
+            )
+            .add_systems(Last, autosave_on_exit.run_if(capability::enabled(Capability::Autosave)));
+    }
+}
+
+fn offer_recovery(mut commands: Commands, autosave: Res<Autosave>, input_map: Res<InputMap>) {
+    let table = match read_recovery(&autosave.path) {
+        Ok(Some(Ok(table))) => table,
+        Ok(None) => return,
+        Ok(Some(Err(e))) => {
+            println!("ignoring broken recovery file {}: {}", autosave.path.display(), e);
+            return;
+        }
+        Err(e) => {
+            println!("could not read recovery file {}: {}", autosave.path.display(), e);
+            return;
+        }
+    };
+
+    let key = |action| input_map.key(action).map(key_name).unwrap_or_else(|| "-".into());
+    println!("unsaved layout found in {}:\n{}", autosave.path.display(), table.to_text());
+    commands.spawn((
+        Text::new(format!(
+            "An unsaved layout was recovered.\n{}: restore   {}: discard",
+            key(Action::RestoreLayout),
+            key(Action::DiscardLayout),
+        )),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.7)),
+        RecoveryPrompt,
+    ));
+    commands.insert_resource(PendingRecovery(Some(table)));
+}
+
+fn answer_recovery(
+    mut commands: Commands,
+    hotkeys: Hotkeys,
+    autosave: Res<Autosave>,
+    mut pending: ResMut<PendingRecovery>,
+    mut session: ResMut<EditorSession>,
+    mut cells_param: ResMut<CellsParam>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    if hotkeys.just_pressed(Action::RestoreLayout) {
+        let Some(table) = pending.0.take() else {
+            return;
+        };
+        cells_param.cell_table = table;
+        // still not saved anywhere but the recovery file
+        session.mark_dirty();
+        respawn.write(RespawnCells);
+        println!("restored layout from {}", autosave.path.display());
+    } else if hotkeys.just_pressed(Action::DiscardLayout) {
+        if let Err(e) = fs::remove_file(&autosave.path) {
+            println!("could not remove {}: {}", autosave.path.display(), e);
+        }
+    } else {
+        return;
+    }
+
+    commands.remove_resource::<PendingRecovery>();
+}
+
+fn close_recovery_prompt(mut commands: Commands, prompt: Query<Entity, With<RecoveryPrompt>>) {
+    for entity in prompt.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Write the working layout every [`Autosave::interval_sec`] while it is dirty,
+/// and drop the recovery file once it has been saved.
+pub fn autosave_layout(
+    time: Res<Time>,
+    autosave: Res<Autosave>,
+    mut session: ResMut<EditorSession>,
+    cells_param: Res<CellsParam>,
+) {
+    if !session.dirty {
+        if session.written {
+            session.written = false;
+            if let Err(e) = fs::remove_file(&autosave.path) {
+                println!("could not remove {}: {}", autosave.path.display(), e);
+            }
+        }
+        return;
+    }
+
+    session.since_autosave += time.delta_secs();
+    if session.written && session.since_autosave < autosave.interval_sec {
+        return;
+    }
+    session.since_autosave = 0.0;
+    match write_recovery(&autosave.path, &cells_param.cell_table) {
+        Ok(()) => session.written = true,
+        Err(e) => println!("autosave to {} failed: {}", autosave.path.display(), e),
+    }
+}
+
+fn autosave_on_exit(
+    mut exit: EventReader<AppExit>,
+    autosave: Res<Autosave>,
+    session: Res<EditorSession>,
+    cells_param: Res<CellsParam>,
+) {
+    if exit.read().count() == 0 || !session.dirty {
+        return;
+    }
+    match write_recovery(&autosave.path, &cells_param.cell_table) {
+        Ok(()) => println!("unsaved layout written to {}", autosave.path.display()),
+        Err(e) => println!("autosave to {} failed: {}", autosave.path.display(), e),
+    }
+}
@@ -0,0 +1,219 @@
+//! Closed loops ("rings") of track cells.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{char_from_move_type, CellTable, CellsParam, MoveType};
+
+/// One closed loop, in the order circles travel along it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ring {
+    pub cells: Vec<UVec2>,
+}
+
+impl Ring {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// 0..1 progress around the ring after `cells` cells of travel.
+    pub fn phase(&self, cells: f64) -> f32 {
+        (cells.rem_euclid(self.len() as f64) / self.len() as f64) as f32
+    }
+
+    /// Top-left and bottom-right cell of the smallest rectangle around the ring.
+    pub fn bounds(&self) -> (UVec2, UVec2) {
+        let min = self.cells.iter().copied().fold(UVec2::MAX, UVec2::min);
+        let max = self.cells.iter().copied().fold(UVec2::ZERO, UVec2::max);
+        (min, max)
+    }
+
+    /// Whether circles go round clockwise, as seen on screen.
+    pub fn is_clockwise(&self) -> bool {
+        // shoelace; positive with the table's y pointing down means clockwise
+        let twice_area = self
+            .cells
+            .iter()
+            .zip(self.cells.iter().cycle().skip(1))
+            .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+            .sum::<i64>();
+        twice_area > 0
+    }
+
+    /// Add a ring running the other way round, `inset` cells inside this
+    /// (rectangular) one, to `table`: 1 fits it snugly, 2 leaves a blank cell
+    /// between the two as in the default layout. Returns the new ring.
+    pub fn insert_inner(&self, table: &mut CellTable, inset: u32) -> Result<Ring, InnerRingError> {
+        let (min, max) = self.bounds();
+        let size = max - min + UVec2::ONE;
+        let on_border = |c: &UVec2| c.x == min.x || c.x == max.x || c.y == min.y || c.y == max.y;
+        if self.is_empty() || self.len() as u32 != 2 * (size.x + size.y) - 4 || !self.cells.iter().all(on_border) {
+            return Err(InnerRingError::NotRectangular);
+        }
+        if size.x < 2 * inset + 2 || size.y < 2 * inset + 2 {
+            return Err(InnerRingError::TooSmall);
+        }
+        let ring = Ring { cells: rectangle(min + UVec2::splat(inset), max - UVec2::splat(inset), !self.is_clockwise()) };
+
+        for cell in &ring.cells {
+            let (x, y) = (cell.x as usize, cell.y as usize);
+            if table.get(x, y) != ' ' {
+                return Err(InnerRingError::Occupied { x, y });
+            }
+        }
+        let n = ring.len();
+        for (i, cell) in ring.cells.iter().enumerate() {
+            // sides toward the neighbours, y up like the endpoints
+            let side = |to: UVec2| {
+                let d = to.as_ivec2() - cell.as_ivec2();
+                Vec2::new(d.x as f32, -d.y as f32)
+            };
+            let move_type = MoveType::from_endpoints(side(ring.cells[(i + n - 1) % n]), side(ring.cells[(i + 1) % n]))
+                .expect("neighbours on a rectangle are on two sides");
+            let row = &mut table.table[cell.y as usize];
+            if row.len() <= cell.x as usize {
+                row.resize(cell.x as usize + 1, ' ');
+            }
+            row[cell.x as usize] = char_from_move_type(move_type);
+        }
+        Ok(ring)
+    }
+}
+
+/// The border cells of the rectangle from `min` to `max` (at least 2x2), in
+/// travel order, starting at the top-left corner.
+fn rectangle(min: UVec2, max: UVec2, clockwise: bool) -> Vec<UVec2> {
+    let top = (min.x..max.x).map(|x| UVec2::new(x, min.y));
+    let right = (min.y..max.y).map(|y| UVec2::new(max.x, y));
+    let bottom = (min.x + 1..=max.x).rev().map(|x| UVec2::new(x, max.y));
+    let left = (min.y + 1..=max.y).rev().map(|y| UVec2::new(min.x, y));
+    let cells = top.chain(right).chain(bottom).chain(left).collect::<Vec<_>>();
+    if clockwise {
+        cells
+    } else {
+        // the same cells the other way, still from the top-left corner
+        std::iter::once(cells[0]).chain(cells[1..].iter().rev().copied()).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InnerRingError {
+    /// only rectangles can be nested
+    NotRectangular,
+    /// nothing fits that far inside
+    TooSmall,
+    /// cell (`x`, `y`) of the new ring is taken
+    Occupied { x: usize, y: usize },
+}
+
+impl std::fmt::Display for InnerRingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InnerRingError::NotRectangular => write!(f, "ring is not a rectangle"),
+            InnerRingError::TooSmall => write!(f, "ring is too small for another inside"),
+            InnerRingError::Occupied { x, y } => write!(f, "cell ({}, {}) is not blank", x, y),
+        }
+    }
+}
+
+impl std::error::Error for InnerRingError {}
+
+/// Every ring of the current layout, longest (usually outermost) first, and
+/// the ring of every cell on one; made with [`Rings::new`].
+#[derive(Resource, Default, Debug)]
+pub struct Rings(pub Vec<Ring>, HashMap<UVec2, usize>);
+
+impl Rings {
+    pub fn new(rings: Vec<Ring>) -> Self {
+        let index = rings
+            .iter()
+            .enumerate()
+            .flat_map(|(i, ring)| ring.cells.iter().map(move |cell| (*cell, i)))
+            .collect();
+        Rings(rings, index)
+    }
+
+    /// Index of the ring `cell` is on, if any.
+    pub fn ring_of(&self, cell: UVec2) -> Option<usize> {
+        self.1.get(&cell).copied()
+    }
+
+    /// Cells of travel until every ring is back at its start at once;
+    /// `u64::MAX` for rings too many and too coprime to ever line up.
+    pub fn global_period(&self) -> u64 {
+        self.0.iter().fold(1, |period, ring| lcm(period, ring.len() as u64))
+    }
+
+    pub fn global_phase(&self, cells: f64) -> f32 {
+        let period = self.global_period() as f64;
+        (cells.rem_euclid(period) / period) as f32
+    }
+}
+
+/// Follow the track from every cell; paths that come back on themselves close
+/// a ring, dead ends and tails leading into a ring are not part of one.
+pub fn find_rings(cells_param: &CellsParam) -> Vec<Ring> {
+    let table = &cells_param.cell_table;
+    let mut seen = HashSet::new();
+    let mut rings = Vec::new();
+
+    for y in 0..table.height as u32 {
+        for x in 0..table.width as u32 {
+            let start = UVec2::new(x, y);
+            if seen.contains(&start) || !cells_param.move_type_at(start).is_track() {
+                continue;
+            }
+
+            let mut path = vec![start];
+            let mut current = start;
+            while let Some(next) = cells_param.next_cell(current) {
+                if let Some(i) = path.iter().position(|cell| *cell == next) {
+                    rings.push(Ring { cells: path[i..].to_vec() });
+                    break;
+                }
+                if seen.contains(&next) {
+                    break;
+                }
+                path.push(next);
+                current = next;
+            }
+            seen.extend(path);
+        }
+    }
+
+    rings.sort_by_key(|ring| std::cmp::Reverse(ring.len()));
+    rings
+}
+
+/// Find the rings again when the table changes.
+pub fn update_rings(
+    cells_param: Res<CellsParam>,
+    mut rings: ResMut<Rings>,
+    mut checked: Local<Option<Vec<Vec<char>>>>,
+) {
+    // CellsParam changes every frame under LFOs; the table rarely does
+    if checked.as_ref() == Some(&cells_param.cell_table.table) {
+        return;
+    }
+    *checked = Some(cells_param.cell_table.table.clone());
+    *rings = Rings::new(find_rings(&cells_param));
+}
+
+/// Least common multiple, saturating.
+fn lcm(a: u64, b: u64) -> u64 {
+    let gcd = |mut a: u64, mut b: u64| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    if a == 0 || b == 0 {
+        return a.max(b);
+    }
+    (a / gcd(a, b)).checked_mul(b).unwrap_or(u64::MAX)
+}
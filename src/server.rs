@@ -0,0 +1,159 @@
+//! Streaming circle positions over WebSocket (feature `server`).
+//!
+//! Meant for a headless app: every tick the positions go out as a small JSON
+//! message (`{"w":..,"h":..,"circles":[[x,y,r],...]}`, world units, y up),
+//! which `examples/preview_server.html` draws on a canvas. The app is
+//! expected to sleep [`PreviewServer::tick_interval`] between updates, so it
+//! idles while nobody is watching.
+//!
+//! Clients may talk back, one command per text message: `flip_ring <index>`
+//! reverses one ring (see [`FlipRing`]). Anything else is ignored, with a
+//! note.
+
+use std::{
+    io,
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use tungstenite::{Message, WebSocket};
+
+use crate::{direction::FlipRing, CellsParam, Circles};
+
+/// Tick rate with at least one client connected.
+const WATCHED_HZ: f64 = 15.0;
+/// Tick rate while nobody is connected; just enough to notice new clients.
+const IDLE_HZ: f64 = 2.0;
+
+#[derive(Resource)]
+pub struct PreviewServer {
+    listener: TcpListener,
+    clients: Vec<WebSocket<TcpStream>>,
+}
+
+impl PreviewServer {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(PreviewServer { listener, clients: Vec::new() })
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// How long to wait before the next update.
+    pub fn tick_interval(&self) -> Duration {
+        let hz = if self.clients.is_empty() { IDLE_HZ } else { WATCHED_HZ };
+        Duration::from_secs_f64(1.0 / hz)
+    }
+
+    fn accept(&mut self) {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return,
+                Err(e) => {
+                    println!("preview server: {}", e);
+                    return;
+                }
+            };
+            // handshake blocking (but bounded), then never block again
+            let handshake = stream
+                .set_nonblocking(false)
+                .and_then(|_| stream.set_read_timeout(Some(Duration::from_secs(1))))
+                .map_err(|e| e.to_string())
+                .and_then(|_| tungstenite::accept(stream).map_err(|e| e.to_string()))
+                .and_then(|ws| ws.get_ref().set_nonblocking(true).map(|_| ws).map_err(|e| e.to_string()));
+            match handshake {
+                Ok(ws) => {
+                    println!("preview server: client connected ({} watching)", self.clients.len() + 1);
+                    self.clients.push(ws);
+                }
+                Err(e) => println!("preview server: handshake failed: {}", e),
+            }
+        }
+    }
+
+    /// The text messages the clients sent since the last call; pings and
+    /// close frames are handled on the way.
+    fn receive(&mut self) -> Vec<String> {
+        let mut texts = Vec::new();
+        self.clients.retain_mut(|ws| loop {
+            match ws.read() {
+                Ok(Message::Text(text)) => texts.push(text.as_str().to_owned()),
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => return true,
+                Err(_) => {
+                    println!("preview server: client left");
+                    return false;
+                }
+            }
+        });
+        texts
+    }
+
+    fn broadcast(&mut self, text: &str) {
+        self.clients.retain_mut(|ws| {
+            match ws.send(Message::text(text)) {
+                Ok(()) => true,
+                // a slow client: the frame stays queued and goes out later
+                Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => true,
+                Err(_) => {
+                    println!("preview server: client left");
+                    false
+                }
+            }
+        });
+    }
+}
+
+pub struct PreviewServerPlugin;
+
+impl Plugin for PreviewServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FlipRing>()
+            .add_systems(Last, stream_circles.run_if(resource_exists::<PreviewServer>));
+    }
+}
+
+/// A command sent by a client, as an event; `None` for anything else.
+pub fn parse_command(text: &str) -> Option<FlipRing> {
+    match text.split_whitespace().collect::<Vec<_>>()[..] {
+        ["flip_ring", index] => index.parse().ok().map(FlipRing),
+        _ => None,
+    }
+}
+
+fn stream_circles(
+    mut server: ResMut<PreviewServer>,
+    cells_param: Res<CellsParam>,
+    circles: Query<&Transform, Circles>,
+    mut flips: EventWriter<FlipRing>,
+) {
+    server.accept();
+    if server.clients.is_empty() {
+        return;
+    }
+    for text in server.receive() {
+        match parse_command(&text) {
+            Some(flip) => {
+                flips.write(flip);
+            }
+            None => println!("preview server: unknown command {:?}", text),
+        }
+    }
+
+    let size = cells_param.cell_table.unit_size() * cells_param.cell_size;
+    let circles = circles
+        .iter()
+        .map(|t| {
+            let r = cells_param.circle_size * t.scale.x;
+            format!("[{:.1},{:.1},{:.1}]", t.translation.x, t.translation.y, r)
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let text = format!("{{\"w\":{:.1},\"h\":{:.1},\"circles\":[{}]}}", size.x, size.y, circles);
+    server.broadcast(&text);
+}
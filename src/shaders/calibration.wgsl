@@ -0,0 +1,18 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct OutputCalibration {
+    white_point: vec3<f32>,
+    brightness: f32,
+    color_matrix: mat3x3<f32>,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+@group(0) @binding(2) var<uniform> calibration: OutputCalibration;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(screen_texture, texture_sampler, in.uv);
+    let rgb = calibration.color_matrix * (color.rgb * calibration.white_point) * calibration.brightness;
+    return vec4<f32>(rgb, color.a);
+}
@@ -0,0 +1,28 @@
+#import bevy_sprite::{mesh2d_vertex_output::VertexOutput, mesh2d_view_bindings::globals}
+
+struct CircleShade {
+    color: vec4<f32>,
+    direction: vec2<f32>,
+    phase: f32,
+    gradient: f32,
+    softness: f32,
+    glow: f32,
+    glow_hz: f32,
+}
+
+@group(2) @binding(0) var<uniform> shade: CircleShade;
+
+const TAU: f32 = 6.28318530718;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // -1..1 across the shape, y up
+    let p = (mesh.uv - vec2<f32>(0.5)) * vec2<f32>(2.0, -2.0);
+    let r = length(p);
+    let edge = 1.0 - smoothstep(1.0 - shade.softness, 1.0, r);
+    // brightest in the middle, leaning the way the circle travels
+    let lean = dot(p, shade.direction) * 0.5;
+    let light = mix(1.0, clamp(1.0 - r + lean, 0.0, 1.5), shade.gradient);
+    let pulse = 1.0 + shade.glow * (0.5 + 0.5 * sin(TAU * (globals.time * shade.glow_hz + shade.phase)));
+    return vec4<f32>(shade.color.rgb * light * pulse, shade.color.a * edge);
+}
@@ -0,0 +1,120 @@
+// The position math of `move_cells`, one invocation per circle, writing the
+// instance buffer `instanced.wgsl` draws from.
+
+struct Motion {
+    // global rate, 1 or -1 with the flow reversed, the flow's shift, half the
+    // swing of a hop (0 gliding)
+    timing: vec4<f32>,
+    // radius of the rotating idle, 1 for arc corners
+    shape: vec4<f32>,
+};
+
+struct GpuCell {
+    // center, half extent
+    pos_half: vec4<f32>,
+    // entry and exit point on the unit cell
+    path: vec4<f32>,
+    // phase, shift, speed, easing
+    timing: vec4<f32>,
+    // z, size, kind (0 center, 1 straight, 2 corner), 1 with the easing
+    // played backwards
+    look: vec4<f32>,
+    color: vec4<f32>,
+};
+
+struct Instance {
+    position_size: vec4<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> motion: Motion;
+@group(0) @binding(1) var<storage, read> cells: array<GpuCell>;
+@group(0) @binding(2) var<storage, read_write> instances: array<Instance>;
+
+const PI: f32 = 3.14159265359;
+const FRAC_PI_2: f32 = 1.57079632679;
+
+fn cubed(t: f32) -> f32 {
+    return t * t * t;
+}
+
+fn bounce_out(t: f32) -> f32 {
+    if t < 4.0 / 11.0 {
+        return (121.0 * t * t) / 16.0;
+    } else if t < 8.0 / 11.0 {
+        return (363.0 / 40.0 * t * t) - (99.0 / 10.0 * t) + 17.0 / 5.0;
+    } else if t < 9.0 / 10.0 {
+        return (4356.0 / 361.0 * t * t) - (35442.0 / 1805.0 * t) + 16061.0 / 1805.0;
+    }
+    return (54.0 / 5.0 * t * t) - (513.0 / 25.0 * t) + 268.0 / 25.0;
+}
+
+// in the order of `Easing::ALL`
+fn ease(easing: u32, t: f32) -> f32 {
+    switch easing {
+        case 1u: { return 1.0 - cos(t * FRAC_PI_2); }
+        case 2u: { return sin(t * FRAC_PI_2); }
+        case 3u: { return -(cos(PI * t) - 1.0) / 2.0; }
+        case 4u: { return cubed(t); }
+        case 5u: { return 1.0 - cubed(1.0 - t); }
+        case 6u: {
+            if t < 0.5 {
+                return 4.0 * cubed(t);
+            }
+            return 1.0 - cubed(-2.0 * t + 2.0) / 2.0;
+        }
+        case 7u: { return pow(2.0, -10.0 * t) * sin((t * 10.0 - 0.75) * 2.0 * PI / 3.0) + 1.0; }
+        case 8u: { return bounce_out(t); }
+        default: { return t; }
+    }
+}
+
+fn stepped(rate: f32) -> f32 {
+    let half = motion.timing.w;
+    if half <= 0.0 {
+        return rate;
+    }
+    if rate < half {
+        return 0.5 * rate / half;
+    } else if rate > 1.0 - half {
+        return 0.5 + 0.5 * (rate - (1.0 - half)) / half;
+    }
+    return 0.5;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= arrayLength(&cells) {
+        return;
+    }
+    let cell = cells[i];
+    let half = cell.pos_half.zw;
+    let cell_rate = fract(motion.timing.x + cell.timing.x * motion.timing.y + motion.timing.z);
+
+    var offset = vec2<f32>(0.0);
+    let kind = u32(cell.look.z);
+    if kind == 0u {
+        let angle = cell_rate * 2.0 * PI;
+        offset = vec2<f32>(cos(angle), sin(angle)) * motion.shape.x;
+    } else {
+        let rate = fract(cell_rate * cell.timing.z + cell.timing.y);
+        var eased = 0.0;
+        if cell.look.w > 0.5 {
+            eased = 1.0 - ease(u32(cell.timing.w), clamp(stepped(1.0 - rate), 0.0, 1.0));
+        } else {
+            eased = ease(u32(cell.timing.w), clamp(stepped(rate), 0.0, 1.0));
+        }
+        let entry = cell.path.xy * half;
+        let exit = cell.path.zw * half;
+        if kind == 2u && motion.shape.y > 0.5 {
+            // quarter arc around the cell corner shared by both edges
+            let pivot = entry + exit;
+            offset = pivot + (entry - pivot) * cos(eased * FRAC_PI_2) + (exit - pivot) * sin(eased * FRAC_PI_2);
+        } else {
+            offset = mix(entry, exit, eased);
+        }
+    }
+
+    instances[i] = Instance(vec4<f32>(cell.pos_half.xy + offset, cell.look.x, cell.look.y), cell.color);
+}
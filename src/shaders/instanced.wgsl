@@ -0,0 +1,27 @@
+#import bevy_sprite::mesh2d_view_bindings::view
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    // per circle: where it is and how much the mesh is scaled
+    @location(8) position_size: vec4<f32>,
+    @location(9) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let world = vertex.position * vertex.position_size.w + vertex.position_size.xyz;
+    var out: VertexOutput;
+    out.clip_position = view.clip_from_world * vec4<f32>(world, 1.0);
+    out.color = vertex.color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,49 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct PostFx {
+    chromatic_aberration: f32,
+    vignette: f32,
+    grain: f32,
+    time: f32,
+    pixel_size: f32,
+    scanlines: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+@group(0) @binding(2) var<uniform> fx: PostFx;
+
+const PI: f32 = 3.14159265359;
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(screen_texture));
+    let block = max(fx.pixel_size, 1.0);
+    // every pixel of a block shows the middle of the block
+    let pixel = floor(in.uv * size / block);
+    let uv = (pixel + 0.5) * block / size;
+    let from_center = uv - vec2<f32>(0.5);
+
+    // red and blue pulled apart, more toward the edges
+    let shift = from_center * fx.chromatic_aberration;
+    let r = textureSample(screen_texture, texture_sampler, uv + shift).r;
+    let center = textureSample(screen_texture, texture_sampler, uv);
+    let b = textureSample(screen_texture, texture_sampler, uv - shift).b;
+    var rgb = vec3<f32>(r, center.g, b);
+
+    // 0 in the middle, 1 in the corners
+    let corner = length(from_center) * 1.41421356;
+    rgb *= 1.0 - fx.vignette * smoothstep(0.3, 1.0, corner);
+
+    rgb += (hash(pixel + fract(fx.time) * 1000.0) - 0.5) * fx.grain;
+
+    // dark between the rows of blocks, or of every other pixel
+    let row = fract(in.uv.y * size.y / max(block, 2.0));
+    rgb *= 1.0 - fx.scanlines * (1.0 - sin(row * PI));
+
+    return vec4<f32>(rgb, center.a);
+}
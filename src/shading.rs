@@ -0,0 +1,247 @@
+//! Shaded circles: a [`Material2d`] with a radial gradient, a soft edge and
+//! a pulsing glow, in place of the flat [`ColorMaterial`].
+//!
+//! With [`CircleShading`] inserted, every circle hands its mesh to a child
+//! drawn with a [`CircleMaterial`] of its own. The circles keep their
+//! `ColorMaterial`s, undrawn, as the source of their colors, so palettes,
+//! color modes and opacity still apply. Each material also carries the
+//! circle's phase and direction of travel: the glow pulses in step with the
+//! motion and the gradient leans forward. Trails aren't drawn for shaded
+//! circles. Send [`RespawnCells`](crate::RespawnCells) after removing it to
+//! get the flat circles back.
+//!
+//! Shaded circles can also be blended additively ([`Blend`]), so overlapping
+//! circles add up to brighter ones; `CellsParam::blend` set to
+//! [`Blend::Additive`] turns (flat) shading on for that.
+
+use bevy::{
+    asset::embedded_asset,
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayoutRef,
+        render_resource::{
+            AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState, RenderPipelineDescriptor,
+            ShaderRef, SpecializedMeshPipelineError,
+        },
+    },
+    sprite::{AlphaMode2d, Material2d, Material2dKey, Material2dPlugin},
+};
+
+use crate::{agent::Agent, shape::Shape, tween::SizeTween, Cell, CellsParam, Circles, MoveType};
+
+const SHADER_PATH: &str = "embedded://moving_cells_01/shaders/circle.wgsl";
+
+pub use uniform::CircleShade;
+
+// encase's ShaderType derive emits per-field checks that are never called
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{prelude::*, render::render_resource::ShaderType};
+
+    /// What the shader knows about one circle.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, ShaderType)]
+    pub struct CircleShade {
+        /// linear RGBA
+        pub color: Vec4,
+        /// unit vector of travel, y up; zero when standing still
+        pub direction: Vec2,
+        /// offset of the glow, in pulses
+        pub phase: f32,
+        /// 0 for flat, 1 for dark at the rim
+        pub gradient: f32,
+        /// width of the faded edge, in radii
+        pub softness: f32,
+        /// extra brightness at the top of a pulse
+        pub glow: f32,
+        pub glow_hz: f32,
+    }
+}
+
+/// How a circle is drawn over what's behind it.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Blend {
+    /// covering it, as far as the circle is opaque
+    #[default]
+    Normal,
+    /// adding to it: where circles overlap they get brighter
+    Additive,
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[bind_group_data(Blend)]
+pub struct CircleMaterial {
+    #[uniform(0)]
+    pub shade: CircleShade,
+    pub blend: Blend,
+}
+
+impl From<&CircleMaterial> for Blend {
+    fn from(material: &CircleMaterial) -> Self {
+        material.blend
+    }
+}
+
+impl Material2d for CircleMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        if key.bind_group_data == Blend::Additive {
+            if let Some(target) = descriptor.fragment.as_mut().and_then(|f| f.targets.first_mut()).and_then(Option::as_mut) {
+                target.blend = Some(BlendState {
+                    color: BlendComponent {
+                        src_factor: BlendFactor::SrcAlpha,
+                        dst_factor: BlendFactor::One,
+                        operation: BlendOperation::Add,
+                    },
+                    alpha: BlendComponent::OVER,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Opt-in: the look of shaded circles.
+#[derive(Resource, Clone, Debug)]
+pub struct CircleShading {
+    pub gradient: f32,
+    pub softness: f32,
+    pub glow: f32,
+    pub glow_hz: f32,
+}
+
+impl Default for CircleShading {
+    fn default() -> Self {
+        CircleShading { gradient: 0.6, softness: 0.15, glow: 0.4, glow_hz: 0.5 }
+    }
+}
+
+impl CircleShading {
+    /// Looking like the flat circles, with just enough of an edge to smooth it.
+    pub fn flat() -> Self {
+        CircleShading { gradient: 0.0, softness: 0.05, glow: 0.0, glow_hz: 0.0 }
+    }
+}
+
+/// The mesh of a shaded circle, at one radius; scaled by the circle size.
+#[derive(Component)]
+pub struct Shaded;
+
+pub struct CircleShadingPlugin;
+
+impl Plugin for CircleShadingPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/circle.wgsl");
+
+        app
+            .add_plugins(Material2dPlugin::<CircleMaterial>::default())
+            .add_systems(Update, shade_for_blending.before(swap_in_shading))
+            .add_systems(
+                Update,
+                (swap_in_shading, shade_circles)
+                    .chain()
+                    .after(crate::respawn_cells)
+                    .after(crate::color_mode::apply_color_mode)
+                    .after(crate::opacity::apply_opacity)
+                    .run_if(resource_exists::<CircleShading>),
+            );
+    }
+}
+
+/// Additive blending needs the circle material; shade flat if not shaded yet.
+pub fn shade_for_blending(mut commands: Commands, cells_param: Res<CellsParam>, shading: Option<Res<CircleShading>>) {
+    if cells_param.blend == Blend::Additive && shading.is_none() {
+        commands.insert_resource(CircleShading::flat());
+    }
+}
+
+/// Direction of travel across a `move_type` cell, y up.
+pub fn travel_direction(move_type: MoveType) -> Vec2 {
+    move_type.endpoints().map_or(Vec2::ZERO, |(from, to)| (to - from).normalize_or_zero())
+}
+
+type Flat<'a> = (Entity, Option<&'a Cell>, Option<&'a Agent>);
+
+/// Move the mesh of every circle still drawn flat to a shaded child.
+pub fn swap_in_shading(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    circles: Query<Flat, (Circles, With<Mesh2d>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<CircleMaterial>>,
+    mut shape_meshes: Local<Vec<(Shape, Handle<Mesh>)>>,
+) {
+    for (entity, cell, agent) in circles.iter() {
+        let shape = match (cell, agent) {
+            (Some(cell), _) => cells_param.shape_for(cell.move_type),
+            _ => cells_param.shape,
+        };
+        let mesh = match shape_meshes.iter().find(|(s, _)| *s == shape) {
+            Some((_, mesh)) => mesh.clone(),
+            None => {
+                let mesh = meshes.add(shape.mesh(1.0));
+                shape_meshes.push((shape, mesh.clone()));
+                mesh
+            }
+        };
+        commands.entity(entity).remove::<Mesh2d>().with_child((
+            Mesh2d(mesh),
+            MeshMaterial2d(materials.add(CircleMaterial { shade: CircleShade::default(), blend: cells_param.blend })),
+            Transform::from_scale(Vec3::splat(cells_param.circle_size)),
+            Shaded,
+        ));
+    }
+}
+
+type Source<'a> = (&'a MeshMaterial2d<ColorMaterial>, Option<&'a Cell>, Option<&'a Agent>);
+
+/// Keep every shaded circle's material and size up to date.
+pub fn shade_circles(
+    shading: Res<CircleShading>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    mut shaded: Query<(&ChildOf, &MeshMaterial2d<CircleMaterial>, &mut Transform), With<Shaded>>,
+    sources: Query<Source>,
+    colors: Res<Assets<ColorMaterial>>,
+    mut materials: ResMut<Assets<CircleMaterial>>,
+) {
+    let size = Vec3::splat(tween.as_ref().map_or(cells_param.circle_size, |tween| tween.circle_size(&cells_param)));
+    for (parent, material, mut transform) in shaded.iter_mut() {
+        if transform.scale != size {
+            transform.scale = size;
+        }
+        let Ok((color, cell, agent)) = sources.get(parent.parent()) else {
+            continue;
+        };
+        let move_type = match (cell, agent) {
+            (Some(cell), _) => cell.move_type,
+            (_, Some(agent)) => cells_param.move_type_at(agent.grid),
+            _ => continue,
+        };
+        let shade = CircleShade {
+            color: colors.get(&color.0).map_or(Vec4::ONE, |c| c.color.to_linear().to_vec4()),
+            direction: travel_direction(move_type),
+            phase: cell.map_or(0.0, |cell| cell.phase),
+            gradient: shading.gradient,
+            softness: shading.softness,
+            glow: shading.glow,
+            glow_hz: shading.glow_hz,
+        };
+        if materials.get(&material.0).is_some_and(|m| m.shade != shade || m.blend != cells_param.blend) {
+            if let Some(m) = materials.get_mut(&material.0) {
+                m.shade = shade;
+                m.blend = cells_param.blend;
+            }
+        }
+    }
+}
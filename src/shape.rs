@@ -0,0 +1,113 @@
+//! What the circles are drawn as: circles, or other shapes of about the same
+//! size, chosen for all cells or per kind of glyph.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+use crate::MoveType;
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum Shape {
+    #[default]
+    Circle,
+    Square,
+    /// `corner` is the corner radius relative to the size, 0 to 1
+    RoundedRect { corner: f32 },
+    Triangle,
+    Hexagon,
+    /// `inner` is the radius of the notches relative to the tips
+    Star { points: u32, inner: f32 },
+    /// `thickness` is the width of the band relative to the size
+    Ring { thickness: f32 },
+}
+
+/// Segments of a quarter circle on rounded corners.
+const CORNER_SEGMENTS: usize = 6;
+
+impl Shape {
+    /// A mesh as wide as a circle with radius `size`; polygons point up.
+    pub fn mesh(self, size: f32) -> Mesh {
+        match self {
+            Shape::Circle => Circle::new(size).into(),
+            Shape::Square => Rectangle::new(size * 2.0, size * 2.0).into(),
+            Shape::RoundedRect { corner } => fan(&rounded_square(size, corner.clamp(0.0, 1.0) * size)),
+            Shape::Triangle => RegularPolygon::new(size, 3).into(),
+            Shape::Hexagon => RegularPolygon::new(size, 6).into(),
+            Shape::Star { points, inner } => fan(&star(size, points.max(2), inner)),
+            Shape::Ring { thickness } => Annulus::new(size * (1.0 - thickness.clamp(0.0, 1.0)), size).into(),
+        }
+    }
+}
+
+/// Outline of a square with sides `2 * half` and corners rounded by `radius`,
+/// counter-clockwise.
+fn rounded_square(half: f32, radius: f32) -> Vec<Vec2> {
+    let inset = half - radius;
+    let corners = [Vec2::new(inset, inset), Vec2::new(-inset, inset), Vec2::new(-inset, -inset), Vec2::new(inset, -inset)];
+    corners
+        .iter()
+        .enumerate()
+        .flat_map(|(i, corner)| {
+            (0..=CORNER_SEGMENTS).map(move |s| {
+                let angle = (i as f32 + s as f32 / CORNER_SEGMENTS as f32) * FRAC_PI_2;
+                *corner + Vec2::from_angle(angle) * radius
+            })
+        })
+        .collect()
+}
+
+/// Outline of a star with `points` tips on a circle of `radius`, the first
+/// pointing up, counter-clockwise.
+fn star(radius: f32, points: u32, inner: f32) -> Vec<Vec2> {
+    (0..points * 2)
+        .map(|i| {
+            let r = if i % 2 == 0 { radius } else { radius * inner };
+            Vec2::from_angle(FRAC_PI_2 + i as f32 / (points * 2) as f32 * TAU) * r
+        })
+        .collect()
+}
+
+/// A triangle fan around the origin, over a counter-clockwise outline that is
+/// star-shaped as seen from the origin.
+fn fan(outline: &[Vec2]) -> Mesh {
+    let extent = outline.iter().fold(0.0_f32, |m, p| m.max(p.abs().max_element())).max(f32::EPSILON);
+    let points = std::iter::once(Vec2::ZERO).chain(outline.iter().copied()).collect::<Vec<_>>();
+    let n = outline.len() as u32;
+    let indices = (0..n).flat_map(|i| [0, 1 + i, 1 + (i + 1) % n]).collect();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, points.iter().map(|p| [p.x, p.y, 0.0]).collect::<Vec<_>>())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; points.len()])
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_UV_0,
+            points.iter().map(|p| [0.5 + p.x / extent / 2.0, 0.5 - p.y / extent / 2.0]).collect::<Vec<_>>(),
+        )
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Kinds of glyphs that can be given their own shape.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum GlyphClass {
+    /// `←↑→↓`: straight through the cell
+    Straight,
+    /// box-drawing corners: turning in the cell
+    Corner,
+    /// `0`
+    Center,
+}
+
+impl GlyphClass {
+    /// `None` for blank cells.
+    pub fn of(move_type: MoveType) -> Option<GlyphClass> {
+        match move_type.endpoints() {
+            Some((entry, exit)) if entry == -exit => Some(GlyphClass::Straight),
+            Some(_) => Some(GlyphClass::Corner),
+            None if move_type == MoveType::Center => Some(GlyphClass::Center),
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Split screen: a second table, with parameters of its own, drawn next to
+//! the main one by a second camera, for comparing variations of a pattern.
+//!
+//! The second table is laid out to the right of the main one, and the two
+//! cameras share the window half and half, each fitted to its table. Only
+//! the motion of the second table follows its own [`CellsParam`]; colors and
+//! overlays go by the main one. Its circles always oscillate (no agents).
+//!
+//! Opt-in with [`SplitScreen`].
+
+use bevy::{
+    prelude::*,
+    render::camera::{ClearColorConfig, Viewport},
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::{
+    calibration::OutputName,
+    camera::{self, CameraFit},
+    cell_circles, cell_offset,
+    clock::{MotionPhase, SimClock},
+    direction::FlowDirection,
+    gravity::Gravity,
+    layers::{Layer, OnLayer},
+    CellsParam, StaticCell,
+};
+
+/// Opt-in: the table compared with the main one.
+#[derive(Resource)]
+pub struct SplitScreen {
+    pub cells_param: CellsParam,
+}
+
+/// A circle of the compared table.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Compared;
+
+/// Cells left between the two tables.
+const GAP_CELLS: f32 = 4.0;
+
+pub struct SplitScreenPlugin;
+
+impl Plugin for SplitScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                sync_split,
+                layout_split.after(camera::unswing_camera).before(camera::fit_camera),
+                move_compared.after(crate::direction::toggle_direction),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// How far the compared table is moved from where its own parameters put
+/// it: right of the main one, level with it.
+pub fn compared_shift(main: &CellsParam, compared: &CellsParam) -> Vec2 {
+    let (main_bounds, bounds) = (main.bounds(), compared.bounds());
+    Vec2::new(
+        main_bounds.max.x + GAP_CELLS * main.cell_size.x - bounds.min.x,
+        main_bounds.center().y - bounds.center().y,
+    )
+}
+
+/// Left and right halves of a window `size` physical pixels across.
+pub fn split_viewports(size: UVec2) -> [Viewport; 2] {
+    let half = UVec2::new(size.x / 2, size.y).max(UVec2::ONE);
+    [
+        Viewport { physical_position: UVec2::ZERO, physical_size: half, ..default() },
+        Viewport { physical_position: UVec2::new(size.x / 2, 0), physical_size: half, ..default() },
+    ]
+}
+
+/// Spawn a circle for every cell of the compared table, moved by `shift`.
+fn spawn_compared(
+    commands: &mut Commands,
+    compared: &CellsParam,
+    shift: Vec2,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let mesh = meshes.add(compared.shape.mesh(compared.circle_size));
+    let white = materials.add(Color::WHITE);
+    let table = &compared.cell_table;
+    for iy in 0..table.height {
+        for ix in 0..table.width {
+            let scale = table.scale(ix, iy).min_element();
+            for mut cell in cell_circles(compared, ix, iy) {
+                cell.pos += shift;
+                let still = !cell.move_type.is_track();
+                let mut circle = commands.spawn((
+                    Mesh2d(mesh.clone()),
+                    Transform::from_translation(cell.pos.extend(0.0)).with_scale(Vec3::splat(scale)),
+                    MeshMaterial2d(white.clone()),
+                    cell,
+                    OnLayer(Layer::Circles),
+                    Compared,
+                ));
+                if still {
+                    circle.insert(StaticCell);
+                }
+            }
+        }
+    }
+}
+
+/// (Re)spawn the compared table and its camera when [`SplitScreen`] comes or
+/// changes, and drop them when it goes.
+#[allow(clippy::too_many_arguments)]
+pub fn sync_split(
+    mut commands: Commands,
+    split: Option<Res<SplitScreen>>,
+    cells_param: Res<CellsParam>,
+    circles: Query<Entity, With<Compared>>,
+    mut cameras: Query<(Entity, &OutputName, &mut Camera), With<Camera2d>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut spawned_at: Local<Option<Vec2>>,
+) {
+    let Some(split) = split else {
+        *spawned_at = None;
+        for entity in circles.iter() {
+            commands.entity(entity).despawn();
+        }
+        for (entity, name, mut camera) in cameras.iter_mut() {
+            match name.0.as_str() {
+                "compared" => commands.entity(entity).despawn(),
+                "main" if camera.viewport.is_some() => {
+                    camera.viewport = None;
+                    // fit the whole window again
+                    commands.queue(|world: &mut World| {
+                        if let Some(mut camera_fit) = world.get_resource_mut::<CameraFit>() {
+                            camera_fit.set_changed();
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+        return;
+    };
+    // the main table only matters for where the compared one goes
+    let shift = compared_shift(&cells_param, &split.cells_param);
+    if !split.is_changed() && *spawned_at == Some(shift) {
+        return;
+    }
+    *spawned_at = Some(shift);
+    for entity in circles.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_compared(&mut commands, &split.cells_param, shift, &mut meshes, &mut materials);
+    if !cameras.iter().any(|(_, name, _)| name.0 == "compared") {
+        commands.spawn((
+            Camera2d,
+            Camera {
+                order: 1,
+                // the main camera has cleared the window already
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            OutputName("compared".into()),
+        ));
+    }
+}
+
+type SplitCamera<'a> = (&'a OutputName, &'a mut Camera, &'a mut Transform, &'a mut Projection);
+
+/// Give each camera its half of the window and fit its table to it, when
+/// the window is resized or the tables change.
+#[allow(clippy::too_many_arguments)]
+pub fn layout_split(
+    mut resized: EventReader<WindowResized>,
+    split: Option<Res<SplitScreen>>,
+    cells_param: Res<CellsParam>,
+    camera_fit: Option<Res<CameraFit>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<SplitCamera, With<Camera2d>>,
+) {
+    let resized = resized.read().count() > 0;
+    let Some(split) = split else {
+        return;
+    };
+    // the compared camera comes a frame after the split
+    let added = cameras.iter_mut().any(|(_, camera, ..)| camera.is_added());
+    if !(resized || split.is_changed() || cells_param.is_changed() || added) {
+        return;
+    }
+    let Ok(window) = window.single() else {
+        return;
+    };
+    let [left, right] = split_viewports(window.physical_size());
+    let shift = compared_shift(&cells_param, &split.cells_param);
+    let fit = CameraFit::default();
+    for (name, mut camera, mut transform, mut projection) in cameras.iter_mut() {
+        match name.0.as_str() {
+            "main" => {
+                camera.viewport = Some(left.clone());
+                if let Some(camera_fit) = &camera_fit {
+                    let size = camera::view_size(&camera, window);
+                    camera::fit(cells_param.bounds(), size, camera_fit, &mut transform, &mut projection);
+                }
+            }
+            "compared" => {
+                camera.viewport = Some(right.clone());
+                let bounds = split.cells_param.bounds();
+                let bounds = Rect::from_corners(bounds.min + shift, bounds.max + shift);
+                let size = camera::view_size(&camera, window);
+                camera::fit(bounds, size, camera_fit.as_deref().unwrap_or(&fit), &mut transform, &mut projection);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Move the circles of the compared table by its own parameters, on the
+/// same clock and flow as the main one.
+pub fn move_compared(
+    clock: Res<SimClock>,
+    phase: Res<MotionPhase>,
+    split: Option<Res<SplitScreen>>,
+    cells_param: Res<CellsParam>,
+    flow: Res<FlowDirection>,
+    mut circles: Query<(&mut Transform, &crate::Cell), With<Compared>>,
+) {
+    let Some(split) = split else {
+        return;
+    };
+    let compared = &split.cells_param;
+    let shift = compared_shift(&cells_param, compared);
+    let rate = phase.rate();
+    let secs = clock.elapsed_secs_f64() as f32;
+    let wells = match compared.gravity {
+        Some(_) => Gravity::wells(compared, compared.cell_size).into_iter().map(|well| well + shift).collect(),
+        None => Vec::new(),
+    };
+    circles.par_iter_mut().for_each(|(mut transform, cell)| {
+        let offset = cell_offset(cell, compared, &flow, rate, secs, &wells, compared.cell_size);
+        transform.translation.x = cell.pos.x + offset.x;
+        transform.translation.y = cell.pos.y + offset.y;
+    });
+}
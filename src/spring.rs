@@ -0,0 +1,73 @@
+//! Spring-damper motion: circles are pulled toward the position the
+//! interpolation gives them instead of sitting on it, so they overshoot and
+//! settle at corners, turnarounds and (with `Stepping::Hop`) every arrival.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{clock::SimClock, CellsParam, Circles};
+
+/// Longest integration step; stiff springs blow up on long frames otherwise.
+const MAX_STEP_SEC: f32 = 1.0 / 240.0;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Spring {
+    /// pull per unit of distance, per second squared
+    pub stiffness: f32,
+    /// drag per unit of speed, per second; `2 * sqrt(stiffness)` is critical
+    pub damping: f32,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        // a little under critical, so arrivals overshoot once
+        Spring { stiffness: 300.0, damping: 20.0 }
+    }
+}
+
+impl Spring {
+    /// Advance `pos`/`vel` toward `target` by `dt` seconds.
+    pub fn step(&self, pos: &mut Vec2, vel: &mut Vec2, target: Vec2, dt: f32) {
+        let steps = (dt / MAX_STEP_SEC).ceil().max(1.0);
+        let h = dt / steps;
+        for _ in 0..steps as u32 {
+            // semi-implicit Euler
+            *vel += (self.stiffness * (target - *pos) - self.damping * *vel) * h;
+            *pos += *vel * h;
+        }
+    }
+}
+
+/// Position and velocity of every sprung circle.
+#[derive(Default)]
+pub struct SpringStates(HashMap<Entity, (Vec2, Vec2)>);
+
+/// Runs after the movers, taking the position they wrote as the target.
+pub fn apply_springs(
+    clock: Res<SimClock>,
+    cells_param: Res<CellsParam>,
+    mut states: Local<SpringStates>,
+    mut circles: Query<(Entity, &mut Transform), Circles>,
+) {
+    let Some(spring) = cells_param.spring else {
+        states.0.clear();
+        return;
+    };
+    let dt = clock.delta_secs();
+
+    for (entity, mut transform) in circles.iter_mut() {
+        let target = transform.translation.truncate();
+        let (pos, vel) = states.0.entry(entity).or_insert((target, Vec2::ZERO));
+        if dt > 0.0 {
+            spring.step(pos, vel, target, dt);
+        } else if dt < 0.0 {
+            // no physics backwards; stepping back lands on the target
+            (*pos, *vel) = (target, Vec2::ZERO);
+        }
+        transform.translation.x = pos.x;
+        transform.translation.y = pos.y;
+    }
+    // respawned or despawned circles
+    states.0.retain(|entity, _| circles.contains(*entity));
+}
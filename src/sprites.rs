@@ -0,0 +1,87 @@
+//! Images instead of circles for some glyphs.
+//!
+//! A RON manifest maps glyphs to image paths under `assets/`, e.g.
+//! `{ '0': "sprites/star.png" }`. Cells of those glyphs are drawn as
+//! [`Sprite`]s as wide as a circle; coloring and opacity, which work on the
+//! circles' materials, leave them alone.
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::{char_from_move_type, tween::SizeTween, Cell, CellsParam};
+
+/// Where `main` looks for a manifest.
+pub const MANIFEST_PATH: &str = "assets/sprites.ron";
+
+/// Glyphs and the paths of their images, relative to `assets/`.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct SpriteManifest(pub HashMap<char, String>);
+
+/// `Ok(None)` when there is no manifest.
+pub fn read_manifest(path: &Path) -> io::Result<Option<Result<SpriteManifest, ron::error::SpannedError>>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(ron::from_str(&text).map(SpriteManifest))),
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The images of the [`SpriteManifest`], by glyph.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GlyphSprites(pub HashMap<char, Handle<Image>>);
+
+pub struct GlyphSpritesPlugin;
+
+impl Plugin for GlyphSpritesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                load_glyph_sprites.run_if(resource_exists_and_changed::<SpriteManifest>),
+                (swap_in_sprites, resize_sprites).run_if(resource_exists::<GlyphSprites>),
+            )
+                .chain()
+                .after(crate::respawn_cells),
+        );
+    }
+}
+
+fn load_glyph_sprites(mut commands: Commands, manifest: Res<SpriteManifest>, asset_server: Res<AssetServer>) {
+    let sprites = manifest.0.iter().map(|(glyph, path)| (*glyph, asset_server.load(path))).collect();
+    commands.insert_resource(GlyphSprites(sprites));
+}
+
+fn sprite_size(cells_param: &CellsParam, tween: &Option<Res<SizeTween>>) -> Vec2 {
+    let circle_size = tween.as_ref().map_or(cells_param.circle_size, |tween| tween.circle_size(cells_param));
+    Vec2::splat(circle_size * 2.0)
+}
+
+/// Replace the circle of every new cell with a sprite of its glyph, if it has one.
+fn swap_in_sprites(
+    mut commands: Commands,
+    sprites: Res<GlyphSprites>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    cells: Query<(Entity, &Cell), Added<Cell>>,
+) {
+    for (entity, cell) in cells.iter() {
+        let Some(image) = sprites.0.get(&char_from_move_type(cell.move_type)) else {
+            continue;
+        };
+        commands
+            .entity(entity)
+            .remove::<(Mesh2d, MeshMaterial2d<ColorMaterial>)>()
+            .insert(Sprite { image: image.clone(), custom_size: Some(sprite_size(&cells_param, &tween)), ..default() });
+    }
+}
+
+/// Keep sprites as wide as the circles as `circle_size` changes.
+fn resize_sprites(cells_param: Res<CellsParam>, tween: Option<Res<SizeTween>>, mut sprites: Query<&mut Sprite, With<Cell>>) {
+    let size = Some(sprite_size(&cells_param, &tween));
+    for mut sprite in sprites.iter_mut() {
+        if sprite.custom_size != size {
+            sprite.custom_size = size;
+        }
+    }
+}
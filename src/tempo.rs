@@ -0,0 +1,72 @@
+//! Musical timing: the motion runs at a BPM, with a tap-tempo key.
+
+use bevy::prelude::*;
+
+use crate::input::{Action, Hotkeys};
+
+/// Taps further apart than this start a new measurement.
+const TAP_RESET_SEC: f64 = 2.0;
+const MAX_TAPS: usize = 8;
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Tempo {
+    pub bpm: f32,
+    /// how many beats a circle takes to cross one cell
+    pub beats_per_cell: f32,
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        // one cell per second, like the original piece
+        Tempo {
+            bpm: 60.0,
+            beats_per_cell: 1.0,
+        }
+    }
+}
+
+impl Tempo {
+    /// Seconds a circle takes to cross one cell.
+    pub fn span_sec(&self) -> f32 {
+        60.0 / self.bpm * self.beats_per_cell
+    }
+}
+
+/// Real time of the recent taps, oldest first.
+#[derive(Resource, Default)]
+pub struct TapTempo(Vec<f64>);
+
+impl TapTempo {
+    /// Register a tap at `now`; returns the measured BPM once there are two taps.
+    pub fn tap(&mut self, now: f64) -> Option<f32> {
+        if self.0.last().is_some_and(|last| now - last > TAP_RESET_SEC) {
+            self.0.clear();
+        }
+        self.0.push(now);
+        if self.0.len() > MAX_TAPS {
+            self.0.remove(0);
+        }
+
+        let (first, last) = (*self.0.first()?, *self.0.last()?);
+        let intervals = self.0.len() - 1;
+        if intervals == 0 {
+            return None;
+        }
+        Some((60.0 * intervals as f64 / (last - first)) as f32)
+    }
+}
+
+pub fn tap_tempo(
+    hotkeys: Hotkeys,
+    time: Res<Time<Real>>,
+    mut taps: ResMut<TapTempo>,
+    mut tempo: ResMut<Tempo>,
+) {
+    if !hotkeys.just_pressed(Action::TapTempo) {
+        return;
+    }
+    if let Some(bpm) = taps.tap(time.elapsed_secs_f64()) {
+        tempo.bpm = bpm;
+        println!("bpm: {:.1}", tempo.bpm);
+    }
+}
@@ -0,0 +1,124 @@
+//! The layout as editable text, in the egui panel (with the `egui` feature).
+//!
+//! [`LayoutText`] follows the [`LayoutSource`] until it is edited; sending
+//! [`ApplyLayoutText`] reads it in the source's dialect and, if that works,
+//! makes it the layout. If it doesn't, the text stays as it is and the error
+//! is kept, with [`LayoutText::error_range`] pointing at the culprit.
+
+use std::ops::Range;
+
+use bevy::prelude::*;
+
+use crate::{
+    dialect::{Dialect, LayoutSource},
+    recovery::EditorSession,
+    CellsParam, LayoutError, RespawnCells,
+};
+
+#[derive(Resource, Default, Debug)]
+pub struct LayoutText {
+    pub text: String,
+    /// why the text couldn't be applied; cleared by the next edit
+    pub error: Option<LayoutError>,
+    /// the text as last taken from, or applied to, the layout
+    synced: String,
+}
+
+impl LayoutText {
+    /// Whether the text has edits that weren't applied.
+    pub fn is_edited(&self) -> bool {
+        self.text != self.synced
+    }
+
+    /// Take the text of `source`, unless that would throw edits away.
+    pub fn sync(&mut self, source: &LayoutSource) {
+        if !self.is_edited() && self.synced != source.text {
+            self.text = source.text.clone();
+            self.synced = source.text.clone();
+        }
+    }
+
+    /// Line and column (from 0, in characters) in the text of where the
+    /// error is, as `dialect` reads the text.
+    pub fn error_position(&self, dialect: Dialect) -> Option<(usize, usize)> {
+        let lines = self.text.lines().collect::<Vec<_>>();
+        // keisen skips the comments and metadata above the table
+        let first_row = match dialect {
+            Dialect::Keisen => lines.iter().take_while(|line| line.trim_start().starts_with('#')).count(),
+            _ => 0,
+        };
+        match self.error.as_ref()? {
+            LayoutError::Empty => None,
+            LayoutError::InvalidMetadata { line } => {
+                lines.iter().position(|l| l == line).map(|i| (i, indent(lines[i])))
+            }
+            // rows are trimmed before they are read
+            LayoutError::InvalidChar { x, y, .. } | LayoutError::OpenTrack { x, y } => {
+                lines.get(first_row + y).map(|line| (first_row + y, indent(line) + x))
+            }
+        }
+    }
+
+    /// Byte range of the character the error is at, for marking it.
+    pub fn error_range(&self, dialect: Dialect) -> Option<Range<usize>> {
+        let (line, column) = self.error_position(dialect)?;
+        let start = self.text.split_inclusive('\n').take(line).map(str::len).sum::<usize>();
+        let (offset, c) = self.text[start..].char_indices().nth(column)?;
+        (c != '\n').then(|| start + offset..start + offset + c.len_utf8())
+    }
+}
+
+fn indent(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+/// Read [`LayoutText`] and make it the layout.
+#[derive(Event, Default)]
+pub struct ApplyLayoutText;
+
+pub struct TextEditorPlugin;
+
+impl Plugin for TextEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LayoutText>()
+            .add_event::<ApplyLayoutText>()
+            .add_systems(Update, (sync_layout_text, apply_layout_text).chain());
+    }
+}
+
+pub fn sync_layout_text(source: Option<Res<LayoutSource>>, mut layout_text: ResMut<LayoutText>) {
+    if let Some(source) = source.filter(|source| source.is_changed()) {
+        layout_text.sync(&source);
+    }
+}
+
+pub fn apply_layout_text(
+    mut commands: Commands,
+    mut events: EventReader<ApplyLayoutText>,
+    source: Option<Res<LayoutSource>>,
+    mut layout_text: ResMut<LayoutText>,
+    mut cells_param: ResMut<CellsParam>,
+    session: Option<ResMut<EditorSession>>,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    let dialect = source.map_or(Dialect::Keisen, |source| source.dialect);
+    match dialect.parse(&layout_text.text) {
+        Ok(table) => {
+            cells_param.cell_table = table;
+            commands.insert_resource(LayoutSource { text: layout_text.text.clone(), dialect });
+            layout_text.synced = layout_text.text.clone();
+            layout_text.error = None;
+            if let Some(mut session) = session {
+                session.mark_dirty();
+            }
+            respawn.write(RespawnCells);
+        }
+        Err(e) => {
+            println!("the layout text is no layout: {}", e);
+            layout_text.error = Some(e);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! What the weight of a box-drawing corner means.
+//!
+//! Classically thin corners run clockwise and thick ones counter-clockwise.
+//! Layouts can instead say (with a `# thickness: speed` or
+//! `# thickness: density` line on top) that thick corners are faster or more
+//! crowded; their direction is then taken from the cells around them.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{char_from_move_type, try_move_type_from_char, MoveType};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Thickness {
+    /// thin corners clockwise, thick ones counter-clockwise
+    #[default]
+    Direction,
+    /// thick corners are crossed twice as fast
+    Speed,
+    /// thick corners carry twice as many circles
+    Density,
+}
+
+/// How much faster / denser thick corners are.
+pub const HEAVY_FACTOR: usize = 2;
+
+impl Thickness {
+    /// The value of a `thickness` metadata line.
+    pub fn from_name(name: &str) -> Option<Thickness> {
+        match name {
+            "direction" => Some(Thickness::Direction),
+            "speed" => Some(Thickness::Speed),
+            "density" => Some(Thickness::Density),
+            _ => None,
+        }
+    }
+
+    /// The value [`Thickness::from_name`] reads.
+    pub fn name(self) -> &'static str {
+        match self {
+            Thickness::Direction => "direction",
+            Thickness::Speed => "speed",
+            Thickness::Density => "density",
+        }
+    }
+}
+
+pub fn is_heavy(c: char) -> bool {
+    matches!(c, '┏' | '┓' | '┗' | '┛')
+}
+
+/// The thick corner of the same shape as `c`; anything else as it is.
+pub fn heavy_glyph(c: char) -> char {
+    match c {
+        '┌' => '┏',
+        '┐' => '┓',
+        '└' => '┗',
+        '┘' => '┛',
+        c => c,
+    }
+}
+
+fn is_corner(move_type: MoveType) -> bool {
+    matches!(move_type.endpoints(), Some((entry, exit)) if entry != -exit)
+}
+
+/// Give every corner in `rows` (y down) the direction that fits its
+/// neighbours, whatever its weight, and return where the thick ones were.
+/// Corners nothing decides on keep their classic reading.
+pub fn resolve_corners(rows: &mut [Vec<char>]) -> HashSet<UVec2> {
+    let mut known = HashMap::new();
+    let mut open = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.iter().enumerate() {
+            let Some(move_type) = try_move_type_from_char(*c).filter(|m| m.is_track()) else {
+                continue;
+            };
+            let grid = IVec2::new(x as i32, y as i32);
+            if is_corner(move_type) {
+                open.push((grid, move_type));
+            } else {
+                known.insert(grid, move_type);
+            }
+        }
+    }
+
+    // endpoints are y up, the table y down
+    let across = |grid: IVec2, side: Vec2| grid + IVec2::new(side.x as i32, -side.y as i32);
+    loop {
+        let before = open.len();
+        open.retain(|&(grid, move_type)| {
+            // neighbours feeding the entry, or fed by the exit
+            let support = |m: MoveType| {
+                let (entry, exit) = m.endpoints().expect("corners are tracks");
+                let fed = known.get(&across(grid, entry)).and_then(|n: &MoveType| n.endpoints()).is_some_and(|(_, e)| e == -entry);
+                let feeds = known.get(&across(grid, exit)).and_then(|n: &MoveType| n.endpoints()).is_some_and(|(e, _)| e == -exit);
+                fed as u8 + feeds as u8
+            };
+            let resolved = match support(move_type).cmp(&support(move_type.reversed())) {
+                std::cmp::Ordering::Greater => move_type,
+                std::cmp::Ordering::Less => move_type.reversed(),
+                std::cmp::Ordering::Equal => return true,
+            };
+            known.insert(grid, resolved);
+            false
+        });
+        if open.len() == before {
+            break;
+        }
+    }
+
+    let mut heavy = HashSet::new();
+    for (grid, move_type) in known.into_iter().filter(|(_, m)| is_corner(*m)) {
+        let c = &mut rows[grid.y as usize][grid.x as usize];
+        if is_heavy(*c) {
+            heavy.insert(grid.as_uvec2());
+        }
+        *c = char_from_move_type(move_type);
+    }
+    heavy.extend(
+        open.iter()
+            .filter(|(grid, _)| is_heavy(rows[grid.y as usize][grid.x as usize]))
+            .map(|(grid, _)| grid.as_uvec2()),
+    );
+    heavy
+}
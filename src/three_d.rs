@@ -0,0 +1,385 @@
+//! An alternate 3D look: spheres rolling on a ground plane, seen by a
+//! perspective camera.
+//!
+//! The movers keep working in the layout's XY plane; the circles just become
+//! children of a board rotated to lie flat, so they travel in XZ. Their
+//! [`ColorMaterial`]s stay on and keep being colored (palettes, color modes,
+//! opacity); the spheres' materials follow them. 2D-only extras (trails,
+//! overlays) aren't shown in 3D.
+//!
+//! Every ring of the layout is laid out as a rail, a tube through its cells,
+//! for the spheres to ride on.
+//!
+//! The camera [`Orbit`]s the middle of the table: drag to turn around it,
+//! scroll to come closer, or let it go round on its own.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+use crate::{
+    calibration::OutputName,
+    input::{Action, Hotkeys},
+    ring::Rings,
+    tween::SizeTween,
+    CellsParam, Circles, RespawnCells,
+};
+
+/// Opt-in: while this exists the scene is drawn in 3D.
+#[derive(Resource, Clone, Debug)]
+pub struct ThreeD {
+    /// camera angle above the ground, in degrees
+    pub elevation_deg: f32,
+    /// rail thickness in circle radii, 0 for none
+    pub rail_radius: f32,
+    /// start orbiting on its own
+    pub auto_orbit: bool,
+    /// how fast the camera orbits on its own, in degrees per second
+    pub orbit_deg_per_sec: f32,
+}
+
+impl Default for ThreeD {
+    fn default() -> Self {
+        ThreeD { elevation_deg: 50.0, rail_radius: 0.3, auto_orbit: false, orbit_deg_per_sec: 6.0 }
+    }
+}
+
+/// Where the 3D camera is around the middle of the table.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Orbit {
+    /// around the vertical, in radians; 0 looks at the table from its bottom
+    /// edge
+    pub yaw: f32,
+    /// above the ground, in radians
+    pub pitch: f32,
+    pub distance: f32,
+    /// how close and far the wheel takes it
+    pub distances: (f32, f32),
+    /// going round on its own
+    pub auto: bool,
+}
+
+impl Orbit {
+    /// The camera's place and orientation.
+    pub fn transform(&self) -> Transform {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let at = Vec3::new(cos_pitch * sin_yaw, sin_pitch, cos_pitch * cos_yaw) * self.distance;
+        Transform::from_translation(at).looking_at(Vec3::ZERO, Vec3::Y)
+    }
+}
+
+/// Everything spawned for the 3D scene: the camera, the light, the ground
+/// and the board the circles sit on.
+#[derive(Component)]
+struct ThreeDScene;
+
+/// Parent of every circle in 3D, turning the layout's XY plane into XZ.
+#[derive(Component)]
+pub struct Board;
+
+/// A circle drawn as a sphere, with the material standing in for its
+/// [`ColorMaterial`].
+#[derive(Component)]
+pub struct Ball(pub Handle<StandardMaterial>);
+
+/// The tube along a ring, by index into [`Rings`].
+#[derive(Component)]
+pub struct Rail(pub usize);
+
+pub struct ThreeDPlugin;
+
+impl Plugin for ThreeDPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_three_d,
+                leave_three_d.run_if(resource_removed::<ThreeD>),
+                (enter_three_d, (swap_in_balls, color_balls, lay_rails))
+                    .chain()
+                    .after(crate::respawn_cells)
+                    .after(crate::color_mode::apply_color_mode)
+                    .after(crate::opacity::apply_opacity)
+                    .run_if(resource_exists::<ThreeD>),
+            )
+                .chain(),
+        )
+        // swinging on top of the orbit
+        .add_systems(Update, orbit_camera.after(crate::camera::unswing_camera).before(crate::camera::swing_camera));
+    }
+}
+
+fn toggle_three_d(mut commands: Commands, hotkeys: Hotkeys, three_d: Option<Res<ThreeD>>) {
+    if !hotkeys.just_pressed(Action::Toggle3d) {
+        return;
+    }
+    match three_d {
+        Some(_) => commands.remove_resource::<ThreeD>(),
+        None => commands.insert_resource(ThreeD::default()),
+    }
+}
+
+/// The 2D cameras; the one named `main` gives way to the 3D camera.
+type MainCameras<'w, 's> = Query<'w, 's, (&'static mut Camera, &'static OutputName), With<Camera2d>>;
+
+/// Size of the layout on the ground.
+fn extent(cells_param: &CellsParam) -> Vec2 {
+    cells_param.cell_table.unit_size() * cells_param.cell_size
+}
+
+/// Set up the scene and put the main 2D camera to rest, once.
+#[allow(clippy::too_many_arguments)]
+fn enter_three_d(
+    mut commands: Commands,
+    three_d: Res<ThreeD>,
+    cells_param: Res<CellsParam>,
+    tween: Res<SizeTween>,
+    scene: Query<(), With<ThreeDScene>>,
+    mut cameras: MainCameras,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !scene.is_empty() {
+        return;
+    }
+    for (mut camera, name) in cameras.iter_mut() {
+        camera.is_active = name.0 != "main";
+    }
+
+    let extent = extent(&cells_param);
+    let distance = extent.max_element() * 1.2;
+    let orbit = Orbit {
+        yaw: 0.0,
+        pitch: three_d.elevation_deg.to_radians(),
+        distance,
+        distances: (distance * 0.2, distance * 4.0),
+        auto: three_d.auto_orbit,
+    };
+    commands.spawn((
+        Camera3d::default(),
+        orbit.transform(),
+        orbit,
+        // standing in for the 2D one (calibration, glow)
+        OutputName("main".into()),
+        ThreeDScene,
+    ));
+    commands.spawn((
+        DirectionalLight { illuminance: 10000.0, shadows_enabled: true, ..default() },
+        Transform::from_xyz(-1.0, 3.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ThreeDScene,
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(extent.x * 1.2, extent.y * 1.2))),
+        MeshMaterial3d(materials.add(Color::srgb(0.08, 0.08, 0.1))),
+        ThreeDScene,
+    ));
+    // layout XY -> ground XZ (y up becomes z toward the camera's far side),
+    // lifted so the spheres rest on the ground
+    commands.spawn((
+        Transform::from_xyz(0.0, tween.circle_size(&cells_param), 0.0).with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+        Visibility::default(),
+        Board,
+        ThreeDScene,
+    ));
+}
+
+/// Turn the camera around while the left button is held, dolly it on the
+/// wheel, and let it orbit on its own (toggled by [`Action::ToggleOrbit`]).
+#[allow(clippy::too_many_arguments)]
+pub fn orbit_camera(
+    three_d: Option<Res<ThreeD>>,
+    time: Res<Time>,
+    hotkeys: Hotkeys,
+    buttons: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut cameras: Query<(&mut Orbit, &mut Transform)>,
+) {
+    let Some(three_d) = three_d else {
+        return;
+    };
+    for (mut orbit, mut transform) in cameras.iter_mut() {
+        if hotkeys.just_pressed(Action::ToggleOrbit) {
+            orbit.auto = !orbit.auto;
+            println!("auto-orbit: {}", if orbit.auto { "on" } else { "off" });
+        }
+        if orbit.auto {
+            orbit.yaw += three_d.orbit_deg_per_sec.to_radians() * time.delta_secs();
+        }
+        if buttons.pressed(MouseButton::Left) {
+            // dragging right turns the table right, dragging down looks from higher up
+            orbit.yaw -= motion.delta.x * 0.005;
+            orbit.pitch = (orbit.pitch + motion.delta.y * 0.005).clamp(5f32.to_radians(), 89f32.to_radians());
+        }
+        let notches = match scroll.unit {
+            MouseScrollUnit::Line => scroll.delta.y,
+            MouseScrollUnit::Pixel => scroll.delta.y / 100.0,
+        };
+        let (near, far) = orbit.distances;
+        orbit.distance = (orbit.distance / 1.1f32.powf(notches)).clamp(near, far);
+        *transform = orbit.transform();
+    }
+}
+
+type Flat<'a> = (Entity, &'a MeshMaterial2d<ColorMaterial>);
+
+/// Turn every circle still drawn flat into a sphere on the board, and keep
+/// the spheres (and the board's lift) at the tweened `circle_size`.
+#[allow(clippy::too_many_arguments)]
+fn swap_in_balls(
+    mut commands: Commands,
+    cells_param: Res<CellsParam>,
+    tween: Res<SizeTween>,
+    mut board: Query<(Entity, &mut Transform), With<Board>>,
+    circles: Query<Flat, (Circles, With<Mesh2d>)>,
+    colors: Res<Assets<ColorMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut sphere: Local<Option<(f32, Handle<Mesh>)>>,
+) {
+    let Ok((board, mut lift)) = board.single_mut() else {
+        return;
+    };
+    let size = tween.circle_size(&cells_param);
+    let mesh = match &*sphere {
+        Some((s, mesh)) if *s == size => mesh.clone(),
+        Some((_, mesh)) => {
+            meshes.insert(mesh.id(), sphere_mesh(size));
+            lift.translation.y = size;
+            let mesh = mesh.clone();
+            *sphere = Some((size, mesh.clone()));
+            mesh
+        }
+        None => {
+            let mesh = meshes.add(sphere_mesh(size));
+            *sphere = Some((size, mesh.clone()));
+            mesh
+        }
+    };
+    for (entity, color) in circles.iter() {
+        let color = colors.get(&color.0).map_or(Color::WHITE, |c| c.color);
+        let material = materials.add(StandardMaterial { base_color: color, alpha_mode: AlphaMode::Blend, ..default() });
+        commands
+            .entity(entity)
+            .remove::<Mesh2d>()
+            .insert((Mesh3d(mesh.clone()), MeshMaterial3d(material.clone()), Ball(material), ChildOf(board)));
+    }
+}
+
+fn sphere_mesh(radius: f32) -> Mesh {
+    Sphere::new(radius).mesh().uv(24, 16)
+}
+
+/// A closed tube of `radius` through `path`, with `sides` faces around;
+/// the path lies in the XY plane.
+pub fn tube(path: &[Vec2], radius: f32, sides: usize) -> Mesh {
+    let n = path.len();
+    let mut positions = Vec::with_capacity(n * sides);
+    let mut normals = Vec::with_capacity(n * sides);
+    let mut uvs = Vec::with_capacity(n * sides);
+    for (i, point) in path.iter().enumerate() {
+        let tangent = (path[(i + 1) % n] - path[(i + n - 1) % n]).normalize_or_zero();
+        // across the track, in the plane; Z is up off it
+        let across = Vec3::new(tangent.y, -tangent.x, 0.0);
+        for j in 0..sides {
+            let (sin, cos) = (j as f32 / sides as f32 * TAU).sin_cos();
+            let normal = Vec3::Z * cos + across * sin;
+            positions.push((point.extend(0.0) + normal * radius).to_array());
+            normals.push(normal.to_array());
+            uvs.push([i as f32 / n as f32, j as f32 / sides as f32]);
+        }
+    }
+    let at = |i: usize, j: usize| ((i % n) * sides + j % sides) as u32;
+    let indices = (0..n)
+        .flat_map(|i| (0..sides).flat_map(move |j| [at(i, j), at(i + 1, j + 1), at(i + 1, j), at(i, j), at(i, j + 1), at(i + 1, j + 1)]))
+        .collect();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Lay a rail along every ring, on the ground under the spheres, anew
+/// whenever the rings change.
+#[allow(clippy::too_many_arguments)]
+fn lay_rails(
+    mut commands: Commands,
+    three_d: Res<ThreeD>,
+    cells_param: Res<CellsParam>,
+    rings: Res<Rings>,
+    board: Query<(Entity, Ref<Board>)>,
+    rails: Query<Entity, With<Rail>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok((board, added)) = board.single() else {
+        return;
+    };
+    if !(added.is_added() || rings.is_changed() || three_d.is_changed()) {
+        return;
+    }
+    for rail in rails.iter() {
+        commands.entity(rail).despawn();
+    }
+    if three_d.rail_radius <= 0.0 {
+        return;
+    }
+    let material = materials.add(StandardMaterial { base_color: Color::srgb(0.3, 0.3, 0.35), metallic: 0.8, ..default() });
+    let radius = cells_param.circle_size * three_d.rail_radius;
+    for (i, ring) in rings.0.iter().enumerate().filter(|(_, ring)| ring.len() > 2) {
+        let path = ring.cells.iter().map(|cell| cells_param.cell_center(cell.x as usize, cell.y as usize)).collect::<Vec<_>>();
+        commands.entity(board).with_child((
+            Mesh3d(meshes.add(tube(&path, radius, 12))),
+            MeshMaterial3d(material.clone()),
+            // the board is lifted by a circle size; this puts the rail back on the ground
+            Transform::from_xyz(0.0, 0.0, -cells_param.circle_size),
+            Rail(i),
+        ));
+    }
+}
+
+/// Carry colors (and alpha) over from the circles' [`ColorMaterial`]s; with
+/// `CellsParam::glow` the spheres light up in them too.
+fn color_balls(
+    cells_param: Res<CellsParam>,
+    balls: Query<(&Ball, &MeshMaterial2d<ColorMaterial>)>,
+    colors: Res<Assets<ColorMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (ball, color) in balls.iter() {
+        let Some(color) = colors.get(&color.0).map(|c| c.color) else {
+            continue;
+        };
+        let emissive = if cells_param.glow.is_some() { color.to_linear() } else { LinearRgba::BLACK };
+        if materials.get(&ball.0).is_some_and(|m| m.base_color != color || m.emissive != emissive) {
+            if let Some(material) = materials.get_mut(&ball.0) {
+                material.base_color = color;
+                material.emissive = emissive;
+            }
+        }
+    }
+}
+
+/// Back to 2D: drop the scene (with the spheres on the board) and respawn
+/// the circles flat.
+fn leave_three_d(
+    mut commands: Commands,
+    scene: Query<Entity, With<ThreeDScene>>,
+    mut cameras: MainCameras,
+    mut respawn: EventWriter<RespawnCells>,
+) {
+    for entity in scene.iter() {
+        commands.entity(entity).despawn();
+    }
+    for (mut camera, _) in cameras.iter_mut() {
+        camera.is_active = true;
+    }
+    respawn.write(RespawnCells);
+}
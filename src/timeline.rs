@@ -0,0 +1,213 @@
+//! Keyframed choreography of the parameters.
+//!
+//! A [`Timeline`] holds keyframes of [`ParamOverrides`] on the simulation
+//! clock. Every field is its own track: a keyframe only moves the fields it
+//! sets, numbers are interpolated between keyframes and everything else
+//! switches when its keyframe is reached. Switching the timeline off leaves
+//! the parameters where it left them.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    capability::{self, Capability},
+    clock::SimClock,
+    easing::Easing,
+    lfo::{LfoOffsets, LfoTarget},
+    tempo::Tempo,
+    tween::SizeTween,
+    CellsParam, Circles, CornerStyle,
+};
+
+/// Parameters a keyframe can set; `None` leaves the field to other keyframes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParamOverrides {
+    pub circle_size: Option<f32>,
+    pub cell_size: Option<Vec2>,
+    pub bpm: Option<f32>,
+    pub easing: Option<Easing>,
+    pub corner_style: Option<CornerStyle>,
+    /// rotation of every circle's hue, in degrees
+    pub hue_shift: Option<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Keyframe {
+    /// seconds on the [`SimClock`]
+    pub time: f64,
+    pub overrides: ParamOverrides,
+    /// curve of the transition arriving at this keyframe
+    pub ease: Easing,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, overrides: ParamOverrides) -> Self {
+        Keyframe { time, overrides, ease: Easing::Linear }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Timeline {
+    /// sorted by time
+    keyframes: Vec<Keyframe>,
+    /// start over after the last keyframe
+    pub looping: bool,
+}
+
+impl Timeline {
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Timeline { keyframes, looping: false }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Time of the last keyframe.
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Every field's value at `time`: held before the first and after the
+    /// last keyframe that sets it.
+    pub fn sample(&self, time: f64) -> ParamOverrides {
+        let time = if self.looping && self.duration() > 0.0 {
+            time.rem_euclid(self.duration())
+        } else {
+            time
+        };
+        let lerp = |a: f32, b: f32, s: f32| a + (b - a) * s;
+        ParamOverrides {
+            circle_size: self.track(time, |o| o.circle_size, lerp),
+            cell_size: self.track(time, |o| o.cell_size, |a, b, s| a.lerp(b, s)),
+            bpm: self.track(time, |o| o.bpm, lerp),
+            easing: self.track(time, |o| o.easing, step),
+            corner_style: self.track(time, |o| o.corner_style, step),
+            hue_shift: self.track(time, |o| o.hue_shift, lerp),
+        }
+    }
+
+    fn track<T: Copy>(
+        &self,
+        time: f64,
+        field: impl Fn(&ParamOverrides) -> Option<T>,
+        mix: impl Fn(T, T, f32) -> T,
+    ) -> Option<T> {
+        let mut keys = self.keyframes.iter().filter_map(|k| field(&k.overrides).map(|v| (k, v)));
+        let (mut from, mut from_value) = keys.next()?;
+        if time <= from.time {
+            return Some(from_value);
+        }
+        for (to, to_value) in keys {
+            if time < to.time {
+                let s = ((time - from.time) / (to.time - from.time)) as f32;
+                return Some(mix(from_value, to_value, to.ease.apply(s)));
+            }
+            (from, from_value) = (to, to_value);
+        }
+        Some(from_value)
+    }
+}
+
+/// Switch at the keyframe, for values that can't be interpolated.
+fn step<T>(a: T, b: T, s: f32) -> T {
+    if s < 1.0 { a } else { b }
+}
+
+/// What the timeline set last frame; fields are only written when their
+/// sampled value moves, so they can be tweaked by hand while it holds still.
+#[derive(Resource, Default, Debug)]
+struct TimelineState {
+    sampled: ParamOverrides,
+    applied: ParamOverrides,
+}
+
+pub struct TimelinePlugin;
+
+impl Plugin for TimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TimelineState>()
+            .add_systems(
+                Update,
+                (sample_timeline, apply_timeline_params, apply_timeline_hue)
+                    .chain()
+                    .run_if(resource_exists::<Timeline>)
+                    .run_if(capability::enabled(Capability::Timeline)),
+            );
+    }
+}
+
+fn sample_timeline(clock: Res<SimClock>, timeline: Res<Timeline>, mut state: ResMut<TimelineState>) {
+    state.sampled = timeline.sample(clock.elapsed_secs_f64());
+}
+
+fn apply_timeline_params(
+    mut state: ResMut<TimelineState>,
+    lfo: Res<LfoOffsets>,
+    mut cells_param: ResMut<CellsParam>,
+    mut tempo: ResMut<Tempo>,
+    mut tween: Option<ResMut<SizeTween>>,
+) {
+    let TimelineState { sampled, applied } = &mut *state;
+    // the timeline interpolates on its own
+    let (cell_size, circle_size) = (cells_param.cell_size, cells_param.circle_size);
+
+    // sets the base value, the LFOs keep adding their offsets on top
+    if sampled.circle_size != applied.circle_size {
+        if let Some(size) = sampled.circle_size {
+            cells_param.circle_size = size + lfo.applied(LfoTarget::CircleSize);
+        }
+    }
+    if sampled.bpm != applied.bpm {
+        if let Some(bpm) = sampled.bpm {
+            tempo.bpm = bpm + lfo.applied(LfoTarget::Bpm);
+        }
+    }
+    if sampled.cell_size != applied.cell_size {
+        if let Some(size) = sampled.cell_size {
+            cells_param.cell_size = size;
+        }
+    }
+    if sampled.easing != applied.easing {
+        if let Some(easing) = sampled.easing {
+            cells_param.easing = easing;
+        }
+    }
+    if sampled.corner_style != applied.corner_style {
+        if let Some(corner_style) = sampled.corner_style {
+            cells_param.corner_style = corner_style;
+        }
+    }
+    if let Some(tween) = &mut tween {
+        tween.shift(cells_param.cell_size - cell_size, cells_param.circle_size - circle_size);
+    }
+
+    let hue_shift = applied.hue_shift;
+    *applied = sampled.clone();
+    // rotated by apply_timeline_hue, relative to the previous shift
+    applied.hue_shift = hue_shift;
+}
+
+fn apply_timeline_hue(
+    mut state: ResMut<TimelineState>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    circles: Query<&MeshMaterial2d<ColorMaterial>, Circles>,
+) {
+    let Some(shift) = state.sampled.hue_shift else {
+        return;
+    };
+    let delta = shift - state.applied.hue_shift.unwrap_or(0.0);
+    state.applied.hue_shift = Some(shift);
+    if delta == 0.0 {
+        return;
+    }
+    let ids = circles.iter().map(|material| material.0.id()).collect::<HashSet<_>>();
+    for id in ids {
+        if let Some(material) = materials.get_mut(id) {
+            material.color = material.color.rotate_hue(delta);
+        }
+    }
+}
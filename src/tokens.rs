@@ -0,0 +1,152 @@
+//! glTF models traveling the tracks in place of the circles.
+//!
+//! With [`GltfTokens`] inserted, every circle swaps its flat mesh for the
+//! first mesh of `GltfAssets::token`, drawn by a 3D camera laid over the 2D
+//! one so the models land exactly where the circles were. Send
+//! [`RespawnCells`](crate::RespawnCells) after removing it to get the circles
+//! back.
+
+use bevy::{
+    gltf::{Gltf, GltfMesh},
+    prelude::*,
+    render::camera::ScalingMode,
+};
+
+use crate::{tween::SizeTween, AssetLoadingState, CellsParam, Circles, GltfAssets};
+
+#[derive(Resource, Clone, Debug)]
+pub struct GltfTokens {
+    /// circle radii per model unit
+    pub scale: f32,
+    /// turns per second around the model's up axis
+    pub spin: f32,
+}
+
+impl Default for GltfTokens {
+    fn default() -> Self {
+        GltfTokens { scale: 1.0, spin: 0.25 }
+    }
+}
+
+/// The model of a circle, a child so the circle's own scale (regions,
+/// agent sizes, the intro) still applies on top.
+#[derive(Component)]
+pub struct Token;
+
+#[derive(Component)]
+struct TokenCamera;
+
+/// Far enough out that tokens at any circle size are in front of the camera.
+const CAMERA_Z: f32 = 1000.0;
+
+pub struct GltfTokensPlugin;
+
+impl Plugin for GltfTokensPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_token_camera, swap_in_tokens, (resize_tokens, spin_tokens))
+                .chain()
+                .after(crate::respawn_cells)
+                .run_if(resource_exists::<GltfTokens>)
+                .run_if(in_state(AssetLoadingState::Loaded)),
+        );
+    }
+}
+
+/// The first mesh of the token model, with its material.
+pub fn token_mesh(
+    assets: &GltfAssets,
+    gltfs: &Assets<Gltf>,
+    gltf_meshes: &Assets<GltfMesh>,
+) -> Option<(Handle<Mesh>, Option<Handle<StandardMaterial>>)> {
+    let gltf = gltfs.get(&assets.token)?;
+    let primitive = gltf_meshes.get(gltf.meshes.first()?)?.primitives.first()?;
+    Some((primitive.mesh.clone(), primitive.material.clone()))
+}
+
+fn spawn_token_camera(mut commands: Commands, cameras: Query<(), With<TokenCamera>>) {
+    if !cameras.is_empty() {
+        return;
+    }
+    commands.spawn((
+        Camera3d::default(),
+        // on top of the 2D camera, one unit per pixel like it
+        Camera { order: 1, clear_color: ClearColorConfig::None, ..default() },
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::WindowSize,
+            far: CAMERA_Z * 2.0,
+            ..OrthographicProjection::default_3d()
+        }),
+        Transform::from_xyz(0.0, 0.0, CAMERA_Z).looking_at(Vec3::ZERO, Vec3::Y),
+        TokenCamera,
+    ));
+    commands.spawn((
+        DirectionalLight { illuminance: 8000.0, ..default() },
+        Transform::from_xyz(-1.0, 2.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        TokenCamera,
+    ));
+}
+
+fn token_scale(tokens: &GltfTokens, cells_param: &CellsParam, tween: &Option<Res<SizeTween>>) -> Vec3 {
+    let circle_size = tween.as_ref().map_or(cells_param.circle_size, |tween| tween.circle_size(cells_param));
+    Vec3::splat(circle_size * tokens.scale)
+}
+
+/// Give every circle still drawn flat its model.
+#[allow(clippy::too_many_arguments)]
+fn swap_in_tokens(
+    mut commands: Commands,
+    tokens: Res<GltfTokens>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    assets: Res<GltfAssets>,
+    gltfs: Res<Assets<Gltf>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    circles: Query<Entity, (Circles, With<Mesh2d>)>,
+) {
+    if circles.is_empty() {
+        return;
+    }
+    let Some((mesh, material)) = token_mesh(&assets, &gltfs, &gltf_meshes) else {
+        return;
+    };
+    let material = material.unwrap_or_else(|| materials.add(StandardMaterial::default()));
+    let scale = token_scale(&tokens, &cells_param, &tween);
+    for entity in circles.iter() {
+        commands
+            .entity(entity)
+            .remove::<(Mesh2d, MeshMaterial2d<ColorMaterial>)>()
+            .with_child((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_scale(scale),
+                Token,
+            ));
+    }
+}
+
+fn resize_tokens(
+    tokens: Res<GltfTokens>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    mut models: Query<&mut Transform, With<Token>>,
+) {
+    let scale = token_scale(&tokens, &cells_param, &tween);
+    for mut transform in models.iter_mut() {
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+fn spin_tokens(time: Res<Time>, tokens: Res<GltfTokens>, mut models: Query<&mut Transform, With<Token>>) {
+    if tokens.spin == 0.0 {
+        return;
+    }
+    let angle = tokens.spin * std::f32::consts::TAU * time.delta_secs();
+    for mut transform in models.iter_mut() {
+        transform.rotate_y(angle);
+    }
+}
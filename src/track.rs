@@ -0,0 +1,150 @@
+//! The track under the circles: a thin line along the path of every track
+//! cell, with the corners rounded as the circles take them, so the structure
+//! the dots follow shows.
+//!
+//! Toggled as [`Capability::Track`]; one mesh for the whole layout, rebuilt
+//! when the layout or its size changes.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+use crate::{
+    capability::{self, Capability},
+    layers::{Layer, OnLayer},
+    move_offset,
+    tween::SizeTween,
+    CellsParam, CornerStyle, MoveType,
+};
+
+#[derive(Resource, Clone, Debug)]
+pub struct TrackOverlay {
+    /// of the line, in world units
+    pub width: f32,
+    pub color: Color,
+}
+
+impl Default for TrackOverlay {
+    fn default() -> Self {
+        TrackOverlay { width: 1.5, color: Color::srgba(1.0, 1.0, 1.0, 0.2) }
+    }
+}
+
+/// Points along a path, so arcs look round.
+const SEGMENTS: usize = 12;
+
+#[derive(Component)]
+pub struct TrackLines;
+
+pub struct TrackPlugin;
+
+impl Plugin for TrackPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TrackOverlay>()
+            .add_systems(
+                PostUpdate,
+                draw_track.after(crate::tween::tween_sizes).run_if(capability::enabled(Capability::Track)),
+            )
+            .add_systems(PostUpdate, clear_track.run_if(not(capability::enabled(Capability::Track))));
+    }
+}
+
+/// The path of a `move_type` cell of half extent `half`, around its center.
+pub fn track_path(move_type: MoveType, half: Vec2, corner_style: CornerStyle) -> Vec<Vec2> {
+    if !move_type.is_track() {
+        return Vec::new();
+    }
+    let segments = if corner_style == CornerStyle::Arc && move_type.is_corner() { SEGMENTS } else { 1 };
+    (0..=segments)
+        .map(|i| move_offset(move_type, i as f32 / segments as f32, half, corner_style))
+        .collect()
+}
+
+/// Add a band `width` wide along `path` to a triangle list. Joins are
+/// mitered, so the band keeps its width round sharp corners.
+fn add_band(path: &[Vec2], width: f32, positions: &mut Vec<[f32; 3]>, indices: &mut Vec<u32>) {
+    let n = path.len();
+    if n < 2 {
+        return;
+    }
+    let normal = |i: usize| {
+        let d = (path[i + 1] - path[i]).normalize_or_zero();
+        Vec2::new(-d.y, d.x)
+    };
+    let start = positions.len() as u32;
+    for (i, point) in path.iter().enumerate() {
+        let side = match i {
+            0 => normal(0),
+            i if i == n - 1 => normal(n - 2),
+            i => {
+                let (a, b) = (normal(i - 1), normal(i));
+                let miter = (a + b).normalize_or_zero();
+                miter / miter.dot(a).max(0.5)
+            }
+        } * width / 2.0;
+        positions.push((point + side).extend(0.0).to_array());
+        positions.push((point - side).extend(0.0).to_array());
+    }
+    for i in 0..n as u32 - 1 {
+        let (a, b) = (start + 2 * i, start + 2 * i + 2);
+        indices.extend([a, a + 1, b, b, a + 1, b + 1]);
+    }
+}
+
+/// Lines along every track cell of the layout, at `cell_size`.
+pub fn track_mesh(cells_param: &CellsParam, cell_size: Vec2, width: f32) -> Mesh {
+    let (mut positions, mut indices) = (Vec::new(), Vec::new());
+    for iy in 0..cells_param.cell_table.height {
+        for ix in 0..cells_param.cell_table.width {
+            let move_type = cells_param.move_type_at(UVec2::new(ix as u32, iy as u32));
+            let center = cells_param.cell_center_at(ix, iy, cell_size);
+            let half = cells_param.cell_extent_at(ix, iy, cell_size) / 2.0;
+            let path = track_path(move_type, half, cells_param.corner_style)
+                .into_iter()
+                .map(|p| center + p)
+                .collect::<Vec<_>>();
+            add_band(&path, width, &mut positions, &mut indices);
+        }
+    }
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Spawn the lines, and rebuild them when anything they depend on changes.
+pub fn draw_track(
+    mut commands: Commands,
+    overlay: Res<TrackOverlay>,
+    cells_param: Res<CellsParam>,
+    tween: Option<Res<SizeTween>>,
+    lines: Query<(&Mesh2d, &MeshMaterial2d<ColorMaterial>), With<TrackLines>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let cell_size = tween.as_ref().map_or(cells_param.cell_size, |tween| tween.cell_size(&cells_param));
+    let Ok((mesh, material)) = lines.single() else {
+        commands.spawn((
+            Mesh2d(meshes.add(track_mesh(&cells_param, cell_size, overlay.width))),
+            MeshMaterial2d(materials.add(overlay.color)),
+            Transform::from_xyz(0.0, 0.0, Layer::Track.z()),
+            TrackLines,
+            OnLayer(Layer::Track),
+        ));
+        return;
+    };
+    if cells_param.is_changed() || overlay.is_changed() || tween.is_some_and(|tween| tween.is_changed()) {
+        meshes.insert(mesh.0.id(), track_mesh(&cells_param, cell_size, overlay.width));
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = overlay.color;
+        }
+    }
+}
+
+fn clear_track(mut commands: Commands, lines: Query<Entity, With<TrackLines>>) {
+    for entity in lines.iter() {
+        commands.entity(entity).despawn();
+    }
+}
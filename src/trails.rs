@@ -0,0 +1,192 @@
+//! Motion trails: fading ghosts behind every circle, so the paths show as
+//! light streaks.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::{
+    capability::{self, Capability},
+    clock::SimClock,
+    layers::{Layer, OnLayer},
+    materials::{paint, SharedMaterials},
+    Circles,
+};
+
+#[derive(Resource, Clone, Debug)]
+pub struct Trails {
+    /// ghosts behind every circle
+    pub length: usize,
+    /// simulated seconds between two ghosts
+    pub spacing_sec: f32,
+    /// alpha of every ghost relative to the one in front of it
+    pub decay: f32,
+}
+
+impl Default for Trails {
+    fn default() -> Self {
+        Trails {
+            length: 8,
+            spacing_sec: 0.03,
+            decay: 0.7,
+        }
+    }
+}
+
+impl Trails {
+    /// Alpha of the `index`-th ghost (0 is right behind the circle).
+    pub fn alpha(&self, index: usize) -> f32 {
+        self.decay.powi(index as i32 + 1)
+    }
+}
+
+#[derive(Component)]
+pub struct TrailGhost {
+    pub source: Entity,
+    pub index: usize,
+}
+
+/// Translation and scale per circle at one point in time.
+type Snapshot = HashMap<Entity, (Vec3, Vec3)>;
+
+/// Snapshots by simulated time, oldest first.
+#[derive(Resource, Default)]
+struct TrailHistory(VecDeque<(f64, Snapshot)>);
+
+pub struct TrailsPlugin;
+
+impl Plugin for TrailsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Trails>()
+            .init_resource::<TrailHistory>()
+            .init_resource::<SharedMaterials>()
+            .add_systems(
+                Update,
+                (spawn_trail_ghosts, record_trail_history, update_trail_ghosts)
+                    .chain()
+                    .after(crate::move_cells)
+                    .after(crate::agent::move_agents)
+                    .run_if(capability::enabled(Capability::Trails)),
+            )
+            .add_systems(Update, clear_trails.run_if(not(capability::enabled(Capability::Trails))));
+    }
+}
+
+/// Ghosts up to `length` for every circle, hidden until there's history.
+fn spawn_trail_ghosts(
+    mut commands: Commands,
+    trails: Res<Trails>,
+    circles: Query<(Entity, &Mesh2d, &MeshMaterial2d<ColorMaterial>, &Transform), Circles>,
+    ghosts: Query<(Entity, &TrailGhost)>,
+    mut shared: ResMut<SharedMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mut haunted = HashMap::<Entity, HashSet<usize>>::new();
+    for (entity, ghost) in ghosts.iter() {
+        if ghost.index >= trails.length {
+            // shortened
+            commands.entity(entity).despawn();
+        } else {
+            haunted.entry(ghost.source).or_default().insert(ghost.index);
+        }
+    }
+
+    for (entity, mesh, material, transform) in circles.iter() {
+        let color = materials.get(&material.0).map_or(Color::WHITE, |m| m.color);
+        for index in 0..trails.length {
+            if haunted.get(&entity).is_some_and(|indices| indices.contains(&index)) {
+                continue;
+            }
+            commands.spawn((
+                mesh.clone(),
+                MeshMaterial2d(shared.get(color.with_alpha(0.0), &mut materials)),
+                *transform,
+                TrailGhost { source: entity, index },
+                OnLayer(Layer::Trails),
+            ));
+        }
+    }
+}
+
+fn record_trail_history(
+    clock: Res<SimClock>,
+    trails: Res<Trails>,
+    mut history: ResMut<TrailHistory>,
+    circles: Query<(Entity, &Transform), Circles>,
+) {
+    let now = clock.elapsed_secs_f64();
+    if history.0.back().is_some_and(|(t, _)| *t > now) {
+        // stepped back; the trail starts over
+        history.0.clear();
+    }
+    if history.0.back().is_some_and(|(t, _)| *t == now) {
+        // paused: the trail holds still
+        return;
+    }
+    let snapshot = circles
+        .iter()
+        .map(|(entity, transform)| (entity, (transform.translation, transform.scale)))
+        .collect();
+    history.0.push_back((now, snapshot));
+
+    // keep one snapshot older than the last ghost
+    let oldest_needed = now - (trails.length as f64 * trails.spacing_sec as f64);
+    while history.0.len() > 1 && history.0[1].0 <= oldest_needed {
+        history.0.pop_front();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_trail_ghosts(
+    mut commands: Commands,
+    clock: Res<SimClock>,
+    trails: Res<Trails>,
+    history: Res<TrailHistory>,
+    sources: Query<&MeshMaterial2d<ColorMaterial>, (Circles, Without<TrailGhost>)>,
+    mut ghosts: Query<(Entity, &TrailGhost, &mut Transform, &mut MeshMaterial2d<ColorMaterial>)>,
+    mut shared: ResMut<SharedMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let now = clock.elapsed_secs_f64();
+    for (entity, ghost, mut transform, mut material) in ghosts.iter_mut() {
+        let Ok(source) = sources.get(ghost.source) else {
+            // the circle is gone
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let target = now - (ghost.index + 1) as f64 * trails.spacing_sec as f64;
+        let snapshot = history.0.iter().rev().find(|(t, _)| *t <= target);
+        let Some((translation, scale)) = snapshot.and_then(|(_, s)| s.get(&ghost.source)) else {
+            // not enough history yet; stay hidden
+            if let Some(color) = materials.get(&material.0).map(|m| m.color) {
+                paint(&mut material, color.with_alpha(0.0), &mut shared, &mut materials);
+            }
+            continue;
+        };
+        // behind the circles, older ghosts further back
+        transform.translation = translation.with_z(translation.z + Layer::Trails.z() * (ghost.index + 1) as f32);
+        transform.scale = *scale;
+
+        // follow the circle's color (LFOs, timeline), faded
+        if let Some(color) = materials.get(&source.0).map(|m| m.color) {
+            let faded = color.with_alpha(color.alpha() * trails.alpha(ghost.index));
+            paint(&mut material, faded, &mut shared, &mut materials);
+        }
+    }
+}
+
+fn clear_trails(
+    mut commands: Commands,
+    mut history: ResMut<TrailHistory>,
+    ghosts: Query<Entity, With<TrailGhost>>,
+) {
+    if ghosts.is_empty() && history.0.is_empty() {
+        return;
+    }
+    for entity in ghosts.iter() {
+        commands.entity(entity).despawn();
+    }
+    history.0.clear();
+}
@@ -0,0 +1,102 @@
+//! Smooth transitions when `cell_size` or `circle_size` change.
+//!
+//! [`CellsParam`] always holds the target; [`SizeTween`] holds the sizes on
+//! screen, which follow the target over `duration_sec` instead of snapping.
+//! Modulators that already move smoothly (LFOs, the timeline) [`shift`] the
+//! tween along with the parameter instead, so they aren't smoothed twice.
+//!
+//! [`shift`]: SizeTween::shift
+
+use bevy::prelude::*;
+
+use crate::{easing::Easing, CellsParam};
+
+#[derive(Resource, Debug)]
+pub struct SizeTween {
+    /// 0.0 snaps
+    pub duration_sec: f32,
+    pub easing: Easing,
+    // (cell_size, circle_size)
+    shown: Option<(Vec2, f32)>,
+    from: (Vec2, f32),
+    to: (Vec2, f32),
+    elapsed: f32,
+}
+
+impl Default for SizeTween {
+    fn default() -> Self {
+        SizeTween::new(0.5, Easing::SineInOut)
+    }
+}
+
+impl SizeTween {
+    pub fn new(duration_sec: f32, easing: Easing) -> Self {
+        SizeTween {
+            duration_sec,
+            easing,
+            shown: None,
+            from: (Vec2::ZERO, 0.0),
+            to: (Vec2::ZERO, 0.0),
+            elapsed: 0.0,
+        }
+    }
+
+    /// `cell_size` on screen right now.
+    pub fn cell_size(&self, cells_param: &CellsParam) -> Vec2 {
+        self.shown.map_or(cells_param.cell_size, |(cell_size, _)| cell_size)
+    }
+
+    /// `circle_size` on screen right now.
+    pub fn circle_size(&self, cells_param: &CellsParam) -> f32 {
+        self.shown.map_or(cells_param.circle_size, |(_, circle_size)| circle_size)
+    }
+
+    /// Move the target and everything on screen by the same amount, for
+    /// changes that shouldn't be tweened.
+    pub fn shift(&mut self, cell_size: Vec2, circle_size: f32) {
+        let Some(shown) = &mut self.shown else {
+            return;
+        };
+        for sizes in [shown, &mut self.from, &mut self.to] {
+            sizes.0 += cell_size;
+            sizes.1 += circle_size;
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.shown.is_some_and(|shown| shown != self.to)
+    }
+}
+
+pub fn tween_sizes(time: Res<Time>, cells_param: Res<CellsParam>, mut tween: ResMut<SizeTween>) {
+    let target = (cells_param.cell_size, cells_param.circle_size);
+    let Some(shown) = tween.shown else {
+        // spawned at these sizes
+        tween.shown = Some(target);
+        tween.to = target;
+        return;
+    };
+
+    if target != tween.to {
+        // retarget from wherever we are, so changes mid-tween stay smooth
+        tween.from = shown;
+        tween.to = target;
+        tween.elapsed = 0.0;
+    }
+    if !tween.is_running() {
+        return;
+    }
+
+    tween.elapsed += time.delta_secs();
+    let s = if tween.duration_sec > 0.0 {
+        tween.easing.apply((tween.elapsed / tween.duration_sec).min(1.0))
+    } else {
+        1.0
+    };
+    let (from, to) = (tween.from, tween.to);
+    tween.shown = Some(if s >= 1.0 {
+        to
+    } else {
+        (from.0.lerp(to.0, s), from.1 + (to.1 - from.1) * s)
+    });
+}
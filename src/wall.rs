@@ -0,0 +1,144 @@
+//! Display walls: the table spread over a grid of monitors, one borderless
+//! fullscreen window and camera per panel, each showing its own part.
+//!
+//! The panels are laid out as if the frames between them were part of the
+//! picture, [`DisplayWall::bezel`] pixels wide, so lines running across the
+//! wall stay straight; what falls behind a frame isn't shown. Every panel
+//! camera has an [`OutputName`] (`wall 0,1` for column 0, row 1) for
+//! calibrating the monitors one by one.
+//!
+//! Opt-in with [`DisplayWall`].
+
+use bevy::{
+    prelude::*,
+    render::camera::RenderTarget,
+    window::{MonitorSelection, WindowMode, WindowRef},
+};
+
+use crate::{calibration::OutputName, CellsParam};
+
+/// Opt-in: the wall the table is spread over.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct DisplayWall {
+    pub columns: u32,
+    pub rows: u32,
+    /// monitor index of the top left panel; the others follow row by row
+    pub first_monitor: usize,
+    /// width of the frames between two panels (across, between columns, and
+    /// down, between rows), in logical pixels of a panel
+    pub bezel: Vec2,
+}
+
+impl Default for DisplayWall {
+    fn default() -> Self {
+        DisplayWall { columns: 2, rows: 1, first_monitor: 0, bezel: Vec2::ZERO }
+    }
+}
+
+impl DisplayWall {
+    /// Size of the whole wall, frames included, in pixels of a panel of
+    /// `panel` logical size.
+    pub fn size(&self, panel: Vec2) -> Vec2 {
+        let panels = Vec2::new(self.columns as f32, self.rows as f32);
+        panels * panel + (panels - Vec2::ONE).max(Vec2::ZERO) * self.bezel
+    }
+
+    /// Where the camera of the panel in `column` and `row` (from the top)
+    /// looks and the scale of its orthographic projection, for the wall to
+    /// show all of `bounds`.
+    pub fn view(&self, bounds: Rect, panel: Vec2, column: u32, row: u32) -> (Vec2, f32) {
+        let wall = self.size(panel);
+        let scale = (bounds.size() / wall).max_element();
+        let step = panel + self.bezel;
+        let from_top_left = Vec2::new(column as f32, row as f32) * step + panel / 2.0 - wall / 2.0;
+        // y is up in the world
+        (bounds.center() + Vec2::new(from_top_left.x, -from_top_left.y) * scale, scale)
+    }
+}
+
+/// The window and the camera of one panel.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WallPanel {
+    pub column: u32,
+    pub row: u32,
+}
+
+pub struct DisplayWallPlugin;
+
+impl Plugin for DisplayWallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (sync_wall, layout_wall).chain());
+    }
+}
+
+/// Open a window per panel when [`DisplayWall`] is inserted or changed,
+/// close them all when it is removed.
+pub fn sync_wall(
+    mut commands: Commands,
+    wall: Option<Res<DisplayWall>>,
+    panels: Query<Entity, With<WallPanel>>,
+) {
+    let rebuild = wall.as_ref().is_some_and(|wall| wall.is_changed());
+    if wall.is_none() || rebuild {
+        for entity in panels.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+    let Some(wall) = wall.filter(|_| rebuild) else {
+        return;
+    };
+    for row in 0..wall.rows {
+        for column in 0..wall.columns {
+            let panel = WallPanel { column, row };
+            let monitor = MonitorSelection::Index(wall.first_monitor + (row * wall.columns + column) as usize);
+            let window = commands
+                .spawn((
+                    Window {
+                        title: format!("moving cells (wall {},{})", column, row),
+                        mode: WindowMode::BorderlessFullscreen(monitor),
+                        ..default()
+                    },
+                    panel,
+                ))
+                .id();
+            commands.spawn((
+                Camera2d,
+                Camera { target: RenderTarget::Window(WindowRef::Entity(window)), ..default() },
+                OutputName(format!("wall {},{}", column, row)),
+                panel,
+            ));
+        }
+    }
+}
+
+/// Point every panel camera at its part of the table.
+pub fn layout_wall(
+    wall: Option<Res<DisplayWall>>,
+    cells_param: Res<CellsParam>,
+    windows: Query<(&Window, &WallPanel)>,
+    mut cameras: Query<(&WallPanel, &mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    let Some(wall) = wall else {
+        return;
+    };
+    let bounds = cells_param.bounds();
+    for (panel, mut transform, mut projection) in cameras.iter_mut() {
+        let Some((window, _)) = windows.iter().find(|(_, p)| *p == panel) else {
+            continue;
+        };
+        let Projection::Orthographic(ortho) = &mut *projection else {
+            continue;
+        };
+        if window.size().min_element() <= 0.0 || bounds.is_empty() {
+            continue;
+        }
+        let (center, scale) = wall.view(bounds, window.size(), panel.column, panel.row);
+        if ortho.scale != scale {
+            ortho.scale = scale;
+        }
+        let translation = center.extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Golden event streams for traveling agents.
+//!
+//! Audio and lighting integrations key off `CellBoundaryCrossed` and
+//! `LoopCompleted`, so both the order and the frame each event lands on are
+//! pinned down here.
+
+use std::time::Duration;
+
+use bevy::{ecs::event::EventCursor, prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    agent::{self, Agent, CellBoundaryCrossed, LoopCompleted},
+    clock::{SimClock, SimClockPlugin},
+    tempo::Tempo,
+    CellTable, CellsParam,
+};
+
+// a 2x2 clockwise loop: (0,0) → (1,0) → (1,1) → (0,1) → (0,0)
+const SQUARE: &str = "\
+    ┌┐
+    └┘";
+
+/// Half a second per cell at 1/8 s per frame: one crossing every 4 frames.
+fn app(table: &str) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(125)))
+        .insert_resource(CellsParam::new(CellTable::new(table)))
+        .insert_resource(Tempo {
+            bpm: 120.0,
+            beats_per_cell: 1.0,
+        })
+        .add_event::<CellBoundaryCrossed>()
+        .add_event::<LoopCompleted>()
+        .add_systems(Update, agent::move_agents);
+    app
+}
+
+fn spawn_agent(app: &mut App, grid: UVec2) -> Entity {
+    app.world_mut()
+        .spawn((Agent::new(grid, Color::WHITE, 10.0), Transform::default()))
+        .id()
+}
+
+/// Run `frames` updates, returning every event of type `E` with the frame it was sent on.
+fn record<E: Event + Clone>(app: &mut App, frames: u32) -> Vec<(u32, E)> {
+    let mut cursor = EventCursor::<E>::default();
+    let mut stream = Vec::new();
+    for frame in 1..=frames {
+        app.update();
+        let events = app.world().resource::<Events<E>>();
+        stream.extend(cursor.read(events).map(|e| (frame, e.clone())));
+    }
+    stream
+}
+
+#[test]
+fn boundary_crossings_follow_the_loop() {
+    let mut app = app(SQUARE);
+    let a = spawn_agent(&mut app, UVec2::new(0, 0));
+
+    let crossed = |frame, from: (u32, u32), to: (u32, u32)| {
+        (frame, CellBoundaryCrossed { agent: a, from: from.into(), to: to.into() })
+    };
+
+    // the first frame has no delta, so crossings land on frames 5, 9, 13, ...
+    assert_eq!(
+        record::<CellBoundaryCrossed>(&mut app, 28),
+        vec![
+            crossed(5, (0, 0), (1, 0)),
+            crossed(9, (1, 0), (1, 1)),
+            crossed(13, (1, 1), (0, 1)),
+            crossed(17, (0, 1), (0, 0)),
+            crossed(21, (0, 0), (1, 0)),
+            crossed(25, (1, 0), (1, 1)),
+        ],
+    );
+}
+
+#[test]
+fn loops_complete_every_four_cells() {
+    let mut app = app(SQUARE);
+    let a = spawn_agent(&mut app, UVec2::new(1, 1));
+
+    assert_eq!(
+        record::<LoopCompleted>(&mut app, 49),
+        vec![
+            (17, LoopCompleted { agent: a, laps: 1 }),
+            (33, LoopCompleted { agent: a, laps: 2 }),
+            (49, LoopCompleted { agent: a, laps: 3 }),
+        ],
+    );
+    assert_eq!(app.world().get::<Agent>(a).unwrap().laps, 3);
+}
+
+#[test]
+fn outer_ring_of_the_default_layout_takes_forty_cells() {
+    let mut app = app("\
+        ┌→→→→→→→→→┐
+        ↑ ┌→→→→→┐ ↓
+        ↑ ↑0   0↓ ↓
+        ↑ └←←←←←┘ ↓
+        ↑ ┏←┓ ┌→┐ ↓
+        ↑ ↓0↑ ↑0↓ ↓
+        ↑ ┗→┛ └←┘ ↓
+        ↑ ┏←←←←←┓ ↓
+        ↑ ↓0   0↑ ↓
+        ↑ ┗→→→→→┛ ↓
+        └←←←←←←←←←┘");
+    let outer = spawn_agent(&mut app, UVec2::new(0, 0));
+    // the small eight-cell counter-clockwise ring on the left
+    let inner = spawn_agent(&mut app, UVec2::new(2, 4));
+
+    let crossings = record::<CellBoundaryCrossed>(&mut app, 1 + 4 * 80);
+    let count = |agent| crossings.iter().filter(|(_, e)| e.agent == agent).count();
+    assert_eq!(count(outer), 80);
+    assert_eq!(count(inner), 80);
+
+    let laps = |agent| app.world().get::<Agent>(agent).unwrap().laps;
+    assert_eq!(laps(outer), 2);
+    assert_eq!(laps(inner), 10);
+}
+
+#[test]
+fn paused_clock_steps_back_across_the_boundary() {
+    let mut app = app(SQUARE);
+    let a = spawn_agent(&mut app, UVec2::new(0, 0));
+    app.world_mut().resource_mut::<SimClock>().paused = true;
+
+    assert_eq!(record::<CellBoundaryCrossed>(&mut app, 8), vec![]);
+
+    app.world_mut().resource_mut::<SimClock>().step(-1);
+    assert_eq!(
+        record::<CellBoundaryCrossed>(&mut app, 1),
+        vec![(1, CellBoundaryCrossed { agent: a, from: UVec2::new(0, 0), to: UVec2::new(0, 1) })],
+    );
+    assert_eq!(app.world().get::<Agent>(a).unwrap().grid, UVec2::new(0, 1));
+}
+
+#[test]
+fn a_zero_tempo_holds_the_agents_still() {
+    let mut app = app(SQUARE);
+    app.insert_resource(Tempo { bpm: 120.0, beats_per_cell: 0.0 });
+    let a = spawn_agent(&mut app, UVec2::new(0, 0));
+
+    assert!(record::<CellBoundaryCrossed>(&mut app, 4).is_empty());
+    assert_eq!(app.world().get::<Agent>(a).unwrap().grid, UVec2::new(0, 0));
+}
+
+#[test]
+fn a_tiny_span_crosses_a_bounded_number_of_cells_per_frame() {
+    let mut app = app(SQUARE);
+    app.insert_resource(Tempo { bpm: 120.0, beats_per_cell: 1e-9 });
+    spawn_agent(&mut app, UVec2::new(0, 0));
+
+    let crossed = record::<CellBoundaryCrossed>(&mut app, 2);
+    assert_eq!(crossed.len(), agent::MAX_CROSSINGS);
+    assert!(crossed.iter().all(|(frame, _)| *frame == 2));
+}
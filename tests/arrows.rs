@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{arrows::arrow, MoveType};
+
+#[test]
+fn arrows_point_the_way_circles_go() {
+    let half = Vec2::splat(10.0);
+    let (from, to) = arrow(MoveType::Right, half, false).unwrap();
+    assert!(from.x < 0.0 && to.x > 0.0);
+    assert_eq!((from.y, to.y), (0.0, 0.0));
+    // and back when the flow is reversed
+    assert_eq!(arrow(MoveType::Right, half, true), Some((to, from)));
+
+    let (from, to) = arrow(MoveType::BottomToRight, half, false).unwrap();
+    assert_eq!((from, to), (Vec2::new(0.0, -6.0), Vec2::new(6.0, 0.0)));
+
+    assert_eq!(arrow(MoveType::Center, half, false), None);
+}
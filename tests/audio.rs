@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    audio::{react_to_spectrum, rings_outside_in, AudioSpectrum, Bars, BeatDetector, RingBandMapping},
+    materials::SharedMaterials,
+    ring::{find_rings, Rings},
+    Cell, CellTable, CellsParam, MoveType,
+};
+
+const NESTED: &str = "┏←←←┓\n↓┌→┐↑\n↓↑0↓↑\n↓└←┘↑\n┗→→→┛";
+
+#[test]
+fn outer_rings_take_the_low_bands() {
+    let cells_param = CellsParam::new(CellTable::new(NESTED));
+    let rings = Rings::new(find_rings(&cells_param));
+    let order = rings_outside_in(&rings, &cells_param);
+    assert_eq!(order.iter().map(|i| rings.0[*i].len()).collect::<Vec<_>>(), [16, 8]);
+
+    let mapping = RingBandMapping::default();
+    assert_eq!((mapping.band(0, 2, 8), mapping.band(1, 2, 8)), (0, 7));
+    assert_eq!((0..3).map(|i| mapping.band(i, 3, 4)).collect::<Vec<_>>(), [0, 2, 3]);
+    let mapping = RingBandMapping { bands: vec![3, 1], ..default() };
+    assert_eq!((mapping.band(0, 2, 8), mapping.band(1, 2, 8)), (3, 1));
+}
+
+#[test]
+fn rings_follow_their_band() {
+    let cells_param = CellsParam::new(CellTable::new(NESTED));
+    let rings = Rings::new(find_rings(&cells_param));
+
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<SharedMaterials>()
+        .insert_resource(rings)
+        .insert_resource(cells_param)
+        .insert_resource(AudioSpectrum { bands: vec![1.0, 0.0] })
+        .insert_resource(RingBandMapping { size_depth: 1.0, brightness_depth: 0.5, bands: Vec::new() })
+        .add_systems(Update, react_to_spectrum);
+
+    // one circle on the outer ring, one on the inner ring, one in the middle,
+    // all drawn with the same material
+    let material = app.world_mut().resource_mut::<Assets<ColorMaterial>>().add(Color::WHITE);
+    let mut spawn = |x: u32, y: u32| {
+        let material = material.clone();
+        let cell = Cell::new(UVec2::new(x, y), Vec2::ZERO, Vec2::ONE, MoveType::Center);
+        app.world_mut().spawn((Transform::default(), MeshMaterial2d(material), cell)).id()
+    };
+    let outer = spawn(0, 2);
+    let inner = spawn(1, 2);
+    let middle = spawn(2, 2);
+
+    let state = |app: &App, entity: Entity| {
+        let scale = app.world().get::<Transform>(entity).unwrap().scale.x;
+        let material = app.world().get::<MeshMaterial2d<ColorMaterial>>(entity).unwrap();
+        let color = app.world().resource::<Assets<ColorMaterial>>().get(&material.0).unwrap().color;
+        (scale, Oklcha::from(color).lightness)
+    };
+
+    app.update();
+    assert_eq!(state(&app, outer), (2.0, 1.0));
+    let (scale, lightness) = state(&app, inner);
+    assert_eq!(scale, 1.0);
+    assert!((lightness - 0.5).abs() < 1e-4);
+    assert_eq!(state(&app, middle), (1.0, 1.0));
+
+    // levels are applied relative to the last frame
+    app.world_mut().resource_mut::<AudioSpectrum>().bands = vec![0.5, 1.0];
+    app.update();
+    let (scale, lightness) = state(&app, outer);
+    assert_eq!(scale, 1.5);
+    assert!((lightness - 0.75).abs() < 1e-4);
+    let (scale, lightness) = state(&app, inner);
+    assert_eq!(scale, 2.0);
+    assert!((lightness - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn kicks_are_beats_and_beats_make_bars() {
+    let mut detector = BeatDetector::default();
+    let mut beats = 0;
+    for frame in 0..300 {
+        // a hum with a little wobble, and a kick in the low bands every half second
+        let hum = 0.2 + 0.02 * (frame % 3) as f32;
+        let kick = if frame % 30 == 0 { 0.8 } else { 0.0 };
+        let bands = [hum + kick, hum + kick, hum, hum, hum, hum];
+        if detector.feed(&bands, 1.0 / 60.0) {
+            beats += 1;
+        }
+    }
+    assert_eq!(beats, 10);
+
+    let mut silence = BeatDetector::default();
+    assert!((0..100).all(|_| !silence.feed(&[0.0; 6], 1.0 / 60.0)));
+
+    let mut bars = Bars::new(4, 2);
+    assert_eq!((0..16).filter(|_| bars.beat()).count(), 2);
+}
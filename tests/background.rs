@@ -0,0 +1,57 @@
+use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+
+use moving_cells_01::{
+    background::{apply_background, gradient_mesh, sync_gradient, Background, GradientQuad},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn the_hue_drifts_with_time() {
+    let drift = Background::HueDrift { color: Color::hsl(10.0, 0.5, 0.5), degrees_per_sec: 6.0 };
+    let Color::Hsla(hsla) = drift.clear_color(10.0) else {
+        panic!("drifting keeps the color space");
+    };
+    assert!((hsla.hue - 70.0).abs() < 1e-3);
+    assert_eq!(Background::Solid(Color::WHITE).clear_color(10.0), Color::WHITE);
+}
+
+#[test]
+fn gradients_run_from_top_to_bottom() {
+    let mesh = gradient_mesh(Color::WHITE, Color::BLACK);
+    let (Some(VertexAttributeValues::Float32x3(positions)), Some(VertexAttributeValues::Float32x4(colors))) =
+        (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.attribute(Mesh::ATTRIBUTE_COLOR))
+    else {
+        panic!("a gradient has positions and colors");
+    };
+    for (position, color) in positions.iter().zip(colors) {
+        let expected = if position[1] > 0.0 { [1.0; 4] } else { [0.0, 0.0, 0.0, 1.0] };
+        assert_eq!(*color, expected);
+    }
+}
+
+#[test]
+fn a_gradient_is_a_quad_behind_everything() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(CellsParam {
+            background: Background::Gradient { top: Color::WHITE, bottom: Color::BLACK },
+            ..CellsParam::new(CellTable::new("0"))
+        })
+        .add_systems(Update, (apply_background, sync_gradient).chain());
+    app.update();
+
+    let quads = |app: &mut App| {
+        let mut quads = app.world_mut().query_filtered::<&Transform, With<GradientQuad>>();
+        quads.iter(app.world()).map(|t| t.translation.z).collect::<Vec<_>>()
+    };
+    assert_eq!(quads(&mut app), vec![-999.9]);
+    assert_eq!(app.world().resource::<ClearColor>().0, Color::BLACK);
+
+    app.world_mut().resource_mut::<CellsParam>().background = Background::Solid(Color::WHITE);
+    app.update();
+    assert!(quads(&mut app).is_empty());
+    assert_eq!(app.world().resource::<ClearColor>().0, Color::WHITE);
+}
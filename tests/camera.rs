@@ -0,0 +1,319 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::RunSystemOnce,
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{PrimaryWindow, WindowResized, WindowResolution},
+};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    clock::{MotionPhase, SimClockPlugin},
+    camera::{
+        fit_camera, fit_scale, follow_camera, pan_zoom, select_followed, swing_camera, unswing_camera, zoom_about,
+        CameraFit, Followed, PanZoom, Shake, Swing, Swung,
+    },
+    input::{Action, InputMap, UiFocus},
+    spawn_cells, CellTable, CellsParam,
+};
+
+#[test]
+fn bounds_cover_every_cell() {
+    let cells_param = CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘"));
+    let bounds = cells_param.bounds();
+    assert_eq!(bounds.size(), Vec2::splat(150.0));
+    for iy in 0..3 {
+        for ix in 0..3 {
+            let cell = Rect::from_center_size(cells_param.cell_center(ix, iy), cells_param.cell_extent(ix, iy));
+            assert_eq!(bounds.union(cell), bounds, "({}, {})", ix, iy);
+        }
+    }
+}
+
+#[test]
+fn fit_scale_leaves_the_margin_free() {
+    let bounds = Rect::new(0.0, 0.0, 900.0, 300.0);
+    // width bound: 900 across 1000 - 2 * 50 pixels
+    assert_eq!(fit_scale(bounds, Vec2::new(1000.0, 1000.0), 0.05), 1.0);
+    // height bound
+    assert_eq!(fit_scale(bounds, Vec2::new(10000.0, 200.0), 0.0), 1.5);
+}
+
+fn fitted_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_event::<WindowResized>()
+        .insert_resource(CellsParam::new(CellTable::new(&"→".repeat(20))))
+        .init_resource::<CameraFit>()
+        .add_systems(Update, fit_camera);
+    app.world_mut().spawn((
+        Window { resolution: WindowResolution::new(1000.0, 500.0), ..default() },
+        PrimaryWindow,
+    ));
+    app.world_mut().spawn((Camera2d, OutputName("main".into())));
+    app.update();
+    app
+}
+
+fn main_camera(app: &mut App) -> (Vec3, f32) {
+    let world = app.world_mut();
+    let (transform, projection) = world.query::<(&Transform, &Projection)>().single(world).unwrap();
+    let Projection::Orthographic(ortho) = projection else {
+        panic!("2D camera");
+    };
+    (transform.translation, ortho.scale)
+}
+
+#[test]
+fn camera_fits_the_table_and_follows_the_window() {
+    let mut app = fitted_app();
+    let bounds = app.world().resource::<CellsParam>().bounds();
+    let (translation, scale) = main_camera(&mut app);
+    assert_eq!(translation.truncate(), bounds.center());
+    assert_eq!(scale, fit_scale(bounds, Vec2::new(1000.0, 500.0), 0.05));
+
+    let window = {
+        let world = app.world_mut();
+        let (entity, mut window) = world.query::<(Entity, &mut Window)>().single_mut(world).unwrap();
+        window.resolution.set(2000.0, 500.0);
+        entity
+    };
+    app.world_mut().send_event(WindowResized { window, width: 2000.0, height: 500.0 });
+    app.update();
+    assert_eq!(main_camera(&mut app).1, fit_scale(bounds, Vec2::new(2000.0, 500.0), 0.05));
+}
+
+#[test]
+fn camera_stays_put_without_fitting() {
+    let mut app = fitted_app();
+    app.world_mut().remove_resource::<CameraFit>();
+    app.world_mut().resource_mut::<CellsParam>().cell_size *= 2.0;
+    app.update();
+    let bounds = app.world().resource::<CellsParam>().bounds();
+    assert_ne!(main_camera(&mut app).1, fit_scale(bounds, Vec2::new(1000.0, 500.0), 0.05));
+}
+
+#[test]
+fn zooming_keeps_the_point_under_the_cursor() {
+    let (mut translation, mut scale) = (Vec3::new(10.0, 20.0, 5.0), 2.0);
+    let cursor = Vec2::new(100.0, -50.0);
+    let under = translation.truncate() + cursor * scale;
+    zoom_about(&mut translation, &mut scale, 0.5, cursor);
+    assert_eq!(scale, 0.5);
+    assert_eq!(translation.truncate() + cursor * scale, under);
+    assert_eq!(translation.z, 5.0);
+}
+
+fn pan_zoom_app() -> App {
+    let mut app = fitted_app();
+    app.init_resource::<PanZoom>()
+        .init_resource::<InputMap>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<AccumulatedMouseMotion>()
+        .init_resource::<AccumulatedMouseScroll>()
+        .add_systems(Update, pan_zoom.before(fit_camera));
+    app
+}
+
+#[test]
+fn dragging_pans_and_stops_the_fitting_until_reset() {
+    let mut app = pan_zoom_app();
+    app.update();
+    let (fitted, scale) = main_camera(&mut app);
+
+    app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+    app.world_mut().resource_mut::<AccumulatedMouseMotion>().delta = Vec2::new(10.0, 4.0);
+    app.update();
+    let (panned, _) = main_camera(&mut app);
+    assert_eq!(panned.truncate(), fitted.truncate() + Vec2::new(-10.0, 4.0) * scale);
+    assert!(app.world().get_resource::<CameraFit>().is_none());
+
+    // the table changing no longer moves the camera
+    app.world_mut().resource_mut::<AccumulatedMouseMotion>().delta = Vec2::ZERO;
+    app.world_mut().resource_mut::<CellsParam>().cell_size *= 2.0;
+    app.update();
+    assert_eq!(main_camera(&mut app).0, panned);
+
+    let key = app.world().resource::<InputMap>().key(Action::ResetView).unwrap();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    let bounds = app.world().resource::<CellsParam>().bounds();
+    assert_eq!(app.world().resource::<CameraFit>(), &CameraFit::default());
+    assert_eq!(main_camera(&mut app), (bounds.center().extend(panned.z), fit_scale(bounds, Vec2::new(1000.0, 500.0), 0.05)));
+}
+
+#[test]
+fn the_wheel_zooms_within_limits() {
+    let mut app = pan_zoom_app();
+    app.update();
+    let (_, fitted) = main_camera(&mut app);
+
+    app.world_mut().resource_mut::<AccumulatedMouseScroll>().delta = Vec2::new(0.0, 1.0);
+    app.update();
+    let step = app.world().resource::<PanZoom>().step;
+    assert!((main_camera(&mut app).1 - fitted / step).abs() < 1e-5);
+
+    // scrolling a combo box
+    app.insert_resource(UiFocus { pointer: true });
+    app.update();
+    assert!((main_camera(&mut app).1 - fitted / step).abs() < 1e-5);
+    app.insert_resource(UiFocus::default());
+
+    app.world_mut().resource_mut::<AccumulatedMouseScroll>().delta = Vec2::new(0.0, 1000.0);
+    app.update();
+    assert_eq!(main_camera(&mut app).1, app.world().resource::<PanZoom>().min_scale);
+    app.world_mut().resource_mut::<AccumulatedMouseScroll>().delta = Vec2::new(0.0, -1000.0);
+    app.update();
+    assert_eq!(main_camera(&mut app).1, app.world().resource::<PanZoom>().max_scale);
+}
+
+#[test]
+fn swing_offset_goes_both_ways_along_the_axis() {
+    let swing = Swing { amplitude: 20.0, frequency: 0.25, axis: Vec3::new(0.0, 3.0, 0.0) };
+    assert_eq!(swing.offset(0.0), Vec3::ZERO);
+    assert!((swing.offset(1.0) - Vec3::new(0.0, 20.0, 0.0)).length() < 1e-4);
+    assert!((swing.offset(3.0) - Vec3::new(0.0, -20.0, 0.0)).length() < 1e-4);
+    assert_eq!(Swing { axis: Vec3::ZERO, ..swing }.offset(1.0), Vec3::ZERO);
+}
+
+#[test]
+fn the_camera_swings_around_its_fitted_place() {
+    let mut app = fitted_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .init_resource::<MotionPhase>()
+        .add_systems(Update, (unswing_camera.before(fit_camera), swing_camera.after(fit_camera)));
+    let swing = Swing { amplitude: 20.0, frequency: 1.0, axis: Vec3::new(1.0, 0.0, 1.0) };
+    app.world_mut().resource_mut::<CellsParam>().swing = Some(swing);
+    let center = app.world().resource::<CellsParam>().bounds().center();
+
+    for _ in 0..6 {
+        app.update();
+        let secs = app.world().resource::<Time>().elapsed_secs();
+        let (translation, _) = main_camera(&mut app);
+        // no z in 2D
+        let offset = swing.offset(secs).truncate();
+        assert!((translation.truncate() - (center + offset)).length() < 1e-3, "{} at {}", translation, secs);
+        assert_eq!(translation.z, 0.0);
+    }
+
+    // refitting moves the place, not the swing
+    app.world_mut().resource_mut::<CellsParam>().cell_size *= 2.0;
+    app.update();
+    let center = app.world().resource::<CellsParam>().bounds().center();
+    let secs = app.world().resource::<Time>().elapsed_secs();
+    assert!((main_camera(&mut app).0.truncate() - (center + swing.offset(secs).truncate())).length() < 1e-3);
+
+    app.world_mut().resource_mut::<CellsParam>().swing = None;
+    app.update();
+    assert_eq!(main_camera(&mut app).0.truncate(), center);
+    let world = app.world_mut();
+    assert!(world.query::<&Swung>().iter(world).next().is_none());
+}
+
+fn followed(app: &mut App) -> Vec<Entity> {
+    let world = app.world_mut();
+    world.query_filtered::<Entity, With<Followed>>().iter(world).collect()
+}
+
+#[test]
+fn the_camera_follows_the_circle_picked() {
+    let mut app = pan_zoom_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_systems(Update, (select_followed.before(pan_zoom), follow_camera.after(fit_camera)));
+    app.world_mut()
+        .run_system_once(
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .unwrap();
+    app.update();
+    let mut circles = {
+        let world = app.world_mut();
+        world.query::<(Entity, &Transform, &moving_cells_01::Cell)>().iter(world).map(|(e, t, _)| (e, t.translation)).collect::<Vec<_>>()
+    };
+    circles.sort_by_key(|(entity, _)| *entity);
+
+    // Tab picks the first, then the next
+    let key = app.world().resource::<InputMap>().key(Action::FollowNext).unwrap();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    assert_eq!(followed(&mut app), vec![circles[0].0]);
+    assert!(app.world().get_resource::<CameraFit>().is_none());
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+    let (target, at) = circles[1];
+    assert_eq!(followed(&mut app), vec![target]);
+
+    // the camera closes in on it
+    let mut distance = (main_camera(&mut app).0.truncate() - at.truncate()).length();
+    for _ in 0..20 {
+        app.update();
+        let now = (main_camera(&mut app).0.truncate() - at.truncate()).length();
+        assert!(now < distance || now < 1e-3, "{} -> {}", distance, now);
+        distance = now;
+    }
+    assert!(distance < 1.0, "{}", distance);
+
+    // a drag lets go
+    app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+    app.world_mut().resource_mut::<AccumulatedMouseMotion>().delta = Vec2::new(5.0, 0.0);
+    app.update();
+    assert!(followed(&mut app).is_empty());
+}
+
+#[test]
+fn shake_kicks_a_new_way_every_cell_and_settles() {
+    let shake = Shake { intensity: 10.0, punch: 0.1, decay: 5.0 };
+    assert_eq!(shake.offset(3, 0.0).length(), 10.0);
+    assert_ne!(shake.offset(3, 0.0), shake.offset(4, 0.0));
+    assert!(shake.offset(3, 2.0).length() < 1e-3);
+    assert!((shake.zoom(0.0) - 0.9).abs() < 1e-6);
+    assert!((shake.zoom(2.0) - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn the_camera_is_kicked_when_the_circles_enter_a_cell() {
+    let mut app = fitted_app();
+    // one cell a second, frames of 0.1 second
+    app.add_plugins(SimClockPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(Shake { intensity: 10.0, punch: 0.1, decay: 5.0 })
+        .add_systems(Update, (unswing_camera.before(fit_camera), swing_camera.after(fit_camera)));
+    let (center, fitted) = (app.world().resource::<CellsParam>().bounds().center(), main_camera(&mut app).1);
+
+    let mut kicks = 0;
+    let mut last_cell = 0;
+    for _ in 0..25 {
+        app.update();
+        let cell = app.world().resource::<MotionPhase>().cells().floor() as i64;
+        let (translation, scale) = main_camera(&mut app);
+        if cell != last_cell {
+            // the frame it crosses: knocked off and zoomed in all the way
+            kicks += 1;
+            assert!(((translation.truncate() - center).length() - 10.0).abs() < 1e-3);
+            assert!((scale - fitted * 0.9).abs() < 1e-4, "{} of {}", scale, fitted);
+        }
+        last_cell = cell;
+        // never anywhere but round the fitted view
+        assert!((translation.truncate() - center).length() <= 10.0 + 1e-3);
+        assert!(scale <= fitted + 1e-4 && scale >= fitted * 0.9 - 1e-4);
+    }
+    assert_eq!(kicks, 2);
+
+    // and back in place without it
+    app.world_mut().remove_resource::<Shake>();
+    app.update();
+    let (translation, scale) = main_camera(&mut app);
+    assert!(translation.abs_diff_eq(center.extend(0.0), 1e-3) && (scale - fitted).abs() < 1e-5);
+}
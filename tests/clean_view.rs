@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    clean_view::{CleanView, CleanViewPlugin},
+    input::InputMap,
+    layers::{HiddenLayers, Layer, LayersPlugin},
+};
+
+fn press(app: &mut App, key: KeyCode) {
+    let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keys.release(key);
+    keys.clear();
+    keys.press(key);
+    app.update();
+}
+
+#[test]
+fn f12_hides_every_overlay_and_brings_back_what_was_shown() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LayersPlugin, CleanViewPlugin))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>();
+    let shown = app.world_mut().spawn((Node::default(), Visibility::Inherited)).id();
+    let closed = app.world_mut().spawn((Node::default(), Visibility::Hidden)).id();
+    app.update();
+
+    press(&mut app, KeyCode::F12);
+    assert!(app.world().contains_resource::<CleanView>());
+    assert_eq!(app.world().get::<Visibility>(shown), Some(&Visibility::Hidden));
+    assert!(app.world().resource::<HiddenLayers>().0.contains(&Layer::Debug));
+
+    // opened while clean, kept out of the way
+    let late = app.world_mut().spawn((Node::default(), Visibility::Visible)).id();
+    app.update();
+    assert_eq!(app.world().get::<Visibility>(late), Some(&Visibility::Hidden));
+
+    press(&mut app, KeyCode::F12);
+    assert!(!app.world().contains_resource::<CleanView>());
+    assert_eq!(app.world().get::<Visibility>(shown), Some(&Visibility::Inherited));
+    assert_eq!(app.world().get::<Visibility>(closed), Some(&Visibility::Hidden));
+    assert_eq!(app.world().get::<Visibility>(late), Some(&Visibility::Visible));
+    assert!(!app.world().resource::<HiddenLayers>().0.contains(&Layer::Debug));
+}
+
+#[test]
+fn a_debug_layer_hidden_before_stays_hidden() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LayersPlugin, CleanViewPlugin))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>();
+    app.world_mut().resource_mut::<HiddenLayers>().toggle(Layer::Debug);
+    press(&mut app, KeyCode::F12);
+    press(&mut app, KeyCode::F12);
+    assert!(app.world().resource::<HiddenLayers>().0.contains(&Layer::Debug));
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::{MotionPhase, SimClock, SimClockPlugin},
+    tempo::Tempo,
+};
+
+#[test]
+fn seeking_while_paused_scrubs_the_phase() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(Tempo { bpm: 60.0, beats_per_cell: 2.0 });
+    for _ in 0..3 {
+        app.update();
+    }
+    app.world_mut().resource_mut::<SimClock>().paused = true;
+    app.update();
+    assert_eq!(app.world().resource::<MotionPhase>().rate(), 0.25);
+
+    // to three quarters of the next cell
+    let target = {
+        let world = app.world();
+        world.resource::<MotionPhase>().elapsed_at(world.resource::<SimClock>(), world.resource::<Tempo>(), 1.75)
+    };
+    assert_eq!(target, 3.5);
+    app.world_mut().resource_mut::<SimClock>().seek(target);
+    app.update();
+    assert_eq!(app.world().resource::<MotionPhase>().cells(), 1.75);
+    assert_eq!(app.world().resource::<SimClock>().elapsed_secs_f64(), 3.5);
+
+    // and back, but never before the start
+    app.world_mut().resource_mut::<SimClock>().seek(-1.0);
+    app.update();
+    assert_eq!(app.world().resource::<MotionPhase>().cells(), 0.0);
+    assert_eq!(app.world().resource::<SimClock>().elapsed_secs_f64(), 0.0);
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    color_mode::{apply_color_mode, ColorMode},
+    materials::SharedMaterials,
+    spawn_cells, Cell, CellTable, CellsParam, MoveType,
+};
+
+#[test]
+fn hue_follows_the_angle_of_travel() {
+    assert_eq!(ColorMode::hue(MoveType::Right), Some(0.0));
+    assert_eq!(ColorMode::hue(MoveType::Up), Some(90.0));
+    assert_eq!(ColorMode::hue(MoveType::Left), Some(180.0));
+    assert_eq!(ColorMode::hue(MoveType::Down), Some(270.0));
+    // in from the right, out at the bottom: down and to the left
+    assert_eq!(ColorMode::hue(MoveType::RightToBottom), Some(225.0));
+    assert_eq!(ColorMode::hue(MoveType::Center), None);
+}
+
+/// Hue and saturation of every circle, by cell.
+fn colors(app: &mut App) -> Vec<(MoveType, f32, f32)> {
+    let world = app.world_mut();
+    let mut circles = world.query::<(&Cell, &MeshMaterial2d<ColorMaterial>)>();
+    let materials = world.resource::<Assets<ColorMaterial>>();
+    let mut colors = circles
+        .iter(world)
+        .map(|(cell, material)| {
+            let color = Hsla::from(materials.get(&material.0).unwrap().color);
+            (cell.move_type, color.hue.round(), color.saturation)
+        })
+        .collect::<Vec<_>>();
+    colors.sort_by_key(|(move_type, ..)| format!("{:?}", move_type));
+    colors
+}
+
+#[test]
+fn circles_recolor_when_they_turn_and_when_the_mode_changes() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<SharedMaterials>()
+        .insert_resource(CellsParam {
+            color_mode: ColorMode::Direction { saturation: 0.8, lightness: 0.6 },
+            ..CellsParam::new(CellTable::new("→0"))
+        })
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, apply_color_mode);
+    app.update();
+    assert_eq!(colors(&mut app), [(MoveType::Center, 0.0, 0.0), (MoveType::Right, 0.0, 0.8)]);
+
+    let world = app.world_mut();
+    for mut cell in world.query::<&mut Cell>().iter_mut(world) {
+        cell.move_type = cell.move_type.reversed();
+    }
+    app.update();
+    assert_eq!(colors(&mut app), [(MoveType::Center, 0.0, 0.0), (MoveType::Left, 180.0, 0.8)]);
+
+    app.world_mut().resource_mut::<CellsParam>().color_mode = ColorMode::Palette;
+    app.update();
+    assert_eq!(colors(&mut app), [(MoveType::Center, 0.0, 0.0), (MoveType::Left, 0.0, 0.0)]);
+}
@@ -0,0 +1,61 @@
+use bevy::{prelude::*, render::camera::RenderTarget, window::WindowRef};
+
+use moving_cells_01::control_window::{ControlOutput, ControlWindow, ControlWindowPlugin};
+
+fn control_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, ControlWindowPlugin));
+    app
+}
+
+fn windows(app: &mut App) -> Vec<Entity> {
+    let world = app.world_mut();
+    world.query_filtered::<Entity, (With<Window>, With<ControlOutput>)>().iter(world).collect()
+}
+
+/// The windows the control cameras draw to.
+fn ui_cameras(app: &mut App) -> Vec<Entity> {
+    let world = app.world_mut();
+    world
+        .query_filtered::<&Camera, (With<IsDefaultUiCamera>, With<ControlOutput>)>()
+        .iter(world)
+        .filter_map(|camera| match camera.target {
+            RenderTarget::Window(WindowRef::Entity(window)) => Some(window),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn the_control_window_opens_and_closes_with_the_resource() {
+    let mut app = control_app();
+    app.update();
+    assert!(windows(&mut app).is_empty());
+
+    app.insert_resource(ControlWindow::default());
+    app.update();
+    let window = windows(&mut app);
+    assert_eq!(window.len(), 1);
+    assert_eq!(ui_cameras(&mut app), window);
+    app.update();
+    assert_eq!(windows(&mut app).len(), 1);
+
+    app.world_mut().remove_resource::<ControlWindow>();
+    app.update();
+    assert!(windows(&mut app).is_empty());
+    assert!(ui_cameras(&mut app).is_empty());
+}
+
+#[test]
+fn closing_the_control_window_turns_it_off() {
+    let mut app = control_app();
+    app.insert_resource(ControlWindow::default());
+    app.update();
+    let window = windows(&mut app)[0];
+    app.world_mut().despawn(window);
+    app.update();
+    assert!(!app.world().contains_resource::<ControlWindow>());
+    assert!(ui_cameras(&mut app).is_empty());
+    app.update();
+    assert!(windows(&mut app).is_empty());
+}
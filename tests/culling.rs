@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    clock::SimClockPlugin,
+    culling::{view_rect, Culling, CullingPlugin, Offscreen},
+    direction::FlowDirection,
+    move_cells, spawn_cells,
+    Cell, CellTable, CellsParam,
+};
+
+fn culled_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin, CullingPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(CellsParam::new(CellTable::new(&"→".repeat(40))))
+        .insert_resource(Culling { margin: 10.0 })
+        .init_resource::<FlowDirection>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+                commands.spawn((
+                    Camera2d,
+                    Projection::Orthographic(OrthographicProjection {
+                        area: Rect::new(-100.0, -100.0, 100.0, 100.0),
+                        ..OrthographicProjection::default_2d()
+                    }),
+                    OutputName("main".into()),
+                ));
+            },
+        )
+        .add_systems(Update, move_cells);
+    app.update();
+    app
+}
+
+/// Cell centers of the circles, and whether they are culled.
+fn circles(app: &mut App) -> Vec<(Vec2, Vec2, bool)> {
+    let world = app.world_mut();
+    world
+        .query::<(&Cell, &Transform, Has<Offscreen>)>()
+        .iter(world)
+        .map(|(cell, transform, offscreen)| (cell.pos, transform.translation.truncate(), offscreen))
+        .collect()
+}
+
+#[test]
+fn cells_out_of_view_stop_moving() {
+    let mut app = culled_app();
+    app.update();
+    let before = circles(&mut app);
+    let culled = before.iter().filter(|(.., offscreen)| *offscreen).count();
+    assert!(culled > 0 && culled < before.len(), "{} of {}", culled, before.len());
+    let view = Rect::new(-110.0, -110.0, 110.0, 110.0);
+    for (pos, _, offscreen) in &before {
+        let size = app.world().resource::<CellsParam>().cell_size;
+        assert_eq!(*offscreen, view.intersect(Rect::from_center_size(*pos, size)).is_empty());
+    }
+
+    app.update();
+    for ((pos, from, offscreen), (_, to, _)) in before.iter().zip(circles(&mut app)) {
+        assert_eq!(*offscreen, *from == to, "{}", pos);
+    }
+
+    // without culling, everything moves again
+    app.world_mut().remove_resource::<Culling>();
+    app.update();
+    assert!(circles(&mut app).iter().all(|(.., offscreen)| !offscreen));
+}
+
+#[test]
+fn view_rect_follows_the_camera_and_grows_by_the_margin() {
+    let projection = Projection::Orthographic(OrthographicProjection {
+        area: Rect::new(-50.0, -20.0, 50.0, 20.0),
+        ..OrthographicProjection::default_2d()
+    });
+    let transform = Transform::from_xyz(100.0, 10.0, 0.0);
+    assert_eq!(view_rect(&transform, &projection, 5.0), Some(Rect::new(45.0, -15.0, 155.0, 35.0)));
+    assert_eq!(view_rect(&transform, &Projection::Perspective(default()), 5.0), None);
+}
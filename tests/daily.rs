@@ -0,0 +1,34 @@
+use moving_cells_01::daily::{daily_layout, Date};
+
+#[test]
+fn dates_round_trip_through_days() {
+    let date = Date::parse("2026-10-15").unwrap();
+    assert_eq!(date.days(), 20741);
+    assert_eq!(Date::from_days(20741), date);
+    assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+    assert_eq!(Date::parse("2024-02-29").map(|d| d.to_string()), Some("2024-02-29".into()));
+    assert_eq!(Date::parse("2025-02-29"), None);
+    assert_eq!(Date::parse("tomorrow"), None);
+}
+
+/// Pinned: every instance has to agree on the layout of a day.
+#[test]
+fn layout_of_a_day_never_changes() {
+    let layout = daily_layout(Date::parse("2026-10-16").unwrap());
+    assert_eq!(
+        layout.to_text(),
+        "\
+┏←←←┓
+↓┌→┐↑
+↓↑0↓↑
+↓└←┘↑
+┗→→→┛",
+    );
+}
+
+#[test]
+fn neighbouring_days_differ() {
+    let day = |d| daily_layout(Date::parse(d).unwrap()).to_text();
+    assert_ne!(day("2026-10-15"), day("2026-10-16"));
+    assert_ne!(day("2026-10-16"), day("2026-10-17"));
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    agent::{self, Agent, CellBoundaryCrossed, LoopCompleted},
+    clock::{self, MotionPhase, SimClock, SimClockPlugin, Timestep},
+    CellTable, CellsParam,
+};
+
+/// (grid id, progress, translation) per agent.
+type AgentState = (u32, f32, [f32; 3]);
+
+/// Agent state after one simulated second, ticking at 50 Hz, with frames
+/// `frame_ms` apart.
+fn run(frame_ms: u64) -> (f64, f64, Vec<AgentState>) {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(Timestep::Fixed { hz: 50.0 })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(frame_ms)))
+        .insert_resource(CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘")))
+        .add_event::<CellBoundaryCrossed>()
+        .add_event::<LoopCompleted>()
+        .add_systems(FixedUpdate, agent::move_agents.run_if(clock::fixed_timestep));
+
+    for grid in [UVec2::new(0, 0), UVec2::new(2, 1)] {
+        app.world_mut().spawn((Agent::new(grid, Color::WHITE, 10.0), Transform::default()));
+    }
+
+    // the first frame has no delta
+    for _ in 0..=1000 / frame_ms {
+        app.update();
+    }
+
+    let world = app.world_mut();
+    let elapsed = world.resource::<SimClock>().elapsed_secs_f64();
+    let cells = world.resource::<MotionPhase>().cells();
+    let mut agents = world.query::<(&Agent, &Transform)>();
+    let mut state = agents
+        .iter(world)
+        .map(|(agent, transform)| (agent.grid.x * 10 + agent.grid.y, agent.progress, transform.translation.to_array()))
+        .collect::<Vec<_>>();
+    state.sort_by_key(|(id, _, _)| *id);
+    (elapsed, cells, state)
+}
+
+#[test]
+fn fixed_timestep_ignores_frame_pacing() {
+    let reference = run(10);
+    // fifty ticks of 1/50 s in f32
+    assert!((reference.0 - 1.0).abs() < 1e-6);
+    for frame_ms in [4, 20, 25, 125] {
+        // bitwise, not approximately
+        assert_eq!(run(frame_ms), reference, "{} ms frames", frame_ms);
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::{diagnostic::DiagnosticsStore, prelude::*};
+
+use moving_cells_01::{
+    culling::Offscreen,
+    diagnostics::{hud_lines, DiagnosticsHud, DiagnosticsHudPlugin, CELLS, CELLS_ANIMATED, MATERIALS},
+    input::InputMap,
+    spawn_cells, Cell, CellTable, CellsParam,
+};
+
+fn hud_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, DiagnosticsHudPlugin))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(CellsParam::new(CellTable::new("→→→→")))
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        );
+    app.update();
+    app
+}
+
+fn latest(app: &App, path: &bevy::diagnostic::DiagnosticPath) -> Option<f64> {
+    app.world().resource::<DiagnosticsStore>().get(path).and_then(|d| d.value())
+}
+
+#[test]
+fn counters_are_measured_only_while_the_hud_is_shown() {
+    let mut app = hud_app();
+    app.update();
+    assert_eq!(latest(&app, &CELLS), None);
+
+    app.insert_resource(DiagnosticsHud);
+    let culled = {
+        let world = app.world_mut();
+        world.query_filtered::<Entity, With<Cell>>().iter(world).next().unwrap()
+    };
+    app.world_mut().entity_mut(culled).insert(Offscreen);
+    app.update();
+    app.update();
+
+    let circles = app.world_mut().query::<&Cell>().iter(app.world()).count() as f64;
+    assert!(circles > 1.0);
+    assert_eq!(latest(&app, &CELLS), Some(circles));
+    assert_eq!(latest(&app, &CELLS_ANIMATED), Some(circles - 1.0));
+    assert!(latest(&app, &MATERIALS).is_some_and(|materials| materials >= 1.0));
+
+    let lines = hud_lines(app.world().resource::<DiagnosticsStore>());
+    assert!(lines.contains("circles animated"));
+    assert!(lines.contains("draw calls"));
+}
+
+#[test]
+fn f9_toggles_the_hud() {
+    let mut app = hud_app();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::F9);
+    app.update();
+    assert!(app.world().contains_resource::<DiagnosticsHud>());
+
+    let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keys.release(KeyCode::F9);
+    keys.clear();
+    keys.press(KeyCode::F9);
+    app.update();
+    assert!(!app.world().contains_resource::<DiagnosticsHud>());
+}
@@ -0,0 +1,80 @@
+use moving_cells_01::{
+    dialect::{Dialect, DialectReport, LayoutSource},
+    CellTable, LayoutError,
+};
+
+const RING: &str = "┌→┐\n↑0↓\n└←┘";
+
+#[test]
+fn ascii_writes_the_way_out_of_every_cell() {
+    let table = CellTable::new(RING);
+    let ascii = Dialect::Ascii.write(&table);
+    assert_eq!(ascii, ">>v\n^0v\n^<<");
+    assert_eq!(Dialect::Ascii.parse(&ascii).unwrap().to_text(), RING);
+
+    // the counter-clockwise corners come back too
+    let ccw = CellTable::new("┏←┓\n┗→┛");
+    assert_eq!(Dialect::Ascii.parse(&Dialect::Ascii.write(&ccw)).unwrap().to_text(), ccw.to_text());
+
+    assert_eq!(Dialect::Ascii.parse(">x").err(), Some(LayoutError::InvalidChar { c: 'x', x: 1, y: 0 }));
+}
+
+#[test]
+fn binary_loops_run_clockwise() {
+    assert_eq!(Dialect::Binary.parse("111\n101\n111").unwrap().to_text(), "┌→┐\n↑ ↓\n└←┘");
+    assert_eq!(Dialect::Binary.write(&CellTable::new(RING)), "111\n101\n111");
+    assert_eq!(Dialect::Binary.parse("111\n100").err(), Some(LayoutError::OpenTrack { x: 2, y: 0 }));
+}
+
+#[test]
+fn switching_reparses_or_migrates() {
+    let table = CellTable::new(RING);
+    let source = LayoutSource { text: RING.into(), dialect: Dialect::Keisen };
+
+    // keisen is no ascii: the table is migrated instead
+    let report = DialectReport::new(&source, &table, Dialect::Ascii);
+    assert!(report.reparsed.is_err());
+    let text = report.to_text(&table);
+    assert!(text.starts_with("as keisen (current)"), "{}", text);
+    assert!(text.contains("| as ascii") && text.contains("| >>v"), "{}", text);
+    assert!(text.contains("9 cells, 8 track, 1 rings"), "{}", text);
+    let (source, migrated) = report.apply().unwrap();
+    assert_eq!(source, LayoutSource { text: ">>v\n^0v\n^<<".into(), dialect: Dialect::Ascii });
+    assert_eq!(migrated.to_text(), RING);
+
+    // binary reads digits ascii has no use for; centers are lost on the way
+    let report = DialectReport::new(&source, &migrated, Dialect::Binary);
+    let (source, table) = report.apply().unwrap();
+    assert_eq!(source.text, "111\n101\n111");
+    assert_eq!(table.to_text(), "┌→┐\n↑ ↓\n└←┘");
+
+    // a source both dialects read is kept as it is, and read anew
+    let binary = LayoutSource { text: "0".into(), dialect: Dialect::Binary };
+    let report = DialectReport::new(&binary, &CellTable::new("0"), Dialect::Keisen);
+    let (source, table) = report.apply().unwrap();
+    assert_eq!(source, LayoutSource { text: "0".into(), dialect: Dialect::Keisen });
+    assert_eq!(table.to_text(), "0");
+}
+
+#[test]
+fn touching_rings_cant_be_written_in_binary() {
+    // the default layout: its rings are side by side
+    let table = CellTable::new("\
+        ┌→→→→→→→→→┐
+        ↑ ┌→→→→→┐ ↓
+        ↑ ↑0   0↓ ↓
+        ↑ └←←←←←┘ ↓
+        ↑ ┏←┓ ┌→┐ ↓
+        ↑ ↓0↑ ↑0↓ ↓
+        ↑ ┗→┛ └←┘ ↓
+        ↑ ┏←←←←←┓ ↓
+        ↑ ↓0   0↑ ↓
+        ↑ ┗→→→→→┛ ↓
+        └←←←←←←←←←┘");
+    let source = LayoutSource { text: table.to_text(), dialect: Dialect::Keisen };
+    let report = DialectReport::new(&source, &table, Dialect::Binary);
+    assert!(report.remigrated.is_err());
+    let text = report.to_text(&table);
+    assert!(text.contains("can't migrate"), "{}", text);
+    assert!(matches!(report.apply(), Err(LayoutError::OpenTrack { .. })));
+}
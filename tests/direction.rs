@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::{SimClock, SimClockPlugin},
+    create_cell,
+    direction::{flip_rings, toggle_direction, FlipRing, FlowDirection},
+    easing::Easing,
+    input::InputMap,
+    move_cells,
+    ring::{find_rings, Rings},
+    Cell, CellTable, CellsParam,
+};
+
+#[test]
+fn flipping_a_ring_keeps_its_circles_in_place_and_leaves_the_others_alone() {
+    let cells_param = CellsParam::new(CellTable::new("→↓┏┓\n↑←┗┛"));
+    let rings = Rings::new(find_rings(&cells_param));
+    assert_eq!(rings.0.len(), 2);
+    let flipped = rings.0[0].cells.clone();
+
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .init_resource::<FlowDirection>()
+        .add_event::<FlipRing>()
+        .add_systems(Update, (flip_rings, move_cells).chain());
+    for iy in 0..2 {
+        for ix in 0..4 {
+            let grid = UVec2::new(ix as u32, iy as u32);
+            let c = cells_param.cell_table.get(ix, iy);
+            let cell = create_cell(c, grid, cells_param.cell_center(ix, iy), cells_param.cell_extent(ix, iy));
+            app.world_mut().spawn((cell, Transform::default()));
+        }
+    }
+    app.insert_resource(cells_param).insert_resource(rings);
+
+    let positions = |app: &mut App| {
+        let world = app.world_mut();
+        let mut query = world.query::<(&Cell, &Transform)>();
+        let mut positions = query.iter(world).map(|(cell, t)| (cell.grid, t.translation.truncate())).collect::<Vec<_>>();
+        positions.sort_by_key(|(grid, _)| (grid.y, grid.x));
+        positions
+    };
+
+    for _ in 0..4 {
+        app.update();
+    }
+    app.world_mut().resource_mut::<SimClock>().paused = true;
+    app.update();
+    let before = positions(&mut app);
+
+    app.world_mut().send_event(FlipRing(0));
+    app.update();
+    for ((grid, a), (_, b)) in before.iter().zip(positions(&mut app)) {
+        assert!(a.distance(b) < 1e-3, "{:?} jumped from {} to {}", grid, a, b);
+    }
+    let table = app.world().resource::<CellsParam>().cell_table.to_text();
+    let expected = if flipped.contains(&UVec2::ZERO) { "←↑┏┓\n↓→┗┛" } else { "→↓┌┐\n↑←└┘" };
+    assert_eq!(table, expected);
+
+    // the cells follow the table
+    let world = app.world_mut();
+    let mut query = world.query::<&Cell>();
+    let cells_param = world.resource::<CellsParam>();
+    assert!(query.iter(world).all(|cell| cell.move_type == cells_param.move_type_at(cell.grid)));
+}
+
+#[test]
+fn no_easing_jumps_at_a_flip() {
+    for easing in Easing::ALL {
+        let mut cells_param = CellsParam::new(CellTable::new("→↓┏┓\n↑←┗┛"));
+        cells_param.easing = easing;
+        let rings = Rings::new(find_rings(&cells_param));
+
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, SimClockPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(130)))
+            .init_resource::<FlowDirection>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<InputMap>()
+            .add_event::<FlipRing>()
+            .add_systems(Update, ((toggle_direction, flip_rings), move_cells).chain());
+        for iy in 0..2 {
+            for ix in 0..4 {
+                let grid = UVec2::new(ix as u32, iy as u32);
+                let c = cells_param.cell_table.get(ix, iy);
+                let cell = create_cell(c, grid, cells_param.cell_center(ix, iy), cells_param.cell_extent(ix, iy));
+                app.world_mut().spawn((cell, Transform::default()));
+            }
+        }
+        app.insert_resource(cells_param).insert_resource(rings);
+
+        let positions = |app: &mut App| {
+            let world = app.world_mut();
+            let mut query = world.query::<(&Cell, &Transform)>();
+            let mut positions = query.iter(world).map(|(cell, t)| (cell.grid, t.translation)).collect::<Vec<_>>();
+            positions.sort_by_key(|(grid, _)| (grid.y, grid.x));
+            positions
+        };
+        let assert_still = |app: &mut App, before: &[(UVec2, Vec3)], what: &str| {
+            for ((grid, a), (_, b)) in before.iter().zip(positions(app)) {
+                assert!(a.distance(b) < 1e-3, "{:?}: {:?} jumped from {} to {} at {}", easing, grid, a, b, what);
+            }
+        };
+
+        for _ in 0..3 {
+            app.update();
+        }
+        app.world_mut().resource_mut::<SimClock>().paused = true;
+        app.update();
+        let before = positions(&mut app);
+
+        // one ring, then everything (the flipped ring back, the other one away)
+        app.world_mut().send_event(FlipRing(0));
+        app.update();
+        assert_still(&mut app, &before, "the ring flip");
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyR);
+        app.update();
+        assert_still(&mut app, &before, "the reversal");
+    }
+}
+
+#[test]
+fn thick_cells_keep_their_place_at_a_ring_flip() {
+    let cells_param = CellsParam::new(CellTable::new("# thickness: speed\n┏→┐\n└←┛"));
+    assert_eq!(cells_param.cell_table.speed(0, 0), 2.0);
+    let rings = Rings::new(find_rings(&cells_param));
+
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(130)))
+        .init_resource::<FlowDirection>()
+        .add_event::<FlipRing>()
+        .add_systems(Update, (flip_rings, move_cells).chain());
+    for iy in 0..2 {
+        for ix in 0..3 {
+            let grid = UVec2::new(ix as u32, iy as u32);
+            let c = cells_param.cell_table.get(ix, iy);
+            let cell = create_cell(c, grid, cells_param.cell_center(ix, iy), cells_param.cell_extent(ix, iy));
+            app.world_mut().spawn((cell, Transform::default()));
+        }
+    }
+    app.insert_resource(cells_param).insert_resource(rings);
+
+    let positions = |app: &mut App| {
+        let world = app.world_mut();
+        let mut query = world.query::<(&Cell, &Transform)>();
+        let mut positions = query.iter(world).map(|(cell, t)| (cell.grid, t.translation)).collect::<Vec<_>>();
+        positions.sort_by_key(|(grid, _)| (grid.y, grid.x));
+        positions
+    };
+
+    for _ in 0..3 {
+        app.update();
+    }
+    app.world_mut().resource_mut::<SimClock>().paused = true;
+    app.update();
+    let before = positions(&mut app);
+
+    app.world_mut().send_event(FlipRing(0));
+    app.update();
+    for ((grid, a), (_, b)) in before.iter().zip(positions(&mut app)) {
+        assert!(a.distance(b) < 1e-3, "{:?} jumped from {} to {}", grid, a, b);
+    }
+}
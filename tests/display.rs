@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use bevy::{
+    input::mouse::AccumulatedMouseMotion,
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, WindowLevel, WindowMode, WindowPosition},
+};
+
+use moving_cells_01::{
+    display::{
+        apply_kiosk, apply_present_mode, hide_idle_cursor, kiosk_keys, toggle_fullscreen, FramePacing, Kiosk,
+        WindowConfig,
+    },
+    input::{Action, InputMap},
+};
+
+#[test]
+fn the_config_opens_the_window_where_asked() {
+    let window = WindowConfig::default().window();
+    assert_eq!(window.mode, WindowMode::Windowed);
+    assert_eq!(window.position, WindowPosition::Centered(MonitorSelection::Primary));
+    assert_eq!(window.resolution.size(), Window::default().resolution.size());
+
+    let window = WindowConfig {
+        title: "wall".into(),
+        size: Some(UVec2::new(1920, 1080)),
+        monitor: Some(1),
+        fullscreen: true,
+        overlay: false,
+    }
+    .window();
+    assert_eq!(window.title, "wall");
+    assert_eq!(window.resolution.size(), Vec2::new(1920.0, 1080.0));
+    assert_eq!(window.position, WindowPosition::Centered(MonitorSelection::Index(1)));
+    assert_eq!(window.mode, WindowMode::BorderlessFullscreen(MonitorSelection::Index(1)));
+    assert!(!window.transparent);
+}
+
+#[test]
+fn overlays_are_transparent_on_top_and_click_through() {
+    let window = WindowConfig { overlay: true, ..default() }.window();
+    assert!(window.transparent);
+    assert!(!window.decorations);
+    assert_eq!(window.window_level, WindowLevel::AlwaysOnTop);
+    assert!(!window.cursor_options.hit_test);
+    assert_eq!(window.mode, WindowMode::Windowed);
+}
+
+fn window_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>()
+        .add_systems(Update, toggle_fullscreen);
+    app.world_mut().spawn((Window::default(), PrimaryWindow));
+    app
+}
+
+fn mode(app: &mut App) -> WindowMode {
+    let world = app.world_mut();
+    world.query_filtered::<&Window, With<PrimaryWindow>>().single(world).unwrap().mode
+}
+
+fn press(app: &mut App, keys: &[KeyCode]) {
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.reset_all();
+    for key in keys {
+        input.press(*key);
+    }
+    app.update();
+}
+
+#[test]
+fn f11_and_alt_enter_toggle_fullscreen() {
+    let mut app = window_app();
+    let key = app.world().resource::<InputMap>().key(Action::ToggleFullscreen).unwrap();
+    assert_eq!(key, KeyCode::F11);
+    press(&mut app, &[key]);
+    assert_eq!(mode(&mut app), WindowMode::BorderlessFullscreen(MonitorSelection::Current));
+    press(&mut app, &[KeyCode::AltLeft, KeyCode::Enter]);
+    assert_eq!(mode(&mut app), WindowMode::Windowed);
+
+    // Enter alone is not enough
+    press(&mut app, &[KeyCode::Enter]);
+    assert_eq!(mode(&mut app), WindowMode::Windowed);
+}
+
+fn kiosk_app() -> App {
+    let mut app = window_app();
+    app.init_resource::<AccumulatedMouseMotion>()
+        .insert_resource(Kiosk { hide_cursor_after: 0.5 })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(200)))
+        .add_systems(PreUpdate, kiosk_keys)
+        .add_systems(Update, (apply_kiosk, hide_idle_cursor));
+    app
+}
+
+fn window(app: &mut App) -> Window {
+    let world = app.world_mut();
+    world.query_filtered::<&Window, With<PrimaryWindow>>().single(world).unwrap().clone()
+}
+
+#[test]
+fn kiosk_windows_are_borderless_on_top_and_hide_an_idle_cursor() {
+    let mut app = kiosk_app();
+    app.update();
+    let shown = window(&mut app);
+    assert!(!shown.decorations);
+    assert_eq!(shown.window_level, WindowLevel::AlwaysOnTop);
+    assert!(shown.cursor_options.visible);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert!(!window(&mut app).cursor_options.visible);
+
+    app.world_mut().resource_mut::<AccumulatedMouseMotion>().delta = Vec2::new(3.0, 0.0);
+    app.update();
+    assert!(window(&mut app).cursor_options.visible);
+
+    app.world_mut().remove_resource::<Kiosk>();
+    app.update();
+    let window = window(&mut app);
+    assert!(window.decorations);
+    assert_eq!(window.window_level, WindowLevel::Normal);
+}
+
+#[test]
+fn kiosks_ignore_keys_but_the_quit_combo() {
+    let mut app = kiosk_app();
+    press(&mut app, &[KeyCode::F11]);
+    assert_eq!(mode(&mut app), WindowMode::Windowed);
+    press(&mut app, &[KeyCode::KeyQ]);
+    assert!(app.should_exit().is_none());
+
+    let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keys.reset_all();
+    keys.press(KeyCode::ControlLeft);
+    keys.press(KeyCode::ShiftRight);
+    app.update();
+    // still held, the next frame
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyQ);
+    app.update();
+    assert_eq!(app.should_exit(), Some(AppExit::Success));
+}
+
+#[test]
+fn frame_pacing_sets_the_present_mode_and_caps_the_rate() {
+    assert_eq!(FramePacing::parse_present_mode("mailbox"), Some(PresentMode::Mailbox));
+    assert_eq!(FramePacing::parse_present_mode("immediate"), Some(PresentMode::Immediate));
+    assert_eq!(FramePacing::parse_present_mode("fast"), None);
+
+    let mut app = window_app();
+    app.insert_resource(FramePacing { present_mode: PresentMode::Immediate, fps_cap: None })
+        .add_systems(Update, apply_present_mode);
+    app.update();
+    assert_eq!(window(&mut app).present_mode, PresentMode::Immediate);
+    app.world_mut().resource_mut::<FramePacing>().present_mode = PresentMode::AutoVsync;
+    app.update();
+    assert_eq!(window(&mut app).present_mode, PresentMode::AutoVsync);
+
+    let uncapped = FramePacing::default();
+    assert_eq!(uncapped.wait(Duration::from_millis(5)), Duration::ZERO);
+    let capped = FramePacing { fps_cap: Some(20.0), ..default() };
+    assert_eq!(capped.wait(Duration::from_millis(20)), Duration::from_millis(30));
+    assert_eq!(capped.wait(Duration::from_millis(80)), Duration::ZERO);
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    exposure::{expose_loop, loop_sec, LongExposure},
+    tempo::Tempo,
+    CellTable, CellsParam,
+};
+
+fn pixel(image: &Image, x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * image.width() + x) * 4) as usize;
+    image.data.as_ref().unwrap()[i..i + 4].try_into().unwrap()
+}
+
+#[test]
+fn a_disc_covers_its_area() {
+    let mut exposure = LongExposure::new(64, 64, 2.0);
+    exposure.add_circle(Vec2::new(4.0, -4.0), 10.0, LinearRgba::WHITE);
+    exposure.end_sample();
+    let image = exposure.to_image(100.0);
+
+    // scaled 2x, y down
+    assert_eq!(pixel(&image, 40, 40), [255; 4]);
+    assert_eq!(pixel(&image, 40, 19)[0], 0);
+    assert_eq!(pixel(&image, 0, 0)[0], 0);
+    let lit = (0..64 * 64).filter(|i| pixel(&image, i % 64, i / 64)[0] > 128).count() as f32;
+    let area = std::f32::consts::PI * 20.0 * 20.0;
+    assert!((lit - area).abs() / area < 0.05, "{} vs {}", lit, area);
+}
+
+#[test]
+fn a_loop_paints_the_track_and_leaves_the_margin_dark() {
+    let cells_param = CellsParam::new(CellTable::new("→↓\n↑←"));
+    let tempo = Tempo::default();
+    assert_eq!(loop_sec(&cells_param, &tempo), 4.0);
+
+    let mut exposure = LongExposure::fit(&cells_param, 1.0);
+    // two cells of 50 plus a cell of margin on each side
+    assert_eq!((exposure.width, exposure.height), (200, 200));
+    expose_loop(cells_param, tempo, 200, &mut exposure);
+    assert_eq!(exposure.samples(), 200);
+    let image = exposure.to_image(8.0);
+
+    // circles travel between the cell centers, 25 from the middle
+    assert!(pixel(&image, 75, 100)[0] > 200);
+    assert!(pixel(&image, 100, 75)[0] > 200);
+    assert_eq!(pixel(&image, 100, 100)[0], 0);
+    assert_eq!(pixel(&image, 10, 10)[0], 0);
+}
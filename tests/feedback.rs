@@ -0,0 +1,38 @@
+use bevy::{prelude::*, render::camera::ClearColorConfig};
+
+use moving_cells_01::feedback::{Feedback, FeedbackPlugin, FeedbackVeil};
+
+#[test]
+fn the_veil_fades_by_the_decay_per_second() {
+    let feedback = Feedback { decay_per_sec: 0.75 };
+    assert_eq!(feedback.veil_alpha(1.0), 0.75);
+    assert_eq!(feedback.veil_alpha(0.5), 0.5);
+    assert_eq!(feedback.veil_alpha(0.0), 0.0);
+    assert_eq!(Feedback { decay_per_sec: 0.0 }.veil_alpha(1.0), 0.0);
+}
+
+#[test]
+fn cameras_stop_clearing_while_feedback_is_on() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(Feedback::default())
+        .add_plugins(FeedbackPlugin);
+    let camera = app.world_mut().spawn(Camera2d).id();
+    app.update();
+
+    let clear_color = |app: &App| app.world().get::<Camera>(camera).unwrap().clear_color;
+    let veils = |app: &mut App| app.world_mut().query::<&FeedbackVeil>().iter(app.world()).count();
+    assert!(matches!(clear_color(&app), ClearColorConfig::None));
+    assert_eq!(app.world().get::<Msaa>(camera), Some(&Msaa::Off));
+    assert_eq!(veils(&mut app), 1);
+    app.update();
+    assert_eq!(veils(&mut app), 1);
+
+    app.world_mut().remove_resource::<Feedback>();
+    app.update();
+    assert!(matches!(clear_color(&app), ClearColorConfig::Default));
+    assert_eq!(veils(&mut app), 0);
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{ClearColorConfig, RenderTarget},
+        view::RenderLayers,
+    },
+    time::TimeUpdateStrategy,
+    window::PrimaryWindow,
+};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    feedback_buffer::{BufferCamera, BufferSprite, FeedbackBuffer, FeedbackBufferPlugin},
+};
+
+#[test]
+fn circles_accumulate_in_a_texture_under_the_live_view() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, FeedbackBufferPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .init_resource::<Assets<Image>>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(FeedbackBuffer::default());
+    let window = app.world_mut().spawn((Window::default(), PrimaryWindow)).id();
+    let main = app.world_mut().spawn((Camera2d, OutputName("main".into()))).id();
+    let preview = app
+        .world_mut()
+        .spawn((Camera2d, OutputName("preview".into()), RenderLayers::layer(1)))
+        .id();
+    app.update();
+    app.update();
+
+    let world = app.world_mut();
+    let camera = world.query_filtered::<&Camera, With<BufferCamera>>().single(world).unwrap().clone();
+    assert!(matches!(camera.clear_color, ClearColorConfig::None));
+    let RenderTarget::Image(target) = camera.target else {
+        panic!("the buffer camera draws to the window");
+    };
+    let sprite = world.query_filtered::<&Sprite, With<BufferSprite>>().single(world).unwrap().clone();
+    assert_eq!(sprite.image, target.handle);
+    assert_eq!(sprite.custom_size, Some(Vec2::new(1280.0, 720.0)));
+    assert_eq!(world.resource::<Assets<Image>>().get(&sprite.image).unwrap().size(), UVec2::new(1280, 720));
+    // the main view sees the buffer, the buffer doesn't see itself
+    assert!(world.get::<RenderLayers>(main).is_some_and(|layers| layers.intersects(&RenderLayers::layer(0))));
+    let veil_layers = camera_layers(world);
+    assert!(!world.get::<RenderLayers>(main).unwrap().intersects(&veil_layers));
+
+    world.get_mut::<Window>(window).unwrap().resolution.set(640.0, 480.0);
+    app.update();
+    let world = app.world_mut();
+    assert_eq!(world.resource::<Assets<Image>>().get(&sprite.image).unwrap().size(), UVec2::new(640, 480));
+
+    world.remove_resource::<FeedbackBuffer>();
+    app.update();
+    let world = app.world_mut();
+    assert!(world.query_filtered::<(), With<BufferCamera>>().iter(world).next().is_none());
+    assert!(world.query_filtered::<(), With<BufferSprite>>().iter(world).next().is_none());
+    assert!(world.get::<RenderLayers>(main).is_none());
+    assert_eq!(world.get::<RenderLayers>(preview), Some(&RenderLayers::layer(1)));
+}
+
+/// Layers only the buffer camera sees.
+fn camera_layers(world: &mut World) -> RenderLayers {
+    let layers = world.query_filtered::<&RenderLayers, With<BufferCamera>>().single(world).unwrap().clone();
+    layers.without(0)
+}
@@ -0,0 +1,71 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use moving_cells_01::flow_field::{table_from_flow_field, FlowFieldError};
+
+/// One pixel per direction (y up), `None` for no flow.
+fn field(rows: &[&[Option<Vec2>]]) -> Image {
+    let data = rows
+        .iter()
+        .flat_map(|row| row.iter())
+        .flat_map(|dir| match dir {
+            Some(d) => {
+                let [r, g] = ((*d + Vec2::ONE) / 2.0 * 255.0).to_array();
+                [r as u8, g as u8, 0, 255]
+            }
+            None => [128, 128, 128, 255],
+        })
+        .collect();
+    Image::new(
+        Extent3d { width: rows[0].len() as u32, height: rows.len() as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+const R: Option<Vec2> = Some(Vec2::X);
+const L: Option<Vec2> = Some(Vec2::NEG_X);
+const U: Option<Vec2> = Some(Vec2::Y);
+const D: Option<Vec2> = Some(Vec2::NEG_Y);
+
+#[test]
+fn turning_flow_becomes_corners() {
+    let image = field(&[
+        &[R, R, D],
+        &[U, None, D],
+        &[U, L, L],
+    ]);
+    let table = table_from_flow_field(&image, 3, 3).unwrap();
+    assert_eq!(table.to_text(), "┌→┐\n↑ ↓\n└←┘");
+}
+
+#[test]
+fn counter_clockwise_flow_uses_thick_corners() {
+    let image = field(&[
+        &[D, L],
+        &[R, U],
+    ]);
+    let table = table_from_flow_field(&image, 2, 2).unwrap();
+    assert_eq!(table.to_text(), "┏┓\n┗┛");
+}
+
+#[test]
+fn cells_average_their_pixels() {
+    // mostly right, with a stray pixel pointing up
+    let image = field(&[
+        &[R, R, L, L],
+        &[R, U, L, L],
+    ]);
+    let table = table_from_flow_field(&image, 2, 1).unwrap();
+    assert_eq!(table.to_text(), "→←");
+
+    assert!(matches!(
+        table_from_flow_field(&image, 5, 1),
+        Err(FlowFieldError::TooSmall { image: UVec2 { x: 4, y: 2 }, table: UVec2 { x: 5, y: 1 } }),
+    ));
+}
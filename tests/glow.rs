@@ -0,0 +1,37 @@
+use bevy::{core_pipeline::bloom::Bloom, prelude::*};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    glow::{apply_glow, Glow},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn the_main_camera_blooms_while_glowing() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .insert_resource(CellsParam {
+            glow: Some(Glow { intensity: 0.5, threshold: 0.8 }),
+            ..CellsParam::new(CellTable::new("0"))
+        })
+        .add_systems(Update, apply_glow);
+    let main = app.world_mut().spawn((Camera2d, OutputName("main".into()))).id();
+    let preview = app.world_mut().spawn((Camera2d, OutputName("preview".into()))).id();
+    app.update();
+
+    let bloom = |app: &App, camera: Entity| {
+        let entity = app.world().entity(camera);
+        (entity.get::<Camera>().unwrap().hdr, entity.get::<Bloom>().map(|b| (b.intensity, b.prefilter.threshold)))
+    };
+    assert_eq!(bloom(&app, main), (true, Some((0.5, 0.8))));
+    assert_eq!(bloom(&app, preview), (false, None));
+
+    app.world_mut().resource_mut::<CellsParam>().glow.as_mut().unwrap().intensity = 0.2;
+    app.update();
+    assert_eq!(bloom(&app, main), (true, Some((0.2, 0.8))));
+
+    app.world_mut().resource_mut::<CellsParam>().glow = None;
+    app.update();
+    assert_eq!(bloom(&app, main), (false, None));
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    direction::FlowDirection,
+    easing::Easing,
+    gpu_motion::{pack_cells, GpuCells, MotionUniform},
+    idle::Idle,
+    instanced::{build_instances, move_instances, CircleInstances},
+    palette::Palette,
+    ring::Rings,
+    CellTable, CellsParam, CornerStyle, Stepping,
+};
+
+fn packed_app(cells_param: CellsParam) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(cells_param)
+        .init_resource::<FlowDirection>()
+        .init_resource::<Rings>()
+        .init_resource::<Palette>()
+        .init_resource::<Assets<Mesh>>()
+        .add_systems(Update, (build_instances, move_instances, pack_cells).chain());
+    app.update();
+    app
+}
+
+#[test]
+fn cells_go_to_the_gpu_once_per_layout() {
+    let mut app = packed_app(CellsParam {
+        circles_per_cell: 3,
+        easing: Easing::CubicIn,
+        ..CellsParam::new(CellTable::new("→┐\n0↓"))
+    });
+
+    let world = app.world_mut();
+    let cells = world.query::<&GpuCells>().single(world).unwrap().0.clone();
+    assert_eq!(cells.len(), 3 + 3 + 3 + 1);
+    assert!(cells.iter().all(|cell| cell.easing == 4));
+    assert_eq!(cells.iter().filter(|cell| cell.kind == 2).count(), 3);
+    assert_eq!(cells.iter().filter(|cell| cell.kind == 0).count(), 1);
+    // the instances are no longer extracted or moved on the CPU
+    assert!(world.query::<&CircleInstances>().single(world).unwrap().instances.is_empty());
+
+    // and stay put while the layout does
+    let tick = world.change_tick();
+    app.update();
+    let world = app.world_mut();
+    let cells = world.query::<Ref<GpuCells>>().single(world).unwrap();
+    assert!(!cells.last_changed().is_newer_than(tick, world.change_tick()));
+}
+
+#[test]
+fn motion_uniform_mirrors_the_parameters() {
+    let cells_param = CellsParam {
+        stepping: Stepping::Hop { swing: 0.5 },
+        idle: Idle::Rotate { radius: 3.0 },
+        corner_style: CornerStyle::Arc,
+        ..CellsParam::new(CellTable::new("→"))
+    };
+    let motion = MotionUniform::new(&cells_param, &FlowDirection::default(), 0.25);
+    assert_eq!(
+        motion,
+        MotionUniform {
+            rate: 0.25,
+            flow_sign: 1.0,
+            flow_shift: 0.0,
+            hop: 0.25,
+            idle_radius: 3.0,
+            arc_corners: true,
+        }
+    );
+
+    let glide = MotionUniform::new(&CellsParam::new(CellTable::new("→")), &FlowDirection::default(), 0.0);
+    assert_eq!(glide.hop, 0.0);
+}
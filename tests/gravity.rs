@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{gravity::Gravity, CellTable, CellsParam};
+
+#[test]
+fn wells_sit_on_the_center_cells() {
+    let cells_param = CellsParam::new(CellTable::new("→0\n0↑"));
+    let wells = Gravity::wells(&cells_param, cells_param.cell_size);
+    assert_eq!(wells, vec![cells_param.cell_center(1, 0), cells_param.cell_center(0, 1)]);
+    let doubled = Gravity::wells(&cells_param, cells_param.cell_size * 2.0);
+    assert_eq!(doubled[0], wells[0] * 2.0);
+}
+
+#[test]
+fn the_pull_fades_with_distance_and_never_overshoots() {
+    let gravity = Gravity { strength: 8.0, reach_cells: 2.0 };
+    let cell_size = Vec2::splat(50.0);
+    let wells = [Vec2::ZERO];
+
+    let near = gravity.offset(Vec2::new(20.0, 0.0), &wells, cell_size);
+    let far = gravity.offset(Vec2::new(60.0, 0.0), &wells, cell_size);
+    assert!(near.x < far.x && far.x < 0.0, "{} {}", near, far);
+    assert_eq!(near.y, 0.0);
+
+    assert_eq!(gravity.offset(Vec2::new(100.0, 0.0), &wells, cell_size), Vec2::ZERO);
+    assert_eq!(gravity.offset(Vec2::ZERO, &wells, cell_size), Vec2::ZERO);
+    assert_eq!(gravity.offset(Vec2::new(0.0, 2.0), &wells, cell_size), Vec2::new(0.0, -2.0));
+}
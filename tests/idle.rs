@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    direction::FlowDirection,
+    idle::{animate_centers, Idle},
+    materials::SharedMaterials,
+    move_cells,
+    opacity::apply_opacity,
+    spawn_cells,
+    tempo::Tempo,
+    Cell, CellTable, CellsParam, StaticCell,
+};
+
+fn center_app(idle: Idle) -> App {
+    let mut cells_param = CellsParam::new(CellTable::new("0"));
+    cells_param.idle = idle;
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            250,
+        )))
+        .insert_resource(cells_param)
+        .insert_resource(Tempo {
+            bpm: 60.0,
+            beats_per_cell: 1.0,
+        })
+        .init_resource::<FlowDirection>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<SharedMaterials>()
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, (move_cells, animate_centers, apply_opacity).chain());
+    // the first frame has no delta
+    app.update();
+    app
+}
+
+/// Offset from the cell center, scale and alpha of the only circle.
+fn center(app: &mut App) -> (Vec2, f32, f32) {
+    let world = app.world_mut();
+    let (cell, transform, material) = world
+        .query::<(&Cell, &Transform, &MeshMaterial2d<ColorMaterial>)>()
+        .single(world)
+        .unwrap();
+    let (offset, scale, material) = (
+        transform.translation.truncate() - cell.pos,
+        transform.scale.x,
+        material.0.clone(),
+    );
+    let alpha = world
+        .resource::<Assets<ColorMaterial>>()
+        .get(&material)
+        .unwrap()
+        .color
+        .alpha();
+    (offset, scale, alpha)
+}
+
+#[test]
+fn pulse_and_breathe_peak_halfway_and_come_back() {
+    let mut app = center_app(Idle::Pulse { depth: 0.5 });
+    app.update();
+    app.update();
+    assert_eq!(center(&mut app), (Vec2::ZERO, 1.5, 1.0));
+    app.update();
+    app.update();
+    let (_, scale, _) = center(&mut app);
+    assert!((scale - 1.0).abs() < 1e-6, "{}", scale);
+
+    let mut app = center_app(Idle::Breathe { depth: 0.5 });
+    app.update();
+    app.update();
+    assert_eq!(center(&mut app), (Vec2::ZERO, 1.0, 0.5));
+}
+
+#[test]
+fn rotate_goes_round_the_center_once_per_cell() {
+    let mut app = center_app(Idle::Rotate { radius: 4.0 });
+    let mut positions = Vec::new();
+    for _ in 0..4 {
+        app.update();
+        positions.push(center(&mut app).0);
+    }
+    let expected = [Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y, Vec2::X].map(|v| v * 4.0);
+    for (position, expected) in positions.iter().zip(expected) {
+        assert!(
+            position.distance(expected) < 1e-4,
+            "{} vs {}",
+            position,
+            expected
+        );
+    }
+}
+
+#[test]
+fn still_centers_are_left_alone_until_something_moves_them() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(CellsParam::new(CellTable::new("0→")))
+        .init_resource::<FlowDirection>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, move_cells);
+    app.update();
+    app.update();
+
+    // which circles were touched by the next frame, static ones first
+    let update = |app: &mut App| {
+        let before = app.world_mut().change_tick();
+        app.update();
+        let world = app.world_mut();
+        let now = world.change_tick();
+        let mut touched = world
+            .query::<(Ref<Transform>, Has<StaticCell>)>()
+            .iter(world)
+            .map(|(transform, still)| (!still, transform.last_changed().is_newer_than(before, now)))
+            .collect::<Vec<_>>();
+        touched.sort();
+        touched
+    };
+    assert_eq!(update(&mut app), [(false, false), (true, true)]);
+
+    app.world_mut().resource_mut::<CellsParam>().idle = Idle::Rotate { radius: 4.0 };
+    assert_eq!(update(&mut app), [(false, true), (true, true)]);
+}
+
+#[test]
+fn centers_go_back_when_the_idle_stops() {
+    let mut app = center_app(Idle::Rotate { radius: 4.0 });
+    app.update();
+    assert!(center(&mut app).0.length() > 3.9);
+    app.world_mut().resource_mut::<CellsParam>().idle = Idle::Still;
+    app.update();
+    assert_eq!(center(&mut app).0, Vec2::ZERO);
+}
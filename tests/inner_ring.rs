@@ -0,0 +1,52 @@
+use moving_cells_01::{
+    ring::{find_rings, InnerRingError},
+    CellTable, CellsParam,
+};
+
+const OUTER: &str = "┌→→→→┐\n↑    ↓\n↑    ↓\n↑    ↓\n↑    ↓\n└←←←←┘";
+
+#[test]
+fn the_inner_ring_runs_the_other_way() {
+    let mut cells_param = CellsParam::new(CellTable::new("┌→→┐\n↑  ↓\n↑  ↓\n└←←┘"));
+    let outer = find_rings(&cells_param).remove(0);
+    assert!(outer.is_clockwise());
+
+    let inner = outer.insert_inner(&mut cells_param.cell_table, 1).unwrap();
+    assert_eq!(cells_param.cell_table.to_text(), "┌→→┐\n↑┏┓↓\n↑┗┛↓\n└←←┘");
+    assert!(!inner.is_clockwise());
+
+    let rings = find_rings(&cells_param);
+    assert_eq!(rings.len(), 2);
+    assert_eq!(rings[1].len(), inner.len());
+    assert!(rings[1].cells.iter().all(|cell| inner.cells.contains(cell)));
+}
+
+#[test]
+fn an_inset_of_two_leaves_a_gap_like_the_default_layout() {
+    let mut table = CellTable::new(OUTER);
+    let outer = find_rings(&CellsParam::new(table.clone())).remove(0);
+    outer.insert_inner(&mut table, 2).unwrap();
+    assert_eq!(table.to_text(), "┌→→→→┐\n↑    ↓\n↑ ┏┓ ↓\n↑ ┗┛ ↓\n↑    ↓\n└←←←←┘");
+
+    // and nesting once more flips back
+    let mut table = CellTable::new(OUTER);
+    let inner = outer.insert_inner(&mut table, 1).unwrap();
+    assert_eq!(inner.len(), 12);
+    let innermost = inner.insert_inner(&mut table, 1).unwrap();
+    assert!(innermost.is_clockwise());
+    assert_eq!(table.to_text(), "┌→→→→┐\n↑┏←←┓↓\n↑↓┌┐↑↓\n↑↓└┘↑↓\n↑┗→→┛↓\n└←←←←┘");
+}
+
+#[test]
+fn nothing_is_written_when_the_ring_does_not_fit() {
+    let mut table = CellTable::new(OUTER);
+    let outer = find_rings(&CellsParam::new(table.clone())).remove(0);
+    assert_eq!(outer.insert_inner(&mut table, 3), Err(InnerRingError::TooSmall));
+
+    let mut crowded = CellTable::new("┌→→→→┐\n↑    ↓\n↑  0 ↓\n↑    ↓\n↑    ↓\n└←←←←┘");
+    assert_eq!(outer.insert_inner(&mut crowded, 2), Err(InnerRingError::Occupied { x: 3, y: 2 }));
+    assert_eq!(crowded.get(2, 2), ' ');
+
+    let bent = find_rings(&CellsParam::new(CellTable::new("┌→┐ \n↑ ┗┐\n└←←┘"))).remove(0);
+    assert_eq!(bent.insert_inner(&mut table, 1), Err(InnerRingError::NotRectangular));
+}
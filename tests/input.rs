@@ -0,0 +1,54 @@
+use std::fs;
+
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+use moving_cells_01::{
+    input::{read_bindings, Action, Bindings, Hotkeys, InputMap, UiFocus},
+    layers::Layer,
+};
+
+#[test]
+fn the_default_bindings_have_no_conflicts() {
+    assert_eq!(InputMap::default().conflicts(), Vec::<KeyCode>::new());
+}
+
+#[test]
+fn bindings_are_read_from_ron_and_move_only_their_actions() {
+    let path = std::env::temp_dir().join("moving_cells_01.test.keys.ron");
+    fs::write(&path, "{ Pause: KeyJ, ToggleLayer(Debug): Backquote }").unwrap();
+    let bindings = read_bindings(&path).unwrap().unwrap().unwrap();
+
+    let mut input_map = InputMap::default();
+    input_map.rebind(&bindings);
+    assert_eq!(input_map.key(Action::Pause), Some(KeyCode::KeyJ));
+    assert_eq!(input_map.key(Action::ToggleLayer(Layer::Debug)), Some(KeyCode::Backquote));
+    assert_eq!(input_map.key(Action::Reverse), Some(KeyCode::KeyR));
+    // still listed where they were
+    assert_eq!(input_map.0.len(), InputMap::default().0.len());
+    assert_eq!(input_map.0[1].0, Action::Pause);
+
+    fs::write(&path, "{ Pause: Nowhere }").unwrap();
+    assert!(read_bindings(&path).unwrap().unwrap().is_err());
+    fs::remove_file(&path).unwrap();
+    assert!(read_bindings(&path).unwrap().is_none());
+}
+
+#[test]
+fn keys_bound_twice_are_conflicts() {
+    let mut input_map = InputMap::default();
+    input_map.rebind(&Bindings::from([(Action::Pause, KeyCode::KeyR)]));
+    assert_eq!(input_map.conflicts(), vec![KeyCode::KeyR]);
+}
+
+#[test]
+fn typing_into_egui_presses_no_hotkey() {
+    let mut world = World::new();
+    world.init_resource::<InputMap>();
+    world.init_resource::<ButtonInput<KeyCode>>();
+    world.resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyR);
+    let reverse = |hotkeys: Hotkeys| hotkeys.just_pressed(Action::Reverse);
+    assert!(world.run_system_once(reverse).unwrap());
+
+    world.insert_resource(UiFocus { pointer: false, keyboard: true });
+    assert!(!world.run_system_once(reverse).unwrap());
+}
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    direction::FlowDirection,
+    instanced::{build_instances, layout_cells, move_instances, CircleInstances},
+    move_cells,
+    palette::Palette,
+    ring::Rings,
+    spawn_cells,
+    tempo::Tempo,
+    Cell, CellTable, CellsParam,
+};
+
+/// Both ways of drawing the same table, side by side.
+fn both_app(cells_param: CellsParam) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )))
+        .insert_resource(cells_param)
+        .insert_resource(Tempo {
+            bpm: 60.0,
+            beats_per_cell: 1.0,
+        })
+        .init_resource::<FlowDirection>()
+        .init_resource::<Rings>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, (move_cells, (build_instances, move_instances).chain()));
+    app.update();
+    app
+}
+
+fn sorted(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points
+}
+
+#[test]
+fn instances_follow_the_circles_they_replace() {
+    let mut app = both_app(CellsParam {
+        circles_per_cell: 2,
+        ..CellsParam::new(CellTable::new("┌→┐\n0 ↓\n└←┘"))
+    });
+    for _ in 0..7 {
+        app.update();
+    }
+
+    let world = app.world_mut();
+    let circles = world
+        .query_filtered::<&Transform, With<Cell>>()
+        .iter(world)
+        .map(|transform| transform.translation.truncate())
+        .collect::<Vec<_>>();
+    let instances = world
+        .query::<&CircleInstances>()
+        .single(world)
+        .unwrap()
+        .instances
+        .iter()
+        .map(|instance| instance.position.truncate())
+        .collect::<Vec<_>>();
+    assert_eq!(instances.len(), 15);
+    for (a, b) in sorted(circles).into_iter().zip(sorted(instances)) {
+        assert!(a.distance(b) < 1e-3, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn one_batch_recolored_with_the_palette() {
+    let mut app = both_app(CellsParam::new(CellTable::new("→0")));
+    app.world_mut().insert_resource(Palette {
+        colors: vec![Color::srgb(1.0, 0.0, 0.0)],
+        ..default()
+    });
+    app.update();
+
+    let world = app.world_mut();
+    let batches = world.query::<&CircleInstances>().iter(world).count();
+    assert_eq!(batches, 1);
+    let batch = world.query::<&CircleInstances>().single(world).unwrap();
+    assert!(batch
+        .instances
+        .iter()
+        .all(|instance| instance.color == Color::srgb(1.0, 0.0, 0.0).to_linear()));
+}
+
+#[test]
+fn blank_cells_get_no_instance() {
+    let cells_param = CellsParam::new(CellTable::new("→ 0\n   "));
+    assert_eq!(layout_cells(&cells_param).len(), 2);
+}
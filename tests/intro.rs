@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    intro::{play_intro, unscale_entering, Entering, Intro, IntroOrder},
+    materials::SharedMaterials,
+    CellTable, CellsParam,
+};
+
+#[test]
+fn rows_come_in_top_first_and_radial_from_the_middle() {
+    let cells_param = CellsParam::new(CellTable::new("→→→\n→0→\n→→→"));
+    let rows = Intro { order: IntroOrder::Rows, stagger_sec: 0.1, duration_sec: 0.4 };
+    assert_eq!(rows.delay(UVec2::new(2, 0), &cells_param), 0.0);
+    assert_eq!(rows.delay(UVec2::new(0, 2), &cells_param), 0.2);
+
+    let radial = Intro { order: IntroOrder::Radial, ..rows };
+    assert_eq!(radial.delay(UVec2::new(1, 1), &cells_param), 0.0);
+    assert_eq!(radial.delay(UVec2::new(1, 0), &cells_param), 0.1);
+    assert!(radial.delay(UVec2::new(0, 0), &cells_param) > radial.delay(UVec2::new(0, 1), &cells_param));
+}
+
+#[test]
+fn circles_fade_and_scale_in_without_losing_their_scale() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<SharedMaterials>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(Intro { order: IntroOrder::Rows, stagger_sec: 0.25, duration_sec: 0.5 })
+        .add_systems(First, unscale_entering)
+        .add_systems(PostUpdate, play_intro);
+
+    let material = app.world_mut().resource_mut::<Assets<ColorMaterial>>().add(Color::WHITE);
+    let circle = app
+        .world_mut()
+        .spawn((Transform::from_scale(Vec3::splat(2.0)), MeshMaterial2d(material), Entering::new(0.25)))
+        .id();
+    let state = |app: &App| {
+        let scale = app.world().get::<Transform>(circle).unwrap().scale.x;
+        // painted with other materials as it fades in
+        let material = app.world().get::<MeshMaterial2d<ColorMaterial>>(circle).unwrap();
+        let alpha = app.world().resource::<Assets<ColorMaterial>>().get(&material.0).unwrap().color.alpha();
+        (scale, alpha)
+    };
+
+    // no delta on the first frame, then waiting out the delay
+    app.update();
+    app.update();
+    assert_eq!(state(&app), (0.0, 0.0));
+
+    // halfway, eased out
+    app.update();
+    assert_eq!(state(&app), (1.5, 0.75));
+
+    app.update();
+    assert_eq!(state(&app), (2.0, 1.0));
+    assert!(app.world().get::<Entering>(circle).is_none());
+}
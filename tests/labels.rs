@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    capability::{Capabilities, Capability},
+    labels::{label_text, CellLabel, CellLabelsPlugin},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn every_glyph_is_labelled_with_its_character_and_coordinates() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, CellLabelsPlugin))
+        .insert_resource(CellsParam::new(CellTable::new("→↓\n0 ")))
+        .insert_resource(Capabilities::default());
+    let labels = |app: &mut App| {
+        let mut labels = app.world_mut().query::<(&CellLabel, &Text2d)>();
+        let mut labels = labels.iter(app.world()).map(|(label, text)| (label.grid, text.0.clone())).collect::<Vec<_>>();
+        labels.sort_by_key(|(grid, _)| (grid.y, grid.x));
+        labels
+    };
+
+    app.update();
+    assert!(labels(&mut app).is_empty());
+
+    app.world_mut().resource_mut::<Capabilities>().set(Capability::Labels, true);
+    app.update();
+    // the blank cell has none
+    assert_eq!(
+        labels(&mut app),
+        vec![
+            (UVec2::new(0, 0), label_text('→', UVec2::new(0, 0))),
+            (UVec2::new(1, 0), "↓\n1,0".to_string()),
+            (UVec2::new(0, 1), "0\n0,1".to_string()),
+        ]
+    );
+
+    app.world_mut().resource_mut::<Capabilities>().set(Capability::Labels, false);
+    app.update();
+    assert!(labels(&mut app).is_empty());
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use moving_cells_01::layers::{show_layers, HiddenLayers, Layer, OnLayer};
+
+#[test]
+fn layers_are_stacked_back_to_front() {
+    for pair in Layer::ALL.windows(2) {
+        assert!(pair[0].z() < pair[1].z(), "{:?} behind {:?}", pair[0], pair[1]);
+    }
+}
+
+#[test]
+fn hidden_layers_hide_everything_on_them() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).init_resource::<HiddenLayers>().add_systems(Update, show_layers);
+    let track = app.world_mut().spawn((OnLayer(Layer::Track), Visibility::default())).id();
+    let circle = app.world_mut().spawn((OnLayer(Layer::Circles), Visibility::default())).id();
+    let visibility = |app: &App, entity: Entity| *app.world().get::<Visibility>(entity).unwrap();
+    app.update();
+
+    app.world_mut().resource_mut::<HiddenLayers>().toggle(Layer::Track);
+    app.update();
+    assert_eq!(visibility(&app, track), Visibility::Hidden);
+    assert_eq!(visibility(&app, circle), Visibility::Inherited);
+
+    // spawned onto a hidden layer
+    let later = app.world_mut().spawn((OnLayer(Layer::Track), Visibility::default())).id();
+    app.update();
+    assert_eq!(visibility(&app, later), Visibility::Hidden);
+
+    app.world_mut().resource_mut::<HiddenLayers>().toggle(Layer::Track);
+    app.update();
+    assert_eq!(visibility(&app, track), Visibility::Inherited);
+}
@@ -0,0 +1,147 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    easing::Easing,
+    input::InputMap,
+    layout_file::{
+        list_layouts, read_tweaks, sidecar_path, write_tweaks, LayoutFile, LayoutFilePlugin, LayoutTweaks, LoadLayoutFile,
+    },
+    palette::Palette,
+    recovery::EditorSession,
+    tempo::Tempo,
+    CellTable, CellsParam, RespawnCells, Stepping,
+};
+
+fn layout(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("moving_cells_01.test.{}.txt", name));
+    fs::write(&path, text).unwrap();
+    let _ = fs::remove_file(sidecar_path(&path));
+    path
+}
+
+#[test]
+fn tweaks_round_trip_through_the_sidecar() {
+    let path = layout("round_trip", "┌┐\n└┘");
+    assert_eq!(sidecar_path(&path).file_name().unwrap(), "moving_cells_01.test.round_trip.tweaks.ron");
+    assert!(read_tweaks(&sidecar_path(&path)).unwrap().is_none());
+
+    let mut cells_param = CellsParam::new(CellTable::new("┌┐\n└┘"));
+    cells_param.stepping = Stepping::Hop { swing: 0.4 };
+    let tweaks = LayoutTweaks::capture(&cells_param, &Tempo::default(), &Palette::default().next());
+    write_tweaks(&sidecar_path(&path), &tweaks).unwrap();
+    let read = read_tweaks(&sidecar_path(&path)).unwrap().unwrap().unwrap();
+    fs::remove_file(sidecar_path(&path)).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(read, tweaks);
+
+    let (mut cells_param, mut tempo, mut palette) =
+        (CellsParam::new(CellTable::new("→")), Tempo::default(), Palette::default());
+    read.apply(&mut cells_param, &mut tempo, &mut palette);
+    assert_eq!(cells_param.stepping, Stepping::Hop { swing: 0.4 });
+    assert_eq!(palette, Palette::default().next());
+}
+
+#[test]
+fn switching_layouts_keeps_the_tweaks_of_each() {
+    let (a, b) = (layout("switch_a", "┌┐\n└┘"), layout("switch_b", "┏┓\n┗┛"));
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, LayoutFilePlugin))
+        .insert_resource(CellsParam::new(CellTable::new("→")))
+        .init_resource::<Tempo>()
+        .init_resource::<Palette>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>()
+        .add_event::<RespawnCells>();
+    let easing = |app: &App| app.world().resource::<CellsParam>().easing;
+
+    app.world_mut().send_event(LoadLayoutFile(a.clone()));
+    app.update();
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.table[0], ['┌', '┐']);
+    app.world_mut().resource_mut::<CellsParam>().easing = Easing::Bounce;
+
+    app.world_mut().send_event(LoadLayoutFile(b.clone()));
+    app.update();
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.table[0], ['┏', '┓']);
+    // b was never tweaked
+    assert_eq!(easing(&app), Easing::Bounce);
+    app.world_mut().resource_mut::<CellsParam>().easing = Easing::SineIn;
+
+    app.world_mut().send_event(LoadLayoutFile(a.clone()));
+    app.update();
+    assert_eq!(easing(&app), Easing::Bounce);
+    app.world_mut().send_event(LoadLayoutFile(b.clone()));
+    app.update();
+    assert_eq!(easing(&app), Easing::SineIn);
+
+    for path in [&a, &b] {
+        fs::remove_file(sidecar_path(path)).unwrap();
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn a_layout_file_leaves_nothing_to_recover() {
+    let path = layout("clean", "┌┐\n└┘");
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, LayoutFilePlugin))
+        .insert_resource(CellsParam::new(CellTable::new("→")))
+        .init_resource::<Tempo>()
+        .init_resource::<Palette>()
+        .init_resource::<EditorSession>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>()
+        .add_event::<RespawnCells>();
+    app.world_mut().resource_mut::<EditorSession>().mark_dirty();
+
+    app.world_mut().send_event(LoadLayoutFile(path.clone()));
+    app.update();
+    let _ = fs::remove_file(sidecar_path(&path));
+    fs::remove_file(&path).unwrap();
+    assert!(!app.world().resource::<EditorSession>().is_dirty());
+}
+
+#[test]
+fn f_goes_through_the_layout_files_of_the_folder() {
+    let dir = std::env::temp_dir().join("moving_cells_01.test.layouts");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+    fs::write(&a, "┌┐\n└┘").unwrap();
+    fs::write(&b, "┏┓\n┗┛").unwrap();
+    assert_eq!(list_layouts(&dir).unwrap(), vec![a.clone(), b.clone()]);
+
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, LayoutFilePlugin))
+        .insert_resource(CellsParam::new(CellTable::new("┌┐\n└┘")))
+        .insert_resource(LayoutFile { path: a.clone() })
+        .init_resource::<Tempo>()
+        .init_resource::<Palette>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>()
+        .add_event::<RespawnCells>();
+    app.update();
+    let press_f = |app: &mut App| {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.release(KeyCode::KeyF);
+        keys.clear();
+        keys.press(KeyCode::KeyF);
+        app.update();
+    };
+
+    // a's tweaks are stored on the way out, and come back with it
+    app.world_mut().resource_mut::<CellsParam>().easing = Easing::Bounce;
+    press_f(&mut app);
+    assert_eq!(app.world().resource::<LayoutFile>().path, b);
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.table[0], ['┏', '┓']);
+    app.world_mut().resource_mut::<CellsParam>().easing = Easing::SineIn;
+    press_f(&mut app);
+    let (path, easing) = (app.world().resource::<LayoutFile>().path.clone(), app.world().resource::<CellsParam>().easing);
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(path, a);
+    assert_eq!(easing, Easing::Bounce);
+}
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    lens::{Lens, LensKind, LensPlugin},
+    lfo::{Lfo, LfoPlugin, LfoTarget, Waveform},
+    spawn_cells,
+    tempo::Tempo,
+    Cell, CellTable, CellsParam,
+};
+
+#[test]
+fn barrel_squeezes_and_pincushion_stretches_the_edges() {
+    let barrel = Lens { radius: 100.0, ..Lens::new(LensKind::Barrel, 0.2) };
+    let pincushion = Lens { kind: LensKind::Pincushion, ..barrel.clone() };
+    let edge = Vec2::new(100.0, 0.0);
+    assert!((barrel.warp(edge, 0.0) - Vec2::new(80.0, 0.0)).length() < 1e-4);
+    assert!((pincushion.warp(edge, 0.0) - Vec2::new(120.0, 0.0)).length() < 1e-4);
+    // the center stays, directions are kept
+    assert_eq!(barrel.warp(Vec2::ZERO, 0.0), Vec2::ZERO);
+    let diagonal = pincushion.warp(Vec2::splat(30.0), 0.0);
+    assert!((diagonal.x - diagonal.y).abs() < 1e-4 && diagonal.x > 30.0);
+    // no strength, no deformation
+    assert_eq!(Lens { strength: 0.0, ..barrel }.warp(edge, 0.0), edge);
+}
+
+#[test]
+fn ripples_travel_outward() {
+    let lens = Lens::new(LensKind::Ripple { wavelength: 40.0, speed: 10.0 }, 5.0);
+    let pos = Vec2::new(10.0, 0.0);
+    // a crest at distance 10 at first...
+    assert!((lens.warp(pos, 0.0).x - 15.0).abs() < 1e-4);
+    // ...then a trough, once it has moved on by half a wavelength
+    assert!((lens.warp(pos, 2.0).x - 5.0).abs() < 1e-4);
+}
+
+fn app(lens: Lens) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, TransformPlugin, SimClockPlugin, LensPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(CellsParam::new(CellTable::new("┏┓\n┗┛")))
+        .insert_resource(lens)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        );
+    app
+}
+
+#[test]
+fn the_warp_is_drawn_but_not_kept() {
+    let lens = Lens { radius: 50.0, center: Vec2::new(7.0, 3.0), ..Lens::new(LensKind::Pincushion, 0.5) };
+    let mut app = app(lens.clone());
+    app.update();
+    app.update();
+
+    let world = app.world_mut();
+    let mut cells = world.query::<(&Cell, &Transform, &GlobalTransform)>();
+    for (cell, transform, global) in cells.iter(world) {
+        let drawn = lens.warp(cell.pos, 0.1);
+        assert_ne!(drawn, cell.pos);
+        assert!((transform.translation.truncate() - drawn).length() < 1e-3, "{:?}", cell.grid);
+        assert!((global.translation().truncate() - drawn).length() < 1e-3, "{:?}", cell.grid);
+    }
+
+    // by the time the next frame's systems run, the layout is undeformed again
+    app.world_mut().run_schedule(First);
+    let world = app.world_mut();
+    let mut cells = world.query::<(&Cell, &Transform)>();
+    for (cell, transform) in cells.iter(world) {
+        assert_eq!(transform.translation.truncate(), cell.pos);
+    }
+}
+
+#[test]
+fn an_lfo_can_modulate_the_strength() {
+    let mut app = app(Lens::new(LensKind::Barrel, 0.1));
+    app
+        .add_plugins(LfoPlugin)
+        .init_resource::<Tempo>()
+        .world_mut()
+        .spawn(Lfo::new(Waveform::Sine, 1.0, 0.3, LfoTarget::LensStrength));
+    let mut strengths = Vec::new();
+    for _ in 0..12 {
+        app.update();
+        strengths.push(app.world().resource::<Lens>().strength);
+    }
+    let (low, high) = strengths.iter().fold((f32::MAX, f32::MIN), |(lo, hi), s| (lo.min(*s), hi.max(*s)));
+    assert!(low < 0.0 && high > 0.2, "{:?}", strengths);
+}
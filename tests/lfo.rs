@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    capability::{Capabilities, Capability},
+    clock::SimClockPlugin,
+    lfo::{Lfo, LfoPlugin, LfoTarget, Waveform},
+    tempo::Tempo,
+    CellTable, CellsParam,
+};
+
+#[test]
+fn waveforms_start_at_zero_going_up() {
+    for waveform in [Waveform::Sine, Waveform::Triangle, Waveform::Square] {
+        assert!(waveform.sample(0.0) >= 0.0);
+        assert!((waveform.sample(0.25) - 1.0).abs() < 1e-6, "{:?}", waveform);
+        assert!((waveform.sample(0.75) + 1.0).abs() < 1e-6, "{:?}", waveform);
+    }
+    assert_eq!(Waveform::Triangle.sample(0.125), 0.5);
+}
+
+#[test]
+fn lfos_add_up_and_leave_manual_changes_alone() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin, LfoPlugin))
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(CellsParam::new(CellTable::new("→")));
+
+    // both at their peak a quarter of a second in
+    app.world_mut().spawn(Lfo::new(Waveform::Sine, 1.0, 4.0, LfoTarget::CircleSize));
+    app.world_mut().spawn(Lfo::new(Waveform::Square, 1.0, 2.0, LfoTarget::CircleSize));
+    app.world_mut().spawn(Lfo::new(Waveform::Triangle, 1.0, 30.0, LfoTarget::Bpm));
+
+    // the first frame has no delta
+    app.update();
+    app.update();
+    assert_eq!(circle_size(&app), 10.0 + 4.0 + 2.0);
+    assert_eq!(app.world().resource::<Tempo>().bpm, 60.0 + 30.0);
+
+    // set by hand while modulated
+    app.world_mut().resource_mut::<CellsParam>().circle_size = 20.0;
+    app.update();
+    // half a second: the peaks are taken off again, sine back at 0, square low
+    assert!((circle_size(&app) - (20.0 - 6.0 - 2.0)).abs() < 1e-4);
+}
+
+#[test]
+fn switching_lfos_off_takes_their_offsets_back() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin, LfoPlugin))
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Capabilities>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(CellsParam::new(CellTable::new("→")));
+    app.world_mut().spawn(Lfo::new(Waveform::Square, 0.1, 5.0, LfoTarget::CircleSize));
+
+    app.update();
+    assert_eq!(circle_size(&app), 15.0);
+
+    app.world_mut().resource_mut::<Capabilities>().set(Capability::Lfo, false);
+    app.update();
+    assert_eq!(circle_size(&app), 10.0);
+
+    app.world_mut().resource_mut::<Capabilities>().set(Capability::Lfo, true);
+    app.update();
+    assert_eq!(circle_size(&app), 15.0);
+}
+
+fn circle_size(app: &App) -> f32 {
+    app.world().resource::<CellsParam>().circle_size
+}
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    agent::Agent,
+    marquee::{sync_marquee, Marquee, MarqueeGlyph},
+    ring::{find_rings, Ring, Rings},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn the_first_glyph_leads() {
+    let ring = Ring { cells: (0..4).map(|x| UVec2::new(x, 0)).collect() };
+    let marquee = Marquee::new("AB C");
+    assert_eq!(
+        marquee.placements(&ring),
+        [('A', UVec2::new(1, 0), 0.5), ('B', UVec2::new(1, 0), 0.0), ('C', UVec2::new(0, 0), 0.0)],
+    );
+
+    // wraps around rings shorter than the text
+    let marquee = Marquee { spacing_cells: 1.5, ..Marquee::new("ABCD") };
+    assert_eq!(marquee.placements(&ring)[0], ('A', UVec2::new(0, 0), 0.5));
+}
+
+#[test]
+fn glyphs_are_agents_on_the_chosen_ring() {
+    let cells_param = CellsParam::new(CellTable::new("┏←←←┓\n↓┌→┐↑\n↓↑0↓↑\n↓└←┘↑\n┗→→→┛"));
+    let rings = Rings::new(find_rings(&cells_param));
+    let inner = rings.0[1].cells.clone();
+
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .insert_resource(cells_param)
+        .insert_resource(rings)
+        .insert_resource(Marquee { ring: 1, ..Marquee::new("HELLO") })
+        .add_systems(Update, sync_marquee);
+    app.update();
+
+    let glyphs = |app: &mut App| {
+        let world = app.world_mut();
+        let mut query = world.query_filtered::<(&Text2d, &Agent), With<MarqueeGlyph>>();
+        query.iter(world).map(|(text, agent)| (text.0.clone(), agent.grid)).collect::<Vec<_>>()
+    };
+    let spawned = glyphs(&mut app);
+    assert_eq!(spawned.len(), 5);
+    assert!(spawned.iter().all(|(_, grid)| inner.contains(grid)));
+
+    // only respawned when something changed
+    app.update();
+    assert_eq!(glyphs(&mut app).len(), 5);
+    app.world_mut().resource_mut::<Marquee>().text = "HI".into();
+    app.update();
+    assert_eq!(glyphs(&mut app).len(), 2);
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    color_mode::apply_color_mode,
+    materials::{paint, quantize, SharedMaterials, STEPS},
+    spawn_cells, CellTable, CellsParam,
+};
+
+#[test]
+fn circles_of_a_color_share_its_material() {
+    let mut materials = Assets::<ColorMaterial>::default();
+    let mut shared = SharedMaterials::default();
+    let red = Color::srgb(1.0, 0.0, 0.0);
+    let a = shared.get(red, &mut materials);
+    assert_eq!(shared.get(red, &mut materials), a);
+    assert_ne!(shared.get(Color::WHITE, &mut materials), a);
+
+    // painting one circle leaves the material it shared alone
+    let mut circle = MeshMaterial2d(a.clone());
+    paint(&mut circle, red.with_alpha(0.5), &mut shared, &mut materials);
+    assert_eq!(materials.get(&a).unwrap().color, red);
+    assert_eq!(materials.get(&circle.0).unwrap().color, red.with_alpha(0.5));
+
+    // changed in place (a hue shift), it's no longer handed out as red
+    materials.get_mut(&a).unwrap().color = Color::srgb(0.0, 1.0, 0.0);
+    let b = shared.get(red, &mut materials);
+    assert_ne!(b, a);
+    assert_eq!(materials.get(&b).unwrap().color, red);
+}
+
+#[test]
+fn a_table_needs_a_material_per_color_not_per_circle() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<SharedMaterials>()
+        .insert_resource(CellsParam::new(CellTable::new("→→→→→→→↓\n↑←←←←←←←")))
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, apply_color_mode);
+    app.update();
+
+    let world = app.world_mut();
+    let mut circles = world.query::<&MeshMaterial2d<ColorMaterial>>();
+    let ids = circles.iter(world).map(|m| m.0.id()).collect::<std::collections::HashSet<_>>();
+    // one ring, one palette color
+    assert_eq!(circles.iter(world).count(), 16);
+    assert_eq!(ids.len(), 1);
+}
+
+#[test]
+fn fading_circles_go_through_a_few_materials() {
+    let mut materials = Assets::<ColorMaterial>::default();
+    let mut shared = SharedMaterials::default();
+    let mut material = MeshMaterial2d(shared.get(Color::WHITE, &mut materials));
+    // a second of fading at 60 fps
+    for frame in 0..60 {
+        let alpha = quantize(1.0 - frame as f32 / 600.0);
+        paint(&mut material, Color::WHITE.with_alpha(alpha), &mut shared, &mut materials);
+    }
+    assert!(shared.len() <= (STEPS / 10.0).ceil() as usize + 1, "{}", shared.len());
+    assert_eq!(quantize(0.5), 0.5);
+    assert_eq!(quantize(1.0), 1.0);
+}
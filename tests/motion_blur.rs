@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    direction::FlowDirection,
+    motion_blur::{MotionBlur, MotionBlurPlugin, Velocity},
+    move_cells, spawn_cells, CellTable, CellsParam,
+};
+
+#[test]
+fn smears_grow_with_speed_up_to_a_limit() {
+    let blur = MotionBlur { exposure_sec: 0.1, max_stretch: 3.0 };
+    assert_eq!(blur.stretch(Vec2::ZERO, 10.0), 1.0);
+    // 20 units in the exposure on a 20 wide circle: twice as long
+    assert_eq!(blur.stretch(Vec2::new(0.0, -200.0), 10.0), 2.0);
+    assert_eq!(blur.stretch(Vec2::new(1000.0, 0.0), 10.0), 3.0);
+}
+
+#[test]
+fn starting_over_in_the_cell_keeps_the_velocity() {
+    let mut velocity = Velocity::default();
+    velocity.track(Vec2::ZERO, Vec2::new(5.0, 0.0), 0.5, 25.0);
+    assert_eq!(velocity, Velocity(Vec2::new(10.0, 0.0)));
+    velocity.track(Vec2::new(24.0, 0.0), Vec2::new(-24.0, 0.0), 0.5, 25.0);
+    velocity.track(Vec2::ZERO, Vec2::ZERO, 0.0, 25.0);
+    assert_eq!(velocity, Velocity(Vec2::new(10.0, 0.0)));
+}
+
+#[test]
+fn moving_circles_are_drawn_stretched() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, TransformPlugin, SimClockPlugin, MotionBlurPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .init_resource::<FlowDirection>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(CellsParam::new(CellTable::new("↓")))
+        .insert_resource(MotionBlur { exposure_sec: 0.5, max_stretch: 4.0 })
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, move_cells);
+    for _ in 0..4 {
+        app.update();
+    }
+
+    let world = app.world_mut();
+    let (velocity, transform) = world.query::<(&Velocity, &Transform)>().single(world).unwrap();
+    assert!(velocity.0.x == 0.0 && velocity.0.y < 0.0, "{:?}", velocity);
+    assert!(transform.scale.x > 1.0 && transform.scale.y == 1.0, "{:?}", transform.scale);
+    // turned to point down
+    assert!((transform.rotation * Vec3::X).abs_diff_eq(Vec3::NEG_Y, 1e-5));
+
+    world.remove_resource::<MotionBlur>();
+    app.update();
+    let world = app.world_mut();
+    let transform = world.query::<&Transform>().single(world).unwrap();
+    assert_eq!((transform.rotation, transform.scale), (Quat::IDENTITY, Vec3::ONE));
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use moving_cells_01::noise::{perlin, Jitter};
+
+#[test]
+fn perlin_is_bounded_smooth_and_zero_on_the_lattice() {
+    for seed in [0, 1, 0xdead_beef] {
+        let mut last = perlin(0.0, seed);
+        for i in 1..4000 {
+            let x = i as f32 / 100.0;
+            let v = perlin(x, seed);
+            assert!((-1.0..=1.0).contains(&v), "{} at {}", v, x);
+            assert!((v - last).abs() < 0.05, "jump at {}", x);
+            last = v;
+        }
+        assert!((0..40).all(|i| perlin(i as f32, seed) == 0.0));
+    }
+}
+
+#[test]
+fn every_cell_jitters_on_its_own_and_the_same_way_every_time() {
+    let jitter = Jitter { amplitude: 4.0, frequency: 1.5 };
+    let (a, b) = (UVec2::new(1, 2), UVec2::new(2, 1));
+    assert_eq!(jitter.offset(a, 0.3), jitter.offset(a, 0.3));
+    assert_ne!(jitter.offset(a, 0.3), jitter.offset(b, 0.3));
+    assert!(jitter.offset(a, 0.3).abs().max_element() <= 4.0);
+    assert_eq!(Jitter { amplitude: 0.0, ..jitter }.offset(a, 0.3), Vec2::ZERO);
+}
@@ -0,0 +1,63 @@
+use moving_cells_01::{
+    opacity::{layer_z, layout_center, Opacity},
+    CellTable, CellsParam, MoveType,
+};
+
+#[test]
+fn edge_fade_dissolves_circles_at_the_cell_boundaries() {
+    let fade = Opacity::EdgeFade { width: 0.25 };
+    assert_eq!(fade.alpha(Some(0.0), 0.0), 0.0);
+    assert_eq!(fade.alpha(Some(0.125), 0.0), 0.5);
+    assert_eq!(fade.alpha(Some(0.5), 0.0), 1.0);
+    assert_eq!(fade.alpha(Some(1.0), 0.0), 0.0);
+    // centers don't travel
+    assert_eq!(fade.alpha(None, 0.0), 1.0);
+}
+
+#[test]
+fn distance_fade_and_curves() {
+    let fade = Opacity::DistanceFade { reach: 100.0, floor: 0.5 };
+    assert_eq!(fade.alpha(None, 0.0), 1.0);
+    assert_eq!(fade.alpha(Some(0.3), 50.0), 0.75);
+    assert_eq!(fade.alpha(None, 300.0), 0.5);
+
+    let curve = Opacity::Curve(vec![(0.25, 0.0), (0.75, 1.0)]);
+    assert_eq!(curve.alpha(Some(0.0), 0.0), 0.0);
+    assert_eq!(curve.alpha(Some(0.5), 0.0), 0.5);
+    assert_eq!(curve.alpha(Some(1.0), 0.0), 1.0);
+}
+
+#[test]
+fn the_layout_center_is_between_the_corner_cells() {
+    let cells_param = CellsParam::new(CellTable::new("→→→\n↑0↓\n←←←"));
+    assert_eq!(layout_center(&cells_param), cells_param.cell_center(1, 1));
+}
+
+#[test]
+fn alpha_per_ring_per_direction_and_pulsing() {
+    let per_ring = Opacity::PerRing(vec![1.0, 0.5]);
+    assert_eq!(per_ring.cell_alpha(Some(1), MoveType::Up, 0.0), 0.5);
+    // off the rings, or a ring without an alpha
+    assert_eq!(per_ring.cell_alpha(None, MoveType::Center, 0.0), 1.0);
+    assert_eq!(per_ring.cell_alpha(Some(2), MoveType::Up, 0.0), 1.0);
+
+    let per_direction = Opacity::PerDirection(vec![(MoveType::Left, 0.25)]);
+    assert_eq!(per_direction.cell_alpha(Some(0), MoveType::Left, 0.0), 0.25);
+    assert_eq!(per_direction.cell_alpha(Some(0), MoveType::Right, 0.0), 1.0);
+
+    let pulse = Opacity::Pulse { cells: 4.0, floor: 0.2 };
+    assert_eq!(pulse.cell_alpha(None, MoveType::Up, 0.0), 1.0);
+    assert!((pulse.cell_alpha(None, MoveType::Up, 2.0) - 0.2).abs() < 1e-6);
+    assert!((pulse.cell_alpha(None, MoveType::Up, 4.0) - 1.0).abs() < 1e-6);
+    // the path curves leave these to cell_alpha
+    assert_eq!(pulse.alpha(Some(0.5), 0.0), 1.0);
+}
+
+#[test]
+fn translucent_rings_are_stacked_longest_in_front() {
+    assert_eq!(layer_z(Some(0), 0), 0.0);
+    assert!(layer_z(Some(1), 0) < layer_z(Some(0), 999));
+    assert!(layer_z(Some(0), 1) < layer_z(Some(0), 0));
+    // all within a trail ghost's step
+    assert!(layer_z(None, 999) > -0.001);
+}
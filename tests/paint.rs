@@ -0,0 +1,85 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    input::{Action, InputMap, UiFocus},
+    paint::{brushes, cell_at, paint, PaintEditor, PaintPlugin},
+    CellTable, CellsParam, RespawnCells,
+};
+
+const RING: &str = "┌→┐\n↑0↓\n└←┘";
+
+#[test]
+fn without_a_brush_clicks_cycle_the_kind_of_cell() {
+    let cycling = PaintEditor::default();
+    assert_eq!(cycling.glyph_for(' '), '0');
+    assert_eq!(cycling.glyph_for('0'), '←');
+    // round and back to blank
+    assert_eq!(cycling.glyph_for('┏'), ' ');
+    assert_eq!(brushes().len(), 14);
+
+    let brush = PaintEditor { brush: Some('↑') };
+    assert_eq!(brush.glyph_for('0'), '↑');
+}
+
+#[test]
+fn cells_are_found_under_the_cursor_and_painted() {
+    let cells_param = CellsParam::new(CellTable::new(RING));
+    assert_eq!(cell_at(&cells_param, cells_param.cell_center(2, 1)), Some(UVec2::new(2, 1)));
+    let corner = cells_param.cell_center(0, 0) + cells_param.cell_size * Vec2::new(-0.4, 0.4);
+    assert_eq!(cell_at(&cells_param, corner), Some(UVec2::ZERO));
+    assert_eq!(cell_at(&cells_param, cells_param.bounds().max + Vec2::ONE), None);
+
+    let painted = paint(&cells_param.cell_table, UVec2::new(1, 1), ' ');
+    assert_eq!(painted.to_text(), "┌→┐\n↑ ↓\n└←┘");
+    // past the end of a short row
+    let short = CellTable::from_rows(vec![vec!['0'], vec!['0', '0']]).unwrap();
+    assert_eq!(paint(&short, UVec2::new(1, 0), '0').to_text(), "00\n00");
+}
+
+#[test]
+fn clicking_paints_the_cell_and_respawns() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, PaintPlugin))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<InputMap>()
+        .add_event::<RespawnCells>()
+        .insert_resource(CellsParam::new(CellTable::new(RING)));
+    let center = app.world().resource::<CellsParam>().cell_center(1, 1);
+    let mut window = Window::default();
+    window.set_cursor_position(Some(window.size() / 2.0));
+    app.world_mut().spawn((window, PrimaryWindow));
+    app.world_mut().spawn((Camera2d, Transform::from_translation(center.extend(0.0)), OutputName("main".into())));
+
+    // not painting yet
+    app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+    app.update();
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.to_text(), RING);
+
+    let key = app.world().resource::<InputMap>().key(Action::TogglePaint).unwrap();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    assert!(app.world().contains_resource::<PaintEditor>());
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+
+    app.insert_resource(PaintEditor { brush: Some(' ') });
+    // a click on an egui window
+    app.insert_resource(UiFocus { pointer: true });
+    let mut buttons = app.world_mut().resource_mut::<ButtonInput<MouseButton>>();
+    buttons.reset_all();
+    buttons.press(MouseButton::Left);
+    app.update();
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.to_text(), RING);
+    assert!(app.world().resource::<ButtonInput<MouseButton>>().pressed(MouseButton::Left));
+
+    app.insert_resource(UiFocus::default());
+    let mut buttons = app.world_mut().resource_mut::<ButtonInput<MouseButton>>();
+    buttons.reset_all();
+    buttons.press(MouseButton::Left);
+    app.update();
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.to_text(), "┌→┐\n↑ ↓\n└←┘");
+    assert_eq!(app.world().resource::<Events<RespawnCells>>().len(), 1);
+    // taken from the camera
+    assert!(!app.world().resource::<ButtonInput<MouseButton>>().pressed(MouseButton::Left));
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    color_mode::apply_color_mode,
+    materials::SharedMaterials,
+    palette::{presets, Assignment, Palette},
+    ring::{find_rings, Rings},
+    spawn_cells, Cell, CellTable, CellsParam,
+};
+
+const RED: Color = Color::srgb(1.0, 0.0, 0.0);
+const BLUE: Color = Color::srgb(0.0, 0.0, 1.0);
+
+#[test]
+fn colors_are_handed_out_by_loop_row_or_at_random() {
+    let cells_param = CellsParam::new(CellTable::new("┏┓┏┓\n┗┛┗┛\n→→→→"));
+    let rings = Rings::new(find_rings(&cells_param));
+    assert_eq!(rings.0.len(), 2);
+
+    let by_loop = Palette::new("two", vec![RED, BLUE], Assignment::ByLoop);
+    assert_eq!(by_loop.color(rings.0[0].cells[0], &rings), RED);
+    assert_eq!(by_loop.color(rings.0[1].cells[0], &rings), BLUE);
+    // on no ring: the color after the last ring's
+    assert_eq!(by_loop.color(UVec2::new(0, 2), &rings), RED);
+
+    let by_row = Palette { assignment: Assignment::ByRow, ..by_loop.clone() };
+    assert_eq!(by_row.color(UVec2::new(3, 0), &rings), RED);
+    assert_eq!(by_row.color(UVec2::new(0, 1), &rings), BLUE);
+
+    let random = Palette { assignment: Assignment::Random { seed: 7 }, ..by_loop };
+    let picks = (0..16).map(|x| random.color(UVec2::new(x, 0), &rings)).collect::<Vec<_>>();
+    assert!(picks.contains(&RED) && picks.contains(&BLUE));
+    assert_eq!(picks, (0..16).map(|x| random.color(UVec2::new(x, 0), &rings)).collect::<Vec<_>>());
+}
+
+#[test]
+fn presets_cycle_around_starting_from_white() {
+    let presets = presets();
+    assert_eq!(Palette::default().colors, vec![Color::WHITE]);
+    let mut palette = Palette::default();
+    for preset in presets.iter().skip(1).chain(&presets[..1]) {
+        palette = palette.next();
+        assert_eq!(&palette, preset);
+    }
+    assert_eq!(Palette::new("custom", vec![RED], Assignment::ByRow).next(), presets[0]);
+}
+
+#[test]
+fn cells_are_repainted_when_the_palette_changes() {
+    let cells_param = CellsParam::new(CellTable::new("┏┓\n┗┛"));
+    let rings = Rings::new(find_rings(&cells_param));
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<SharedMaterials>()
+        .insert_resource(cells_param)
+        .insert_resource(rings)
+        .insert_resource(Palette::new("red", vec![RED], Assignment::ByLoop))
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, apply_color_mode);
+
+    let colors = |app: &mut App| {
+        let world = app.world_mut();
+        let mut circles = world.query_filtered::<&MeshMaterial2d<ColorMaterial>, With<Cell>>();
+        let materials = world.resource::<Assets<ColorMaterial>>();
+        circles.iter(world).map(|m| materials.get(&m.0).unwrap().color).collect::<Vec<_>>()
+    };
+    app.update();
+    assert_eq!(colors(&mut app), [RED; 4]);
+
+    app.insert_resource(Palette::new("blue", vec![BLUE], Assignment::ByRow));
+    app.update();
+    assert_eq!(colors(&mut app), [BLUE; 4]);
+}
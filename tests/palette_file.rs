@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use bevy::{asset::AssetPlugin, prelude::*};
+
+use moving_cells_01::{
+    palette::{presets, Palette},
+    palette_file::{parse_palette, FilePalettes, PaletteFileError, PaletteFilesPlugin, PALETTE_FOLDER},
+};
+
+fn srgb(palette: &Palette) -> Vec<[u8; 4]> {
+    palette.colors.iter().map(|c| c.to_srgba().to_u8_array()).collect()
+}
+
+#[test]
+fn hex_files_have_a_color_per_line() {
+    let palette = parse_palette("dusk", "hex", "2e2240\n\n#ffd29a\r\n").unwrap();
+    assert_eq!(palette.name, "dusk");
+    assert_eq!(srgb(&palette), vec![[0x2e, 0x22, 0x40, 255], [0xff, 0xd2, 0x9a, 255]]);
+
+    match parse_palette("bad", "hex", "2e2240\nnope") {
+        Err(PaletteFileError::InvalidLine { line: 2, text }) => assert_eq!(text, "nope"),
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(parse_palette("none", "hex", "\n"), Err(PaletteFileError::Empty)));
+}
+
+#[test]
+fn gimp_and_jasc_palettes_are_read() {
+    let gpl = "GIMP Palette\nName: two\nColumns: 2\n#\n255   0   0\tred\n  0 128 255 Untitled\n";
+    let pal = "JASC-PAL\n0100\n2\n255 0 0\n0 128 255\n";
+    for (extension, text) in [("gpl", gpl), ("pal", pal)] {
+        let palette = parse_palette("two", extension, text).unwrap();
+        assert_eq!(srgb(&palette), vec![[255, 0, 0, 255], [0, 128, 255, 255]], "{}", extension);
+    }
+    assert!(matches!(parse_palette("two", "gpl", pal), Err(PaletteFileError::MissingHeader)));
+}
+
+#[test]
+fn palettes_in_the_folder_are_loaded_and_cycled_after_the_presets() {
+    let root = std::env::temp_dir().join("moving_cells_01.test.palette_assets");
+    let folder = root.join(PALETTE_FOLDER);
+    std::fs::create_dir_all(&folder).unwrap();
+    std::fs::write(folder.join("duo.hex"), "000000\nffffff\n").unwrap();
+
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, AssetPlugin { file_path: root.to_string_lossy().into(), ..default() }))
+        .add_plugins(PaletteFilesPlugin);
+    let start = Instant::now();
+    while app.world().resource::<FilePalettes>().0.is_empty() {
+        assert!(start.elapsed() < Duration::from_secs(10), "palette never loaded");
+        app.update();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let files = &app.world().resource::<FilePalettes>().0;
+    assert_eq!(files.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["duo"]);
+    let mut palettes = presets();
+    palettes.extend(files.iter().cloned());
+    let last_preset = presets().pop().unwrap();
+    assert_eq!(last_preset.next_in(palettes.clone()).name, "duo");
+    assert_eq!(files[0].next_in(palettes).name, presets()[0].name);
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{move_offset, Cell, CornerStyle, MoveType};
+
+#[test]
+fn paths_are_worked_out_once_and_kept_up_to_date() {
+    let mut cell = Cell::new(UVec2::ZERO, Vec2::ZERO, Vec2::new(40.0, 20.0), MoveType::BottomToRight);
+    assert_eq!(cell.path(), Some((Vec2::new(0.0, -10.0), Vec2::new(20.0, 0.0))));
+
+    cell.reverse();
+    assert_eq!(cell.move_type, MoveType::RightToBottom);
+    assert_eq!(cell.path(), Some((Vec2::new(20.0, 0.0), Vec2::new(0.0, -10.0))));
+
+    cell.resize(Vec2::ONE, Vec2::splat(10.0));
+    assert_eq!(cell.path(), Some((Vec2::new(5.0, 0.0), Vec2::new(0.0, -5.0))));
+
+    let center = Cell::new(UVec2::ZERO, Vec2::ZERO, Vec2::splat(10.0), MoveType::Center);
+    assert_eq!(center.path(), None);
+    assert_eq!(center.offset(0.5, CornerStyle::Arc), Vec2::ZERO);
+}
+
+#[test]
+fn offsets_match_the_move_type() {
+    let size = Vec2::new(30.0, 20.0);
+    for move_type in [MoveType::Left, MoveType::Up, MoveType::TopToLeft, MoveType::RightToBottom] {
+        let cell = Cell::new(UVec2::ZERO, Vec2::ZERO, size, move_type);
+        for corner_style in [CornerStyle::Arc, CornerStyle::Straight] {
+            for rate in [0.0, 0.3, 0.5, 1.0] {
+                let expected = move_offset(move_type, rate, size / 2.0, corner_style);
+                assert!(cell.offset(rate, corner_style).distance(expected) < 1e-5);
+            }
+        }
+    }
+}
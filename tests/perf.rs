@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    perf::{parse_size, run, run_table, standard_scenes, tiled_table, Bench, BenchPlugin, Report, Scene},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn standard_scenes_cover_the_sizes_with_and_without_trails() {
+    let scenes = standard_scenes();
+    assert_eq!(scenes.len(), 7);
+    assert_eq!(scenes[0].cells(), 1024);
+    assert_eq!(scenes[2].cells(), 10_000);
+    assert!(scenes[5].cells() >= 99_000 && scenes[5].trails);
+    assert_eq!(scenes[0].cell_table().width, 32);
+    assert!(scenes[6].centers && scenes[6].cells() == scenes[5].cells());
+}
+
+#[test]
+fn reports_round_trip_through_their_json_lines() {
+    let report = run(&Scene::new("tiny+trails", 2, true), 3);
+    assert_eq!(report.cells, 16);
+    assert_eq!(report.frames, 3);
+    // every circle has its ghosts
+    assert!(report.entities > 16 * 8, "{}", report.entities);
+    assert!(report.p50_ms <= report.p95_ms && report.p95_ms <= report.max_ms);
+
+    let line = report.to_json();
+    assert_eq!(Report::field(&line, "scene").as_deref(), Some("tiny+trails"));
+    assert_eq!(Report::field(&line, "cells").as_deref(), Some("16"));
+    assert_eq!(Report::field(&line, "max_ms"), Some(format!("{:.4}", report.max_ms)));
+    assert_eq!(Report::field(&line, "fps"), None);
+}
+
+#[test]
+fn bench_sizes_parse_and_tile() {
+    assert_eq!(parse_size("400x250"), Some((400, 250)));
+    assert_eq!(parse_size("3X5"), Some((3, 5)));
+    assert_eq!(parse_size("0x5"), None);
+    assert_eq!(parse_size("400"), None);
+
+    // rounded up to whole rings
+    let table = tiled_table(3, 5, false);
+    assert_eq!((table.width, table.height), (4, 6));
+
+    let report = run_table("4x6", table, false, 2);
+    assert_eq!((report.scene.as_str(), report.cells, report.frames), ("4x6", 24, 2));
+}
+
+#[test]
+fn windowed_bench_reports_and_quits() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BenchPlugin))
+        .insert_resource(CellsParam::new(CellTable::new("→")))
+        .insert_resource(Bench { name: "tiny".into(), frames: 3 });
+    let mut updates = 0;
+    while app.should_exit().is_none() {
+        app.update();
+        updates += 1;
+        assert!(updates < 100);
+    }
+    // after the warmup
+    assert!(updates > 3);
+}
+
+#[test]
+fn statistics_come_from_the_sorted_times() {
+    let report = Report::from_times("s", 1, 1, vec![4.0, 1.0, 3.0, 2.0]);
+    assert_eq!((report.frames, report.mean_ms, report.p50_ms, report.max_ms), (4, 2.5, 3.0, 4.0));
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    audio::BarsElapsed,
+    playlist::{AdvancePlaylist, Playlist, PlaylistPlugin},
+    CellTable, CellsParam, RespawnCells,
+};
+
+#[test]
+fn bars_advance_the_playlist_when_asked_to() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, PlaylistPlugin))
+        .add_event::<RespawnCells>()
+        .insert_resource(CellsParam::new(CellTable::new("→↓\n↑←")))
+        .insert_resource(Playlist::new(vec![CellTable::new("→↓\n↑←"), CellTable::new("0")]));
+    let table = |app: &App| app.world().resource::<CellsParam>().cell_table.to_text();
+
+    app.world_mut().send_event(BarsElapsed);
+    app.update();
+    assert_eq!(table(&app), "→↓\n↑←");
+
+    app.world_mut().resource_mut::<Playlist>().advance_on_bars = true;
+    app.world_mut().send_event(BarsElapsed);
+    app.update();
+    assert_eq!(table(&app), "0");
+    assert_eq!(app.world().resource::<Events<RespawnCells>>().len(), 1);
+
+    // wraps around
+    app.world_mut().send_event(AdvancePlaylist);
+    app.update();
+    assert_eq!(table(&app), "→↓\n↑←");
+}
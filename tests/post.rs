@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    post::{post_fx, sync_post_fx, Pixelate, PostEffects, PostFx},
+};
+
+#[test]
+fn the_main_camera_gets_the_effects_and_moving_grain() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(PostEffects { chromatic_aberration: 0.02, vignette: 0.0, grain: 0.1 })
+        .add_systems(PostUpdate, sync_post_fx);
+    let main = app.world_mut().spawn((Camera2d, OutputName("main".into()))).id();
+    let preview = app.world_mut().spawn((Camera2d, OutputName("preview".into()))).id();
+    app.update();
+    app.update();
+
+    let fx = |app: &App, camera: Entity| app.world().get::<PostFx>(camera).copied();
+    let first = fx(&app, main).unwrap();
+    assert_eq!((first.chromatic_aberration, first.vignette, first.grain), (0.02, 0.0, 0.1));
+    assert!(fx(&app, preview).is_none());
+    app.update();
+    assert!(fx(&app, main).unwrap().time > first.time);
+
+    app.world_mut().remove_resource::<PostEffects>();
+    app.update();
+    assert!(fx(&app, main).is_none());
+}
+
+#[test]
+fn pixelating_leaves_the_other_effects_off() {
+    let fx = post_fx(None, Some(&Pixelate { pixel_size: 8, scanlines: 0.5 }), 1.0);
+    assert_eq!(fx, PostFx { pixel_size: 8.0, scanlines: 0.5, time: 1.0, ..default() });
+    let both = post_fx(Some(&PostEffects::default()), Some(&Pixelate::default()), 0.0);
+    assert_eq!((both.vignette, both.pixel_size), (0.5, 6.0));
+}
@@ -0,0 +1,96 @@
+use std::fs;
+
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    easing::Easing,
+    palette::Palette,
+    preset::{list_presets, read_preset, valid_name, write_preset, LoadPreset, Preset, PresetPlugin, Presets, SavePreset},
+    recovery::EditorSession,
+    tempo::Tempo,
+    thickness::Thickness,
+    CellTable, CellsParam, RespawnCells,
+};
+
+fn preset_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("moving_cells_01.test.presets.{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn presets_round_trip_through_their_folder() {
+    let dir = preset_dir("round_trip");
+    assert_eq!(list_presets(&dir).unwrap(), Vec::<String>::new());
+    assert!(read_preset(&dir, "calm").unwrap().is_none());
+
+    let mut cells_param = CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘"));
+    cells_param.easing = Easing::Bounce;
+    cells_param.cell_size = Vec2::new(30.0, 40.0);
+    let tempo = Tempo { bpm: 90.0, beats_per_cell: 2.0 };
+    let preset = Preset::capture(&cells_param, &tempo, &Palette::default().next());
+    write_preset(&dir, "calm", &preset).unwrap();
+    write_preset(&dir, "busy", &preset).unwrap();
+    assert_eq!(list_presets(&dir).unwrap(), vec!["busy".to_string(), "calm".to_string()]);
+    let read = read_preset(&dir, "calm").unwrap().unwrap().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(read, preset);
+
+    let (mut cells_param, mut tempo, mut palette) =
+        (CellsParam::new(CellTable::new("→")), Tempo::default(), Palette::default());
+    read.apply(&mut cells_param, &mut tempo, &mut palette).unwrap();
+    assert_eq!(cells_param.cell_table.to_text(), "┌→┐\n↑0↓\n└←┘");
+    assert_eq!(cells_param.easing, Easing::Bounce);
+    assert_eq!(cells_param.cell_size, Vec2::new(30.0, 40.0));
+    assert_eq!((tempo.bpm, tempo.beats_per_cell), (90.0, 2.0));
+    assert_eq!(palette, Palette::default().next());
+}
+
+#[test]
+fn presets_keep_the_thickness() {
+    let cells_param = CellsParam::new(CellTable::new("# thickness: speed\n┏→┐\n└←┛"));
+    let preset = Preset::capture(&cells_param, &Tempo::default(), &Palette::default());
+
+    let (mut applied, mut tempo, mut palette) = (CellsParam::new(CellTable::new("→")), Tempo::default(), Palette::default());
+    preset.apply(&mut applied, &mut tempo, &mut palette).unwrap();
+    assert_eq!(applied.cell_table.thickness, Thickness::Speed);
+    assert_eq!(applied.cell_table.heavy, cells_param.cell_table.heavy);
+    assert_eq!(applied.cell_table.speed(0, 0), 2.0);
+}
+
+#[test]
+fn names_stay_in_the_folder() {
+    assert!(valid_name("slow ripple"));
+    assert!(!valid_name(""));
+    assert!(!valid_name("  "));
+    assert!(!valid_name("../escape"));
+    assert!(!valid_name(".hidden"));
+}
+
+#[test]
+fn saving_and_loading_from_the_app() {
+    let dir = preset_dir("app");
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, PresetPlugin))
+        .add_event::<RespawnCells>()
+        .insert_resource(Presets { dir: dir.clone(), names: Vec::new() })
+        .insert_resource(CellsParam::new(CellTable::new("┌┐\n└┘")))
+        .init_resource::<Tempo>()
+        .init_resource::<Palette>()
+        .init_resource::<EditorSession>();
+    app.update();
+
+    app.world_mut().resource_mut::<EditorSession>().mark_dirty();
+    app.world_mut().send_event(SavePreset("small".into()));
+    app.update();
+    assert_eq!(app.world().resource::<Presets>().names, vec!["small".to_string()]);
+    assert!(!app.world().resource::<EditorSession>().is_dirty());
+
+    app.world_mut().resource_mut::<CellsParam>().cell_table = CellTable::new("┌→→┐\n└←←┘");
+    app.world_mut().send_event(LoadPreset("small".into()));
+    app.update();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.to_text(), "┌┐\n└┘");
+    assert_eq!(app.world().resource::<Events<RespawnCells>>().len(), 1);
+    assert!(app.world().resource::<EditorSession>().is_dirty());
+}
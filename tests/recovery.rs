@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    recovery::{self, Autosave, EditorSession},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn recovery_file_keeps_leading_blanks() {
+    let path = std::env::temp_dir().join("moving_cells_01.test.leading_blanks.txt");
+    let table = CellTable::from_rows(vec![
+        "  ┌┐".chars().collect(),
+        "  └┘".chars().collect(),
+    ])
+    .unwrap();
+
+    recovery::write_recovery(&path, &table).unwrap();
+    let restored = recovery::read_recovery(&path).unwrap().unwrap().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(restored.table, table.table);
+    assert_eq!(restored.width, 4);
+}
+
+#[test]
+fn dirty_layouts_are_autosaved_until_saved() {
+    let path = std::env::temp_dir().join("moving_cells_01.test.autosave.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(CellsParam::new(CellTable::new("┌┐\n└┘")))
+        .insert_resource(Autosave { path: path.clone(), interval_sec: 0.5 })
+        .init_resource::<EditorSession>()
+        .add_systems(Update, recovery::autosave_layout);
+
+    app.update();
+    assert!(!path.exists(), "nothing to recover while clean");
+
+    // written straight away, then every interval
+    app.world_mut().resource_mut::<EditorSession>().mark_dirty();
+    app.update();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "┌┐\n└┘");
+
+    app.world_mut().resource_mut::<CellsParam>().cell_table.reverse();
+    app.update();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "┌┐\n└┘");
+    app.update();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "┏┓\n┗┛");
+
+    app.world_mut().resource_mut::<EditorSession>().mark_saved();
+    app.update();
+    assert!(!path.exists(), "the recovery file goes once saved");
+}
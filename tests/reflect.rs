@@ -0,0 +1,12 @@
+use bevy::{prelude::*, reflect::GetPath};
+
+use moving_cells_01::{CellTable, CellsParam};
+
+#[test]
+fn the_sizes_are_open_to_the_inspector() {
+    let mut cells_param = CellsParam::new(CellTable::new("┌┐\n└┘"));
+    *cells_param.path_mut::<f32>("circle_size").unwrap() = 20.0;
+    assert_eq!(cells_param.circle_size, 20.0);
+    assert_eq!(*cells_param.path::<Vec2>("cell_size").unwrap(), cells_param.cell_size);
+    assert!(cells_param.reflect_path("cell_table").is_err());
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    ring::{find_rings, update_rings, Ring, Rings},
+    CellTable, CellsParam,
+};
+
+const DEFAULT_LAYOUT: &str = "\
+    ┌→→→→→→→→→┐
+    ↑ ┌→→→→→┐ ↓
+    ↑ ↑0   0↓ ↓
+    ↑ └←←←←←┘ ↓
+    ↑ ┏←┓ ┌→┐ ↓
+    ↑ ↓0↑ ↑0↓ ↓
+    ↑ ┗→┛ └←┘ ↓
+    ↑ ┏←←←←←┓ ↓
+    ↑ ↓0   0↑ ↓
+    ↑ ┗→→→→→┛ ↓
+    └←←←←←←←←←┘";
+
+fn rings(layout: &str) -> Vec<Ring> {
+    find_rings(&CellsParam::new(CellTable::new(layout)))
+}
+
+#[test]
+fn default_layout_has_five_rings() {
+    let rings = rings(DEFAULT_LAYOUT);
+    assert_eq!(rings.iter().map(Ring::len).collect::<Vec<_>>(), vec![40, 16, 16, 8, 8]);
+
+    let rings = Rings::new(rings);
+    assert_eq!(rings.global_period(), 80);
+    assert_eq!(rings.global_phase(20.0), 0.25);
+    assert_eq!(rings.0[3].phase(20.0), 0.5);
+    for (i, ring) in rings.0.iter().enumerate() {
+        assert!(ring.cells.iter().all(|cell| rings.ring_of(*cell) == Some(i)));
+    }
+    // a center
+    assert_eq!(rings.ring_of(UVec2::new(3, 2)), None);
+}
+
+#[test]
+fn tails_and_dead_ends_are_not_rings() {
+    // a tail running into a 2x2 ring, and a dead end
+    let rings = rings("\
+        →┌┐
+        ↑└┘
+        →→ ");
+    assert_eq!(rings, vec![Ring { cells: vec![[1, 0].into(), [2, 0].into(), [2, 1].into(), [1, 1].into()] }]);
+}
+
+#[test]
+fn rings_are_found_again_only_for_a_new_table() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<Rings>()
+        .insert_resource(CellsParam::new(CellTable::new(DEFAULT_LAYOUT)))
+        .add_systems(Update, update_rings);
+    app.update();
+    assert_eq!(app.world().resource::<Rings>().0.len(), 5);
+    let found = app.world().resource_ref::<Rings>().last_changed();
+
+    // what LFOs do every frame
+    app.world_mut().resource_mut::<CellsParam>().circle_size += 1.0;
+    app.update();
+    assert_eq!(app.world().resource_ref::<Rings>().last_changed(), found);
+
+    app.world_mut().resource_mut::<CellsParam>().cell_table = CellTable::new("┌→┐\n↑0↓\n└←┘");
+    app.update();
+    assert_ne!(app.world().resource_ref::<Rings>().last_changed(), found);
+    assert_eq!(app.world().resource::<Rings>().0.len(), 1);
+}
+
+#[test]
+fn the_period_of_many_coprime_rings_saturates() {
+    let primes = [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71];
+    let rings = Rings::new(
+        primes
+            .iter()
+            .enumerate()
+            .map(|(y, len)| Ring { cells: (0..*len).map(|x| UVec2::new(x, y as u32)).collect() })
+            .collect(),
+    );
+    assert_eq!(rings.global_period(), u64::MAX);
+    assert!((0.0..=1.0).contains(&rings.global_phase(1e6)));
+
+    // a few of them still line up exactly
+    let few = Rings::new(rings.0[..4].to_vec());
+    assert_eq!(few.global_period(), 210);
+}
@@ -0,0 +1,12 @@
+#![cfg(feature = "server")]
+
+use moving_cells_01::server::parse_command;
+
+#[test]
+fn clients_flip_rings_by_index() {
+    assert_eq!(parse_command("flip_ring 2").map(|flip| flip.0), Some(2));
+    assert_eq!(parse_command("  flip_ring   0 ").map(|flip| flip.0), Some(0));
+    assert!(parse_command("flip_ring").is_none());
+    assert!(parse_command("flip_ring -1").is_none());
+    assert!(parse_command("reverse").is_none());
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    shading::{
+        shade_circles, shade_for_blending, swap_in_shading, travel_direction, Blend, CircleMaterial, CircleShading, Shaded,
+    },
+    spawn_cells, Cell, CellTable, CellsParam, MoveType,
+};
+
+#[test]
+fn directions_follow_the_glyphs() {
+    assert_eq!(travel_direction(MoveType::Right), Vec2::X);
+    assert_eq!(travel_direction(MoveType::Down), Vec2::NEG_Y);
+    assert_eq!(travel_direction(MoveType::TopToRight), Vec2::new(1.0, -1.0).normalize());
+    assert_eq!(travel_direction(MoveType::Center), Vec2::ZERO);
+}
+
+#[test]
+fn circles_are_drawn_shaded_in_their_colors() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<CircleMaterial>>()
+        .insert_resource(CellsParam::new(CellTable::new("→↓")))
+        .insert_resource(CircleShading::default())
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, (swap_in_shading, shade_circles).chain());
+    app.update();
+
+    let world = app.world_mut();
+    let red = {
+        let mut cells = world.query::<(&Cell, &MeshMaterial2d<ColorMaterial>)>();
+        let (_, material) = cells.iter(world).find(|(cell, _)| cell.move_type == MoveType::Right).unwrap();
+        material.0.clone()
+    };
+    world.resource_mut::<Assets<ColorMaterial>>().get_mut(&red).unwrap().color = Color::srgb(1.0, 0.0, 0.0);
+    world.resource_mut::<CellsParam>().circle_size = 15.0;
+    app.update();
+
+    let world = app.world_mut();
+    assert!(world.query_filtered::<(), (With<Cell>, With<Mesh2d>)>().iter(world).next().is_none());
+    let mut shaded = world.query_filtered::<(&ChildOf, &MeshMaterial2d<CircleMaterial>, &Transform), With<Shaded>>();
+    let shaded = shaded
+        .iter(world)
+        .map(|(parent, material, transform)| {
+            let cell = world.get::<Cell>(parent.parent()).unwrap();
+            let shade = world.resource::<Assets<CircleMaterial>>().get(&material.0).unwrap().shade;
+            (cell.move_type, shade.color, shade.direction, transform.scale)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(shaded.len(), 2);
+    for (move_type, color, direction, scale) in shaded {
+        assert_eq!(direction, travel_direction(move_type));
+        assert_eq!(scale, Vec3::splat(15.0));
+        if move_type == MoveType::Right {
+            assert_eq!(color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+        }
+    }
+}
+
+#[test]
+fn additive_circles_are_shaded_flat() {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<CircleMaterial>>()
+        .insert_resource(CellsParam { blend: Blend::Additive, ..CellsParam::new(CellTable::new("→↓")) })
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(
+            Update,
+            (shade_for_blending, (swap_in_shading, shade_circles).chain().run_if(resource_exists::<CircleShading>)).chain(),
+        );
+    app.update();
+    app.update();
+
+    let world = app.world_mut();
+    assert_eq!(world.resource::<CircleShading>().gradient, 0.0);
+    let materials = world.resource::<Assets<CircleMaterial>>();
+    assert_eq!(materials.len(), 2);
+    assert!(materials.iter().all(|(_, m)| m.blend == Blend::Additive && m.shade.glow == 0.0));
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+
+use moving_cells_01::{
+    shape::{GlyphClass, Shape},
+    spawn_cells, Cell, CellTable, CellsParam, MoveType,
+};
+
+fn extent(mesh: &Mesh) -> Vec2 {
+    let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+        panic!("no positions");
+    };
+    positions.iter().fold(Vec2::ZERO, |m, p| m.max(Vec2::new(p[0], p[1]).abs()))
+}
+
+#[test]
+fn every_shape_is_about_as_wide_as_the_circle() {
+    let shapes = [
+        Shape::Circle,
+        Shape::Square,
+        Shape::RoundedRect { corner: 0.4 },
+        Shape::Triangle,
+        Shape::Hexagon,
+        Shape::Star { points: 5, inner: 0.5 },
+        Shape::Ring { thickness: 0.3 },
+    ];
+    for shape in shapes {
+        let mesh = shape.mesh(10.0);
+        let extent = extent(&mesh);
+        assert!(extent.max_element() > 9.9 && extent.max_element() < 10.1, "{:?}: {}", shape, extent);
+        assert!(mesh.indices().is_some_and(|i| i.len() % 3 == 0), "{:?}", shape);
+    }
+}
+
+#[test]
+fn stars_have_a_tip_and_a_notch_per_point() {
+    let mesh = Shape::Star { points: 5, inner: 0.4 }.mesh(10.0);
+    // the center and the outline
+    assert_eq!(mesh.count_vertices(), 1 + 10);
+    assert_eq!(mesh.indices().unwrap().len(), 10 * 3);
+}
+
+#[test]
+fn glyphs_are_classed_by_how_they_cross_the_cell() {
+    assert_eq!(GlyphClass::of(MoveType::Up), Some(GlyphClass::Straight));
+    assert_eq!(GlyphClass::of(MoveType::RightToBottom), Some(GlyphClass::Corner));
+    assert_eq!(GlyphClass::of(MoveType::Center), Some(GlyphClass::Center));
+    assert_eq!(GlyphClass::of(MoveType::Blank), None);
+}
+
+#[test]
+fn glyph_classes_can_have_their_own_shape() {
+    let cells_param = CellsParam {
+        shape: Shape::Hexagon,
+        class_shapes: HashMap::from([(GlyphClass::Corner, Shape::Square)]),
+        ..CellsParam::new(CellTable::new("┏→0"))
+    };
+    assert_eq!(cells_param.shape_for(MoveType::RightToBottom), Shape::Square);
+    assert_eq!(cells_param.shape_for(MoveType::Right), Shape::Hexagon);
+
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(cells_param)
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        );
+    app.update();
+
+    let world = app.world_mut();
+    let mut cells = world.query::<(&Cell, &Mesh2d)>();
+    let meshes = cells
+        .iter(world)
+        .map(|(cell, mesh)| (cell.move_type, mesh.0.clone()))
+        .collect::<HashMap<_, _>>();
+    // the straight and the center cell share the hexagon
+    assert_eq!(meshes[&MoveType::Right], meshes[&MoveType::Center]);
+    assert_ne!(meshes[&MoveType::Right], meshes[&MoveType::RightToBottom]);
+    let assets = world.resource::<Assets<Mesh>>();
+    assert_eq!(assets.len(), 2);
+    let square = extent(assets.get(&meshes[&MoveType::RightToBottom]).unwrap());
+    assert_eq!(square, Vec2::splat(10.0));
+}
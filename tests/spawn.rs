@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    agent::{Agent, TravelMode},
+    spawn_cells, Cell, CellTable, CellsParam, MoveType, StaticCell,
+};
+
+fn spawned(cells_param: CellsParam) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(cells_param)
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        );
+    app.update();
+    app
+}
+
+#[test]
+fn blank_cells_get_no_circle() {
+    let mut app = spawned(CellsParam::new(CellTable::new("→ 0\n↑ ←")));
+
+    let world = app.world_mut();
+    let mut cells = world.query::<&Cell>();
+    let move_types = cells.iter(world).map(|cell| cell.move_type).collect::<Vec<_>>();
+    assert_eq!(move_types.len(), 4);
+    assert!(!move_types.contains(&MoveType::Blank));
+}
+
+#[test]
+fn track_cells_get_evenly_spaced_circles() {
+    let four_per_cell = |travel_mode| CellsParam {
+        circles_per_cell: 4,
+        travel_mode,
+        ..CellsParam::new(CellTable::new("→0"))
+    };
+    let mut app = spawned(four_per_cell(TravelMode::Oscillate));
+
+    let world = app.world_mut();
+    let mut cells = world.query::<&Cell>();
+    let mut shifts = cells
+        .iter(world)
+        .filter(|cell| cell.move_type == MoveType::Right)
+        .map(|cell| cell.shift)
+        .collect::<Vec<_>>();
+    shifts.sort_by(f32::total_cmp);
+    assert_eq!(shifts, [0.0, 0.25, 0.5, 0.75]);
+    assert_eq!(cells.iter(world).filter(|cell| cell.move_type == MoveType::Center).count(), 1);
+
+    let mut app = spawned(four_per_cell(TravelMode::Agents));
+    let world = app.world_mut();
+    let mut agents = world.query::<&Agent>();
+    let mut progress = agents.iter(world).map(|agent| agent.progress).collect::<Vec<_>>();
+    progress.sort_by(f32::total_cmp);
+    assert_eq!(progress, [0.0, 0.25, 0.5, 0.75]);
+}
+
+#[test]
+fn only_centers_are_static() {
+    let mut app = spawned(CellsParam::new(CellTable::new("→ 0\n0 ←")));
+
+    let world = app.world_mut();
+    let mut cells = world.query::<(&Cell, Has<StaticCell>)>();
+    assert!(cells.iter(world).all(|(cell, still)| still == (cell.move_type == MoveType::Center)));
+    assert_eq!(cells.iter(world).filter(|(_, still)| *still).count(), 2);
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{PrimaryWindow, WindowResized, WindowResolution},
+};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    camera::{fit_camera, unswing_camera, CameraFit},
+    clock::{MotionPhase, SimClockPlugin},
+    direction::{toggle_direction, FlowDirection},
+    easing::Easing,
+    input::{Action, InputMap},
+    cell_offset, move_cells, spawn_cells,
+    split::{compared_shift, split_viewports, Compared, SplitScreen, SplitScreenPlugin},
+    Cell, CellTable, CellsParam, CornerStyle,
+};
+
+#[test]
+fn the_compared_table_sits_right_of_the_main_one() {
+    let main = CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘"));
+    let compared = CellsParam::new(CellTable::new("┌→→→┐\n└←←←┘"));
+    let shift = compared_shift(&main, &compared);
+    let (main_bounds, bounds) = (main.bounds(), compared.bounds());
+    let moved = Rect::from_corners(bounds.min + shift, bounds.max + shift);
+    assert!(moved.min.x > main_bounds.max.x);
+    assert_eq!(moved.center().y, main_bounds.center().y);
+}
+
+#[test]
+fn viewports_halve_the_window() {
+    let [left, right] = split_viewports(UVec2::new(1001, 600));
+    assert_eq!((left.physical_position, left.physical_size), (UVec2::ZERO, UVec2::new(500, 600)));
+    assert_eq!((right.physical_position, right.physical_size), (UVec2::new(500, 0), UVec2::new(500, 600)));
+}
+
+fn split_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimClockPlugin, SplitScreenPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .add_event::<WindowResized>()
+        .init_resource::<FlowDirection>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<CameraFit>()
+        .insert_resource(CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘")))
+        .insert_resource(SplitScreen {
+            cells_param: CellsParam {
+                easing: Easing::SineInOut,
+                corner_style: CornerStyle::Straight,
+                ..CellsParam::new(CellTable::new("┌→→→┐\n└←←←┘"))
+            },
+        })
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                commands.spawn((
+                    Window { resolution: WindowResolution::new(1000.0, 500.0), ..default() },
+                    PrimaryWindow,
+                ));
+                commands.spawn((Camera2d, OutputName("main".into())));
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        )
+        .add_systems(Update, (unswing_camera, fit_camera, move_cells).chain());
+    app.update();
+    app.update();
+    app
+}
+
+fn cameras(app: &mut App) -> Vec<(String, Option<UVec2>, Vec3)> {
+    let world = app.world_mut();
+    let mut cameras = world
+        .query::<(&OutputName, &Camera, &Transform)>()
+        .iter(world)
+        .map(|(name, camera, transform)| {
+            (name.0.clone(), camera.viewport.as_ref().map(|v| v.physical_position), transform.translation)
+        })
+        .collect::<Vec<_>>();
+    cameras.sort_by(|a, b| a.0.cmp(&b.0));
+    cameras
+}
+
+#[test]
+fn each_camera_shows_its_table_on_its_half() {
+    let mut app = split_app();
+    let shift = {
+        let world = app.world();
+        compared_shift(world.resource::<CellsParam>(), &world.resource::<SplitScreen>().cells_param)
+    };
+    let compared_center = app.world().resource::<SplitScreen>().cells_param.bounds().center() + shift;
+    let main_center = app.world().resource::<CellsParam>().bounds().center();
+    assert_eq!(
+        cameras(&mut app),
+        vec![
+            ("compared".into(), Some(UVec2::new(500, 0)), compared_center.extend(0.0)),
+            ("main".into(), Some(UVec2::ZERO), main_center.extend(0.0)),
+        ]
+    );
+
+    // without the split, the main camera has the window back
+    app.world_mut().remove_resource::<SplitScreen>();
+    app.update();
+    app.update();
+    assert_eq!(cameras(&mut app), vec![("main".into(), None, main_center.extend(0.0))]);
+    let world = app.world_mut();
+    assert!(world.query_filtered::<(), With<Compared>>().iter(world).next().is_none());
+    assert_eq!(world.query::<&Cell>().iter(world).count(), 9);
+}
+
+#[test]
+fn the_compared_table_moves_by_its_own_parameters() {
+    let mut app = split_app();
+    for _ in 0..3 {
+        app.update();
+    }
+    let shift = {
+        let world = app.world();
+        compared_shift(world.resource::<CellsParam>(), &world.resource::<SplitScreen>().cells_param)
+    };
+    let world = app.world_mut();
+    let circles = world
+        .query::<(&Cell, &Transform, Has<Compared>)>()
+        .iter(world)
+        .map(|(cell, transform, compared)| (cell.clone(), transform.translation.truncate(), compared))
+        .collect::<Vec<_>>();
+    assert_eq!(circles.iter().filter(|(.., compared)| *compared).count(), 10);
+    assert_eq!(circles.iter().filter(|(.., compared)| !*compared).count(), 9);
+
+    let rate = world.resource::<MotionPhase>().rate();
+    let flow = world.resource::<FlowDirection>();
+    let (main, split) = (world.resource::<CellsParam>(), world.resource::<SplitScreen>());
+    for (cell, at, compared) in &circles {
+        let params = if *compared { &split.cells_param } else { main };
+        let origin = if *compared { params.cell_center(cell.grid.x as usize, cell.grid.y as usize) + shift } else { cell.pos };
+        assert_eq!(cell.pos, origin);
+        let offset = cell_offset(cell, params, flow, rate, 0.0, &[], params.cell_size);
+        assert!((*at - (cell.pos + offset)).length() < 1e-3, "{:?} {} vs {}", cell.grid, at, cell.pos + offset);
+    }
+}
+
+#[test]
+fn reversing_reverses_the_compared_table_too() {
+    let mut app = split_app();
+    app.init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputMap>()
+        .add_systems(Update, toggle_direction.before(move_cells));
+    let compared = |app: &mut App| {
+        let world = app.world_mut();
+        let mut circles = world.query_filtered::<Entity, With<Compared>>().iter(world).collect::<Vec<_>>();
+        circles.sort();
+        circles
+    };
+    let before = (app.world().resource::<SplitScreen>().cells_param.cell_table.get(1, 0), compared(&mut app));
+    let key = app.world().resource::<InputMap>().key(Action::Reverse).unwrap();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    // turned round in place, not respawned
+    let after = (app.world().resource::<SplitScreen>().cells_param.cell_table.get(1, 0), compared(&mut app));
+    assert_eq!((before.0, after.0), ('→', '←'));
+    assert_eq!(before.1, after.1);
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    spring::{apply_springs, Spring},
+    Cell, CellTable, CellsParam, MoveType,
+};
+
+fn settle(spring: Spring) -> (f32, Vec2) {
+    let (mut pos, mut vel) = (Vec2::ZERO, Vec2::ZERO);
+    let mut furthest = 0.0f32;
+    for _ in 0..180 {
+        spring.step(&mut pos, &mut vel, Vec2::new(10.0, 0.0), 1.0 / 60.0);
+        furthest = furthest.max(pos.x);
+    }
+    (furthest, pos)
+}
+
+#[test]
+fn underdamped_springs_overshoot_and_settle() {
+    let (furthest, pos) = settle(Spring::default());
+    assert!(furthest > 10.5, "{}", furthest);
+    assert!((pos - Vec2::new(10.0, 0.0)).length() < 0.01);
+
+    let critical = Spring { stiffness: 300.0, damping: 2.0 * 300.0f32.sqrt() };
+    let (furthest, pos) = settle(critical);
+    assert!(furthest <= 10.0 + 1e-3, "{}", furthest);
+    assert!((pos - Vec2::new(10.0, 0.0)).length() < 0.01);
+}
+
+#[test]
+fn circles_chase_where_the_movers_put_them() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(50)))
+        .insert_resource(CellsParam { spring: Some(Spring::default()), ..CellsParam::new(CellTable::new("→")) })
+        // stands in for move_cells, which writes the target every frame
+        .add_systems(Update, (place, apply_springs).chain());
+    let circle = app
+        .world_mut()
+        .spawn((Transform::default(), Cell::new(UVec2::ZERO, Vec2::ZERO, Vec2::ONE, MoveType::Right)))
+        .id();
+    let x = |app: &App| app.world().get::<Transform>(circle).unwrap().translation.x;
+
+    app.update();
+    assert_eq!(x(&app), 0.0);
+    app.insert_resource(Target(10.0));
+    app.update();
+    assert!(x(&app) > 0.0 && x(&app) < 10.0, "{}", x(&app));
+
+    // off again: back on the interpolated position at once
+    app.world_mut().resource_mut::<CellsParam>().spring = None;
+    app.update();
+    assert_eq!(x(&app), 10.0);
+}
+
+#[derive(Resource, Default)]
+struct Target(f32);
+
+fn place(target: Option<Res<Target>>, mut circles: Query<&mut Transform, With<Cell>>) {
+    for mut transform in circles.iter_mut() {
+        transform.translation.x = target.as_ref().map_or(0.0, |t| t.0);
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    spawn_cells,
+    sprites::{read_manifest, GlyphSprites, GlyphSpritesPlugin, SpriteManifest},
+    Cell, CellTable, CellsParam, MoveType,
+};
+
+#[test]
+fn manifests_map_glyphs_to_images() {
+    let path = std::env::temp_dir().join("moving_cells_01.test.sprites.ron");
+    std::fs::write(&path, "{ '0': \"sprites/star.png\", '→': \"sprites/arrow.png\" }").unwrap();
+    let manifest = read_manifest(&path).unwrap().unwrap().unwrap();
+    assert_eq!(
+        manifest,
+        SpriteManifest(HashMap::from([('0', "sprites/star.png".into()), ('→', "sprites/arrow.png".into())])),
+    );
+
+    std::fs::write(&path, "{ '0' }").unwrap();
+    assert!(read_manifest(&path).unwrap().unwrap().is_err());
+    std::fs::remove_file(&path).unwrap();
+    assert!(read_manifest(&path).unwrap().is_none());
+}
+
+#[test]
+fn cells_with_a_sprite_lose_their_circle() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, GlyphSpritesPlugin))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(CellsParam::new(CellTable::new("→0")))
+        .insert_resource(GlyphSprites(HashMap::from([('0', Handle::default())])))
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        );
+    app.update();
+
+    let world = app.world_mut();
+    let mut cells = world.query::<(&Cell, Option<&Sprite>, Option<&Mesh2d>)>();
+    for (cell, sprite, mesh) in cells.iter(world) {
+        if cell.move_type == MoveType::Center {
+            assert!(mesh.is_none());
+            assert_eq!(sprite.and_then(|s| s.custom_size), Some(Vec2::splat(20.0)));
+        } else {
+            assert!(mesh.is_some() && sprite.is_none());
+        }
+    }
+
+    app.world_mut().resource_mut::<CellsParam>().circle_size = 15.0;
+    app.update();
+    let world = app.world_mut();
+    let mut sprites = world.query::<&Sprite>();
+    assert_eq!(sprites.single(world).unwrap().custom_size, Some(Vec2::splat(30.0)));
+}
@@ -0,0 +1,22 @@
+use moving_cells_01::Stepping;
+
+#[test]
+fn glide_is_the_identity() {
+    for rate in [0.0, 0.3, 0.99] {
+        assert_eq!(Stepping::Glide.apply(rate), rate);
+    }
+}
+
+#[test]
+fn hops_rest_in_the_middle_of_the_cell() {
+    let hop = Stepping::Hop { swing: 0.5 };
+    // the second half of the hop, arriving from the previous cell
+    assert_eq!(hop.apply(0.0), 0.0);
+    assert_eq!(hop.apply(0.125), 0.25);
+    // resting
+    assert_eq!(hop.apply(0.25), 0.5);
+    assert_eq!(hop.apply(0.6), 0.5);
+    assert_eq!(hop.apply(0.75), 0.5);
+    // the first half of the hop, towards the next cell
+    assert_eq!(hop.apply(0.875), 0.75);
+}
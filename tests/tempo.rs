@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::{MotionPhase, SimClockPlugin},
+    tempo::{TapTempo, Tempo},
+};
+
+#[test]
+fn span_follows_bpm_and_beats_per_cell() {
+    let tempo = Tempo { bpm: 120.0, beats_per_cell: 2.0 };
+    assert_eq!(tempo.span_sec(), 1.0);
+}
+
+#[test]
+fn tempo_changes_keep_the_phase() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)));
+
+    // the first frame has no delta
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<MotionPhase>().rate(), 0.5);
+
+    // a quarter second at half the speed only moves an eighth of a cell on
+    app.world_mut().resource_mut::<Tempo>().bpm = 30.0;
+    app.update();
+    assert_eq!(app.world().resource::<MotionPhase>().rate(), 0.625);
+}
+
+#[test]
+fn tap_tempo_averages_intervals_and_resets_after_a_pause() {
+    let mut taps = TapTempo::default();
+    assert_eq!(taps.tap(10.0), None);
+    assert_eq!(taps.tap(10.5), Some(120.0));
+    assert_eq!(taps.tap(11.0), Some(120.0));
+    assert_eq!(taps.tap(11.6), Some(112.5));
+
+    // a long pause starts over
+    assert_eq!(taps.tap(20.0), None);
+    assert_eq!(taps.tap(21.0), Some(60.0));
+}
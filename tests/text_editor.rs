@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    dialect::{Dialect, LayoutSource},
+    text_editor::{ApplyLayoutText, LayoutText, TextEditorPlugin},
+    CellTable, CellsParam, LayoutError, RespawnCells,
+};
+
+const RING: &str = "┌→┐\n↑0↓\n└←┘";
+
+fn editor_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TextEditorPlugin))
+        .add_event::<RespawnCells>()
+        .insert_resource(CellsParam::new(CellTable::new(RING)))
+        .insert_resource(LayoutSource { text: RING.into(), dialect: Dialect::Keisen });
+    app.update();
+    app
+}
+
+fn apply(app: &mut App, text: &str) {
+    app.world_mut().resource_mut::<LayoutText>().text = text.into();
+    app.world_mut().send_event(ApplyLayoutText);
+    app.update();
+}
+
+#[test]
+fn the_text_follows_the_layout_until_it_is_edited() {
+    let mut app = editor_app();
+    assert_eq!(app.world().resource::<LayoutText>().text, RING);
+
+    app.world_mut().resource_mut::<LayoutText>().text = "┌┐\n└┘".into();
+    app.insert_resource(LayoutSource { text: "┌→→┐\n└←←┘".into(), dialect: Dialect::Keisen });
+    app.update();
+    let layout_text = app.world().resource::<LayoutText>();
+    assert_eq!(layout_text.text, "┌┐\n└┘");
+    assert!(layout_text.is_edited());
+}
+
+#[test]
+fn applying_replaces_the_layout() {
+    let mut app = editor_app();
+    apply(&mut app, "┌→→┐\n└←←┘");
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.to_text(), "┌→→┐\n└←←┘");
+    assert_eq!(app.world().resource::<LayoutSource>().text, "┌→→┐\n└←←┘");
+    assert_eq!(app.world().resource::<Events<RespawnCells>>().len(), 1);
+    assert!(!app.world().resource::<LayoutText>().is_edited());
+}
+
+#[test]
+fn bad_text_is_kept_and_the_culprit_marked() {
+    let mut app = editor_app();
+    apply(&mut app, "# a ring\n  ┌→┐\n  ↑x↓\n  └←┘");
+    assert_eq!(app.world().resource::<CellsParam>().cell_table.to_text(), RING);
+    let layout_text = app.world().resource::<LayoutText>();
+    assert_eq!(layout_text.error, Some(LayoutError::InvalidChar { c: 'x', x: 1, y: 1 }));
+    assert!(layout_text.is_edited());
+    assert_eq!(layout_text.error_position(Dialect::Keisen), Some((2, 3)));
+    let range = layout_text.error_range(Dialect::Keisen).unwrap();
+    assert_eq!(&layout_text.text[range], "x");
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    direction::FlowDirection, thickness::Thickness, Cell, CellTable, CellsParam, LayoutError, MoveType,
+};
+
+#[test]
+fn thick_corners_run_counter_clockwise_by_default() {
+    let table = CellTable::parse("# a comment\n┏┓\n┗┛").unwrap();
+    assert_eq!(table.thickness, Thickness::Direction);
+    assert!(table.heavy.is_empty());
+    assert_eq!(table.to_text(), "┏┓\n┗┛");
+}
+
+#[test]
+fn with_speed_thick_corners_follow_their_neighbours() {
+    let table = CellTable::parse("# thickness: speed\n┏→┐\n└←┛").unwrap();
+    assert_eq!(table.thickness, Thickness::Speed);
+    // the clockwise ring, with two of its corners drawn thick
+    assert_eq!(table.table, vec![vec!['┌', '→', '┐'], vec!['└', '←', '┘']]);
+    assert_eq!(table.to_text(), "# thickness: speed\n┏→┐\n└←┛");
+    assert_eq!(table.heavy.len(), 2);
+    assert!(table.heavy.contains(&UVec2::new(0, 0)) && table.heavy.contains(&UVec2::new(2, 1)));
+    assert_eq!(table.speed(0, 0), 2.0);
+    assert_eq!(table.speed(1, 0), 1.0);
+    assert_eq!(table.density(0, 0), 1);
+
+    // corners nothing decides on keep their classic reading
+    let lone = CellTable::parse("# thickness: speed\n┏┓\n┗┛").unwrap();
+    assert_eq!(lone.to_text(), "# thickness: speed\n┏┓\n┗┛");
+    assert_eq!(lone.heavy.len(), 4);
+}
+
+#[test]
+fn thick_cells_are_crossed_faster() {
+    let cells_param = CellsParam::new(CellTable::parse("# thickness: speed\n┏→┐\n└←┛").unwrap());
+    let flow = FlowDirection::default();
+    let heavy = Cell::new(UVec2::new(0, 0), Vec2::ZERO, Vec2::ONE, MoveType::BottomToRight);
+    let light = Cell::new(UVec2::new(1, 0), Vec2::ZERO, Vec2::ONE, MoveType::Right);
+    assert_eq!(heavy.progress(&cells_param, &flow, 0.25), 0.5);
+    assert_eq!(light.progress(&cells_param, &flow, 0.25), 0.25);
+}
+
+#[test]
+fn with_density_thick_cells_carry_more_circles() {
+    let table = CellTable::parse("# thickness: density\n┏→┐\n└←┛").unwrap();
+    assert_eq!(table.density(0, 0), 2);
+    assert_eq!(table.density(1, 0), 1);
+    assert_eq!(table.speed(0, 0), 1.0);
+}
+
+#[test]
+fn unknown_thickness_is_an_error() {
+    assert_eq!(
+        CellTable::parse("# thickness: loud\n┏┓\n┗┛").err(),
+        Some(LayoutError::InvalidMetadata { line: "# thickness: loud".into() }),
+    );
+}
+
+#[test]
+fn thickness_and_thick_corners_survive_the_text() {
+    for layout in ["# thickness: speed\n┏→┐\n└←┛", "# thickness: density\n ┏→┐\n ┗←┘", "┏┓\n┗┛"] {
+        let table = CellTable::parse(layout).unwrap();
+        let again = CellTable::from_text(&table.to_text()).unwrap();
+        assert_eq!(again.thickness, table.thickness, "{}", layout);
+        assert_eq!(again.heavy, table.heavy, "{}", layout);
+        assert_eq!(again.table, table.table, "{}", layout);
+    }
+
+    // leading blanks are kept
+    let indented = CellTable::from_text("# thickness: density\n ┏┓\n ┗┛").unwrap();
+    assert_eq!(indented.to_text(), "# thickness: density\n ┏┓\n ┗┛");
+}
@@ -0,0 +1,173 @@
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+
+use moving_cells_01::{
+    calibration::OutputName,
+    input::{Action, InputMap},
+    spawn_cells,
+    ring::{find_rings, Rings},
+    three_d::{tube, Ball, Board, Orbit, Rail, ThreeD, ThreeDPlugin},
+    tween::SizeTween,
+    Cell, CellTable, CellsParam, RespawnCells,
+};
+
+#[test]
+fn circles_roll_on_the_ground_as_spheres() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, TransformPlugin, ThreeDPlugin))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<AccumulatedMouseMotion>()
+        .init_resource::<AccumulatedMouseScroll>()
+        .init_resource::<InputMap>()
+        .init_resource::<SizeTween>()
+        .add_event::<RespawnCells>()
+        .init_resource::<Rings>()
+        .insert_resource(CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘")))
+        .insert_resource(ThreeD::default())
+        .add_systems(
+            Startup,
+            |mut commands: Commands,
+             cells_param: Res<CellsParam>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<ColorMaterial>>| {
+                commands.spawn((Camera2d, OutputName("main".into())));
+                spawn_cells(&mut commands, &cells_param, &mut meshes, &mut materials);
+            },
+        );
+    let rings = find_rings(app.world().resource::<CellsParam>());
+    app.insert_resource(Rings::new(rings));
+    app.update();
+    app.update();
+
+    let world = app.world_mut();
+    assert!(world.query_filtered::<&Camera, With<Camera3d>>().single(world).is_ok_and(|c| c.is_active));
+    assert!(world.query_filtered::<&Camera, With<Camera2d>>().iter(world).all(|c| !c.is_active));
+
+    let board = world.query_filtered::<Entity, With<Board>>().single(world).unwrap();
+    let mut cells = world.query_filtered::<(&Transform, &GlobalTransform, &ChildOf, Option<&Mesh2d>, Option<&Mesh3d>), With<Cell>>();
+    assert_eq!(cells.iter(world).count(), 9);
+    for (transform, global, parent, mesh2d, mesh3d) in cells.iter(world) {
+        assert_eq!(parent.parent(), board);
+        assert!(mesh2d.is_none() && mesh3d.is_some());
+        // the layout's y runs away from the camera, the spheres rest on the ground
+        let t = transform.translation;
+        assert!(global.translation().abs_diff_eq(Vec3::new(t.x, 10.0, -t.y), 1e-3), "{:?}", global.translation());
+    }
+
+    // one rail along the ring, on the ground
+    let mut rails = world.query::<(&Rail, &ChildOf, &GlobalTransform)>();
+    let (rail, parent, global) = rails.single(world).unwrap();
+    assert_eq!((rail.0, parent.parent()), (0, board));
+    assert!(global.translation().abs_diff_eq(Vec3::ZERO, 1e-3));
+
+    // colors carry over
+    let (ball, material) = {
+        let mut balls = world.query::<(&Ball, &MeshMaterial2d<ColorMaterial>)>();
+        let (ball, material) = balls.iter(world).next().unwrap();
+        (ball.0.clone(), material.0.clone())
+    };
+    world.resource_mut::<Assets<ColorMaterial>>().get_mut(&material).unwrap().color = Color::srgba(1.0, 0.0, 0.0, 0.5);
+    app.update();
+    let world = app.world_mut();
+    assert_eq!(world.resource::<Assets<StandardMaterial>>().get(&ball).unwrap().base_color, Color::srgba(1.0, 0.0, 0.0, 0.5));
+
+    // and back to flat
+    world.remove_resource::<ThreeD>();
+    app.update();
+    let world = app.world_mut();
+    assert!(world.query_filtered::<(), With<Board>>().iter(world).next().is_none());
+    assert!(world.query_filtered::<(), With<Camera3d>>().iter(world).next().is_none());
+    assert!(world.query_filtered::<&Camera, With<Camera2d>>().single(world).unwrap().is_active);
+    assert!(!world.resource::<Events<RespawnCells>>().is_empty());
+}
+
+#[test]
+fn tubes_wrap_around_their_path() {
+    let path = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)];
+    let mesh = tube(&path, 0.25, 8);
+    assert_eq!(mesh.count_vertices(), 4 * 8);
+    assert_eq!(mesh.indices().unwrap().len(), 4 * 8 * 6);
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap().as_float3().unwrap();
+    for (i, point) in path.iter().enumerate() {
+        for p in &positions[i * 8..(i + 1) * 8] {
+            assert!(((Vec3::from_array(*p) - point.extend(0.0)).length() - 0.25).abs() < 1e-5);
+        }
+    }
+    // the first vertex of every section is on top
+    assert!(positions.iter().step_by(8).all(|p| p[2] == 0.25));
+}
+
+#[test]
+fn orbit_puts_the_camera_around_the_middle() {
+    let orbit = Orbit { yaw: 0.0, pitch: 0.5, distance: 100.0, distances: (10.0, 1000.0), auto: false };
+    let transform = orbit.transform();
+    assert!(transform.translation.abs_diff_eq(Vec3::new(0.0, 100.0 * 0.5f32.sin(), 100.0 * 0.5f32.cos()), 1e-3));
+    assert!(transform.forward().dot(-transform.translation.normalize()) > 0.9999);
+
+    let turned = Orbit { yaw: FRAC_PI_2, ..orbit }.transform();
+    assert!((turned.translation.length() - 100.0).abs() < 1e-3);
+    assert!(turned.translation.x > 0.0 && turned.translation.z.abs() < 1e-3);
+}
+
+#[test]
+fn the_camera_orbits_on_its_own_and_follows_the_mouse() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, ThreeDPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(1)))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<AccumulatedMouseMotion>()
+        .init_resource::<AccumulatedMouseScroll>()
+        .init_resource::<InputMap>()
+        .init_resource::<SizeTween>()
+        .init_resource::<Rings>()
+        .add_event::<RespawnCells>()
+        .insert_resource(CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘")))
+        .insert_resource(ThreeD { auto_orbit: true, orbit_deg_per_sec: 10.0, ..default() });
+    app.update();
+    let orbit = |app: &mut App| {
+        let world = app.world_mut();
+        let (orbit, transform) = world.query::<(&Orbit, &Transform)>().single(world).unwrap();
+        assert_eq!(*transform, orbit.transform());
+        orbit.clone()
+    };
+    let start = orbit(&mut app);
+    assert!((start.pitch - 50f32.to_radians()).abs() < 1e-5);
+
+    app.update();
+    app.update();
+    let turned = orbit(&mut app);
+    assert!(turned.yaw > start.yaw && turned.yaw < start.yaw + 20f32.to_radians() + 1e-4, "{}", turned.yaw);
+
+    let key = app.world().resource::<InputMap>().key(Action::ToggleOrbit).unwrap();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+    let stopped = orbit(&mut app);
+    assert!(!stopped.auto);
+    app.update();
+    assert_eq!(orbit(&mut app).yaw, stopped.yaw);
+
+    // dragging up looks from lower down, scrolling comes closer, within limits
+    app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+    app.world_mut().resource_mut::<AccumulatedMouseMotion>().delta = Vec2::new(0.0, -100.0);
+    app.world_mut().resource_mut::<AccumulatedMouseScroll>().delta = Vec2::new(0.0, 1000.0);
+    app.update();
+    let moved = orbit(&mut app);
+    assert!(moved.pitch < stopped.pitch);
+    assert_eq!(moved.distance, moved.distances.0);
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    clock::SimClockPlugin,
+    easing::Easing,
+    lfo::LfoOffsets,
+    tempo::Tempo,
+    timeline::{Keyframe, ParamOverrides, Timeline, TimelinePlugin},
+    CellTable, CellsParam,
+};
+
+fn growing_and_speeding_up() -> Timeline {
+    Timeline::new(vec![
+        Keyframe::new(0.0, ParamOverrides { circle_size: Some(10.0), bpm: Some(60.0), ..default() }),
+        Keyframe::new(2.0, ParamOverrides { circle_size: Some(20.0), easing: Some(Easing::CubicInOut), ..default() }),
+        Keyframe::new(4.0, ParamOverrides { bpm: Some(120.0), ..default() }),
+    ])
+}
+
+#[test]
+fn every_field_is_its_own_track() {
+    let timeline = growing_and_speeding_up();
+
+    let at_one = timeline.sample(1.0);
+    assert_eq!(at_one.circle_size, Some(15.0));
+    // bpm goes from the first keyframe straight to the third
+    assert_eq!(at_one.bpm, Some(75.0));
+    // held before its only keyframe, switched once reached
+    assert_eq!(at_one.easing, Some(Easing::CubicInOut));
+    assert_eq!(at_one.cell_size, None);
+
+    let at_five = timeline.sample(5.0);
+    assert_eq!(at_five.circle_size, Some(20.0));
+    assert_eq!(at_five.bpm, Some(120.0));
+}
+
+#[test]
+fn looping_timelines_start_over() {
+    let mut timeline = growing_and_speeding_up();
+    timeline.looping = true;
+    assert_eq!(timeline.sample(5.0), timeline.sample(1.0));
+}
+
+#[test]
+fn the_timeline_drives_the_params() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin, TimelinePlugin))
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<LfoOffsets>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(CellsParam::new(CellTable::new("→")))
+        .insert_resource(growing_and_speeding_up());
+
+    // the first frame has no delta, then half a second
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<CellsParam>().circle_size, 12.5);
+    assert_eq!(app.world().resource::<Tempo>().bpm, 67.5);
+}
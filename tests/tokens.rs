@@ -0,0 +1,42 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::AssetPlugin,
+    gltf::{Gltf, GltfMesh, GltfPlugin},
+    prelude::*,
+};
+
+use moving_cells_01::{tokens::token_mesh, GltfAssets};
+
+#[test]
+fn the_token_model_has_a_mesh() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, AssetPlugin { file_path: concat!(env!("CARGO_MANIFEST_DIR"), "/assets").into(), ..default() }))
+        .init_asset::<Mesh>()
+        .init_asset::<Image>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Scene>()
+        .add_plugins(GltfPlugin::default());
+    app.finish();
+    let token = app.world().resource::<AssetServer>().load("models/token.glb");
+    app.insert_resource(GltfAssets { token });
+
+    let start = Instant::now();
+    loop {
+        app.update();
+        let world = app.world();
+        let found = token_mesh(
+            world.resource::<GltfAssets>(),
+            world.resource::<Assets<Gltf>>(),
+            world.resource::<Assets<GltfMesh>>(),
+        );
+        if let Some((mesh, material)) = found {
+            assert!(world.resource::<Assets<Mesh>>().get(&mesh).is_some_and(|m| m.count_vertices() == 24));
+            assert!(material.is_some());
+            break;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "token never loaded");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
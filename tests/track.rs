@@ -0,0 +1,57 @@
+use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+
+use moving_cells_01::{
+    capability::{Capabilities, Capability},
+    track::{track_mesh, track_path, TrackLines, TrackPlugin},
+    CellTable, CellsParam, CornerStyle, MoveType,
+};
+
+#[test]
+fn paths_run_from_edge_to_edge_round_the_corners() {
+    let half = Vec2::splat(25.0);
+    assert_eq!(track_path(MoveType::Right, half, CornerStyle::Arc), vec![Vec2::new(-25.0, 0.0), Vec2::new(25.0, 0.0)]);
+    assert!(track_path(MoveType::Center, half, CornerStyle::Arc).is_empty());
+
+    let arc = track_path(MoveType::BottomToRight, half, CornerStyle::Arc);
+    assert!(arc.len() > 2);
+    // a quarter circle round the corner the two edges share
+    for point in &arc {
+        assert!((point.distance(Vec2::new(25.0, -25.0)) - 25.0).abs() < 1e-3);
+    }
+    assert_eq!(track_path(MoveType::BottomToRight, half, CornerStyle::Straight).len(), 2);
+}
+
+#[test]
+fn one_band_per_track_cell() {
+    let cells_param = CellsParam { corner_style: CornerStyle::Straight, ..CellsParam::new(CellTable::new("→0→")) };
+    let mesh = track_mesh(&cells_param, cells_param.cell_size, 2.0);
+    let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+        panic!("the track has positions");
+    };
+    assert_eq!(positions.len(), 2 * 4);
+    let y = cells_param.cell_center(0, 0).y;
+    assert!(positions.iter().all(|p| (p[1] - y).abs() == 1.0));
+}
+
+#[test]
+fn the_track_comes_and_goes_with_its_capability() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, TrackPlugin))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(CellsParam::new(CellTable::new("→↓\n↑←")))
+        .insert_resource(Capabilities::default());
+    let lines = |app: &mut App| app.world_mut().query_filtered::<(), With<TrackLines>>().iter(app.world()).count();
+
+    app.update();
+    assert_eq!(lines(&mut app), 0);
+
+    app.world_mut().resource_mut::<Capabilities>().set(Capability::Track, true);
+    app.update();
+    assert_eq!(lines(&mut app), 1);
+
+    app.world_mut().resource_mut::<Capabilities>().set(Capability::Track, false);
+    app.update();
+    assert_eq!(lines(&mut app), 0);
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    capability::{Capabilities, Capability},
+    clock::SimClockPlugin,
+    trails::{TrailGhost, Trails, TrailsPlugin},
+    Cell, MoveType,
+};
+
+#[test]
+fn ghosts_follow_behind_fading_out() {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, SimClockPlugin, TrailsPlugin))
+        .init_resource::<Assets<ColorMaterial>>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(125)))
+        .insert_resource(Trails { length: 2, spacing_sec: 0.125, decay: 0.5 })
+        .add_systems(PreUpdate, step_right);
+    let mut capabilities = Capabilities::default();
+    capabilities.set(Capability::Trails, true);
+    app.insert_resource(capabilities);
+
+    let material = app.world_mut().resource_mut::<Assets<ColorMaterial>>().add(Color::WHITE);
+    app.world_mut().spawn((
+        Mesh2d(Handle::default()),
+        MeshMaterial2d(material),
+        Transform::default(),
+        Cell::new(UVec2::ZERO, Vec2::ZERO, Vec2::ONE, MoveType::Right),
+    ));
+
+    for _ in 0..5 {
+        app.update();
+    }
+    let mut trail = ghosts(&mut app);
+    trail.sort_by_key(|(index, ..)| *index);
+    // the circle is at 50 now, a frame of 10 apart
+    assert_eq!(trail, [(0, 40.0, 0.5), (1, 30.0, 0.25)]);
+
+    app.world_mut().resource_mut::<Trails>().length = 1;
+    app.update();
+    assert_eq!(ghosts(&mut app).len(), 1);
+
+    app.world_mut().resource_mut::<Capabilities>().toggle(Capability::Trails);
+    app.update();
+    app.update();
+    assert!(ghosts(&mut app).is_empty());
+}
+
+fn step_right(mut circles: Query<&mut Transform, With<Cell>>) {
+    for mut transform in circles.iter_mut() {
+        transform.translation.x += 10.0;
+    }
+}
+
+/// (index, x, alpha) of every ghost
+fn ghosts(app: &mut App) -> Vec<(usize, f32, f32)> {
+    let world = app.world_mut();
+    let mut query = world.query::<(&TrailGhost, &Transform, &MeshMaterial2d<ColorMaterial>)>();
+    let materials = world.resource::<Assets<ColorMaterial>>();
+    query
+        .iter(world)
+        .map(|(ghost, transform, material)| {
+            (ghost.index, transform.translation.x, materials.get(&material.0).unwrap().color.alpha())
+        })
+        .collect()
+}
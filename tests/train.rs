@@ -0,0 +1,29 @@
+use moving_cells_01::{agent::train_placements, ring::find_rings, CellTable, CellsParam};
+
+#[test]
+fn cars_of_a_train_are_spaced_by_path_length() {
+    // four quarter arcs of the same length
+    let cells_param = CellsParam::new(CellTable::new("┏┓\n┗┛"));
+    let ring = &find_rings(&cells_param)[0];
+    let placements = train_placements(&cells_param, ring, 8);
+    assert_eq!(placements.len(), 8);
+    for (k, (grid, progress)) in placements.iter().enumerate() {
+        assert_eq!(*grid, ring.cells[k / 2]);
+        let expected = if k % 2 == 0 { 0.0 } else { 0.5 };
+        assert!((progress - expected).abs() < 1e-3, "car {} at {}", k, progress);
+    }
+
+    // arcs are shorter than straights, but the ring is symmetric: the second
+    // of two cars is exactly opposite the first
+    let cells_param = CellsParam::new(CellTable::new("┏←┓\n┗→┛"));
+    let ring = &find_rings(&cells_param)[0];
+    assert_eq!(ring.len(), 6);
+    let placements = train_placements(&cells_param, ring, 2);
+    assert_eq!(placements[0], (ring.cells[0], 0.0));
+    assert_eq!(placements[1].0, ring.cells[3]);
+    assert!(placements[1].1.abs() < 1e-3, "{}", placements[1].1);
+
+    // spacing by cells would start the second of three cars on the third cell
+    let placements = train_placements(&cells_param, ring, 3);
+    assert_ne!(placements[1], (ring.cells[2], 0.0));
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use moving_cells_01::{
+    easing::Easing,
+    tween::{tween_sizes, SizeTween},
+    CellTable, CellsParam,
+};
+
+fn tween_app(duration_sec: f32) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+        .insert_resource(CellsParam::new(CellTable::new("→")))
+        .insert_resource(SizeTween::new(duration_sec, Easing::Linear))
+        .add_systems(Update, tween_sizes);
+    // the first frame has no delta
+    app.update();
+    app
+}
+
+fn shown(app: &App) -> (Vec2, f32) {
+    let cells_param = app.world().resource::<CellsParam>();
+    let tween = app.world().resource::<SizeTween>();
+    (tween.cell_size(cells_param), tween.circle_size(cells_param))
+}
+
+#[test]
+fn sizes_follow_the_parameters_over_the_duration() {
+    let mut app = tween_app(1.0);
+    assert_eq!(shown(&app), (Vec2::splat(50.0), 10.0));
+
+    app.world_mut().resource_mut::<CellsParam>().cell_size = Vec2::splat(150.0);
+    app.update();
+    assert_eq!(shown(&app).0, Vec2::splat(75.0));
+    app.update();
+    assert_eq!(shown(&app).0, Vec2::splat(100.0));
+
+    // retargeted mid-way, it carries on from where it is
+    app.world_mut().resource_mut::<CellsParam>().circle_size = 30.0;
+    app.update();
+    assert_eq!(shown(&app), (Vec2::splat(112.5), 15.0));
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(shown(&app), (Vec2::splat(150.0), 30.0));
+    assert!(!app.world().resource::<SizeTween>().is_running());
+}
+
+#[test]
+fn shifts_and_zero_duration_are_not_tweened() {
+    let mut app = tween_app(0.0);
+    app.world_mut().resource_mut::<CellsParam>().circle_size = 30.0;
+    app.update();
+    assert_eq!(shown(&app).1, 30.0);
+
+    let mut app = tween_app(1.0);
+    app.world_mut().resource_mut::<CellsParam>().circle_size = 12.0;
+    app.world_mut().resource_mut::<SizeTween>().shift(Vec2::ZERO, 2.0);
+    app.update();
+    assert_eq!(shown(&app).1, 12.0);
+}
+
+#[test]
+fn tweening_to_a_zero_cell_size_shrinks_smoothly() {
+    let mut app = tween_app(1.0);
+    let start = app.world().resource::<CellsParam>().cell_center(0, 0);
+    assert_ne!(start, Vec2::ZERO);
+    app.world_mut().resource_mut::<CellsParam>().cell_size = Vec2::ZERO;
+    app.update();
+    let cells_param = app.world().resource::<CellsParam>();
+    // a quarter of the way there, not already on the origin
+    assert_eq!(cells_param.cell_center_at(0, 0, shown(&app).0), start * 0.75);
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use moving_cells_01::{
+    calibration::OutputName,
+    wall::{DisplayWall, DisplayWallPlugin, WallPanel},
+    CellTable, CellsParam,
+};
+
+#[test]
+fn the_wall_counts_its_frames() {
+    let wall = DisplayWall { columns: 3, rows: 2, first_monitor: 0, bezel: Vec2::new(40.0, 30.0) };
+    assert_eq!(wall.size(Vec2::new(1920.0, 1080.0)), Vec2::new(3.0 * 1920.0 + 80.0, 2.0 * 1080.0 + 30.0));
+    let single = DisplayWall { columns: 1, rows: 1, ..wall.clone() };
+    assert_eq!(single.size(Vec2::new(1920.0, 1080.0)), Vec2::new(1920.0, 1080.0));
+}
+
+#[test]
+fn panels_show_their_part_and_skip_what_is_behind_the_frames() {
+    let wall = DisplayWall { columns: 2, rows: 1, first_monitor: 0, bezel: Vec2::new(20.0, 0.0) };
+    // the wall is 220 x 100 pixels, the table 440 x 200: 2 units a pixel
+    let bounds = Rect::new(-220.0, -100.0, 220.0, 100.0);
+    let panel = Vec2::new(100.0, 100.0);
+    let (left, scale) = wall.view(bounds, panel, 0, 0);
+    let (right, _) = wall.view(bounds, panel, 1, 0);
+    assert_eq!(scale, 2.0);
+    assert_eq!(left, Vec2::new(-120.0, 0.0));
+    assert_eq!(right, Vec2::new(120.0, 0.0));
+    // 200 units shown on either side, 40 hidden between them
+    assert_eq!(right.x - left.x - panel.x * scale, 40.0);
+
+    let wall = DisplayWall { columns: 1, rows: 2, first_monitor: 0, bezel: Vec2::ZERO };
+    let (top, _) = wall.view(bounds, panel, 0, 0);
+    let (bottom, _) = wall.view(bounds, panel, 0, 1);
+    assert!(top.y > bottom.y);
+}
+
+#[test]
+fn a_window_and_camera_per_panel() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, DisplayWallPlugin))
+        .insert_resource(CellsParam::new(CellTable::new("┌→┐\n↑0↓\n└←┘")))
+        .insert_resource(DisplayWall { columns: 3, rows: 2, first_monitor: 1, bezel: Vec2::ZERO });
+    app.update();
+    let world = app.world_mut();
+    assert_eq!(world.query_filtered::<&WallPanel, With<Window>>().iter(world).count(), 6);
+    let mut names = world.query::<&OutputName>().iter(world).map(|name| name.0.clone()).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names.first().map(String::as_str), Some("wall 0,0"));
+    assert_eq!(names.last().map(String::as_str), Some("wall 2,1"));
+
+    app.world_mut().remove_resource::<DisplayWall>();
+    app.update();
+    let world = app.world_mut();
+    assert_eq!(world.query::<&WallPanel>().iter(world).count(), 0);
+}